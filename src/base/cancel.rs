@@ -0,0 +1,32 @@
+//! Cancellation of long-running queries.
+
+use std::fmt;
+
+use tokio_util::sync::CancellationToken;
+
+/// Error returned by a query that was abandoned because the state it was
+/// computed from has been superseded (e.g., a newer edit arrived).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Cancelled {
+    /// Return `Err(Cancelled)` if `token` has been cancelled.
+    pub fn check(token: &CancellationToken) -> Result<(), Cancelled> {
+        if token.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Result of a query that can be cancelled.
+pub type Cancellable<T> = Result<T, Cancelled>;
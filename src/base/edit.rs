@@ -0,0 +1,156 @@
+//! Text edits on source files.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::base::{FileId, LineCol, LineIndex};
+
+/// Replace the text of a file between two positions.
+///
+/// An insertion has an empty range, a deletion an empty `new_text`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// The file to edit.
+    pub file: FileId,
+    /// Start line (0-indexed).
+    pub start_line: u32,
+    /// Start column (0-indexed).
+    pub start_col: u32,
+    /// End line (0-indexed).
+    pub end_line: u32,
+    /// End column (0-indexed).
+    pub end_col: u32,
+    /// The replacement text.
+    pub new_text: Arc<str>,
+}
+
+impl TextEdit {
+    /// Replace the range from `start` to `end` (`(line, col)`) with `new_text`.
+    pub fn replace(
+        file: FileId,
+        start: (u32, u32),
+        end: (u32, u32),
+        new_text: impl Into<Arc<str>>,
+    ) -> Self {
+        Self {
+            file,
+            start_line: start.0,
+            start_col: start.1,
+            end_line: end.0,
+            end_col: end.1,
+            new_text: new_text.into(),
+        }
+    }
+
+    /// Insert `new_text` at `(line, col)`.
+    pub fn insert(file: FileId, at: (u32, u32), new_text: impl Into<Arc<str>>) -> Self {
+        Self::replace(file, at, at, new_text)
+    }
+
+    /// Delete the range from `start` to `end` (`(line, col)`).
+    pub fn delete(file: FileId, start: (u32, u32), end: (u32, u32)) -> Self {
+        Self::replace(file, start, end, "")
+    }
+}
+
+/// Apply the `edits` of one file to its `text`.
+///
+/// Positions refer to `text` before any edit. Edits overlapping an earlier
+/// one or outside the text are skipped.
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let line_index = LineIndex::new(text);
+    let offset = |line, col| {
+        let offset = usize::from(line_index.offset(LineCol { line, col })?);
+        text.is_char_boundary(offset).then_some(offset)
+    };
+    let mut ranges: Vec<_> = edits
+        .iter()
+        .filter_map(|edit| {
+            let start = offset(edit.start_line, edit.start_col)?;
+            let end = offset(edit.end_line, edit.end_col)?;
+            (start <= end).then_some((start, end, &edit.new_text))
+        })
+        .collect();
+    ranges.sort_by_key(|&(start, end, _)| (start, end));
+
+    let mut result = String::with_capacity(text.len());
+    let mut applied = 0;
+    for (start, end, new_text) in ranges {
+        if start < applied {
+            continue;
+        }
+        result.push_str(&text[applied..start]);
+        result.push_str(new_text);
+        applied = end;
+    }
+    result.push_str(&text[applied..]);
+    result
+}
+
+/// Text edits across several files, applied together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkspaceEdit {
+    /// The edits of each file, in document order.
+    pub changes: BTreeMap<FileId, Vec<TextEdit>>,
+}
+
+impl WorkspaceEdit {
+    /// Create an empty edit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `edit` to the edits of its file.
+    pub fn push(&mut self, edit: TextEdit) {
+        let edits = self.changes.entry(edit.file).or_default();
+        let at = edits
+            .partition_point(|e| (e.start_line, e.start_col) <= (edit.start_line, edit.start_col));
+        edits.insert(at, edit);
+    }
+
+    /// The edits of `file`, in document order.
+    pub fn edits(&self, file: FileId) -> &[TextEdit] {
+        self.changes.get(&file).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every edit, grouped by file.
+    pub fn iter(&self) -> impl Iterator<Item = &TextEdit> {
+        self.changes.values().flatten()
+    }
+
+    /// Check if there is nothing to edit.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_edits() {
+        let file = FileId::new(0);
+        let text = "part def A;\npart a : A;";
+        let edits = [
+            TextEdit::replace(file, (1, 9), (1, 10), "B"),
+            TextEdit::replace(file, (0, 9), (0, 10), "B"),
+            TextEdit::insert(file, (1, 0), "// typed\n"),
+        ];
+        assert_eq!(
+            apply_edits(text, &edits),
+            "part def B;\n// typed\npart a : B;"
+        );
+    }
+
+    #[test]
+    fn test_apply_edits_skips_overlapping_and_out_of_range() {
+        let file = FileId::new(0);
+        let edits = [
+            TextEdit::replace(file, (0, 0), (0, 4), "item"),
+            TextEdit::replace(file, (0, 2), (0, 6), "x"),
+            TextEdit::insert(file, (3, 0), "y"),
+        ];
+        assert_eq!(apply_edits("part p;", &edits), "item p;");
+    }
+}
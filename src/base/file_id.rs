@@ -12,6 +12,7 @@ use std::fmt;
 /// - Reduces memory usage (4 bytes vs ~24+ bytes)
 /// - Enables cheap copying and hashing
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileId(pub u32);
 
 impl FileId {
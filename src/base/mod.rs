@@ -6,16 +6,22 @@
 //! - [`LineCol`], [`LineIndex`] - Line/column conversion
 //! - [`Position`], [`Span`] - Line/column positions for AST nodes
 //! - [`Name`], [`Interner`] - String interning
+//! - [`Cancelled`], [`Cancellable`] - Early exit for superseded queries
+//! - [`TextEdit`], [`WorkspaceEdit`] - Replacements of source text
 //! - Domain constants (file extensions, relationship types)
 //!
 //! This module has NO dependencies on other syster modules.
 
+mod cancel;
 pub mod constants;
+mod edit;
 mod file_id;
 mod intern;
 mod position;
 mod span;
 
+pub use cancel::{Cancellable, Cancelled};
+pub use edit::{TextEdit, WorkspaceEdit, apply_edits};
 pub use file_id::FileId;
 pub use intern::{Interner, Name};
 pub use position::{Position, Span};
@@ -4,6 +4,7 @@
 /// like hover, go-to-definition, and error reporting.
 /// A span representing a range in source code (0-indexed for LSP compatibility)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub start: Position,
     pub end: Position,
@@ -11,6 +12,7 @@ pub struct Span {
 
 /// A position in source code (0-indexed)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub line: usize,
     pub column: usize,
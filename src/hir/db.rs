@@ -2,12 +2,16 @@
 
 use std::sync::Arc;
 
+use indexmap::IndexMap;
+use salsa::Setter;
+
 use crate::base::FileId;
 use crate::syntax::SyntaxFile;
 use crate::syntax::file::FileExtension;
 
 use super::input::SourceRoot;
-use super::symbols::{HirSymbol, extract_symbols_unified};
+use super::resolve::SymbolIndex;
+use super::symbols::{ExtractionResult, HirSymbol, extract_symbols_unified, extract_with_filters};
 
 // ============================================================================
 // INPUTS
@@ -21,6 +25,13 @@ pub struct FileText {
     pub file: FileId,
     #[return_ref]
     pub text: String,
+    /// The language to parse the text as (SysML unless set otherwise).
+    #[default]
+    pub extension: FileExtension,
+    /// A tree already parsed from `text`, e.g. by incremental reparsing.
+    /// When set, [`parse_file`] returns it instead of parsing the text again.
+    #[default]
+    pub parsed: Option<Arc<SyntaxFile>>,
 }
 
 /// Input: Configuration for the source root.
@@ -30,6 +41,16 @@ pub struct SourceRootInput {
     pub root: SourceRoot,
 }
 
+/// Input: The set of files registered with the database.
+///
+/// Kept as an input (rather than a plain field) so that adding or removing
+/// a file bumps the revision exactly like editing one does.
+#[salsa::input]
+pub struct SourceFiles {
+    #[return_ref]
+    pub files: IndexMap<FileId, FileText>,
+}
+
 // ============================================================================
 // DATABASE
 // ============================================================================
@@ -43,6 +64,9 @@ pub struct SourceRootInput {
 #[derive(Default, Clone)]
 pub struct RootDatabase {
     storage: salsa::Storage<Self>,
+    /// Registry of files added via [`set_file_text()`](Self::set_file_text).
+    /// Created lazily on the first registration.
+    source_files: Option<SourceFiles>,
 }
 
 #[salsa::db]
@@ -57,6 +81,197 @@ impl RootDatabase {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Register a file with the database, or update its text if it is already known.
+    ///
+    /// Returns the `FileText` input for the file. Unlike a bare `FileText::new()`,
+    /// registered files are visible to workspace-wide consumers such as
+    /// [`workspace_index`] and `interchange::model_from_database()`.
+    pub fn set_file_text(&mut self, file: FileId, text: impl Into<String>) -> FileText {
+        self.set_file_texts([(file, text)])[0]
+    }
+
+    /// Register several files at once, or update the texts of known ones.
+    ///
+    /// Like calling [`set_file_text()`](Self::set_file_text) for each file, but
+    /// the registry is only updated once, so loading a workspace costs time
+    /// linear in its size. Returns the `FileText` inputs in the same order.
+    pub fn set_file_texts<T: Into<String>>(
+        &mut self,
+        files: impl IntoIterator<Item = (FileId, T)>,
+    ) -> Vec<FileText> {
+        // Only copied once a new file is actually added
+        let mut registered: Option<IndexMap<FileId, FileText>> = None;
+        let mut file_texts = Vec::new();
+
+        for (file, text) in files {
+            let text = text.into();
+            let known = match &registered {
+                Some(registered) => registered.get(&file).copied(),
+                None => self.file_text(file),
+            };
+
+            let file_text = match known {
+                Some(file_text) => {
+                    if file_text.text(self) != &text {
+                        file_text.set_text(self).to(text);
+                        if file_text.parsed(self).is_some() {
+                            file_text.set_parsed(self).to(None);
+                        }
+                    }
+                    file_text
+                }
+                None => {
+                    let file_text = FileText::new(self, file, text);
+                    registered
+                        .get_or_insert_with(|| self.registered_files())
+                        .insert(file, file_text);
+                    file_text
+                }
+            };
+            file_texts.push(file_text);
+        }
+
+        if let Some(registered) = registered {
+            self.set_registered_files(registered);
+        }
+        file_texts
+    }
+
+    /// Register a file together with the tree parsed from `text`.
+    ///
+    /// Like [`set_file_text()`](Self::set_file_text), but [`parse_file`] returns
+    /// `syntax_file` rather than parsing the text again. The tree must have been
+    /// parsed from exactly `text`; it also sets the file's language.
+    pub fn set_file_syntax(
+        &mut self,
+        file: FileId,
+        text: impl Into<String>,
+        syntax_file: impl Into<Arc<SyntaxFile>>,
+    ) -> FileText {
+        self.set_file_syntaxes([(file, text, syntax_file)])[0]
+    }
+
+    /// Register several files together with their parsed trees at once.
+    ///
+    /// The batch form of [`set_file_syntax()`](Self::set_file_syntax), updating
+    /// the registry only once like [`set_file_texts()`](Self::set_file_texts).
+    pub fn set_file_syntaxes<T: Into<String>, S: Into<Arc<SyntaxFile>>>(
+        &mut self,
+        files: impl IntoIterator<Item = (FileId, T, S)>,
+    ) -> Vec<FileText> {
+        let (texts, syntax_files): (Vec<_>, Vec<Arc<SyntaxFile>>) = files
+            .into_iter()
+            .map(|(file, text, syntax_file)| ((file, text), syntax_file.into()))
+            .unzip();
+        let file_texts = self.set_file_texts(texts);
+        for (&file_text, syntax_file) in file_texts.iter().zip(syntax_files) {
+            self.set_file_extension(file_text.file(self), syntax_file.extension());
+            file_text.set_parsed(self).to(Some(syntax_file));
+        }
+        file_texts
+    }
+
+    /// Set the language a registered file is parsed as.
+    ///
+    /// Does nothing if the file is not registered or already has this extension.
+    pub fn set_file_extension(&mut self, file: FileId, extension: FileExtension) {
+        if let Some(file_text) = self.file_text(file) {
+            if file_text.extension(self) != extension {
+                file_text.set_extension(self).to(extension);
+            }
+        }
+    }
+
+    /// Remove a file from the registry.
+    ///
+    /// Salsa inputs cannot be deleted, so the `FileText` itself stays alive,
+    /// but it will no longer be visited by workspace-wide queries.
+    pub fn remove_file(&mut self, file: FileId) -> Option<FileText> {
+        let mut files = self.registered_files();
+        let removed = files.shift_remove(&file)?;
+        self.set_registered_files(files);
+        Some(removed)
+    }
+
+    /// Get the `FileText` input registered for a file.
+    pub fn file_text(&self, file: FileId) -> Option<FileText> {
+        let source_files = self.source_files?;
+        source_files.files(self).get(&file).copied()
+    }
+
+    /// Get all registered files, ordered by `FileId`.
+    pub fn file_texts(&self) -> Vec<FileText> {
+        let mut files: Vec<_> = self.registered_files().into_iter().collect();
+        files.sort_by_key(|(id, _)| *id);
+        files.into_iter().map(|(_, file_text)| file_text).collect()
+    }
+
+    /// Extract symbols from the given files in parallel, in the same order.
+    ///
+    /// Each rayon task queries through its own handle on the shared storage, so
+    /// the memoized results are reused by later single-threaded queries too.
+    pub fn file_extractions(&self, files: &[FileText]) -> Vec<ExtractionResult> {
+        self.par_map(files, |db, file_text| {
+            file_extraction(db, file_text).clone()
+        })
+    }
+
+    /// Run `f` over the given files across the rayon pool, in the same order.
+    fn par_map<T: Send>(
+        &self,
+        files: &[FileText],
+        f: impl Fn(&RootDatabase, FileText) -> T + Sync,
+    ) -> Vec<T> {
+        use rayon::prelude::*;
+
+        // A few chunks per thread keeps the work balanced across uneven files
+        let chunk_size = files
+            .len()
+            .div_ceil(rayon::current_num_threads() * 4)
+            .max(1);
+        let tasks: Vec<(RootDatabase, &[FileText])> = files
+            .chunks(chunk_size)
+            .map(|chunk| (self.clone(), chunk))
+            .collect();
+
+        let f = &f;
+        tasks
+            .into_par_iter()
+            .flat_map_iter(|(db, chunk)| chunk.iter().map(move |&file_text| f(&db, file_text)))
+            .collect()
+    }
+
+    /// The registry input, or `None` if no file was registered yet.
+    ///
+    /// Workspace-wide queries such as [`workspace_index`] are keyed on it.
+    pub fn source_files(&self) -> Option<SourceFiles> {
+        self.source_files
+    }
+
+    /// Get the number of registered files.
+    pub fn file_count(&self) -> usize {
+        self.source_files
+            .map_or(0, |source_files| source_files.files(self).len())
+    }
+
+    /// Snapshot of the registry (empty if nothing was registered yet).
+    fn registered_files(&self) -> IndexMap<FileId, FileText> {
+        self.source_files
+            .map(|source_files| source_files.files(self).clone())
+            .unwrap_or_default()
+    }
+
+    fn set_registered_files(&mut self, files: IndexMap<FileId, FileText>) {
+        match self.source_files {
+            Some(source_files) => {
+                source_files.set_files(self).to(files);
+            }
+            None => {
+                self.source_files = Some(SourceFiles::new(self, files));
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -134,20 +349,45 @@ impl ParseResult {
 #[salsa::tracked]
 pub fn parse_file(db: &dyn salsa::Database, file_text: FileText) -> ParseResult {
     let text = file_text.text(db);
+    let extension = file_text.extension(db);
+
+    // Parse using the rowan parser via SyntaxFile, unless the tree was supplied
+    // with the text (only trusted while it still matches the input)
+    let syntax_file = match file_text.parsed(db) {
+        Some(parsed)
+            if parsed.extension() == extension
+                && usize::from(parsed.parse().green.text_len()) == text.len() =>
+        {
+            parsed
+        }
+        _ => Arc::new(SyntaxFile::new(text, extension)),
+    };
+
+    let errors: Vec<String> = syntax_file
+        .errors()
+        .iter()
+        .map(|e| e.message.clone())
+        .collect();
+    ParseResult {
+        success: true,
+        errors,
+        syntax_file: Some(syntax_file),
+    }
+}
 
-    // Parse using the rowan parser via SyntaxFile
-    let syntax_file = SyntaxFile::new(text, FileExtension::SysML);
+/// Fingerprint of a file's input (text and language).
+///
+/// Cheap to re-query for unchanged files, so callers holding derived state
+/// outside the database can compare fingerprints to find what changed. Uses a
+/// fixed algorithm, since fingerprints feed on-disk cache keys.
+#[salsa::tracked]
+pub fn file_fingerprint(db: &dyn salsa::Database, file_text: FileText) -> u64 {
+    use std::hash::{Hash, Hasher};
 
-    if syntax_file.has_errors() {
-        let errors: Vec<String> = syntax_file
-            .errors()
-            .iter()
-            .map(|e| e.message.clone())
-            .collect();
-        ParseResult::ok_with_errors(syntax_file, errors)
-    } else {
-        ParseResult::ok(syntax_file)
-    }
+    let mut hasher = rustc_hash::FxHasher::default();
+    file_text.text(db).hash(&mut hasher);
+    file_text.extension(db).hash(&mut hasher);
+    hasher.finish()
 }
 
 /// Extract symbols from a parsed file.
@@ -172,6 +412,99 @@ pub fn file_symbols_from_text(db: &dyn salsa::Database, file_text: FileText) ->
     }
 }
 
+/// Extract symbols and import/scope filters from a file given its text.
+///
+/// Like [`file_symbols_from_text`], but keeps the filter information that
+/// `SymbolIndex::add_extraction_result` needs for filtered imports.
+#[salsa::tracked(return_ref)]
+pub fn file_extraction(db: &dyn salsa::Database, file_text: FileText) -> ExtractionResult {
+    let file = file_text.file(db);
+    let result = parse_file(db, file_text);
+    match result.syntax_file {
+        Some(ref syntax_file) => extract_with_filters(file, syntax_file),
+        None => ExtractionResult::default(),
+    }
+}
+
+// ============================================================================
+// WORKSPACE QUERIES
+// ============================================================================
+
+/// A `SymbolIndex` held by a workspace query.
+///
+/// Compared by identity: the query only reruns after an input change, and the
+/// index it then builds is never worth comparing to the previous one.
+#[derive(Clone, Debug)]
+struct SharedIndex(Arc<SymbolIndex>);
+
+impl PartialEq for SharedIndex {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedIndex {}
+
+/// Index the symbols of every registered file and build the visibility map
+/// of every scope.
+///
+/// Reads each file through the memoized [`file_extraction`] query, so it is
+/// only rerun once a file is added, removed or edited.
+#[salsa::tracked]
+fn workspace_symbols(db: &dyn salsa::Database, source_files: SourceFiles) -> SharedIndex {
+    let mut files: Vec<_> = source_files.files(db).iter().collect();
+    files.sort_by_key(|(id, _)| **id);
+
+    let mut index = SymbolIndex::new();
+    for (&file, &file_text) in files {
+        index.add_extraction_result(file, file_extraction(db, file_text).clone());
+    }
+    index.ensure_visibility_maps();
+    SharedIndex(Arc::new(index))
+}
+
+/// The index of `workspace_symbols` with every type reference resolved.
+#[salsa::tracked]
+fn resolved_symbols(db: &dyn salsa::Database, source_files: SourceFiles) -> SharedIndex {
+    let mut index = SymbolIndex::clone(&workspace_symbols(db, source_files).0);
+    index.resolve_all_type_refs();
+    SharedIndex(Arc::new(index))
+}
+
+/// Build a fully resolved `SymbolIndex` from every file registered in the database.
+///
+/// Visibility maps and type-ref resolution are tracked queries over the file
+/// registry, so the index is only rebuilt after a file is added, removed or
+/// edited. Files whose extraction is out of date are re-extracted in parallel.
+pub fn workspace_index(db: &RootDatabase) -> Arc<SymbolIndex> {
+    let Some(source_files) = db.source_files() else {
+        return Arc::new(SymbolIndex::new());
+    };
+
+    // Warm the per-file queries across the rayon pool; the tracked queries
+    // then find every extraction memoized
+    db.par_map(&db.file_texts(), |db, file_text| {
+        file_extraction(db, file_text);
+    });
+    resolved_symbols(db, source_files).0
+}
+
+/// Build a fully resolved `SymbolIndex` from every registered file, owned by the caller.
+///
+/// Unlike [`workspace_index`], the index itself is not memoized, so a caller
+/// that keeps it can edit it in place without first copying the memoized one.
+/// Only files whose extraction is out of date are re-extracted, in parallel.
+pub fn build_workspace_index(db: &RootDatabase) -> SymbolIndex {
+    let file_texts = db.file_texts();
+    let mut index = SymbolIndex::new();
+    for (file_text, result) in file_texts.iter().zip(db.file_extractions(&file_texts)) {
+        index.add_extraction_result(file_text.file(db), result);
+    }
+    index.ensure_visibility_maps();
+    index.resolve_all_type_refs();
+    index
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,6 +605,144 @@ mod tests {
         assert_eq!(widget.unwrap().kind, SymbolKind::PartDefinition);
     }
 
+    #[test]
+    fn test_registered_files() {
+        let mut db = RootDatabase::new();
+        assert_eq!(db.file_count(), 0);
+        assert!(db.file_texts().is_empty());
+
+        let b = db.set_file_text(FileId::new(1), "part def B;");
+        let a = db.set_file_text(FileId::new(0), "part def A;");
+        assert_eq!(db.file_count(), 2);
+        // Ordered by FileId, not by registration order
+        assert_eq!(db.file_texts(), vec![a, b]);
+
+        // Re-registering a known file updates the existing input
+        let a2 = db.set_file_text(FileId::new(0), "part def A2;");
+        assert_eq!(a, a2);
+        assert_eq!(a.text(&db), "part def A2;");
+
+        assert_eq!(db.remove_file(FileId::new(1)), Some(b));
+        assert_eq!(db.file_text(FileId::new(1)), None);
+        assert_eq!(db.file_texts(), vec![a]);
+    }
+
+    #[test]
+    fn test_set_file_texts_registers_a_batch_at_once() {
+        let mut db = RootDatabase::new();
+        let a = db.set_file_text(FileId::new(0), "part def A;");
+
+        // Adding many files updates the registry in a single revision, the
+        // same one a database adding a single file ends up at
+        let files = db.set_file_texts((1..50).map(|i| (FileId::new(i), format!("part def P{i};"))));
+        assert_eq!(files.len(), 49);
+        assert_eq!(db.file_count(), 50);
+        let mut single = RootDatabase::new();
+        single.set_file_text(FileId::new(0), "part def A;");
+        single.set_file_text(FileId::new(1), "part def P1;");
+        assert_eq!(
+            salsa::plumbing::current_revision(&db),
+            salsa::plumbing::current_revision(&single)
+        );
+
+        // Known files in a batch keep their inputs
+        let again = db.set_file_texts([(FileId::new(0), "part def A2;"), (FileId::new(50), "")]);
+        assert_eq!(again[0], a);
+        assert_eq!(a.text(&db), "part def A2;");
+        assert_eq!(db.file_count(), 51);
+        assert_eq!(db.file_texts()[1..50], files[..]);
+    }
+
+    #[test]
+    fn test_file_extractions_keep_input_order() {
+        let mut db = RootDatabase::new();
+        let files: Vec<FileText> = (0..20)
+            .map(|i| db.set_file_text(FileId::new(i), format!("part def P{i};")))
+            .collect();
+
+        let results = db.file_extractions(&files);
+        assert_eq!(results.len(), files.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.symbols[0].name.as_ref(), format!("P{i}"));
+        }
+    }
+
+    #[test]
+    fn test_file_extension_selects_parser() {
+        let mut db = RootDatabase::new();
+        let file_text = db.set_file_text(FileId::new(0), "package Lib { classifier Thing; }");
+        assert!(
+            parse_file(&db, file_text)
+                .get_syntax_file()
+                .unwrap()
+                .is_sysml()
+        );
+        let sysml_fingerprint = file_fingerprint(&db, file_text);
+
+        db.set_file_extension(FileId::new(0), FileExtension::KerML);
+        let result = parse_file(&db, file_text);
+        assert!(result.get_syntax_file().unwrap().is_kerml());
+        assert!(!result.has_errors());
+        assert_ne!(file_fingerprint(&db, file_text), sysml_fingerprint);
+    }
+
+    #[test]
+    fn test_edit_invalidates_symbols() {
+        // Both versions parse without errors, so the re-parse must not be
+        // mistaken for an unchanged result.
+        let mut db = RootDatabase::new();
+        let file_text = db.set_file_text(FileId::new(0), "part def Before;");
+        let before = file_symbols_from_text(&db, file_text);
+        assert_eq!(before[0].name.as_ref(), "Before");
+
+        db.set_file_text(FileId::new(0), "part def After;");
+        let after = file_symbols_from_text(&db, file_text);
+        assert_eq!(after[0].name.as_ref(), "After");
+    }
+
+    #[test]
+    fn test_workspace_index_resolves_across_files() {
+        use crate::hir::symbols::TypeRefKind;
+
+        let mut db = RootDatabase::new();
+        db.set_file_text(FileId::new(0), "package A { part def Engine; }");
+        db.set_file_text(
+            FileId::new(1),
+            "package B { import A::*; part engine : Engine; }",
+        );
+
+        // The memoized index and an owned one resolve alike
+        for index in [workspace_index(&db), Arc::new(build_workspace_index(&db))] {
+            assert_eq!(index.file_count(), 2);
+
+            let engine = index.lookup_qualified("B::engine").expect("B::engine");
+            let resolved = engine.type_refs.iter().find_map(|trk| match trk {
+                TypeRefKind::Simple(tr) => tr.resolved_target.clone(),
+                TypeRefKind::Chain(_) => None,
+            });
+            assert_eq!(resolved.as_deref(), Some("A::Engine"));
+        }
+    }
+
+    #[test]
+    fn test_workspace_index_is_memoized() {
+        let mut db = RootDatabase::new();
+        assert!(workspace_index(&db).is_empty());
+
+        db.set_file_text(FileId::new(0), "package A { part def Engine; }");
+        let first = workspace_index(&db);
+        assert!(Arc::ptr_eq(&first, &workspace_index(&db)));
+
+        // Any edit reruns the visibility and resolution queries
+        db.set_file_text(FileId::new(0), "package A { part def Motor; }");
+        let edited = workspace_index(&db);
+        assert!(!Arc::ptr_eq(&first, &edited));
+        assert!(edited.lookup_qualified("A::Motor").is_some());
+
+        db.set_file_text(FileId::new(1), "package B;");
+        assert_eq!(workspace_index(&db).file_count(), 2);
+    }
+
     #[test]
     fn test_salsa_memoization() {
         // Test that queries are memoized (same input returns same result)
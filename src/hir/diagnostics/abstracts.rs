@@ -0,0 +1,255 @@
+//! Abstract definition instantiation (W0005).
+//!
+//! A usage typed directly by an abstract definition has no concrete type to
+//! be instantiated with, unless it's abstract itself or further specialized:
+//! it specializes, subsets or redefines other features, has members of its
+//! own, or is specialized or redefined by another feature.
+//! `individual` usages model one specific occurrence, so they're always
+//! reported.
+//!
+//! These are warnings: the model is valid, but incomplete. References,
+//! non-composite features of a type, ends and directed features (parameters)
+//! don't instantiate their types and are skipped, as are symbols from KerML
+//! files.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::hir::symbols::{HirSymbol, RefKind, SymbolKind, TypeRefKind};
+
+/// The features specialized by other features or by their own members.
+#[derive(Debug, Default)]
+pub(super) struct SpecializedFeatures {
+    /// Resolved targets of those relationships.
+    qualified_names: HashSet<Arc<str>>,
+    /// Names written in redefinitions, with the owners of the redefining
+    /// features, since a shorthand redefinition (`part :>> engine : V8;`) may
+    /// not resolve to the redefined feature.
+    redefined_names: HashMap<Arc<str>, Vec<Arc<str>>>,
+    /// Elements with members, which makes a feature a specialization of its
+    /// types (`action integrate : Integrate { in getDerivative = ...; }`).
+    owners: HashSet<Arc<str>>,
+}
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_abstract_instantiation(&mut self, symbol: &HirSymbol) {
+        if !symbol.kind.is_usage()
+            || symbol.is_abstract
+            || symbol.is_end
+            || symbol.direction.is_some()
+            || symbol.kind == SymbolKind::ReferenceUsage
+            || self.index.is_kerml_file(symbol.file)
+        {
+            return;
+        }
+        // A usage that specializes, subsets or redefines other features is
+        // more specific than its abstract type (`do action doAction : Action
+        // :>> 'do';`)
+        if Self::own_type_refs(symbol).any(|tr| {
+            matches!(
+                tr.kind,
+                RefKind::Specializes | RefKind::Subsets | RefKind::Redefines
+            )
+        }) {
+            return;
+        }
+        let index = self.index;
+        // Within a type, only composite features own their values
+        // (`ref part backup : Engine;` refers to an existing one)
+        let in_package = index
+            .lookup_qualified(&Self::extract_scope(&symbol.qualified_name))
+            .is_none_or(|owner| owner.kind == SymbolKind::Package);
+        if !in_package && symbol.is_composite == Some(false) {
+            return;
+        }
+
+        for tr in Self::own_type_refs(symbol).filter(|tr| tr.kind == RefKind::TypedBy) {
+            let Some(ty) = tr
+                .resolved_target
+                .as_deref()
+                .and_then(|target| index.lookup_qualified(target))
+            else {
+                continue;
+            };
+            if !ty.is_abstract || !ty.kind.is_definition() || index.is_kerml_file(ty.file) {
+                continue;
+            }
+            let (message, fix) = if symbol.is_individual {
+                let message = format!(
+                    "abstract instantiation: individual '{}' is typed by abstract definition '{}'",
+                    symbol.name, ty.name
+                );
+                (message, None)
+            } else if !self.is_specialized(symbol) {
+                let message = format!(
+                    "abstract instantiation: '{}' is typed by abstract definition '{}' and is never specialized or redefined",
+                    symbol.name, ty.name
+                );
+                (message, self.make_abstract_fix(symbol))
+            } else {
+                continue;
+            };
+            let mut diagnostic =
+                Diagnostic::warning(symbol.file, tr.start_line, tr.start_col, message)
+                    .with_span(tr.end_line, tr.end_col)
+                    .with_code(codes::ABSTRACT_TYPED_USAGE)
+                    .with_related(RelatedInfo {
+                        file: ty.file,
+                        line: ty.start_line,
+                        col: ty.start_col,
+                        message: Arc::from(format!("'{}' is declared abstract here", ty.name)),
+                    });
+            diagnostic.fixes.extend(fix);
+            self.collector.add(diagnostic);
+        }
+    }
+
+    /// Whether `feature` is specialized, subsetted or redefined, or has members.
+    ///
+    /// A redefinition naming `feature` only counts if its owner is, or
+    /// specializes, the owner of `feature`.
+    fn is_specialized(&mut self, feature: &HirSymbol) -> bool {
+        let specialized = self.specialized_features();
+        if specialized
+            .qualified_names
+            .contains(&feature.qualified_name)
+            || specialized.owners.contains(&feature.qualified_name)
+        {
+            return true;
+        }
+        let Some(redefining_owners) = specialized.redefined_names.get(&feature.name).cloned()
+        else {
+            return false;
+        };
+        let owner = Self::extract_scope(&feature.qualified_name);
+        for redefining_owner in redefining_owners {
+            if *redefining_owner == *owner {
+                return true;
+            }
+            let Some(redefining_owner) = self.index.lookup_qualified(&redefining_owner) else {
+                continue;
+            };
+            if self
+                .all_generals(redefining_owner)
+                .iter()
+                .any(|general| *general.qualified_name == *owner)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The features specialized anywhere in the index, collected on first use.
+    fn specialized_features(&mut self) -> &SpecializedFeatures {
+        let index = self.index;
+        self.specialized.get_or_insert_with(|| {
+            let mut specialized = SpecializedFeatures::default();
+            for symbol in index.all_symbols() {
+                let owner: Arc<str> = Self::extract_scope(&symbol.qualified_name).into();
+                specialized.owners.insert(owner.clone());
+                for type_ref in &symbol.type_refs {
+                    let tr = match type_ref {
+                        TypeRefKind::Simple(tr) => tr,
+                        TypeRefKind::Chain(chain) => match chain.parts.last() {
+                            Some(tr) => tr,
+                            None => continue,
+                        },
+                    };
+                    if !matches!(
+                        tr.kind,
+                        RefKind::Specializes | RefKind::Subsets | RefKind::Redefines
+                    ) {
+                        continue;
+                    }
+                    if let Some(target) = &tr.resolved_target {
+                        if **target != *symbol.qualified_name {
+                            specialized.qualified_names.insert(target.clone());
+                        }
+                    }
+                    if tr.kind == RefKind::Redefines {
+                        specialized
+                            .redefined_names
+                            .entry(tr.target.clone())
+                            .or_default()
+                            .push(owner.clone());
+                    }
+                }
+            }
+            specialized
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::diagnostics_for;
+
+    fn abstract_messages(source: &str) -> Vec<String> {
+        diagnostics_for(source, codes::ABSTRACT_TYPED_USAGE)
+            .into_iter()
+            .map(|d| d.message.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_usage_of_abstract_definition() {
+        let messages = abstract_messages(
+            "package P { abstract part def Engine; part def V8 :> Engine; \
+             part car { part engine : Engine; part spare : V8; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "abstract instantiation: 'engine' is typed by abstract definition 'Engine' \
+              and is never specialized or redefined"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_specialized_or_abstract_usages_are_allowed() {
+        let messages = abstract_messages(
+            "package P { abstract part def Engine; part def V8 :> Engine; \
+             part def Vehicle { part engine : Engine; abstract part spare : Engine; \
+             ref part backup : Engine; in part supplied : Engine; \
+             part tuned : Engine :> engine; part custom : Engine { part cylinders; } } \
+             part def Car :> Vehicle { part :>> engine : V8; } \
+             abstract action def Step; \
+             state def Machine { action work; do action running : Step :>> work; } }",
+        );
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+
+    #[test]
+    fn test_unrelated_redefinition_of_same_name() {
+        let messages = abstract_messages(
+            "package P { abstract part def Engine; part def V8 :> Engine; \
+             part def Vehicle { part engine : Engine; } \
+             part def Boat { part engine : V8; } part def Yacht :> Boat { part :>> engine; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "abstract instantiation: 'engine' is typed by abstract definition 'Engine' \
+              and is never specialized or redefined"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_individual_of_abstract_definition() {
+        let messages = abstract_messages(
+            "package P { abstract part def Vehicle; \
+             individual part myCar : Vehicle; part def Car { part :>> myCar; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "abstract instantiation: individual 'myCar' is typed by abstract definition 'Vehicle'"
+            ]
+        );
+    }
+}
@@ -0,0 +1,545 @@
+//! Connection and flow endpoint compatibility (E0016).
+//!
+//! The direction of a connected feature is seen from the connection's owner:
+//!
+//! - it's reversed once for each conjugated port (`port fuelIn : ~FuelPort`)
+//!   the endpoint reaches into, including the connected port itself, unless
+//!   the feature is declared in the port usage rather than its definition;
+//! - a port or directed feature of the owner itself (`connect fuelIn to
+//!   engine.fuelIn`) is on its boundary, so what it receives from outside is
+//!   sent inside.
+//!
+//! A connection, interface or flow between two ports can't have both ends
+//! send, or both receive, a feature of the same name (`fuel`, `inner.fuel`
+//! for nested ports). A flow between directed features must go from one that
+//! can send to one that can receive, and the payload of a flow (`flow of
+//! Fuel`) must be carried by both ends. Binding connectors and endpoints that
+//! don't fully resolve are skipped.
+
+use std::sync::Arc;
+
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::hir::symbols::{HirSymbol, RefKind, SymbolKind, TypeRef};
+use crate::parser::Direction;
+
+/// A resolved connection or flow endpoint.
+struct Endpoint<'a> {
+    /// The endpoint as written (`tank.fuelOut`).
+    text: String,
+    first: &'a TypeRef,
+    last: &'a TypeRef,
+    /// The connected feature.
+    feature: &'a HirSymbol,
+    /// Whether directions are reversed when seen from the connection's owner.
+    reversed: bool,
+}
+
+/// A directed feature of a port, with its direction seen from the connection.
+struct PortFeature<'a> {
+    /// Path from the port (`fuel`, `inner.fuel`).
+    path: String,
+    feature: &'a HirSymbol,
+    direction: Direction,
+}
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_connection_endpoints(&mut self, symbol: &HirSymbol) {
+        if !matches!(
+            symbol.kind,
+            SymbolKind::ConnectionUsage
+                | SymbolKind::InterfaceUsage
+                | SymbolKind::FlowConnectionUsage
+        ) || self.index.is_kerml_file(symbol.file)
+            || symbol
+                .qualified_name
+                .rsplit("::")
+                .next()
+                .is_some_and(|name| name.starts_with("<bind:"))
+        {
+            return;
+        }
+        let Some([source, target]) = self.endpoints(symbol) else {
+            return;
+        };
+
+        let source_features = self.port_features(&source);
+        let target_features = self.port_features(&target);
+        let is_flow = symbol.kind == SymbolKind::FlowConnectionUsage;
+        match (&source_features, &target_features) {
+            (Some(sources), Some(targets)) => {
+                self.check_port_directions(symbol, &source, sources, &target, targets)
+            }
+            (None, None) => self.check_feature_directions(symbol, &source, &target, is_flow),
+            _ => {}
+        }
+
+        if is_flow {
+            for (endpoint, features) in [(&source, &source_features), (&target, &target_features)] {
+                self.check_payload(symbol, endpoint, features.as_deref());
+            }
+        }
+    }
+
+    /// The two ends of a connection: the references after `connect`/`from`
+    /// and `to`, or those of its `end` features.
+    fn endpoints(&self, symbol: &HirSymbol) -> Option<[Endpoint<'a>; 2]> {
+        let index = self.index;
+        let mut ends: Vec<Vec<&'a TypeRef>> = Vec::new();
+        // The indexed symbol, whose references live as long as the index
+        let own = index.lookup_qualified(&symbol.qualified_name)?;
+        for type_ref in &own.type_refs {
+            let parts = type_ref.as_refs();
+            if parts.first().is_some_and(|tr| tr.kind == RefKind::Other) {
+                ends.push(parts);
+            }
+        }
+        if ends.is_empty() {
+            let prefix = format!("{}::", symbol.qualified_name);
+            ends.extend(
+                index
+                    .symbols_in_file(symbol.file)
+                    .into_iter()
+                    .filter(|end| end.is_end && end.qualified_name.starts_with(&prefix))
+                    .filter_map(end_reference),
+            );
+        }
+        if ends.len() != 2 {
+            return None;
+        }
+
+        let owner = index.lookup_qualified(&Self::extract_scope(&symbol.qualified_name));
+        let in_package = owner.is_none_or(|owner| owner.kind == SymbolKind::Package);
+        let target = self.endpoint(ends.pop()?, in_package)?;
+        let source = self.endpoint(ends.pop()?, in_package)?;
+        Some([source, target])
+    }
+
+    fn endpoint(&self, parts: Vec<&'a TypeRef>, in_package: bool) -> Option<Endpoint<'a>> {
+        let features = self.resolve_parts(&parts)?;
+        // A port or directed feature of the owner itself is on its boundary,
+        // unlike the ends of a connection definition
+        let first = *features.first()?;
+        let on_boundary =
+            !in_package && !first.is_end && (first.direction.is_some() || self.is_port(first));
+        let text = parts
+            .iter()
+            .map(|tr| &*tr.target)
+            .collect::<Vec<_>>()
+            .join(".");
+        Some(Endpoint {
+            text,
+            first: parts.first()?,
+            last: parts.last()?,
+            feature: *features.last()?,
+            reversed: self.reversed_through(&features, true) ^ on_boundary,
+        })
+    }
+
+    fn resolve_parts(&self, parts: &[&TypeRef]) -> Option<Vec<&'a HirSymbol>> {
+        parts
+            .iter()
+            .map(|tr| self.index.lookup_qualified(tr.resolved_target.as_deref()?))
+            .collect()
+    }
+
+    /// Whether reaching the last of `features` through the others reverses
+    /// its direction: each conjugated port reverses the features of its
+    /// definition, not those declared in the port usage itself.
+    fn reversed_through(&self, features: &[&HirSymbol], follow_ends: bool) -> bool {
+        features
+            .windows(2)
+            .filter(|pair| {
+                self.is_conjugated(pair[0], follow_ends) && !is_owned_by(pair[1], pair[0])
+            })
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Whether `feature` is a conjugated port. A connection end (`end a ::>
+    /// engine.fuelIn`) is conjugated if the port it references is.
+    fn is_conjugated(&self, feature: &HirSymbol, follow_ends: bool) -> bool {
+        let referenced = follow_ends && feature.is_end && {
+            end_reference(feature)
+                .and_then(|parts| self.resolve_parts(&parts))
+                .is_some_and(|features| {
+                    self.reversed_through(&features, false)
+                        ^ features.last().is_some_and(|last| last.is_conjugated)
+                })
+        };
+        feature.is_conjugated ^ referenced
+    }
+
+    /// Whether `feature` is a port: a port usage, or a feature typed by a port
+    /// definition (`end source : ~PublicationPort`).
+    fn is_port(&self, feature: &HirSymbol) -> bool {
+        feature.kind == SymbolKind::PortUsage
+            || self
+                .explicit_types(feature)
+                .is_some_and(|types| types.iter().any(|ty| ty.kind == SymbolKind::PortDefinition))
+    }
+
+    /// The directed features of a port endpoint, or `None` if it isn't a port.
+    fn port_features(&mut self, endpoint: &Endpoint<'a>) -> Option<Vec<PortFeature<'a>>> {
+        let port = endpoint.feature;
+        if port.direction.is_some() || !self.is_port(port) {
+            return None;
+        }
+        let mut features = Vec::new();
+        let mut visiting = Vec::new();
+        let reversed = endpoint.reversed;
+        self.collect_port_features(port, "", reversed, &mut visiting, &mut features);
+        Some(features)
+    }
+
+    /// Collect the directed features of `port`, its port definitions and their
+    /// generalizations, following nested ports.
+    ///
+    /// Features declared in the port usage itself (`port p : ~PortDef { out ref
+    /// :>> x; }`) already have the port's direction; those of a conjugated
+    /// port definition are reversed.
+    fn collect_port_features(
+        &mut self,
+        port: &'a HirSymbol,
+        prefix: &str,
+        reversed: bool,
+        visiting: &mut Vec<Arc<str>>,
+        features: &mut Vec<PortFeature<'a>>,
+    ) {
+        if visiting.contains(&port.qualified_name) {
+            return;
+        }
+        visiting.push(port.qualified_name.clone());
+
+        let mut namespaces = vec![(port, reversed)];
+        let conjugated = self.is_conjugated(port, true);
+        for port_def in self.explicit_types(port).unwrap_or_default() {
+            let reversed = reversed ^ conjugated;
+            namespaces.push((port_def, reversed));
+            namespaces.extend(
+                self.all_generals(port_def)
+                    .into_iter()
+                    .map(|general| (general, reversed)),
+            );
+        }
+        for (namespace, reversed) in namespaces {
+            let members: Vec<&'a HirSymbol> = self
+                .index
+                .symbols_in_file(namespace.file)
+                .into_iter()
+                .filter(|member| {
+                    Self::extract_scope(&member.qualified_name) == *namespace.qualified_name
+                })
+                .collect();
+            for member in members {
+                let path = format!("{}{}", prefix, member.name);
+                if features.iter().any(|feature| feature.path == path) {
+                    continue;
+                }
+                if let Some(direction) = member.direction {
+                    features.push(PortFeature {
+                        path,
+                        feature: member,
+                        direction: reverse_if(direction, reversed),
+                    });
+                } else if self.is_port(member) {
+                    let prefix = format!("{}.", path);
+                    self.collect_port_features(member, &prefix, reversed, visiting, features);
+                }
+            }
+        }
+        visiting.pop();
+    }
+
+    /// Check two ports don't both send, or both receive, the same feature.
+    fn check_port_directions(
+        &mut self,
+        symbol: &HirSymbol,
+        source: &Endpoint<'a>,
+        sources: &[PortFeature<'a>],
+        target: &Endpoint<'a>,
+        targets: &[PortFeature<'a>],
+    ) {
+        let conflict = sources.iter().find_map(|sent| {
+            let received = targets.iter().find(|received| received.path == sent.path)?;
+            let verb = conflicting(sent.direction, received.direction)?;
+            Some((sent, received, verb))
+        });
+        if let Some((sent, received, verb)) = conflict {
+            let message = format!(
+                "incompatible endpoints: '{}' and '{}' both {} '{}'",
+                source.text, target.text, verb, sent.path
+            );
+            self.report_endpoints(symbol, source.first, target.last, message, received.feature);
+        }
+    }
+
+    /// Check the directions of two directed features: a flow must go from one
+    /// that can send to one that can receive, a connection can't join two that
+    /// both send or both receive.
+    fn check_feature_directions(
+        &mut self,
+        symbol: &HirSymbol,
+        source: &Endpoint<'a>,
+        target: &Endpoint<'a>,
+        is_flow: bool,
+    ) {
+        let direction = |endpoint: &Endpoint<'a>| {
+            let direction = endpoint.feature.direction?;
+            Some(reverse_if(direction, endpoint.reversed))
+        };
+        let (source_direction, target_direction) = (direction(source), direction(target));
+        if is_flow {
+            if source_direction == Some(Direction::In) {
+                let message = format!(
+                    "incompatible endpoints: flow source '{}' can't send",
+                    source.text
+                );
+                self.report_endpoints(symbol, source.first, source.last, message, source.feature);
+            }
+            if target_direction == Some(Direction::Out) {
+                let message = format!(
+                    "incompatible endpoints: flow target '{}' can't receive",
+                    target.text
+                );
+                self.report_endpoints(symbol, target.first, target.last, message, target.feature);
+            }
+        } else if let Some(verb) = source_direction
+            .zip(target_direction)
+            .and_then(|(source, target)| conflicting(source, target))
+        {
+            let message = format!(
+                "incompatible endpoints: '{}' and '{}' both {}",
+                source.text, target.text, verb
+            );
+            self.report_endpoints(symbol, source.first, target.last, message, target.feature);
+        }
+    }
+
+    /// Check the payload of a flow (`flow of Fuel`) is carried by `endpoint`:
+    /// one of the types of the connected feature, or of a port's directed
+    /// features, conforms to the payload type or generalizes it.
+    fn check_payload(
+        &mut self,
+        symbol: &HirSymbol,
+        endpoint: &Endpoint<'a>,
+        port_features: Option<&[PortFeature<'a>]>,
+    ) {
+        let index = self.index;
+        let payloads: Vec<(&TypeRef, &'a HirSymbol)> = Self::own_type_refs(symbol)
+            .filter(|tr| tr.kind == RefKind::TypedBy)
+            .filter_map(|tr| Some((tr, index.lookup_qualified(tr.resolved_target.as_deref()?)?)))
+            .filter(|(_, ty)| {
+                !matches!(
+                    ty.kind,
+                    SymbolKind::ConnectionDefinition
+                        | SymbolKind::InterfaceDefinition
+                        | SymbolKind::AllocationDefinition
+                )
+            })
+            .collect();
+        if payloads.is_empty() {
+            return;
+        }
+
+        let carried: Vec<&'a HirSymbol> = match port_features {
+            Some(features) => features
+                .iter()
+                .filter_map(|feature| self.explicit_types(feature.feature))
+                .flatten()
+                .collect(),
+            None => self.explicit_types(endpoint.feature).unwrap_or_default(),
+        };
+        if carried.is_empty() {
+            return;
+        }
+        for (_, payload) in payloads {
+            let payload_generals = self.all_generals(payload);
+            let is_carried = carried.iter().any(|ty| {
+                ty.qualified_name == payload.qualified_name
+                    || payload_generals
+                        .iter()
+                        .any(|general| general.qualified_name == ty.qualified_name)
+            }) || carried.iter().any(|ty| {
+                self.all_generals(ty)
+                    .iter()
+                    .any(|general| general.qualified_name == payload.qualified_name)
+            });
+            if !is_carried {
+                let message = format!(
+                    "incompatible endpoints: flow payload '{}' is not carried by '{}'",
+                    payload.name, endpoint.text
+                );
+                self.report_endpoints(
+                    symbol,
+                    endpoint.first,
+                    endpoint.last,
+                    message,
+                    endpoint.feature,
+                );
+            }
+        }
+    }
+
+    fn report_endpoints(
+        &mut self,
+        symbol: &HirSymbol,
+        first: &TypeRef,
+        last: &TypeRef,
+        message: String,
+        feature: &HirSymbol,
+    ) {
+        self.collector.add(
+            Diagnostic::error(symbol.file, first.start_line, first.start_col, message)
+                .with_span(last.end_line, last.end_col)
+                .with_code(codes::INCOMPATIBLE_ENDPOINTS)
+                .with_related(RelatedInfo {
+                    file: feature.file,
+                    line: feature.start_line,
+                    col: feature.start_col,
+                    message: Arc::from(format!("'{}' is declared here", feature.name)),
+                }),
+        );
+    }
+}
+
+fn reverse_if(direction: Direction, reversed: bool) -> Direction {
+    match direction {
+        Direction::In if reversed => Direction::Out,
+        Direction::Out if reversed => Direction::In,
+        direction => direction,
+    }
+}
+
+/// The references of a connection end (`end a ::> engine.fuelIn`).
+fn end_reference(end: &HirSymbol) -> Option<Vec<&TypeRef>> {
+    end.type_refs
+        .iter()
+        .map(|type_ref| type_ref.as_refs())
+        .find(|parts| {
+            parts
+                .first()
+                .is_some_and(|tr| tr.kind == RefKind::References)
+        })
+}
+
+fn is_owned_by(feature: &HirSymbol, owner: &HirSymbol) -> bool {
+    feature
+        .qualified_name
+        .strip_prefix(&*owner.qualified_name)
+        .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// What both ends do if two directions can't be connected.
+fn conflicting(direction: Direction, other: Direction) -> Option<&'static str> {
+    match (direction, other) {
+        (Direction::Out, Direction::Out) => Some("send"),
+        (Direction::In, Direction::In) => Some("receive"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::diagnostics_for;
+
+    const PORTS: &str = "item def Fuel; item def Diesel :> Fuel; item def Water; \
+         port def FuelPort { out item fuel : Fuel; } \
+         port def TankPort { port inner : FuelPort; } \
+         part def Tank { port fuelOut : FuelPort; port tankOut : TankPort; } \
+         part def Engine { port fuelIn : ~FuelPort; port tankIn : ~TankPort; }";
+
+    fn endpoint_messages(source: &str) -> Vec<String> {
+        diagnostics_for(source, codes::INCOMPATIBLE_ENDPOINTS)
+            .into_iter()
+            .map(|d| d.message.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_compatible_endpoints() {
+        let messages = endpoint_messages(&format!(
+            "package P {{ {PORTS} \
+             part def Car {{ port fuelIn : ~FuelPort; part engine : Engine; \
+             connect fuelIn to engine.fuelIn; }} \
+             part car {{ part tank : Tank; part engine : Engine; \
+             connect tank.fuelOut to engine.fuelIn; \
+             interface tank.tankOut to engine.tankIn; \
+             flow of Diesel from tank.fuelOut.fuel to engine.fuelIn.fuel; \
+             connection c connect a ::> tank.fuelOut to b ::> engine.fuelIn; }} }}"
+        ));
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+
+    #[test]
+    fn test_ports_sending_the_same_feature() {
+        let messages = endpoint_messages(&format!(
+            "package P {{ {PORTS} \
+             part car {{ part tank : Tank; part spare : Tank; part engine : Engine; \
+             connect tank.fuelOut to spare.fuelOut; \
+             interface engine.tankIn.inner to engine.fuelIn; \
+             connect tank.tankOut to spare.tankOut; }} }}"
+        ));
+        assert_eq!(
+            messages,
+            [
+                "incompatible endpoints: 'tank.fuelOut' and 'spare.fuelOut' both send 'fuel'",
+                "incompatible endpoints: 'engine.tankIn.inner' and 'engine.fuelIn' both receive 'fuel'",
+                "incompatible endpoints: 'tank.tankOut' and 'spare.tankOut' both send 'inner.fuel'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conjugated_connection_ends() {
+        let messages = endpoint_messages(&format!(
+            "package P {{ {PORTS} \
+             interface def FuelInterface {{ end supply : FuelPort; end demand : FuelPort; \
+             flow supply.fuel to demand.fuel; }} \
+             part car {{ part tank : Tank; part engine : Engine; \
+             connection c connect a ::> tank.fuelOut to b ::> engine.fuelIn {{ \
+             flow a.fuel to b.fuel; flow b.fuel to a.fuel; }} }} }}"
+        ));
+        assert_eq!(
+            messages,
+            [
+                "incompatible endpoints: flow target 'demand.fuel' can't receive",
+                "incompatible endpoints: flow source 'b.fuel' can't send",
+                "incompatible endpoints: flow target 'a.fuel' can't receive"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flow_between_directed_features() {
+        let messages = endpoint_messages(
+            "package P { part def Pump { in attribute inlet : Real; out attribute outlet : Real; } \
+             part def Station { in attribute supply : Real; part pump : Pump; \
+             flow supply to pump.inlet; flow pump.inlet to pump.outlet; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "incompatible endpoints: flow source 'pump.inlet' can't send",
+                "incompatible endpoints: flow target 'pump.outlet' can't receive"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flow_payload_not_carried() {
+        let messages = endpoint_messages(&format!(
+            "package P {{ {PORTS} \
+             part car {{ part tank : Tank; part engine : Engine; \
+             flow of Water from tank.fuelOut to engine.fuelIn; }} }}"
+        ));
+        assert_eq!(
+            messages,
+            [
+                "incompatible endpoints: flow payload 'Water' is not carried by 'tank.fuelOut'",
+                "incompatible endpoints: flow payload 'Water' is not carried by 'engine.fuelIn'"
+            ]
+        );
+    }
+}
@@ -0,0 +1,400 @@
+//! Circular dependency detection (E0007).
+//!
+//! Specialization, subsetting and redefinition (and conjugation, which is
+//! extracted as specialization) form a graph over symbols that must be
+//! acyclic. Cycles are found as strongly connected components of that graph,
+//! so a cycle spanning several files is reported in each of them.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::base::FileId;
+use crate::hir::resolve::SymbolIndex;
+use crate::hir::symbols::{HirSymbol, RefKind, TypeRefKind};
+
+/// A resolved specialization-like reference from one symbol to another.
+#[derive(Clone, Debug)]
+struct Edge {
+    source: Arc<str>,
+    target: Arc<str>,
+    kind: RefKind,
+    file: FileId,
+    start_line: u32,
+    start_col: u32,
+    end_line: u32,
+    end_col: u32,
+}
+
+impl Edge {
+    fn verb(&self) -> &'static str {
+        match self.kind {
+            RefKind::Subsets => "subsets",
+            RefKind::Redefines => "redefines",
+            _ => "specializes",
+        }
+    }
+}
+
+/// The outgoing edges of `symbol` that take part in cycle detection.
+fn edges_of(index: &SymbolIndex, symbol: &HirSymbol) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for type_ref in &symbol.type_refs {
+        // Chains (`:> a.b`) name a feature path rather than a single symbol
+        let TypeRefKind::Simple(tr) = type_ref else {
+            continue;
+        };
+        if !matches!(
+            tr.kind,
+            RefKind::Specializes | RefKind::Subsets | RefKind::Redefines
+        ) {
+            continue;
+        }
+        let Some(target) = &tr.resolved_target else {
+            continue;
+        };
+        // A usage naming itself (`attribute :>> m`) is the resolver finding the
+        // feature before the inherited one it really refers to
+        if *target == symbol.qualified_name && !symbol.kind.is_definition() {
+            continue;
+        }
+        // Ends of one association may subset each other to make the link
+        // symmetric (`Links::SelfLink`)
+        if tr.kind == RefKind::Subsets && symbol.is_end && is_sibling_end(index, symbol, target) {
+            continue;
+        }
+        edges.push(Edge {
+            source: symbol.qualified_name.clone(),
+            target: target.clone(),
+            kind: tr.kind,
+            file: symbol.file,
+            start_line: tr.start_line,
+            start_col: tr.start_col,
+            end_line: tr.end_line,
+            end_col: tr.end_col,
+        });
+    }
+    edges
+}
+
+fn is_sibling_end(index: &SymbolIndex, symbol: &HirSymbol, target: &str) -> bool {
+    let owner = |qualified_name: &str| {
+        qualified_name
+            .rsplit_once("::")
+            .map(|(owner, _)| owner.to_string())
+    };
+    index.lookup_qualified(target).is_some_and(|target| {
+        target.is_end && owner(&target.qualified_name) == owner(&symbol.qualified_name)
+    })
+}
+
+/// Tarjan's strongly connected components, run iteratively so long
+/// specialization chains can't overflow the stack.
+struct CycleFinder<'a> {
+    index: &'a SymbolIndex,
+    ids: HashMap<Arc<str>, usize>,
+    edges: Vec<Vec<Edge>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    /// Components that contain a cycle, as node ids.
+    cycles: Vec<Vec<usize>>,
+}
+
+impl<'a> CycleFinder<'a> {
+    fn new(index: &'a SymbolIndex) -> Self {
+        Self {
+            index,
+            ids: HashMap::new(),
+            edges: Vec::new(),
+            lowlink: Vec::new(),
+            on_stack: Vec::new(),
+            stack: Vec::new(),
+            cycles: Vec::new(),
+        }
+    }
+
+    /// Register a newly discovered node; ids double as DFS discovery order.
+    fn discover(&mut self, qualified_name: &Arc<str>) -> usize {
+        let id = self.edges.len();
+        self.ids.insert(qualified_name.clone(), id);
+        self.edges.push(
+            self.index
+                .lookup_qualified(qualified_name)
+                .map(|symbol| edges_of(self.index, symbol))
+                .unwrap_or_default(),
+        );
+        self.lowlink.push(id);
+        self.on_stack.push(true);
+        self.stack.push(id);
+        id
+    }
+
+    fn visit(&mut self, root: &Arc<str>) {
+        if self.ids.contains_key(root) {
+            return;
+        }
+        let root = self.discover(root);
+        let mut work = vec![(root, 0usize)];
+
+        while let Some((node, next)) = work.last_mut() {
+            let node = *node;
+            if let Some(edge) = self.edges[node].get(*next) {
+                *next += 1;
+                let target = edge.target.clone();
+                match self.ids.get(&target) {
+                    None => {
+                        let id = self.discover(&target);
+                        work.push((id, 0));
+                    }
+                    Some(&id) if self.on_stack[id] => {
+                        self.lowlink[node] = self.lowlink[node].min(id);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                self.lowlink[parent] = self.lowlink[parent].min(self.lowlink[node]);
+            }
+            if self.lowlink[node] == node {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                let is_cycle = component.len() > 1
+                    || self.edges[node]
+                        .iter()
+                        .any(|e| self.ids.get(&e.target) == Some(&node));
+                if is_cycle {
+                    component.reverse();
+                    self.cycles.push(component);
+                }
+            }
+        }
+    }
+
+    /// The shortest path of edges from `start` back to itself inside `component`.
+    fn shortest_cycle(&self, start: usize, component: &HashSet<usize>) -> Vec<&Edge> {
+        let mut came_from: HashMap<usize, &Edge> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for edge in &self.edges[node] {
+                let Some(&next) = self.ids.get(&edge.target) else {
+                    continue;
+                };
+                if !component.contains(&next) {
+                    continue;
+                }
+                if next == start {
+                    let mut path = vec![edge];
+                    let mut at = node;
+                    while at != start {
+                        let edge = came_from[&at];
+                        path.push(edge);
+                        at = self.ids[&edge.source];
+                    }
+                    path.reverse();
+                    return path;
+                }
+                if let std::collections::hash_map::Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(edge);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Vec::new()
+    }
+}
+
+impl SemanticChecker<'_> {
+    /// Report every symbol that takes part in a specialization, subsetting
+    /// or redefinition cycle reachable from `roots`.
+    ///
+    /// With `file` set, only participants declared in that file are reported,
+    /// though the cycle itself may pass through other files.
+    pub(super) fn check_cycles<'s>(
+        &mut self,
+        roots: impl IntoIterator<Item = &'s HirSymbol>,
+        file: Option<FileId>,
+    ) {
+        let mut finder = CycleFinder::new(self.index);
+        for root in roots {
+            finder.visit(&root.qualified_name);
+        }
+
+        for component in &finder.cycles {
+            let members: HashSet<usize> = component.iter().copied().collect();
+            for &member in component {
+                let path = finder.shortest_cycle(member, &members);
+                let Some((first, rest)) = path.split_first() else {
+                    continue;
+                };
+                if file.is_some_and(|file| first.file != file) {
+                    continue;
+                }
+                self.circular_dependency(first, rest);
+            }
+        }
+    }
+
+    fn circular_dependency(&mut self, first: &Edge, rest: &[&Edge]) {
+        let name = |qualified_name: &Arc<str>| -> Arc<str> {
+            self.index
+                .lookup_qualified(qualified_name)
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| qualified_name.clone())
+        };
+
+        let mut message = format!(
+            "circular dependency: '{}' {} '{}'",
+            name(&first.source),
+            first.verb(),
+            name(&first.target)
+        );
+        for edge in rest {
+            message.push_str(&format!(", which {} '{}'", edge.verb(), name(&edge.target)));
+        }
+
+        let mut diag = Diagnostic::error(first.file, first.start_line, first.start_col, message)
+            .with_span(first.end_line, first.end_col)
+            .with_code(codes::CIRCULAR_DEPENDENCY);
+        for edge in rest {
+            diag = diag.with_related(RelatedInfo {
+                file: edge.file,
+                line: edge.start_line,
+                col: edge.start_col,
+                message: Arc::from(format!(
+                    "'{}' {} '{}' here",
+                    name(&edge.source),
+                    edge.verb(),
+                    name(&edge.target)
+                )),
+            });
+        }
+        self.collector.add(diag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::index_for;
+
+    fn build_index(files: &[&str]) -> SymbolIndex {
+        build_index_with_extension(files, "sysml")
+    }
+
+    fn build_index_with_extension(files: &[&str], extension: &str) -> SymbolIndex {
+        let path = format!("test.{extension}");
+        let files: Vec<_> = files
+            .iter()
+            .map(|source| (path.as_str(), *source))
+            .collect();
+        index_for(&files)
+    }
+
+    fn cycle_diagnostics(index: &SymbolIndex, file: Option<FileId>) -> Vec<Diagnostic> {
+        let mut checker = SemanticChecker::new(index);
+        match file {
+            Some(file) => checker.check_file(file).unwrap(),
+            None => checker.check_all().unwrap(),
+        }
+        checker
+            .finish()
+            .into_iter()
+            .filter(|d| d.code.as_deref() == Some(codes::CIRCULAR_DEPENDENCY))
+            .collect()
+    }
+
+    #[test]
+    fn test_specialization_cycle_reports_each_participant() {
+        let index = build_index(&[
+            "package P { part def A :> B; part def B :> C; part def C :> A; part def D :> A; }",
+        ]);
+        let diagnostics = cycle_diagnostics(&index, None);
+
+        assert_eq!(diagnostics.len(), 3);
+        let first = diagnostics
+            .iter()
+            .find(|d| d.message.starts_with("circular dependency: 'A'"))
+            .unwrap();
+        assert_eq!(
+            first.message.as_ref(),
+            "circular dependency: 'A' specializes 'B', which specializes 'C', which specializes 'A'"
+        );
+        assert_eq!(first.related.len(), 2);
+        assert_eq!(
+            first.related[0].message.as_ref(),
+            "'B' specializes 'C' here"
+        );
+        assert!(!diagnostics.iter().any(|d| d.message.contains("'D'")));
+    }
+
+    #[test]
+    fn test_self_specialization() {
+        let index = build_index(&["package P { part def A :> A; }"]);
+        let diagnostics = cycle_diagnostics(&index, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].related.is_empty());
+    }
+
+    #[test]
+    fn test_cycle_across_files() {
+        let index = build_index(&[
+            "package P { part def A :> Q::B; }",
+            "package Q { part def B :> P::A; }",
+        ]);
+
+        let diagnostics = cycle_diagnostics(&index, Some(FileId::new(1)));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, FileId::new(1));
+        assert_eq!(diagnostics[0].related[0].file, FileId::new(0));
+    }
+
+    #[test]
+    fn test_subsetting_and_redefinition_cycles() {
+        let index = build_index(&[
+            "package P { part def V { attribute x :> y; attribute y :> x; } \
+             part def W :> V { attribute :>> x; } }",
+        ]);
+        let diagnostics = cycle_diagnostics(&index, None);
+        // The shorthand redefinition resolving to itself is not a cycle
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_conjugation_cycle() {
+        let index = build_index(&["package P { port def A conjugates B; port def B ~ A; }"]);
+        let diagnostics = cycle_diagnostics(&index, None);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().any(|d| d.message.as_ref()
+            == "circular dependency: 'A' specializes 'B', which specializes 'A'"));
+    }
+
+    #[test]
+    fn test_acyclic_hierarchy() {
+        let index = build_index(&[
+            "package P { part def A; part def B :> A; part def C :> A, B; \
+             part c : C { part :>> c; } }",
+        ]);
+        assert!(cycle_diagnostics(&index, None).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations_and_symmetric_ends_are_not_cycles() {
+        let index = build_index_with_extension(
+            &["package P { \
+               classifier A unions B, C; classifier B :> A; classifier C :> A; \
+               assoc L { end feature x subsets y; end feature y subsets x; } }"],
+            "kerml",
+        );
+        assert!(cycle_diagnostics(&index, None).is_empty());
+    }
+}
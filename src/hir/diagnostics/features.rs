@@ -0,0 +1,564 @@
+//! Redefinition and subsetting validity (E0009, E0010).
+//!
+//! A redefined feature must be inherited by the redefining feature's owner,
+//! i.e. owned (possibly through nested features) by one of the owner's
+//! generalizations. The redefining feature may narrow the redefined feature's
+//! type, but not widen it (multiplicities are checked in [`super::multiplicity`]).
+//!
+//! A subsetted feature must be a feature accessible from the subsetting one.
+//! A qualified name may only reach features owned by a package, or by the
+//! subsetting feature's owner, an enclosing element or one of their
+//! generalizations. Explicit types must be of compatible kinds: subsetting
+//! doesn't require conformance (`item pwrCmd : PwrCmd subsets driverCmd`), but
+//! a feature can't subset one whose values it can never have.
+//!
+//! Generalizations are followed transitively through typing, specialization,
+//! subsetting and redefinition, including the implicit library supertypes.
+//! Symbols from KerML files and references that didn't resolve are skipped.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use super::kinds::are_compatible_kinds;
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::hir::resolve::{ResolveResult, Resolver};
+use crate::hir::symbols::{HirSymbol, RefKind, SymbolKind, TypeRef};
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_features(&mut self, symbol: &HirSymbol) {
+        if !symbol.kind.is_usage() || self.index.is_kerml_file(symbol.file) {
+            return;
+        }
+        for tr in Self::own_type_refs(symbol) {
+            match tr.kind {
+                RefKind::Redefines => self.check_redefinition(symbol, tr),
+                // `:>` on a usage is subsetting
+                RefKind::Subsets | RefKind::Specializes => self.check_subsetting(symbol, tr),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_redefinition(&mut self, symbol: &HirSymbol, tr: &TypeRef) {
+        let index = self.index;
+        let Some(owner) = index.lookup_qualified(&Self::extract_scope(&symbol.qualified_name))
+        else {
+            return;
+        };
+        if owner.kind == SymbolKind::Package || is_anonymous(&owner.qualified_name) {
+            return;
+        }
+        let generals = self.all_generals(owner);
+        // An owner without generalizations only has implicit ones (timeslices,
+        // variants), whose features aren't known here
+        let check_inherited = !generals.is_empty();
+        // Features of enclosing elements may be redefined by name too
+        // (`item be :> edges { attribute :>> semiMajorAxis = ...; }`)
+        let mut candidates = generals;
+        candidates.extend(self.feature_contexts(owner));
+
+        let Some(redefined) = self.referenced_feature(symbol, tr, &candidates) else {
+            return;
+        };
+        if is_anonymous(&redefined.qualified_name) {
+            return;
+        }
+
+        // Package-level features may be redefined anywhere, and features
+        // nested in inherited ones through a qualified name
+        // (`:>> Shape::faces::edges`)
+        if check_inherited
+            && !self.is_package_level(redefined)
+            && !is_nested_in_any(redefined, &candidates)
+        {
+            let message = format!(
+                "invalid redefinition: '{}' is not inherited by '{}' (it is not a feature of any of its supertypes)",
+                redefined.qualified_name, owner.name
+            );
+            self.report(symbol, tr, message, codes::INVALID_REDEFINITION, redefined);
+            return;
+        }
+
+        if let Some(message) = self.nonconforming_types(symbol, redefined) {
+            let message = format!(
+                "invalid redefinition: {}, the type of redefined feature '{}'",
+                message, redefined.qualified_name
+            );
+            self.report(symbol, tr, message, codes::INVALID_REDEFINITION, redefined);
+        }
+
+        self.check_redefined_multiplicity(symbol, tr, redefined);
+    }
+
+    fn check_subsetting(&mut self, symbol: &HirSymbol, tr: &TypeRef) {
+        let contexts = self.feature_contexts(symbol);
+        let Some(subsetted) = self.referenced_feature(symbol, tr, &contexts) else {
+            return;
+        };
+        if subsetted.kind == SymbolKind::Alias || is_anonymous(&subsetted.qualified_name) {
+            return;
+        }
+
+        if !subsetted.kind.is_usage() {
+            let message = format!(
+                "invalid subsetting: '{}' is a {}, not a feature",
+                subsetted.name,
+                subsetted.kind.display().to_lowercase()
+            );
+            self.report(symbol, tr, message, codes::INVALID_SUBSETTING, subsetted);
+            return;
+        }
+
+        // An unqualified name only finds features in scope (enclosing,
+        // inherited or imported); a qualified one can reach into any type
+        if tr.target.contains("::")
+            && !self.is_package_level(subsetted)
+            && !is_anonymous(&symbol.qualified_name)
+            && !is_member_of_any(subsetted, &contexts)
+        {
+            let message = format!(
+                "invalid subsetting: '{}' is not accessible from '{}' (it is not a feature of an enclosing element or of their supertypes)",
+                subsetted.qualified_name, symbol.name
+            );
+            self.report(symbol, tr, message, codes::INVALID_SUBSETTING, subsetted);
+            return;
+        }
+
+        if let Some(message) = self.incompatible_types(symbol, subsetted) {
+            let message = format!(
+                "invalid subsetting: {}, the type of subsetted feature '{}'",
+                message, subsetted.qualified_name
+            );
+            self.report(symbol, tr, message, codes::INVALID_SUBSETTING, subsetted);
+        }
+        self.check_subsetted_multiplicity(symbol, tr, subsetted);
+    }
+
+    /// The feature `tr` refers to, looked up in `candidates` (nearest first).
+    ///
+    /// A shorthand redefinition (`:>> mass`) may resolve to the referencing
+    /// feature itself, and an unqualified name to a same-named feature found
+    /// outside `candidates`; the name is then looked up among the members of
+    /// `candidates`, falling back to the resolved target.
+    fn referenced_feature(
+        &self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+        candidates: &[&'a HirSymbol],
+    ) -> Option<&'a HirSymbol> {
+        let index = self.index;
+        let resolved = index
+            .lookup_qualified(tr.resolved_target.as_deref()?)
+            .filter(|target| target.qualified_name != symbol.qualified_name);
+        if tr.target.contains("::") || resolved.is_some_and(|r| is_member_of_any(r, candidates)) {
+            return resolved;
+        }
+
+        let by_name = candidates.iter().find_map(|candidate| {
+            let member = index
+                .visibility_for_scope(&candidate.qualified_name)?
+                .lookup_direct(&tr.target)?;
+            index
+                .lookup_qualified(member)
+                .filter(|member| member.qualified_name != symbol.qualified_name)
+        });
+        by_name.or(resolved).or_else(|| {
+            // A shorthand redefinition of a feature visible from the owner's
+            // scope, e.g. a package-level one
+            let owner = Self::extract_scope(&symbol.qualified_name);
+            let resolver = Resolver::new(index).with_scope(Self::extract_scope(&owner));
+            match resolver.resolve(&tr.target) {
+                ResolveResult::Found(found) if found.qualified_name != symbol.qualified_name => {
+                    index.lookup_qualified(&found.qualified_name)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Whether `feature` is owned by a package rather than a type.
+    fn is_package_level(&self, feature: &HirSymbol) -> bool {
+        self.index
+            .lookup_qualified(&Self::extract_scope(&feature.qualified_name))
+            .is_none_or(|owner| owner.kind == SymbolKind::Package)
+    }
+
+    /// The elements enclosing `symbol` up to the nearest package, and their
+    /// generalizations, nearest first.
+    fn feature_contexts(&mut self, symbol: &HirSymbol) -> Vec<&'a HirSymbol> {
+        let index = self.index;
+        let mut contexts = Vec::new();
+        let mut scope = Self::extract_scope(&symbol.qualified_name);
+        while let Some(enclosing) = index.lookup_qualified(&scope) {
+            if enclosing.kind == SymbolKind::Package {
+                break;
+            }
+            contexts.push(enclosing);
+            contexts.extend(self.all_generals(enclosing));
+            scope = Self::extract_scope(&scope);
+        }
+        contexts
+    }
+
+    /// Transitive generalizations of `symbol`, nearest first, excluding
+    /// `symbol` itself.
+    ///
+    /// A symbol whose generalizations are requested while they're being
+    /// computed (through a shorthand redefinition) gets none. Results are
+    /// cached unless such a cycle cut them short, in which case they're
+    /// recomputed when requested again.
+    pub(super) fn all_generals(&mut self, symbol: &HirSymbol) -> Vec<&'a HirSymbol> {
+        if let Some(generals) = self.generals.get(&symbol.qualified_name) {
+            return generals.clone();
+        }
+        if !self
+            .generals_in_progress
+            .insert(symbol.qualified_name.clone())
+        {
+            self.generals_incomplete = true;
+            return Vec::new();
+        }
+        let incomplete = std::mem::take(&mut self.generals_incomplete);
+
+        let mut seen = HashSet::from([symbol.qualified_name.clone()]);
+        let mut generals = Vec::new();
+        let mut queue = VecDeque::from(self.direct_generals(symbol));
+        while let Some(general) = queue.pop_front() {
+            if !seen.insert(general.qualified_name.clone()) {
+                continue;
+            }
+            generals.push(general);
+            queue.extend(self.direct_generals(general));
+        }
+
+        self.generals_in_progress.remove(&symbol.qualified_name);
+        if !self.generals_incomplete {
+            self.generals
+                .insert(symbol.qualified_name.clone(), generals.clone());
+        }
+        // A cycle only cuts short the symbols computed while it was open
+        self.generals_incomplete =
+            incomplete || (self.generals_incomplete && !self.generals_in_progress.is_empty());
+        generals
+    }
+
+    /// The types, specialized, subsetted and redefined features of `symbol`.
+    fn direct_generals(&mut self, symbol: &HirSymbol) -> Vec<&'a HirSymbol> {
+        let index = self.index;
+        let mut generals = Vec::new();
+        let mut written = HashSet::new();
+
+        for tr in Self::own_type_refs(symbol) {
+            if !matches!(
+                tr.kind,
+                RefKind::TypedBy | RefKind::Specializes | RefKind::Subsets | RefKind::Redefines
+            ) {
+                continue;
+            }
+            written.insert(tr.target.clone());
+            let Some(target) = tr.resolved_target.as_deref() else {
+                continue;
+            };
+            if target != &*symbol.qualified_name {
+                generals.extend(index.lookup_qualified(target));
+            } else if tr.kind == RefKind::Redefines {
+                // Shorthand redefinition resolved to itself
+                let owner = index.lookup_qualified(&Self::extract_scope(&symbol.qualified_name));
+                if let Some(owner) = owner.filter(|owner| owner.kind != SymbolKind::Package) {
+                    let mut candidates = self.all_generals(owner);
+                    candidates.extend(self.feature_contexts(owner));
+                    generals.extend(self.referenced_feature(symbol, tr, &candidates));
+                }
+            }
+        }
+
+        // Implicit supertypes (`Parts::Part`) have no reference of their own,
+        // and semantic metadata (`#systemdd part p;`) implies its base type
+        let resolver = Resolver::new(index).with_scope(Self::extract_scope(&symbol.qualified_name));
+        let base_types = symbol
+            .metadata_annotations
+            .iter()
+            .filter_map(|annotation| index.get_metadata_basetype(annotation));
+        for supertype in symbol.supertypes.iter().cloned().chain(base_types) {
+            if written.contains(&supertype) {
+                continue;
+            }
+            if let ResolveResult::Found(general) = resolver.resolve(&supertype) {
+                if general.qualified_name != symbol.qualified_name {
+                    generals.extend(index.lookup_qualified(&general.qualified_name));
+                }
+            }
+        }
+        generals
+    }
+
+    /// Describe how `feature`'s explicit types fail to conform to `general`'s.
+    ///
+    /// Each of `general`'s types must be specialized (or equalled) by one of
+    /// `feature`'s. Returns `None` if they conform, or if either side has no
+    /// explicit types or an unresolved one.
+    fn nonconforming_types(&mut self, feature: &HirSymbol, general: &HirSymbol) -> Option<String> {
+        let types = self.explicit_types(feature)?;
+        let general_types = self.explicit_types(general)?;
+
+        let mut conforming = HashSet::new();
+        for ty in &types {
+            conforming.insert(ty.qualified_name.clone());
+            conforming.extend(
+                self.all_generals(ty)
+                    .into_iter()
+                    .map(|g| g.qualified_name.clone()),
+            );
+        }
+        let missing: Vec<_> = general_types
+            .iter()
+            .filter(|general_type| !conforming.contains(&general_type.qualified_name))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "'{}' is typed by {}, which does not conform to {}",
+            feature.name,
+            quoted_names(&types),
+            quoted_names(&missing)
+        ))
+    }
+
+    /// Describe a type of `feature` that is incompatible with one of
+    /// `general`'s, i.e. whose kinds are disjoint (see
+    /// [`are_compatible_kinds`]).
+    fn incompatible_types(&self, feature: &HirSymbol, general: &HirSymbol) -> Option<String> {
+        let types = self.explicit_types(feature)?;
+        let general_types = self.explicit_types(general)?;
+        types.iter().find_map(|ty| {
+            let general_type = general_types
+                .iter()
+                .find(|general_type| !are_compatible_kinds(ty.kind, general_type.kind))?;
+            Some(format!(
+                "'{}' is typed by {} '{}', which is incompatible with {} '{}'",
+                feature.name,
+                ty.kind.display().to_lowercase(),
+                ty.name,
+                general_type.kind.display().to_lowercase(),
+                general_type.name
+            ))
+        })
+    }
+
+    /// The resolved types `symbol` is explicitly typed by, or `None` if it has
+    /// none, one didn't resolve or one can't be compared.
+    ///
+    /// KerML types are skipped since their implicit supertypes are unknown, and
+    /// so are metadata definitions and metaclasses: the reflective library
+    /// model types features by metaclasses that don't conform to the features
+    /// they subset.
+    pub(super) fn explicit_types(&self, symbol: &HirSymbol) -> Option<Vec<&'a HirSymbol>> {
+        let index = self.index;
+        let types = Self::own_type_refs(symbol)
+            .filter(|tr| tr.kind == RefKind::TypedBy)
+            .map(|tr| index.lookup_qualified(tr.resolved_target.as_deref()?))
+            .map(|ty| {
+                ty.filter(|ty| {
+                    !index.is_kerml_file(ty.file)
+                        && ty.kind.is_definition()
+                        && ty.kind != SymbolKind::MetadataDefinition
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (!types.is_empty()).then_some(types)
+    }
+
+    pub(super) fn report(
+        &mut self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+        message: String,
+        code: &str,
+        target: &HirSymbol,
+    ) {
+        self.collector.add(
+            Diagnostic::error(symbol.file, tr.start_line, tr.start_col, message)
+                .with_span(tr.end_line, tr.end_col)
+                .with_code(code)
+                .with_related(RelatedInfo {
+                    file: target.file,
+                    line: target.start_line,
+                    col: target.start_col,
+                    message: Arc::from(format!("'{}' is declared here", target.name)),
+                }),
+        );
+    }
+}
+
+/// Whether `qualified_name` goes through an anonymous element (`<anon#1@L29>`),
+/// whose members can't be looked up reliably.
+fn is_anonymous(qualified_name: &str) -> bool {
+    qualified_name.contains('<') && qualified_name.contains('#')
+}
+
+/// Whether `feature` is directly owned by one of `owners`.
+fn is_member_of_any(feature: &HirSymbol, owners: &[&HirSymbol]) -> bool {
+    let owner = SemanticChecker::extract_scope(&feature.qualified_name);
+    owners
+        .iter()
+        .any(|candidate| *candidate.qualified_name == *owner)
+}
+
+/// Whether `feature` is owned by one of `owners`, directly or through other
+/// features.
+fn is_nested_in_any(feature: &HirSymbol, owners: &[&HirSymbol]) -> bool {
+    owners.iter().any(|owner| {
+        feature
+            .qualified_name
+            .strip_prefix(&*owner.qualified_name)
+            .is_some_and(|rest| rest.starts_with("::"))
+    })
+}
+
+fn quoted_names(symbols: &[&HirSymbol]) -> String {
+    symbols
+        .iter()
+        .map(|symbol| format!("'{}'", symbol.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::index_for;
+
+    fn feature_messages(source: &str) -> Vec<String> {
+        let index = index_for(&[("test.sysml", source)]);
+        let mut checker = SemanticChecker::new(&index);
+        checker.check_all().unwrap();
+        checker
+            .finish()
+            .into_iter()
+            .filter(|d| {
+                matches!(
+                    d.code.as_deref(),
+                    Some(codes::INVALID_REDEFINITION | codes::INVALID_SUBSETTING)
+                )
+            })
+            .map(|d| d.message.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_redefinitions() {
+        let messages = feature_messages(
+            "package P { part def Engine; part def V8 :> Engine; \
+             part def Vehicle { part engine : Engine[1..2]; attribute speed; } \
+             part def Car :> Vehicle { part engine : V8[1] redefines engine; attribute :>> speed; } }",
+        );
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+
+    #[test]
+    fn test_redefined_feature_not_inherited() {
+        let messages = feature_messages(
+            "package P { part def Other { attribute speed; } part def Vehicle; \
+             part def Car :> Vehicle { attribute speed redefines Other::speed; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid redefinition: 'P::Other::speed' is not inherited by 'Car' \
+              (it is not a feature of any of its supertypes)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redefinition_with_nonconforming_type() {
+        let messages = feature_messages(
+            "package P { part def Engine; part def Wheel; \
+             part def Vehicle { part engine : Engine; } \
+             part def Car :> Vehicle { part engine : Wheel redefines engine; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid redefinition: 'engine' is typed by 'Wheel', which does not conform to \
+              'Engine', the type of redefined feature 'P::Vehicle::engine'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subsetting_a_definition() {
+        let messages = feature_messages(
+            "package P { part def Wheel; part def Car { part wheels : Wheel; part front :> Wheel; } }",
+        );
+        assert_eq!(
+            messages,
+            ["invalid subsetting: 'Wheel' is a part def, not a feature"]
+        );
+    }
+
+    #[test]
+    fn test_subsetting_inaccessible_feature() {
+        let messages = feature_messages(
+            "package P { part def Wheel; part def Bike { part wheels : Wheel; } \
+             part def Car { part front : Wheel :> Bike::wheels; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid subsetting: 'P::Bike::wheels' is not accessible from 'front' \
+              (it is not a feature of an enclosing element or of their supertypes)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subsetting_with_incompatible_type() {
+        let messages = feature_messages(
+            "package P { part def Wheel; part def Seat; port def Socket; \
+             part def Car { part wheels : Wheel[4]; port front : Socket :> wheels; \
+             part rear : Seat :> wheels; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid subsetting: 'front' is typed by port def 'Socket', which is incompatible \
+                 with part def 'Wheel', the type of subsetted feature 'P::Car::wheels'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generals_cut_short_by_a_cycle_are_not_cached() {
+        // `Car` needs `X`'s generalizations, which need `Car`'s (for `speed`)
+        let source = "package P { part def Vehicle { attribute speed; } part def Base { attribute f; } \
+             part def X :> Base, Car::speed { attribute :>> f; } \
+             part def Car :> Vehicle, X::f { attribute :>> speed; } }";
+        let index = index_for(&[("test.sysml", source)]);
+
+        let names = |generals: Vec<&HirSymbol>| -> Vec<String> {
+            generals
+                .iter()
+                .map(|g| g.qualified_name.to_string())
+                .collect()
+        };
+        let symbols: Vec<_> = index.all_symbols().collect();
+        for first in &symbols {
+            let mut checker = SemanticChecker::new(&index);
+            checker.all_generals(first);
+            for symbol in &symbols {
+                let fresh = SemanticChecker::new(&index).all_generals(symbol);
+                assert_eq!(
+                    names(checker.all_generals(symbol)),
+                    names(fresh),
+                    "{} after {}",
+                    symbol.qualified_name,
+                    first.qualified_name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! Machine-applicable fixes for diagnostics.
+//!
+//! A [`Fix`] is a labelled set of [`TextEdit`]s a client can apply as is:
+//!
+//! - undefined references (E0001) suggest similarly named elements visible
+//!   from the reference ("did you mean `Engine`?", only for checkers given a
+//!   range with [`SemanticChecker::with_suggestions`]) and imports of the
+//!   packages that declare the name ("add `import ISQ::*;`");
+//! - duplicate definitions (E0004) can be removed;
+//! - usages of abstract definitions (W0005) can be made abstract themselves.
+//!
+//! Fixes that only rewrite a reference are built from the index. The others
+//! insert or remove declarations and need the file's syntax tree, so they're
+//! only offered by checkers given one with [`SemanticChecker::with_syntax`].
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use super::SemanticChecker;
+use crate::base::{FileId, LineCol, LineIndex, TextEdit, TextSize};
+use crate::hir::resolve::{ResolveResult, Resolver};
+use crate::hir::symbols::{HirSymbol, SymbolKind, TypeRef};
+use crate::parser::{SyntaxKind, SyntaxNode, declaration_removal_range};
+use crate::syntax::SyntaxFile;
+
+/// Most "did you mean" suggestions offered for one reference.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Gives the checker a file's syntax tree, if it's available.
+pub(super) type SyntaxLookup<'a> = Box<dyn Fn(FileId) -> Option<Arc<SyntaxFile>> + 'a>;
+
+/// A labelled set of edits that fixes a diagnostic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    /// What the fix does (e.g., "add `import ISQ::*;`").
+    pub label: Arc<str>,
+    /// The edits to apply, all at once.
+    pub edits: Vec<TextEdit>,
+}
+
+impl Fix {
+    /// A fix made of a single edit.
+    pub fn new(label: impl Into<Arc<str>>, edit: TextEdit) -> Self {
+        Self {
+            label: label.into(),
+            edits: vec![edit],
+        }
+    }
+}
+
+/// The text of a file with its line index, shared by the fixes built for it.
+pub(super) struct SourceText {
+    text: String,
+    line_index: LineIndex,
+}
+
+/// The declaration of a symbol in its file's syntax tree.
+struct Declaration {
+    file: FileId,
+    node: SyntaxNode,
+    source: Arc<SourceText>,
+}
+
+impl Declaration {
+    fn position(&self, offset: TextSize) -> (u32, u32) {
+        let LineCol { line, col } = self.source.line_index.line_col(offset);
+        (line, col)
+    }
+
+    /// The whitespace before `offset` on its line, or `None` if there's
+    /// other text before it.
+    fn indent(&self, offset: TextSize) -> Option<&str> {
+        let text = &self.source.text;
+        let offset = usize::from(offset);
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &text[line_start..offset];
+        indent.chars().all(char::is_whitespace).then_some(indent)
+    }
+
+    /// `text` inserted before `anchor`, on a line of its own if `anchor`
+    /// starts one.
+    fn insert_before(&self, anchor: &SyntaxNode, text: &str) -> TextEdit {
+        let offset = anchor.text_range().start();
+        let new_text = match self.indent(offset) {
+            Some(indent) => format!("{}\n{}", text, indent),
+            None => format!("{} ", text),
+        };
+        TextEdit::insert(self.file, self.position(offset), new_text)
+    }
+}
+
+impl<'a> SemanticChecker<'a> {
+    /// Read syntax trees through `syntax` to offer fixes that edit declarations.
+    pub fn with_syntax(mut self, syntax: impl Fn(FileId) -> Option<Arc<SyntaxFile>> + 'a) -> Self {
+        self.syntax = Some(Box::new(syntax));
+        self
+    }
+
+    /// Offer "did you mean" fixes for the undefined references overlapping
+    /// `range` (start_line, start_col, end_line, end_col).
+    ///
+    /// Finding similar names compares the reference with every name in the
+    /// index, so it's left to code action requests rather than done for every
+    /// diagnostics run.
+    pub fn with_suggestions(mut self, range: (u32, u32, u32, u32)) -> Self {
+        self.suggestions = Some(range);
+        self
+    }
+
+    /// "Did you mean" and import fixes for an unresolved simple reference.
+    pub(super) fn undefined_reference_fixes(
+        &mut self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+    ) -> Vec<Fix> {
+        let name = &*tr.target;
+        if name.contains("::") {
+            return Vec::new();
+        }
+        let suggest = self
+            .suggestions
+            .is_some_and(|(start_line, start_col, end_line, end_col)| {
+                (tr.start_line, tr.start_col) <= (end_line, end_col)
+                    && (tr.end_line, tr.end_col) >= (start_line, start_col)
+            });
+        let suggestions = if suggest {
+            let scope = Self::extract_scope(&symbol.qualified_name);
+            self.similar_names(name, &Resolver::new(self.index).with_scope(scope))
+        } else {
+            Vec::new()
+        };
+
+        let mut fixes: Vec<Fix> = suggestions
+            .into_iter()
+            .map(|suggestion| {
+                Fix::new(
+                    format!("did you mean `{}`?", suggestion),
+                    TextEdit::replace(
+                        symbol.file,
+                        (tr.start_line, tr.start_col),
+                        (tr.end_line, tr.end_col),
+                        suggestion,
+                    ),
+                )
+            })
+            .collect();
+
+        let packages: BTreeSet<String> = self
+            .index
+            .lookup_simple(name)
+            .into_iter()
+            .filter(|candidate| {
+                &*candidate.name == name
+                    && (candidate.kind.is_definition() || candidate.kind.is_usage())
+            })
+            .map(|candidate| Self::extract_scope(&candidate.qualified_name))
+            .filter(|owner| {
+                self.index
+                    .lookup_qualified(owner)
+                    .is_some_and(|owner| owner.kind == SymbolKind::Package)
+            })
+            .collect();
+        if packages.is_empty() {
+            return fixes;
+        }
+        let Some(declaration) = self.declaration(symbol) else {
+            return fixes;
+        };
+        // First member of the innermost package, so the import is visible
+        // to the reference; the element itself at the root
+        let anchor = declaration
+            .node
+            .ancestors()
+            .skip(1)
+            .find(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::PACKAGE | SyntaxKind::LIBRARY_PACKAGE
+                )
+            })
+            .and_then(|package| {
+                package
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::NAMESPACE_BODY)
+            })
+            .and_then(|body| body.children().next())
+            .unwrap_or_else(|| {
+                declaration
+                    .node
+                    .ancestors()
+                    .take_while(|node| node.parent().is_some())
+                    .last()
+                    .unwrap_or_else(|| declaration.node.clone())
+            });
+        for package in packages {
+            let import = format!("import {}::*;", package);
+            fixes.push(Fix::new(
+                format!("add `{}`", import),
+                declaration.insert_before(&anchor, &import),
+            ));
+        }
+        fixes
+    }
+
+    /// Delete `symbol`'s declaration, with its visibility and its line if
+    /// nothing else is on it.
+    pub(super) fn remove_declaration_fix(&mut self, symbol: &HirSymbol) -> Option<Fix> {
+        let declaration = self.declaration(symbol)?;
+        let range = declaration_removal_range(&declaration.node, &declaration.source.text);
+
+        Some(Fix::new(
+            format!("remove duplicate definition of '{}'", symbol.name),
+            TextEdit::delete(
+                symbol.file,
+                declaration.position(range.start()),
+                declaration.position(range.end()),
+            ),
+        ))
+    }
+
+    /// Declare `symbol` abstract.
+    pub(super) fn make_abstract_fix(&mut self, symbol: &HirSymbol) -> Option<Fix> {
+        let declaration = self.declaration(symbol)?;
+        // `abstract` follows the direction and `derived`
+        let offset = declaration
+            .node
+            .children_with_tokens()
+            .find(|element| {
+                !element.kind().is_trivia()
+                    && !matches!(
+                        element.kind(),
+                        SyntaxKind::IN_KW
+                            | SyntaxKind::OUT_KW
+                            | SyntaxKind::INOUT_KW
+                            | SyntaxKind::DERIVED_KW
+                    )
+            })?
+            .text_range()
+            .start();
+        Some(Fix::new(
+            format!("make '{}' abstract", symbol.name),
+            TextEdit::insert(symbol.file, declaration.position(offset), "abstract "),
+        ))
+    }
+
+    /// Names within edit distance of `name` that resolve from the resolver's
+    /// scope, closest first.
+    fn similar_names(&self, name: &str, resolver: &Resolver<'_>) -> Vec<Arc<str>> {
+        let max_distance = (name.chars().count() / 3).max(1);
+        let mut candidates: Vec<(usize, Arc<str>)> = self
+            .index
+            .simple_names()
+            .filter(|candidate| {
+                &***candidate != name
+                    && candidate.len().abs_diff(name.len()) <= max_distance
+                    && !candidate.starts_with('<')
+            })
+            .filter_map(|candidate| {
+                let distance = edit_distance(name, candidate);
+                (distance <= max_distance).then(|| (distance, candidate.clone()))
+            })
+            .collect();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .filter(|(_, candidate)| matches!(resolver.resolve(candidate), ResolveResult::Found(_)))
+            .map(|(_, candidate)| candidate)
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Find `symbol`'s declaration in its file's syntax tree.
+    fn declaration(&mut self, symbol: &HirSymbol) -> Option<Declaration> {
+        let syntax = self.syntax.as_ref()?(symbol.file)?;
+        let source = self
+            .sources
+            .entry(symbol.file)
+            .or_insert_with(|| {
+                let text = syntax.source_text();
+                let line_index = LineIndex::new(&text);
+                Arc::new(SourceText { text, line_index })
+            })
+            .clone();
+        let node = syntax.member_at(&source.line_index, symbol.start_line, symbol.start_col)?;
+        Some(Declaration {
+            file: symbol.file,
+            node,
+            source,
+        })
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::hir::diagnostics::{Diagnostic, codes, index_for};
+    use crate::syntax::parser::parse_content;
+
+    /// Check `sources[0]` with the others loaded, returning its diagnostics with `code`.
+    fn diagnostics(sources: &[&str], code: &str) -> Vec<Diagnostic> {
+        diagnostics_with_suggestions(sources, code, Some((0, 0, u32::MAX, u32::MAX)))
+    }
+
+    fn diagnostics_with_suggestions(
+        sources: &[&str],
+        code: &str,
+        suggestions: Option<(u32, u32, u32, u32)>,
+    ) -> Vec<Diagnostic> {
+        let files: Vec<_> = sources
+            .iter()
+            .map(|source| ("test.sysml", *source))
+            .collect();
+        let index = index_for(&files);
+        let files: Vec<_> = sources
+            .iter()
+            .map(|source| Arc::new(parse_content(source, Path::new("test.sysml")).unwrap()))
+            .collect();
+
+        let mut checker =
+            SemanticChecker::new(&index).with_syntax(|file| files.get(file.0 as usize).cloned());
+        if let Some(range) = suggestions {
+            checker = checker.with_suggestions(range);
+        }
+        checker.check_file(FileId::new(0)).unwrap();
+        checker
+            .finish()
+            .into_iter()
+            .filter(|d| d.code.as_deref() == Some(code))
+            .collect()
+    }
+
+    /// `source` with the edits of `fix` applied.
+    fn apply(source: &str, fix: &Fix) -> String {
+        let line_index = LineIndex::new(source);
+        let mut text = source.to_string();
+        let mut edits = fix.edits.clone();
+        edits.sort_by_key(|edit| std::cmp::Reverse((edit.start_line, edit.start_col)));
+        for edit in edits {
+            let start = line_index.offset(LineCol::new(edit.start_line, edit.start_col));
+            let end = line_index.offset(LineCol::new(edit.end_line, edit.end_col));
+            text.replace_range(
+                usize::from(start.unwrap())..usize::from(end.unwrap()),
+                &edit.new_text,
+            );
+        }
+        text
+    }
+
+    fn labels(diagnostic: &Diagnostic) -> Vec<&str> {
+        diagnostic.fixes.iter().map(|fix| &*fix.label).collect()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("Engine", "Engine"), 0);
+        assert_eq!(edit_distance("Engin", "Engine"), 1);
+        assert_eq!(edit_distance("Egnine", "Engine"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let source = "package P { part def Engine; part def Wheel; part car { part e : Engin; } }";
+        let diagnostics = diagnostics(&[source], codes::UNDEFINED_REFERENCE);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(labels(&diagnostics[0]), ["did you mean `Engine`?"]);
+        assert_eq!(
+            apply(source, &diagnostics[0].fixes[0]),
+            "package P { part def Engine; part def Wheel; part car { part e : Engine; } }"
+        );
+    }
+
+    #[test]
+    fn test_did_you_mean_only_in_suggestion_range() {
+        let source =
+            "package P {\n    part def Engine;\n    part a : Engin;\n    part b : Engin;\n}";
+        let diagnostics = diagnostics_with_suggestions(&[source], codes::UNDEFINED_REFERENCE, None);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.fixes.is_empty()));
+
+        let diagnostics = diagnostics_with_suggestions(
+            &[source],
+            codes::UNDEFINED_REFERENCE,
+            Some((3, 0, 3, 20)),
+        );
+        assert!(diagnostics[0].fixes.is_empty());
+        assert_eq!(labels(&diagnostics[1]), ["did you mean `Engine`?"]);
+    }
+
+    #[test]
+    fn test_add_import() {
+        let source =
+            "package Vehicles {\n    part def Car {\n        part wheels : Wheel;\n    }\n}";
+        let library = "package Chassis { part def Wheel; }";
+        let diagnostics = diagnostics(&[source, library], codes::UNDEFINED_REFERENCE);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(labels(&diagnostics[0]), ["add `import Chassis::*;`"]);
+        assert_eq!(
+            apply(source, &diagnostics[0].fixes[0]),
+            "package Vehicles {\n    import Chassis::*;\n    part def Car {\n        part wheels : Wheel;\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_remove_duplicate_definition() {
+        let source = "package P {\n    part def Engine;\n    private part def Engine { }\n    part def Wheel;\n}";
+        let diagnostics = diagnostics(&[source], codes::DUPLICATE_DEFINITION);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            labels(&diagnostics[0]),
+            ["remove duplicate definition of 'Engine'"]
+        );
+        assert_eq!(
+            apply(source, &diagnostics[0].fixes[0]),
+            "package P {\n    part def Engine;\n    part def Wheel;\n}"
+        );
+    }
+
+    #[test]
+    fn test_make_abstract() {
+        let source = "package P { abstract part def Engine; part engine : Engine; }";
+        let diagnostics = diagnostics(&[source], codes::ABSTRACT_TYPED_USAGE);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(labels(&diagnostics[0]), ["make 'engine' abstract"]);
+        assert_eq!(
+            apply(source, &diagnostics[0].fixes[0]),
+            "package P { abstract part def Engine; abstract part engine : Engine; }"
+        );
+    }
+
+    #[test]
+    fn test_no_syntax_fixes_without_syntax() {
+        let source = "package P { part def Engine; part def Engine; }";
+        let index = index_for(&[("test.sysml", source)]);
+        let mut checker = SemanticChecker::new(&index);
+        checker.check_file(FileId::new(0)).unwrap();
+        let diagnostics = checker.finish();
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.code.as_deref() == Some(codes::DUPLICATE_DEFINITION))
+        );
+        assert!(diagnostics.iter().all(|d| d.fixes.is_empty()));
+    }
+}
@@ -0,0 +1,313 @@
+//! Import validation (E0014).
+//!
+//! An import must name an element that resolves from the importing namespace.
+//! Beyond that:
+//!
+//! - a namespace import (`::*`) or recursive import (`::**`) must import a
+//!   namespace, not a comment, dependency or another import;
+//! - each `@Metadata` filter of a filtered import must be a metadata
+//!   definition;
+//! - a membership import (`import A::x;`) can't reach through a private
+//!   import of `A`, which `A` doesn't re-export.
+//!
+//! Every diagnostic points at the import's target.
+
+use std::sync::Arc;
+
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::hir::resolve::{ResolveResult, Resolver};
+use crate::hir::symbols::{HirSymbol, SymbolKind, TypeRef, TypeRefKind};
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_import(&mut self, symbol: &HirSymbol) {
+        if symbol.kind != SymbolKind::Import {
+            return;
+        }
+        let Some(tr) = symbol.type_refs.iter().find_map(|type_ref| match type_ref {
+            TypeRefKind::Simple(tr) => Some(tr),
+            TypeRefKind::Chain(_) => None,
+        }) else {
+            return;
+        };
+
+        self.check_import_filters(symbol);
+
+        let Some(target) = self.import_target(symbol, tr) else {
+            if !Self::is_builtin_type(&tr.target) {
+                let message = format!("invalid import: cannot resolve '{}'", tr.target);
+                self.report_import(symbol, message, None);
+            }
+            return;
+        };
+
+        let is_namespace_import = symbol.name.ends_with("::*") || symbol.name.ends_with("::**");
+        if is_namespace_import {
+            if !is_namespace(self.aliased(target)) {
+                let message = format!(
+                    "invalid import: '{}' is not a namespace, so its members can't be imported",
+                    tr.target
+                );
+                self.report_import(symbol, message, Some((target, "declared here")));
+            }
+        } else {
+            self.check_private_reexport(symbol, tr, target);
+        }
+    }
+
+    /// Check each `@Metadata` filter names a metadata definition.
+    fn check_import_filters(&mut self, symbol: &HirSymbol) {
+        let index = self.index;
+        let resolver = Resolver::new(index).with_scope(import_scope(&symbol.qualified_name));
+        for filter in index.import_filters(&symbol.qualified_name) {
+            match resolver.resolve(filter) {
+                ResolveResult::Found(metadata)
+                    if metadata.kind != SymbolKind::MetadataDefinition =>
+                {
+                    let message = format!(
+                        "invalid import: filter '@{}' is not a metadata definition",
+                        filter
+                    );
+                    let related = index.lookup_qualified(&metadata.qualified_name);
+                    self.report_import(symbol, message, related.map(|m| (m, "declared here")));
+                }
+                ResolveResult::NotFound if !Self::is_builtin_type(filter) => {
+                    let message = format!("invalid import: cannot resolve filter '@{}'", filter);
+                    self.report_import(symbol, message, None);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Check a membership import (`import A::x;`) doesn't import `x` through
+    /// `A` when `A` only imports it privately.
+    fn check_private_reexport(&mut self, symbol: &HirSymbol, tr: &TypeRef, target: &HirSymbol) {
+        let index = self.index;
+        let Some((namespace_path, member)) = tr.target.rsplit_once("::") else {
+            return;
+        };
+        let resolver = Resolver::new(index).with_scope(import_scope(&symbol.qualified_name));
+        let ResolveResult::Found(namespace) = resolver.resolve(namespace_path) else {
+            return;
+        };
+        // Owned members are visible whatever `A` imports
+        if target_is_owned_by(target, &namespace.qualified_name)
+            || index
+                .lookup_qualified(&format!("{}::{}", namespace.qualified_name, member))
+                .is_some()
+        {
+            return;
+        }
+        if index
+            .visibility_for_scope(&namespace.qualified_name)
+            .is_some_and(|vis| {
+                vis.public_reexports()
+                    .iter()
+                    .any(|ns| target_is_owned_by(target, ns))
+            })
+        {
+            return;
+        }
+
+        let providers: Vec<&HirSymbol> = index
+            .symbols_in_file(namespace.file)
+            .into_iter()
+            .filter(|s| {
+                s.kind == SymbolKind::Import
+                    && import_scope(&s.qualified_name) == &*namespace.qualified_name
+                    && self.imports(s, &target.qualified_name)
+            })
+            .collect();
+        if providers.is_empty() || providers.iter().any(|import| import.is_public) {
+            return;
+        }
+        let message = format!(
+            "invalid import: '{}' is imported privately by '{}', which doesn't re-export it",
+            member, namespace.name
+        );
+        self.report_import(
+            symbol,
+            message,
+            Some((providers[0], "imported privately here")),
+        );
+    }
+
+    /// The element `tr` names, resolved from the namespace `import` belongs to
+    /// if type references haven't been resolved yet.
+    fn import_target(&self, import: &HirSymbol, tr: &TypeRef) -> Option<&'a HirSymbol> {
+        if let Some(target) = tr.resolved_target.as_deref() {
+            return self.index.lookup_qualified(target);
+        }
+        let resolver = Resolver::new(self.index).with_scope(import_scope(&import.qualified_name));
+        match resolver.resolve(&tr.target) {
+            ResolveResult::Found(target) => self.index.lookup_qualified(&target.qualified_name),
+            _ => None,
+        }
+    }
+
+    /// Whether `import` makes the element named `qualified_name` visible.
+    fn imports(&self, import: &HirSymbol, qualified_name: &str) -> bool {
+        let Some(imported) = import
+            .type_refs
+            .iter()
+            .flat_map(|type_ref| type_ref.as_refs())
+            .find_map(|tr| self.import_target(import, tr))
+        else {
+            return false;
+        };
+        let imported = &*imported.qualified_name;
+        if import.name.ends_with("::**") {
+            qualified_name
+                .strip_prefix(imported)
+                .is_some_and(|rest| rest.starts_with("::"))
+        } else if import.name.ends_with("::*") {
+            qualified_name
+                .rsplit_once("::")
+                .is_some_and(|(owner, _)| owner == imported)
+        } else {
+            qualified_name == imported
+        }
+    }
+
+    /// The element an alias stands for, or `symbol` itself.
+    fn aliased(&self, symbol: &'a HirSymbol) -> &'a HirSymbol {
+        if symbol.kind != SymbolKind::Alias {
+            return symbol;
+        }
+        symbol
+            .type_refs
+            .iter()
+            .flat_map(|type_ref| type_ref.as_refs())
+            .find_map(|tr| self.index.lookup_qualified(tr.resolved_target.as_deref()?))
+            .unwrap_or(symbol)
+    }
+
+    fn report_import(
+        &mut self,
+        symbol: &HirSymbol,
+        message: String,
+        related: Option<(&HirSymbol, &str)>,
+    ) {
+        let mut diagnostic =
+            Diagnostic::error(symbol.file, symbol.start_line, symbol.start_col, message)
+                .with_span(symbol.end_line, symbol.end_col)
+                .with_code(codes::INVALID_IMPORT);
+        if let Some((related, note)) = related {
+            diagnostic = diagnostic.with_related(RelatedInfo {
+                file: related.file,
+                line: related.start_line,
+                col: related.start_col,
+                message: Arc::from(format!("'{}' {}", related.name, note)),
+            });
+        }
+        self.collector.add(diagnostic);
+    }
+}
+
+/// The namespace an import belongs to (`P::import:A::*` belongs to `P`).
+fn import_scope(qualified_name: &str) -> &str {
+    match qualified_name.find("::import:") {
+        Some(pos) => &qualified_name[..pos],
+        None => "",
+    }
+}
+
+/// Whether `symbol` is a namespace whose members can be imported.
+fn is_namespace(symbol: &HirSymbol) -> bool {
+    !matches!(
+        symbol.kind,
+        SymbolKind::Import
+            | SymbolKind::Comment
+            | SymbolKind::Dependency
+            | SymbolKind::ExposeRelationship
+    )
+}
+
+fn target_is_owned_by(target: &HirSymbol, namespace: &str) -> bool {
+    target
+        .qualified_name
+        .strip_prefix(namespace)
+        .is_some_and(|rest| rest.starts_with("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::diagnostics_for;
+
+    fn import_messages(source: &str) -> Vec<String> {
+        diagnostics_for(source, codes::INVALID_IMPORT)
+            .into_iter()
+            .map(|d| d.message.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_imports() {
+        let messages = import_messages(
+            "package Defs { part def Engine; metadata def Approved; } \
+             package Shared { public import Defs::Engine; } \
+             package Model { private import Defs::*; import Defs::**; \
+             import Shared::Engine; import Defs::*[@Defs::Approved]; }",
+        );
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+
+    #[test]
+    fn test_unresolved_import() {
+        let messages = import_messages(
+            "package Defs { part def Engine; } \
+             package Model { import Defs::Engin; import Def::*; }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid import: cannot resolve 'Defs::Engin'",
+                "invalid import: cannot resolve 'Def'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_import_of_non_namespace() {
+        let messages = import_messages(
+            "package Defs { part def Engine; comment note /* unused */ } \
+             package Model { import Defs::note::**; import Defs::Engine::**; }",
+        );
+        assert_eq!(
+            messages,
+            ["invalid import: 'Defs::note' is not a namespace, so its members can't be imported"]
+        );
+    }
+
+    #[test]
+    fn test_filter_is_not_metadata_definition() {
+        let messages = import_messages(
+            "package Defs { part def Engine; metadata def Approved; } \
+             package Model { import Defs::*[@Defs::Engine]; } \
+             package Other { import Defs::**[@Reviewed]; }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid import: filter '@Defs::Engine' is not a metadata definition",
+                "invalid import: cannot resolve filter '@Reviewed'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_through_private_import() {
+        let messages = import_messages(
+            "package Defs { part def Engine; part def Wheel; } \
+             package Shared { private import Defs::*; public import Defs::Wheel; } \
+             package Model { public import Shared::Engine; import Shared::Wheel; }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid import: 'Engine' is imported privately by 'Shared', which doesn't re-export it"
+            ]
+        );
+    }
+}
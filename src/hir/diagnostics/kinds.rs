@@ -0,0 +1,352 @@
+//! Specialization kind compatibility (E0006).
+//!
+//! SysML restricts which kinds of definition a usage may be typed by
+//! (attributes by attribute definitions, parts by part definitions, ports by
+//! port definitions, ...) and which definitions may specialize each other.
+//! Kinds are compared through the definition metaclass hierarchy, e.g. a part
+//! definition is an item definition, which is an occurrence definition.
+//!
+//! Symbols from KerML files are skipped: their kinds only approximate the
+//! KerML metaclasses (a `classifier` is extracted as a part definition).
+
+use std::sync::Arc;
+
+use super::{Diagnostic, RelatedInfo, SemanticChecker, codes};
+use crate::hir::symbols::{HirSymbol, RefKind, SymbolKind, TypeRef};
+
+/// Definition metaclasses, ordered by their place in the SysML hierarchy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Category {
+    Attribute,
+    Occurrence,
+    Item,
+    Part,
+    Connection,
+    Interface,
+    Allocation,
+    View,
+    Rendering,
+    Metadata,
+    Port,
+    Action,
+    Calculation,
+    State,
+    Case,
+    AnalysisCase,
+    VerificationCase,
+    Constraint,
+    Requirement,
+    Concern,
+    Viewpoint,
+}
+
+impl Category {
+    /// The category of a definition kind.
+    fn of_definition(kind: SymbolKind) -> Option<Self> {
+        Some(match kind {
+            SymbolKind::AttributeDefinition
+            | SymbolKind::EnumerationDefinition
+            | SymbolKind::DataType => Self::Attribute,
+            SymbolKind::OccurrenceDefinition | SymbolKind::Class => Self::Occurrence,
+            SymbolKind::ItemDefinition | SymbolKind::Structure => Self::Item,
+            SymbolKind::PartDefinition => Self::Part,
+            SymbolKind::ConnectionDefinition => Self::Connection,
+            SymbolKind::InterfaceDefinition => Self::Interface,
+            SymbolKind::AllocationDefinition => Self::Allocation,
+            SymbolKind::ViewDefinition => Self::View,
+            SymbolKind::RenderingDefinition => Self::Rendering,
+            SymbolKind::MetadataDefinition => Self::Metadata,
+            SymbolKind::PortDefinition => Self::Port,
+            SymbolKind::ActionDefinition | SymbolKind::Interaction | SymbolKind::Behavior => {
+                Self::Action
+            }
+            SymbolKind::CalculationDefinition | SymbolKind::Function => Self::Calculation,
+            SymbolKind::StateDefinition => Self::State,
+            // `case def` and `use case def` share a kind
+            SymbolKind::UseCaseDefinition => Self::Case,
+            SymbolKind::AnalysisCaseDefinition => Self::AnalysisCase,
+            SymbolKind::VerificationCaseDefinition => Self::VerificationCase,
+            SymbolKind::ConstraintDefinition => Self::Constraint,
+            SymbolKind::RequirementDefinition => Self::Requirement,
+            SymbolKind::ConcernDefinition => Self::Concern,
+            SymbolKind::ViewpointDefinition => Self::Viewpoint,
+            _ => return None,
+        })
+    }
+
+    /// The category a usage kind must be typed by.
+    fn required_by_usage(kind: SymbolKind) -> Option<Self> {
+        Some(match kind {
+            SymbolKind::AttributeUsage => Self::Attribute,
+            SymbolKind::OccurrenceUsage => Self::Occurrence,
+            SymbolKind::ItemUsage => Self::Item,
+            // Parts are commonly typed by item definitions alone, including in
+            // the standard library (`part subSpatialParts : SpatialItem`)
+            SymbolKind::PartUsage => Self::Item,
+            SymbolKind::ConnectionUsage => Self::Connection,
+            SymbolKind::InterfaceUsage => Self::Interface,
+            SymbolKind::AllocationUsage => Self::Allocation,
+            SymbolKind::ViewUsage => Self::View,
+            SymbolKind::RenderingUsage => Self::Rendering,
+            SymbolKind::PortUsage => Self::Port,
+            // Plain action usages aren't checked: accept actions are extracted
+            // as action usages named and typed after their payload
+            // (`accept sig : Signal`)
+            SymbolKind::PerformActionUsage => Self::Action,
+            SymbolKind::CalculationUsage => Self::Calculation,
+            SymbolKind::StateUsage | SymbolKind::ExhibitStateUsage => Self::State,
+            SymbolKind::UseCaseUsage | SymbolKind::IncludeUseCaseUsage => Self::Case,
+            SymbolKind::AnalysisCaseUsage => Self::AnalysisCase,
+            SymbolKind::VerificationCaseUsage => Self::VerificationCase,
+            SymbolKind::ConstraintUsage | SymbolKind::AssertConstraintUsage => Self::Constraint,
+            SymbolKind::RequirementUsage | SymbolKind::SatisfyRequirementUsage => Self::Requirement,
+            SymbolKind::ViewpointUsage => Self::Viewpoint,
+            _ => return None,
+        })
+    }
+
+    fn parent(self) -> Option<Self> {
+        match self {
+            Self::Attribute | Self::Occurrence => None,
+            Self::Item | Self::Port | Self::Action | Self::Constraint => Some(Self::Occurrence),
+            Self::Part | Self::Metadata => Some(Self::Item),
+            Self::Connection | Self::View | Self::Rendering => Some(Self::Part),
+            Self::Interface | Self::Allocation => Some(Self::Connection),
+            Self::Calculation | Self::State => Some(Self::Action),
+            Self::Case => Some(Self::Calculation),
+            Self::AnalysisCase | Self::VerificationCase => Some(Self::Case),
+            Self::Requirement => Some(Self::Constraint),
+            Self::Concern | Self::Viewpoint => Some(Self::Requirement),
+        }
+    }
+
+    /// Whether every definition of this category is also one of `general`.
+    fn conforms_to(self, general: Self) -> bool {
+        let mut current = Some(self);
+        while let Some(category) = current {
+            if category == general {
+                return true;
+            }
+            current = category.parent();
+        }
+        false
+    }
+
+    fn display(self) -> &'static str {
+        match self {
+            Self::Attribute => "attribute def",
+            Self::Occurrence => "occurrence def",
+            Self::Item => "item def",
+            Self::Part => "part def",
+            Self::Connection => "connection def",
+            Self::Interface => "interface def",
+            Self::Allocation => "allocation def",
+            Self::View => "view def",
+            Self::Rendering => "rendering def",
+            Self::Metadata => "metadata def",
+            Self::Port => "port def",
+            Self::Action => "action def",
+            Self::Calculation => "calc def",
+            Self::State => "state def",
+            Self::Case => "case def",
+            Self::AnalysisCase => "analysis case def",
+            Self::VerificationCase => "verification case def",
+            Self::Constraint => "constraint def",
+            Self::Requirement => "requirement def",
+            Self::Concern => "concern def",
+            Self::Viewpoint => "viewpoint def",
+        }
+    }
+}
+
+/// Whether definitions of kinds `kind` and `other` may classify the same
+/// values: one's category conforms to the other's, as for specialization.
+/// Kinds without a category are assumed compatible.
+pub(super) fn are_compatible_kinds(kind: SymbolKind, other: SymbolKind) -> bool {
+    match (
+        Category::of_definition(kind),
+        Category::of_definition(other),
+    ) {
+        (Some(category), Some(other)) => category.conforms_to(other) || other.conforms_to(category),
+        _ => true,
+    }
+}
+
+impl<'a> SemanticChecker<'a> {
+    /// Check that `symbol`'s types and supertypes are of compatible kinds.
+    pub(super) fn check_specialization_kinds(&mut self, symbol: &HirSymbol) {
+        if self.index.is_kerml_file(symbol.file) {
+            return;
+        }
+
+        if let Some(own) = Category::of_definition(symbol.kind) {
+            for (tr, general, category) in self.resolved_definitions(symbol, RefKind::Specializes) {
+                // Definitions may specialize more general kinds and, less
+                // commonly, more specific ones; unrelated kinds are disjoint
+                if !own.conforms_to(category) && !category.conforms_to(own) {
+                    let expected = format!("a definition compatible with {}", own.display());
+                    self.invalid_specialization(symbol, tr, general, "specialize", &expected);
+                }
+            }
+        } else if let Some(required) = Category::required_by_usage(symbol.kind) {
+            // Connection ends are extracted as ports whatever they are typed by
+            if symbol.is_end {
+                return;
+            }
+            let types = self.resolved_definitions(symbol, RefKind::TypedBy);
+            let has_conforming = types.iter().any(|(_, _, c)| c.conforms_to(required));
+            for (tr, general, category) in &types {
+                // Metadata annotations (`@Safety { ... }`) are extracted as
+                // attributes typed by their metadata definition
+                if *category == Category::Metadata {
+                    continue;
+                }
+                // Extra, more general types are fine next to a conforming one
+                // (`part p : Vehicle, SomeItem;`)
+                if category.conforms_to(required)
+                    || (has_conforming && required.conforms_to(*category))
+                {
+                    continue;
+                }
+                let expected = format!("{} or a specialization of it", required.display());
+                self.invalid_specialization(symbol, tr, general, "be typed by", &expected);
+            }
+        }
+    }
+
+    /// The definitions `symbol` itself references with `kind`, with their
+    /// categories.
+    fn resolved_definitions<'s>(
+        &self,
+        symbol: &'s HirSymbol,
+        kind: RefKind,
+    ) -> Vec<(&'s TypeRef, &'a HirSymbol, Category)> {
+        let index = self.index;
+        Self::own_type_refs(symbol)
+            .filter(|tr| tr.kind == kind)
+            .filter_map(|tr| {
+                let target = index.lookup_qualified(tr.resolved_target.as_ref()?)?;
+                if index.is_kerml_file(target.file) {
+                    return None;
+                }
+                let category = Category::of_definition(target.kind)?;
+                Some((tr, target, category))
+            })
+            .collect()
+    }
+
+    fn invalid_specialization(
+        &mut self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+        general: &HirSymbol,
+        verb: &str,
+        expected: &str,
+    ) {
+        let found = general.kind.display().to_lowercase();
+        self.collector.add(
+            Diagnostic::error(
+                symbol.file,
+                tr.start_line,
+                tr.start_col,
+                format!(
+                    "invalid specialization: {} '{}' cannot {} '{}' (expected {}, found {})",
+                    symbol.kind.display().to_lowercase(),
+                    symbol.name,
+                    verb,
+                    general.name,
+                    expected,
+                    found
+                ),
+            )
+            .with_span(tr.end_line, tr.end_col)
+            .with_code(codes::INVALID_SPECIALIZATION)
+            .with_related(RelatedInfo {
+                file: general.file,
+                line: general.start_line,
+                col: general.start_col,
+                message: Arc::from(format!(
+                    "'{}' is declared as a {} here",
+                    general.name, found
+                )),
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::index_for;
+
+    fn kind_diagnostics(files: &[(&str, &str)]) -> Vec<Diagnostic> {
+        let index = index_for(files);
+        let mut checker = SemanticChecker::new(&index);
+        checker.check_all().unwrap();
+        checker
+            .finish()
+            .into_iter()
+            .filter(|d| d.code.as_deref() == Some(codes::INVALID_SPECIALIZATION))
+            .collect()
+    }
+
+    #[test]
+    fn test_definition_of_unrelated_kind() {
+        let diagnostics = kind_diagnostics(&[(
+            "test.sysml",
+            "package P { port def PortDef1; part def Car :> PortDef1; }",
+        )]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message.as_ref(),
+            "invalid specialization: part def 'Car' cannot specialize 'PortDef1' \
+             (expected a definition compatible with part def, found port def)"
+        );
+        assert_eq!(diagnostics[0].related.len(), 1);
+    }
+
+    #[test]
+    fn test_usage_typed_by_wrong_kind() {
+        let diagnostics = kind_diagnostics(&[(
+            "test.sysml",
+            "package P { part def Engine; attribute def Mass; port def FuelPort; \
+             part car { attribute x : Engine; attribute m : Mass; \
+             port p : Engine; port fuel : FuelPort; part e : Engine; } }",
+        )]);
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_ref()).collect();
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].contains(
+            "attribute 'x' cannot be typed by 'Engine' (expected attribute def or a specialization of it, found part def)"
+        ));
+        assert!(messages[1].contains("port 'p'"));
+    }
+
+    #[test]
+    fn test_compatible_kinds() {
+        let diagnostics = kind_diagnostics(&[(
+            "test.sysml",
+            "package P { item def Fuel; part def Tank :> Fuel; \
+             connection def Link; interface def Plug :> Link; \
+             enum def Color { red; } attribute c : Color; \
+             item fuel : Tank; part tank : Tank, Fuel; \
+             calc def Area; case def Study :> Area; analysis def Trade :> Study; \
+             metadata def Safety; part bumper { @Safety; } }",
+        )]);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn test_kerml_symbols_are_skipped() {
+        let diagnostics = kind_diagnostics(&[
+            (
+                "lib.kerml",
+                "package L { classifier Anything; datatype Value :> Anything; }",
+            ),
+            (
+                "test.sysml",
+                "package P { attribute def A :> L::Anything; }",
+            ),
+        ]);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+}
@@ -0,0 +1,552 @@
+//! Configurable lints (W0001-W0003).
+//!
+//! Lints are optional checks of house style rather than of the language: each
+//! has a stable id used in configuration, a diagnostic code and a default
+//! [`LintLevel`]. A [`LintRegistry`] holds the lints a workspace runs and the
+//! level configured for each; [`SemanticChecker::with_lints`] runs every lint
+//! that isn't allowed on each symbol it checks.
+//!
+//! Built-in lints:
+//!
+//! - `naming-convention` (W0003, allowed) — definitions are PascalCase and
+//!   usages camelCase;
+//! - `deprecated` (W0002, warning) — references to elements annotated with
+//!   `Deprecated` metadata (`#Deprecated part def OldEngine;`);
+//! - `unused` (W0001, allowed) — definitions nothing references.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::{Diagnostic, DiagnosticCollector, RelatedInfo, SemanticChecker, Severity, codes};
+use crate::base::FileId;
+use crate::hir::resolve::SymbolIndex;
+use crate::hir::symbols::{HirSymbol, RefKind, SymbolKind};
+
+/// How a lint's findings are reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// The lint doesn't run.
+    Allow,
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LintLevel {
+    /// The severity of the lint's diagnostics, or `None` if it doesn't run.
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Allow => None,
+            LintLevel::Hint => Some(Severity::Hint),
+            LintLevel::Info => Some(Severity::Info),
+            LintLevel::Warning => Some(Severity::Warning),
+            LintLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    /// Parse a level as written in configuration (`allow`, `warn`, `error`, ...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "allow" | "off" => Ok(LintLevel::Allow),
+            "hint" => Ok(LintLevel::Hint),
+            "info" => Ok(LintLevel::Info),
+            "warn" | "warning" => Ok(LintLevel::Warning),
+            "error" | "deny" => Ok(LintLevel::Error),
+            other => Err(format!("unknown lint level '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Hint => "hint",
+            LintLevel::Info => "info",
+            LintLevel::Warning => "warning",
+            LintLevel::Error => "error",
+        })
+    }
+}
+
+/// A check run on every symbol by the [`SemanticChecker`].
+pub trait Lint: Send + Sync {
+    /// Identifier used in configuration (e.g., `naming-convention`).
+    fn id(&self) -> &'static str;
+
+    /// Code of the lint's diagnostics (e.g., `W0003`).
+    fn code(&self) -> &'static str;
+
+    /// The level used unless the workspace configures another.
+    fn default_level(&self) -> LintLevel;
+
+    /// Check `symbol`, reporting findings through `cx`.
+    fn check_symbol(&self, cx: &mut LintContext<'_>, symbol: &HirSymbol);
+}
+
+/// The lints a workspace runs, with their configured levels.
+#[derive(Clone)]
+pub struct LintRegistry {
+    lints: Vec<(Arc<dyn Lint>, LintLevel)>,
+}
+
+impl Default for LintRegistry {
+    /// A registry with the built-in lints at their default levels.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(NamingConvention);
+        registry.register(Deprecated);
+        registry.register(UnusedDefinition);
+        registry
+    }
+}
+
+impl fmt::Debug for LintRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.lints.iter().map(|(lint, level)| (lint.id(), level)))
+            .finish()
+    }
+}
+
+impl LintRegistry {
+    /// A registry without any lints.
+    pub fn empty() -> Self {
+        Self { lints: Vec::new() }
+    }
+
+    /// Add `lint` at its default level, replacing any lint with the same id.
+    pub fn register(&mut self, lint: impl Lint + 'static) {
+        let level = lint.default_level();
+        self.lints.retain(|(other, _)| other.id() != lint.id());
+        self.lints.push((Arc::new(lint), level));
+    }
+
+    /// Set the level of the lint with the given id or code.
+    ///
+    /// Returns `false` if there's no such lint.
+    pub fn set_level(&mut self, lint: &str, level: LintLevel) -> bool {
+        let mut found = false;
+        for (registered, registered_level) in &mut self.lints {
+            if registered.id() == lint || registered.code() == lint {
+                *registered_level = level;
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// The level of the lint with the given id or code.
+    pub fn level(&self, lint: &str) -> Option<LintLevel> {
+        self.lints
+            .iter()
+            .find(|(registered, _)| registered.id() == lint || registered.code() == lint)
+            .map(|(_, level)| *level)
+    }
+
+    /// All registered lints with their levels.
+    pub fn lints(&self) -> impl Iterator<Item = (&dyn Lint, LintLevel)> {
+        self.lints
+            .iter()
+            .map(|(lint, level)| (lint.as_ref(), *level))
+    }
+}
+
+/// What a [`Lint`] sees of the checker.
+pub struct LintContext<'c> {
+    index: &'c SymbolIndex,
+    referenced: &'c HashSet<Arc<str>>,
+    collector: &'c mut DiagnosticCollector,
+    severity: Severity,
+    code: &'static str,
+}
+
+impl<'c> LintContext<'c> {
+    /// The index being checked.
+    pub fn index(&self) -> &'c SymbolIndex {
+        self.index
+    }
+
+    /// Whether any reference resolves to the symbol named `qualified_name`.
+    pub fn is_referenced(&self, qualified_name: &str) -> bool {
+        self.referenced.contains(qualified_name)
+            || !self.index.dependents_of(qualified_name).is_empty()
+    }
+
+    /// A diagnostic with the lint's configured severity and code.
+    pub fn diagnostic(
+        &self,
+        file: FileId,
+        line: u32,
+        col: u32,
+        message: impl Into<Arc<str>>,
+    ) -> Diagnostic {
+        Diagnostic::warning(file, line, col, message)
+            .with_severity(self.severity)
+            .with_code(self.code)
+    }
+
+    /// Report `diagnostic`.
+    pub fn emit(&mut self, diagnostic: Diagnostic) {
+        self.collector.add(diagnostic);
+    }
+}
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_lints(&mut self, symbol: &HirSymbol) {
+        let lints = Arc::clone(&self.lints);
+        for (lint, level) in lints.lints() {
+            let Some(severity) = level.severity() else {
+                continue;
+            };
+            let mut cx = LintContext {
+                index: self.index,
+                referenced: &self.referenced,
+                collector: &mut self.collector,
+                severity,
+                code: lint.code(),
+            };
+            lint.check_symbol(&mut cx, symbol);
+        }
+    }
+}
+
+// ============================================================================
+// BUILT-IN LINTS
+// ============================================================================
+
+/// Definitions are PascalCase (`FuelTank`), usages camelCase (`fuelTank`).
+///
+/// Unrestricted names (`'Fuel Tank'`), anonymous elements and redefinitions,
+/// whose name is the redefined feature's, are skipped.
+struct NamingConvention;
+
+impl Lint for NamingConvention {
+    fn id(&self) -> &'static str {
+        "naming-convention"
+    }
+
+    fn code(&self) -> &'static str {
+        codes::NAMING_CONVENTION
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Allow
+    }
+
+    fn check_symbol(&self, cx: &mut LintContext<'_>, symbol: &HirSymbol) {
+        let name = &*symbol.name;
+        if !is_identifier(name) {
+            return;
+        }
+        let (what, convention, expected) =
+            if symbol.kind.is_definition() && symbol.kind != SymbolKind::Package {
+                ("definition", "PascalCase", to_pascal_case(name))
+            } else if symbol.kind.is_usage() {
+                let redefines = symbol
+                    .type_refs
+                    .iter()
+                    .flat_map(|type_ref| type_ref.as_refs())
+                    .any(|tr| tr.kind == RefKind::Redefines);
+                if redefines {
+                    return;
+                }
+                ("usage", "camelCase", to_camel_case(name))
+            } else {
+                return;
+            };
+        if expected == name {
+            return;
+        }
+        let message = format!(
+            "naming convention: {} '{}' should be {} ('{}')",
+            what, name, convention, expected
+        );
+        let diagnostic = cx
+            .diagnostic(symbol.file, symbol.start_line, symbol.start_col, message)
+            .with_span(symbol.end_line, symbol.end_col);
+        cx.emit(diagnostic);
+    }
+}
+
+/// References to elements annotated with `Deprecated` metadata.
+struct Deprecated;
+
+impl Lint for Deprecated {
+    fn id(&self) -> &'static str {
+        "deprecated"
+    }
+
+    fn code(&self) -> &'static str {
+        codes::DEPRECATED
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warning
+    }
+
+    fn check_symbol(&self, cx: &mut LintContext<'_>, symbol: &HirSymbol) {
+        let index = cx.index();
+        for tr in symbol
+            .type_refs
+            .iter()
+            .flat_map(|type_ref| type_ref.as_refs())
+        {
+            let Some(target) = tr
+                .resolved_target
+                .as_deref()
+                .and_then(|target| index.lookup_qualified(target))
+            else {
+                continue;
+            };
+            // Members of a deprecated element may refer to it
+            let is_inside = symbol
+                .qualified_name
+                .strip_prefix(&*target.qualified_name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+            if is_inside || !is_deprecated(target) {
+                continue;
+            }
+            let message = format!("deprecated: '{}' is deprecated", target.name);
+            let diagnostic = cx
+                .diagnostic(symbol.file, tr.start_line, tr.start_col, message)
+                .with_span(tr.end_line, tr.end_col)
+                .with_related(RelatedInfo {
+                    file: target.file,
+                    line: target.start_line,
+                    col: target.start_col,
+                    message: Arc::from(format!("'{}' is declared here", target.name)),
+                });
+            cx.emit(diagnostic);
+        }
+    }
+}
+
+/// Definitions nothing references, including through specialization.
+///
+/// Opt-in: only references resolved in the index count, and library
+/// definitions are meant to be used elsewhere.
+struct UnusedDefinition;
+
+impl Lint for UnusedDefinition {
+    fn id(&self) -> &'static str {
+        "unused"
+    }
+
+    fn code(&self) -> &'static str {
+        codes::UNUSED_SYMBOL
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Allow
+    }
+
+    fn check_symbol(&self, cx: &mut LintContext<'_>, symbol: &HirSymbol) {
+        if !symbol.kind.is_definition()
+            || symbol.kind == SymbolKind::Package
+            || cx.index().is_kerml_file(symbol.file)
+            || cx.is_referenced(&symbol.qualified_name)
+        {
+            return;
+        }
+        let message = format!(
+            "unused {}: '{}'",
+            symbol.kind.display().to_lowercase(),
+            symbol.name
+        );
+        let diagnostic = cx
+            .diagnostic(symbol.file, symbol.start_line, symbol.start_col, message)
+            .with_span(symbol.end_line, symbol.end_col);
+        cx.emit(diagnostic);
+    }
+}
+
+fn is_deprecated(symbol: &HirSymbol) -> bool {
+    symbol
+        .metadata_annotations
+        .iter()
+        .any(|annotation| &**annotation == "Deprecated")
+}
+
+/// Whether `name` is a basic name (`fuelTank`), not an unrestricted one
+/// (`'Fuel Tank'`) or an anonymous element's.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The words of `name`: `fuel_tank`, `FuelTank` and `FUELTank` all give
+/// `fuel`/`FUEL` and `tank`/`Tank`.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && prev.is_some_and(|prev| {
+                prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `fuel_tank` and `fuelTank` become `FuelTank`; acronyms are kept (`ISQBase`).
+fn to_pascal_case(name: &str) -> String {
+    words(name).iter().map(|word| capitalize(word)).collect()
+}
+
+/// `FuelTank` and `fuel_tank` become `fuelTank`, `ISQBase` becomes `isqBase`.
+fn to_camel_case(name: &str) -> String {
+    let words = words(name);
+    let mut camel = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 {
+            camel.push_str(&word.to_lowercase());
+        } else {
+            camel.push_str(&capitalize(word));
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::index_for;
+
+    fn lint_diagnostics(source: &str, lints: LintRegistry) -> Vec<Diagnostic> {
+        let index = index_for(&[("test.sysml", source)]);
+        let mut checker = SemanticChecker::new(&index).with_lints(Arc::new(lints));
+        checker.check_all().unwrap();
+        checker
+            .finish()
+            .into_iter()
+            .filter(|d| d.code.as_deref().is_some_and(|code| code.starts_with('W')))
+            .collect()
+    }
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.message.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_case_conversions() {
+        assert_eq!(to_pascal_case("fuel_tank"), "FuelTank");
+        assert_eq!(to_pascal_case("fuelTank"), "FuelTank");
+        assert_eq!(to_pascal_case("ISQBase"), "ISQBase");
+        assert_eq!(to_camel_case("FuelTank"), "fuelTank");
+        assert_eq!(to_camel_case("fuel_tank_2"), "fuelTank2");
+        assert_eq!(to_camel_case("ISQBase"), "isqBase");
+        assert_eq!(to_camel_case("engine"), "engine");
+    }
+
+    #[test]
+    fn test_naming_convention() {
+        let source = "package P { part def fuel_tank; part def Engine { attribute :>> mass; } \
+                      part Car { part engine : Engine; part 'Spare Tank' : fuel_tank; } }";
+        // House style is opt-in
+        assert!(lint_diagnostics(source, LintRegistry::default()).is_empty());
+
+        let mut lints = LintRegistry::default();
+        lints.set_level("naming-convention", LintLevel::Warning);
+        let diagnostics = lint_diagnostics(source, lints);
+        assert_eq!(
+            messages(&diagnostics),
+            [
+                "naming convention: definition 'fuel_tank' should be PascalCase ('FuelTank')",
+                "naming convention: usage 'Car' should be camelCase ('car')"
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn test_deprecated_references() {
+        let diagnostics = lint_diagnostics(
+            "package P { metadata def Deprecated; \
+             #Deprecated part def OldEngine { part inner : OldEngine; } \
+             part def Engine; part def Diesel :> OldEngine; part car { part e : OldEngine; } }",
+            LintRegistry::default(),
+        );
+        let deprecated: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code.as_deref() == Some(codes::DEPRECATED))
+            .collect();
+        assert_eq!(deprecated.len(), 2, "{deprecated:?}");
+        assert!(
+            deprecated
+                .iter()
+                .all(|d| &*d.message == "deprecated: 'OldEngine' is deprecated")
+        );
+        assert_eq!(deprecated[0].related.len(), 1);
+    }
+
+    #[test]
+    fn test_configured_levels() {
+        let source = "package P { part def Engine; part def Spare; part car { part e : Engine; } \
+                      part Truck; }";
+
+        let mut lints = LintRegistry::default();
+        assert!(lints.set_level("unused", LintLevel::Warning));
+        assert!(lints.set_level("W0003", "error".parse().unwrap()));
+        assert!(!lints.set_level("no-such-lint", LintLevel::Error));
+        let diagnostics = lint_diagnostics(source, lints);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_deref().unwrap(), d.severity, d.message.as_ref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    codes::UNUSED_SYMBOL,
+                    Severity::Warning,
+                    "unused part def: 'Spare'"
+                ),
+                (
+                    codes::NAMING_CONVENTION,
+                    Severity::Error,
+                    "naming convention: usage 'Truck' should be camelCase ('truck')"
+                )
+            ]
+        );
+
+        let mut lints = LintRegistry::default();
+        lints.set_level("W0003", LintLevel::Warning);
+        lints.set_level("naming-convention", LintLevel::Allow);
+        assert!(lint_diagnostics(source, lints).is_empty());
+    }
+}
@@ -2,12 +2,44 @@
 //!
 //! This module provides diagnostic types for semantic analysis errors
 //! and warnings. It integrates with the symbol index and resolver.
-
+//!
+//! # Module structure
+//!
+//! - [`abstracts`] — Usages of abstract definitions (W0005)
+//! - [`connections`] — Connection and flow endpoint compatibility (E0016)
+//! - [`cycles`] — Circular specialization, subsetting and redefinition (E0007)
+//! - [`features`] — Redefinition and subsetting validity (E0009, E0010)
+//! - [`fixes`] — Machine-applicable fixes for diagnostics
+//! - [`imports`] — Import targets, filters and re-exports (E0014)
+//! - [`kinds`] — Specialization and typing kind compatibility (E0006)
+//! - [`lints`] — Configurable naming, deprecation and unused lints (W0001-W0003)
+//! - [`multiplicity`] — Multiplicity bound consistency (E0015)
+//! - [`suppressions`] — `syster-ignore` comments and unused suppressions (W0004)
+
+mod abstracts;
+mod connections;
+mod cycles;
+mod features;
+mod fixes;
+mod imports;
+mod kinds;
+mod lints;
+mod multiplicity;
+mod suppressions;
+
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use tokio_util::sync::CancellationToken;
+
 use super::resolve::{ResolveResult, Resolver, SymbolIndex};
-use super::symbols::{HirSymbol, SymbolKind};
-use crate::base::FileId;
+use super::symbols::{HirSymbol, SymbolKind, TypeRef, TypeRefKind};
+use crate::base::{Cancellable, Cancelled, FileId};
+
+pub use fixes::Fix;
+pub use lints::{Lint, LintContext, LintLevel, LintRegistry};
+pub use suppressions::Suppression;
+pub(crate) use suppressions::collect_suppressions;
 
 // ============================================================================
 // DIAGNOSTIC TYPES
@@ -55,6 +87,8 @@ pub struct Diagnostic {
     pub message: Arc<str>,
     /// Optional related information.
     pub related: Vec<RelatedInfo>,
+    /// Machine-applicable fixes, best first.
+    pub fixes: Vec<Fix>,
 }
 
 /// Related information for a diagnostic.
@@ -83,6 +117,7 @@ impl Diagnostic {
             code: None,
             message: message.into(),
             related: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -98,6 +133,7 @@ impl Diagnostic {
             code: None,
             message: message.into(),
             related: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -108,6 +144,18 @@ impl Diagnostic {
         self
     }
 
+    /// Add a fix.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    /// Set the severity.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
     /// Set the error code.
     pub fn with_code(mut self, code: impl Into<Arc<str>>) -> Self {
         self.code = Some(code.into());
@@ -165,6 +213,10 @@ pub mod codes {
     pub const ABSTRACT_INSTANTIATION: &str = "E0013";
     /// Invalid import statement.
     pub const INVALID_IMPORT: &str = "E0014";
+    /// Inconsistent multiplicity bounds.
+    pub const INVALID_MULTIPLICITY: &str = "E0015";
+    /// Incompatible connection or flow endpoints.
+    pub const INCOMPATIBLE_ENDPOINTS: &str = "E0016";
 
     // ========================================================================
     // WARNINGS (W0001-W0099)
@@ -176,6 +228,10 @@ pub mod codes {
     pub const DEPRECATED: &str = "W0002";
     /// Naming convention violation.
     pub const NAMING_CONVENTION: &str = "W0003";
+    /// Suppression comment that suppresses nothing.
+    pub const UNUSED_SUPPRESSION: &str = "W0004";
+    /// Usage typed by an abstract definition and never specialized.
+    pub const ABSTRACT_TYPED_USAGE: &str = "W0005";
 }
 
 // ============================================================================
@@ -199,9 +255,22 @@ impl DiagnosticCollector {
         self.diagnostics.push(diagnostic);
     }
 
+    /// Add a diagnostic, returning it so fixes can be attached.
+    fn push(&mut self, diagnostic: Diagnostic) -> &mut Diagnostic {
+        self.diagnostics.push(diagnostic);
+        self.diagnostics
+            .last_mut()
+            .expect("diagnostic was just pushed")
+    }
+
     /// Add an undefined reference error.
-    pub fn undefined_reference(&mut self, file: FileId, symbol: &HirSymbol, name: &str) {
-        self.add(
+    pub fn undefined_reference(
+        &mut self,
+        file: FileId,
+        symbol: &HirSymbol,
+        name: &str,
+    ) -> &mut Diagnostic {
+        self.push(
             Diagnostic::error(
                 file,
                 symbol.start_line,
@@ -210,7 +279,7 @@ impl DiagnosticCollector {
             )
             .with_span(symbol.end_line, symbol.end_col)
             .with_code(codes::UNDEFINED_REFERENCE),
-        );
+        )
     }
 
     /// Add an ambiguous reference error.
@@ -252,8 +321,13 @@ impl DiagnosticCollector {
     }
 
     /// Add a duplicate definition error.
-    pub fn duplicate_definition(&mut self, file: FileId, symbol: &HirSymbol, existing: &HirSymbol) {
-        self.add(
+    pub fn duplicate_definition(
+        &mut self,
+        file: FileId,
+        symbol: &HirSymbol,
+        existing: &HirSymbol,
+    ) -> &mut Diagnostic {
+        self.push(
             Diagnostic::error(
                 file,
                 symbol.start_line,
@@ -268,7 +342,7 @@ impl DiagnosticCollector {
                 col: existing.start_col,
                 message: Arc::from(format!("previous definition of '{}'", existing.name)),
             }),
-        );
+        )
     }
 
     /// Add a type mismatch error.
@@ -353,6 +427,27 @@ pub struct SemanticChecker<'a> {
     collector: DiagnosticCollector,
     /// Track which symbols are referenced (for unused detection).
     referenced: std::collections::HashSet<Arc<str>>,
+    /// Observed by workspace-wide checks so they can stop early.
+    cancel: CancellationToken,
+    /// Transitive generalizations computed by the feature checks.
+    generals: HashMap<Arc<str>, Vec<&'a HirSymbol>>,
+    /// Symbols whose generalizations are being computed, to stop at cycles.
+    generals_in_progress: std::collections::HashSet<Arc<str>>,
+    /// Whether a cycle cut short the generalizations being computed.
+    generals_incomplete: bool,
+    /// Features specialized anywhere in the index, for the abstract checks.
+    specialized: Option<abstracts::SpecializedFeatures>,
+    /// Lints run on every checked symbol.
+    lints: Arc<LintRegistry>,
+    /// Files checked so far, whose unused suppressions are reported.
+    checked_files: std::collections::HashSet<FileId>,
+    /// Syntax trees of the checked files, for fixes that edit declarations.
+    syntax: Option<fixes::SyntaxLookup<'a>>,
+    /// Text of the files fixes were built for, read once per file.
+    sources: HashMap<FileId, Arc<fixes::SourceText>>,
+    /// Range (start_line, start_col, end_line, end_col) whose undefined
+    /// references get "did you mean" fixes.
+    suggestions: Option<(u32, u32, u32, u32)>,
 }
 
 impl<'a> SemanticChecker<'a> {
@@ -362,35 +457,74 @@ impl<'a> SemanticChecker<'a> {
             index,
             collector: DiagnosticCollector::new(),
             referenced: std::collections::HashSet::new(),
+            cancel: CancellationToken::new(),
+            generals: HashMap::new(),
+            generals_in_progress: std::collections::HashSet::new(),
+            generals_incomplete: false,
+            specialized: None,
+            lints: Arc::new(LintRegistry::default()),
+            checked_files: std::collections::HashSet::new(),
+            syntax: None,
+            sources: HashMap::new(),
+            suggestions: None,
         }
     }
 
+    /// Run `lints` at their configured levels instead of the defaults.
+    pub fn with_lints(mut self, lints: Arc<LintRegistry>) -> Self {
+        self.lints = lints;
+        self
+    }
+
+    /// Stop [`check_file()`](Self::check_file) and [`check_all()`](Self::check_all)
+    /// early once `cancel` is signalled.
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     /// Check all symbols in a file.
-    pub fn check_file(&mut self, file: FileId) {
+    ///
+    /// Returns `Err(Cancelled)` if the checker's cancellation token is signalled
+    /// before all symbols were checked; diagnostics collected so far are kept.
+    pub fn check_file(&mut self, file: FileId) -> Cancellable<()> {
+        self.checked_files.insert(file);
         let symbols = self.index.symbols_in_file(file);
 
         // Pass 1: Check references and collect what's referenced
         for symbol in &symbols {
+            Cancelled::check(&self.cancel)?;
             self.check_symbol(symbol);
         }
 
         // Pass 2: Check for duplicates within this file
+        Cancelled::check(&self.cancel)?;
         self.check_duplicates(file, &symbols);
+
+        // Pass 3: Cycles through this file's symbols, wherever they lead
+        Cancelled::check(&self.cancel)?;
+        self.check_cycles(symbols.iter().copied(), Some(file));
+        Ok(())
     }
 
     /// Run all checks across the entire index (for workspace-wide diagnostics).
-    pub fn check_all(&mut self) {
+    ///
+    /// Returns `Err(Cancelled)` if the checker's cancellation token is signalled
+    /// before all symbols were checked; diagnostics collected so far are kept.
+    pub fn check_all(&mut self) -> Cancellable<()> {
         // Collect all symbols first
         let all_symbols: Vec<_> = self.index.all_symbols().cloned().collect();
 
         // Check each symbol
         for symbol in &all_symbols {
+            Cancelled::check(&self.cancel)?;
+            self.checked_files.insert(symbol.file);
             self.check_symbol(symbol);
         }
 
-        // Check for unused definitions (only meaningful after checking all references)
-        // Disabled by default as it can be noisy - uncomment to enable
-        // self.check_unused(&all_symbols);
+        Cancelled::check(&self.cancel)?;
+        self.check_cycles(&all_symbols, None);
+        Ok(())
     }
 
     /// Check a single symbol.
@@ -402,6 +536,13 @@ impl<'a> SemanticChecker<'a> {
 
         // Check type_refs based on their RefKind
         self.check_type_refs(symbol);
+        self.check_specialization_kinds(symbol);
+        self.check_features(symbol);
+        self.check_multiplicity(symbol);
+        self.check_abstract_instantiation(symbol);
+        self.check_import(symbol);
+        self.check_connection_endpoints(symbol);
+        self.check_lints(symbol);
     }
 
     /// Check type references in a symbol's body, filtering by RefKind.
@@ -426,7 +567,7 @@ impl<'a> SemanticChecker<'a> {
                     // Check based on reference kind
                     if tr.kind.is_type_reference() {
                         // Type references resolve via scope walking
-                        self.check_type_reference(symbol, &tr.target);
+                        self.check_type_reference(symbol, tr);
                     } else if tr.kind.is_feature_reference() {
                         // Feature references (Redefines, Subsets) resolve via inheritance
                         self.check_feature_reference(symbol, &tr.target);
@@ -657,7 +798,8 @@ impl<'a> SemanticChecker<'a> {
     }
 
     /// Check a type reference resolves correctly.
-    fn check_type_reference(&mut self, symbol: &HirSymbol, name: &str) {
+    fn check_type_reference(&mut self, symbol: &HirSymbol, tr: &TypeRef) {
+        let name = &*tr.target;
         let scope = Self::extract_scope(&symbol.qualified_name);
         let resolver = Resolver::new(self.index).with_scope(scope);
 
@@ -675,8 +817,10 @@ impl<'a> SemanticChecker<'a> {
                 // Also skip expression paths that contain dots (like "foo.bar.baz")
                 // These are member access expressions, not type references
                 if !Self::is_builtin_type(name) && !name.contains('.') {
+                    let fixes = self.undefined_reference_fixes(symbol, tr);
                     self.collector
-                        .undefined_reference(symbol.file, symbol, name);
+                        .undefined_reference(symbol.file, symbol, name)
+                        .fixes = fixes;
                 }
             }
         }
@@ -716,40 +860,16 @@ impl<'a> SemanticChecker<'a> {
                 // Report error on all but the first definition
                 let first = defs[0];
                 for dup in &defs[1..] {
-                    self.collector.duplicate_definition(file, dup, first);
+                    let fix = self.remove_declaration_fix(dup);
+                    self.collector
+                        .duplicate_definition(file, dup, first)
+                        .fixes
+                        .extend(fix);
                 }
             }
         }
     }
 
-    /// Check for unused definitions (optional, can be noisy).
-    #[allow(dead_code)]
-    fn check_unused(&mut self, symbols: &[HirSymbol]) {
-        for symbol in symbols {
-            // Only check definitions, not usages
-            if !symbol.kind.is_definition() {
-                continue;
-            }
-
-            // Skip packages - they're organizational
-            if symbol.kind == SymbolKind::Package {
-                continue;
-            }
-
-            // Skip if referenced
-            if self.referenced.contains(&symbol.qualified_name) {
-                continue;
-            }
-
-            // Skip if it has supertypes (might be used via specialization)
-            if !symbol.supertypes.is_empty() {
-                continue;
-            }
-
-            self.collector.unused_symbol(symbol);
-        }
-    }
-
     /// Check if a type name is a built-in/primitive that doesn't need resolution.
     ///
     /// This includes:
@@ -891,28 +1011,79 @@ impl<'a> SemanticChecker<'a> {
         }
     }
 
-    /// Get the collected diagnostics, deduplicated.
-    pub fn finish(self) -> Vec<Diagnostic> {
+    /// The simple references written in `symbol`'s own relationships.
+    ///
+    /// `type_refs` also carries the references of anonymous members (`return :
+    /// Real;`, `accept s : Signal`) and of each prefix of a qualified name, so
+    /// only those matching one of the symbol's relationships are kept.
+    fn own_type_refs(symbol: &HirSymbol) -> impl Iterator<Item = &TypeRef> {
+        symbol
+            .type_refs
+            .iter()
+            .filter_map(|type_ref| match type_ref {
+                TypeRefKind::Simple(tr) => Some(tr),
+                TypeRefKind::Chain(_) => None,
+            })
+            .filter(|tr| {
+                symbol.relationships.iter().any(|rel| {
+                    rel.start_line == tr.start_line
+                        && rel.start_col == tr.start_col
+                        && rel.target == tr.target
+                })
+            })
+    }
+
+    /// Get the collected diagnostics, deduplicated and without suppressed ones.
+    pub fn finish(mut self) -> Vec<Diagnostic> {
         let mut seen = std::collections::HashSet::new();
-        self.collector
-            .diagnostics
+        let diagnostics = std::mem::take(&mut self.collector.diagnostics)
             .into_iter()
             .filter(|d| {
                 // Deduplicate by (file, line, col, message)
                 let key = (d.file, d.start_line, d.start_col, d.message.clone());
                 seen.insert(key)
             })
-            .collect()
+            .collect();
+        self.apply_suppressions(diagnostics)
     }
 }
 
 /// Check a file and return diagnostics.
 pub fn check_file(index: &SymbolIndex, file: FileId) -> Vec<Diagnostic> {
     let mut checker = SemanticChecker::new(index);
-    checker.check_file(file);
+    // The checker's own token is never cancelled
+    let _ = checker.check_file(file);
     checker.finish()
 }
 
+/// Index `files` (path and source), with every type reference resolved.
+#[cfg(test)]
+pub(crate) fn index_for(files: &[(&str, &str)]) -> SymbolIndex {
+    let mut index = SymbolIndex::new();
+    for (i, (path, source)) in files.iter().enumerate() {
+        let syntax =
+            crate::syntax::parser::parse_content(source, std::path::Path::new(path)).unwrap();
+        let file = FileId::new(i as u32);
+        index.add_extraction_result(file, crate::hir::extract_with_filters(file, &syntax));
+    }
+    index.ensure_visibility_maps();
+    index.resolve_all_type_refs();
+    index
+}
+
+/// The diagnostics with `code` reported by checking `source` as a SysML file.
+#[cfg(test)]
+pub(crate) fn diagnostics_for(source: &str, code: &str) -> Vec<Diagnostic> {
+    let index = index_for(&[("test.sysml", source)]);
+    let mut checker = SemanticChecker::new(&index);
+    checker.check_all().unwrap();
+    checker
+        .finish()
+        .into_iter()
+        .filter(|d| d.code.as_deref() == Some(code))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -953,6 +1124,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -1051,4 +1223,21 @@ mod tests {
         // Should have no errors - reference resolves
         assert_eq!(diagnostics.len(), 0);
     }
+
+    #[test]
+    fn test_semantic_checker_cancelled() {
+        let mut index = SymbolIndex::new();
+        let wheel_def = make_symbol("Wheel", "Wheel", SymbolKind::PartDefinition, 0);
+        index.add_file(FileId::new(0), vec![wheel_def]);
+
+        let cancel = CancellationToken::new();
+        let mut checker = SemanticChecker::new(&index).with_cancellation(cancel.clone());
+        assert_eq!(checker.check_all(), Ok(()));
+        assert_eq!(checker.check_file(FileId::new(0)), Ok(()));
+
+        cancel.cancel();
+        let mut checker = SemanticChecker::new(&index).with_cancellation(cancel);
+        assert_eq!(checker.check_all(), Err(Cancelled));
+        assert_eq!(checker.check_file(FileId::new(0)), Err(Cancelled));
+    }
 }
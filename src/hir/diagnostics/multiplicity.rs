@@ -0,0 +1,243 @@
+//! Multiplicity bound consistency (E0015).
+//!
+//! A multiplicity's lower bound must not exceed its upper bound (`[5..2]`).
+//! Against the feature it's related to, a feature's multiplicity may be
+//! narrower but not wider:
+//!
+//! - a redefinition must stay within both bounds of the redefined feature;
+//! - a subsetting feature can't have more values than the subsetted feature;
+//! - a connector end can't connect more values than the connected feature has.
+//!
+//! Only literal bounds are compared; bounds given by an expression leave the
+//! multiplicity unknown.
+
+use super::{Diagnostic, SemanticChecker, codes};
+use crate::hir::symbols::{HirSymbol, RefKind, TypeRef, TypeRefKind};
+use crate::parser::Multiplicity;
+
+impl<'a> SemanticChecker<'a> {
+    pub(super) fn check_multiplicity(&mut self, symbol: &HirSymbol) {
+        let Some(multiplicity) = &symbol.multiplicity else {
+            return;
+        };
+        if is_inverted(multiplicity) {
+            let message = format!(
+                "invalid multiplicity: lower bound of {} on '{}' exceeds its upper bound",
+                display_multiplicity(multiplicity),
+                symbol.name
+            );
+            self.collector.add(
+                Diagnostic::error(symbol.file, symbol.start_line, symbol.start_col, message)
+                    .with_span(symbol.end_line, symbol.end_col)
+                    .with_code(codes::INVALID_MULTIPLICITY),
+            );
+            return;
+        }
+
+        if symbol.is_end {
+            self.check_end_multiplicity(symbol, multiplicity);
+        }
+    }
+
+    /// Check a redefinition keeps within the redefined feature's bounds.
+    pub(super) fn check_redefined_multiplicity(
+        &mut self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+        redefined: &HirSymbol,
+    ) {
+        let Some((narrow, wide)) = self.comparable(symbol, redefined) else {
+            return;
+        };
+        if lower(narrow) < lower(wide) || exceeds_upper(narrow, wide) {
+            let message = format!(
+                "invalid multiplicity: {} of '{}' is wider than {} of redefined feature '{}'",
+                display_multiplicity(narrow),
+                symbol.name,
+                display_multiplicity(wide),
+                redefined.qualified_name
+            );
+            self.report(symbol, tr, message, codes::INVALID_MULTIPLICITY, redefined);
+        }
+    }
+
+    /// Check a subsetting feature has no more values than the subsetted one.
+    pub(super) fn check_subsetted_multiplicity(
+        &mut self,
+        symbol: &HirSymbol,
+        tr: &TypeRef,
+        subsetted: &HirSymbol,
+    ) {
+        let Some((narrow, wide)) = self.comparable(symbol, subsetted) else {
+            return;
+        };
+        if exceeds_upper(narrow, wide) {
+            let message = format!(
+                "invalid multiplicity: upper bound of {} on '{}' exceeds {} of subsetted feature '{}'",
+                display_multiplicity(narrow),
+                symbol.name,
+                display_multiplicity(wide),
+                subsetted.qualified_name
+            );
+            self.report(symbol, tr, message, codes::INVALID_MULTIPLICITY, subsetted);
+        }
+    }
+
+    /// Both features' multiplicities, if both are given and consistent.
+    ///
+    /// Features from KerML files are skipped: they're mostly reached through
+    /// implicit supertypes, which only approximate the library's (a connection
+    /// definition always specializes `BinaryConnection`).
+    fn comparable<'s>(
+        &self,
+        feature: &'s HirSymbol,
+        general: &'s HirSymbol,
+    ) -> Option<(&'s Multiplicity, &'s Multiplicity)> {
+        if self.index.is_kerml_file(general.file) {
+            return None;
+        }
+        let narrow = feature.multiplicity.as_ref()?;
+        let wide = general.multiplicity.as_ref()?;
+        (!is_inverted(narrow) && !is_inverted(wide)).then_some((narrow, wide))
+    }
+
+    /// Check a connector end (`connect [2] e ::> wheels`, `end [2] part e
+    /// ::> wheels;`) connects no more values than the connected feature has.
+    fn check_end_multiplicity(&mut self, symbol: &HirSymbol, multiplicity: &Multiplicity) {
+        let index = self.index;
+        let Some((tr, connected)) = symbol.type_refs.iter().find_map(|type_ref| {
+            // The connected feature is the last part of a chain (`wheel1.lugNutPort`)
+            let tr = match type_ref {
+                TypeRefKind::Simple(tr) => tr,
+                TypeRefKind::Chain(chain) => chain.parts.last()?,
+            };
+            if tr.kind != RefKind::References {
+                return None;
+            }
+            Some((tr, index.lookup_qualified(tr.resolved_target.as_deref()?)?))
+        }) else {
+            return;
+        };
+        let Some(connected_multiplicity) = &connected.multiplicity else {
+            return;
+        };
+        if connected.qualified_name == symbol.qualified_name || is_inverted(connected_multiplicity)
+        {
+            return;
+        }
+
+        if exceeds_upper(multiplicity, connected_multiplicity) {
+            let message = format!(
+                "invalid multiplicity: end '{}' connects {} but connected feature '{}' has {}",
+                symbol.name,
+                display_multiplicity(multiplicity),
+                connected.qualified_name,
+                display_multiplicity(connected_multiplicity)
+            );
+            self.report(symbol, tr, message, codes::INVALID_MULTIPLICITY, connected);
+        }
+    }
+}
+
+/// Whether the lower bound exceeds the upper one (`[5..2]`).
+fn is_inverted(multiplicity: &Multiplicity) -> bool {
+    matches!(multiplicity, Multiplicity { lower: Some(lower), upper: Some(upper) } if lower > upper)
+}
+
+/// The lower bound, where `[*]` has a lower bound of 0.
+fn lower(multiplicity: &Multiplicity) -> u64 {
+    multiplicity.lower.unwrap_or(0)
+}
+
+/// Whether `narrow` allows more values than `wide`'s upper bound.
+fn exceeds_upper(narrow: &Multiplicity, wide: &Multiplicity) -> bool {
+    match (narrow.upper, wide.upper) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(upper), Some(wide_upper)) => upper > wide_upper,
+    }
+}
+
+fn display_multiplicity(multiplicity: &Multiplicity) -> String {
+    let bound = |bound: Option<u64>| bound.map_or_else(|| "*".to_string(), |b| b.to_string());
+    match (multiplicity.lower, multiplicity.upper) {
+        (lower, upper) if lower == upper => format!("[{}]", bound(lower)),
+        (lower, upper) => format!("[{}..{}]", bound(lower), bound(upper)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::diagnostics_for;
+
+    fn multiplicity_messages(source: &str) -> Vec<String> {
+        diagnostics_for(source, codes::INVALID_MULTIPLICITY)
+            .into_iter()
+            .map(|d| d.message.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_inverted_bounds() {
+        let messages = multiplicity_messages(
+            "package P { part def Wheel; part car { part wheels : Wheel[5..2]; part spare : Wheel[0..1]; } }",
+        );
+        assert_eq!(
+            messages,
+            ["invalid multiplicity: lower bound of [5..2] on 'wheels' exceeds its upper bound"]
+        );
+    }
+
+    #[test]
+    fn test_expression_bounds_are_not_compared() {
+        let messages = multiplicity_messages(
+            "package P { part def Wheel; attribute n = 4; \
+             part def Vehicle { part wheels : Wheel[2..4]; } \
+             part def Car :> Vehicle { part :>> wheels[1..n-1]; } }",
+        );
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+
+    #[test]
+    fn test_redefinition_widening_multiplicity() {
+        let messages = multiplicity_messages(
+            "package P { part def Wheel; part def Vehicle { part wheels : Wheel[2..4]; } \
+             part def Car :> Vehicle { part :>> wheels[5]; } \
+             part def Cart :> Vehicle { part :>> wheels[0..*]; } \
+             part def Bike :> Vehicle { part :>> wheels[2]; } }",
+        );
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].contains("[5] of 'wheels' is wider than [2..4]"));
+        assert!(messages[1].contains("[0..*] of 'wheels' is wider than [2..4]"));
+    }
+
+    #[test]
+    fn test_subsetting_exceeds_upper_bound() {
+        let messages = multiplicity_messages(
+            "package P { part def Wheel; part car { part wheels : Wheel[4]; \
+             part driven : Wheel[0..6] :> wheels; part front : Wheel[0..2] :> wheels; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid multiplicity: upper bound of [0..6] on 'driven' exceeds [4] of subsetted feature 'P::car::wheels'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_connection_end_exceeds_connected_feature() {
+        let messages = multiplicity_messages(
+            "package P { part def Wheel; part def Axle; part car { \
+             part axle : Axle[1]; part wheels : Wheel[4]; \
+             connect [1] a ::> axle to [5] w ::> wheels; } }",
+        );
+        assert_eq!(
+            messages,
+            [
+                "invalid multiplicity: end 'w' connects [5] but connected feature 'P::car::wheels' has [4]"
+            ]
+        );
+    }
+}
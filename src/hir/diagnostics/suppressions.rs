@@ -0,0 +1,336 @@
+//! Inline diagnostic suppression comments (W0004).
+//!
+//! A comment of the form `// syster-ignore E0001` (or `/* ... */`) suppresses
+//! diagnostics with the listed codes reported inside the element that follows
+//! it; placed before a package, it covers the whole package.
+//! `syster-ignore-next-line` only covers the line after the comment:
+//!
+//! ```sysml
+//! // syster-ignore E0001, W0003 -- generated from the legacy model
+//! part def legacy_engine :> OldEngine;
+//!
+//! /* syster-ignore-next-line W0003 */
+//! part def fuel_tank;
+//! ```
+//!
+//! Without codes, every diagnostic is suppressed. Anything after `--` is a
+//! reason and ignored. Suppressions that don't suppress anything in a checked
+//! file are reported, so they don't outlive the problems they hid; codes of
+//! lints that are allowed (and so never reported) aren't considered unused.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Diagnostic, LintLevel, SemanticChecker, codes};
+use crate::base::LineIndex;
+use crate::parser::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+const IGNORE: &str = "syster-ignore";
+const IGNORE_NEXT_LINE: &str = "syster-ignore-next-line";
+
+/// A `syster-ignore` comment, collected during symbol extraction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub struct Suppression {
+    /// Codes suppressed; empty suppresses every diagnostic.
+    pub codes: Vec<Arc<str>>,
+    /// Start line of the comment (0-indexed).
+    pub line: u32,
+    /// Start column of the comment (0-indexed).
+    pub col: u32,
+    /// End line of the comment (0-indexed).
+    pub end_line: u32,
+    /// End column of the comment (0-indexed).
+    pub end_col: u32,
+    /// Start and end `(line, col)` of the range diagnostics are suppressed
+    /// in, or `None` if nothing follows the comment.
+    pub scope: Option<((u32, u32), (u32, u32))>,
+}
+
+impl Suppression {
+    /// Whether `diagnostic` starts in the suppressed range and has a suppressed code.
+    pub fn suppresses(&self, diagnostic: &Diagnostic) -> bool {
+        let Some((start, end)) = self.scope else {
+            return false;
+        };
+        let position = (diagnostic.start_line, diagnostic.start_col);
+        if position < start || position > end {
+            return false;
+        }
+        self.codes.is_empty()
+            || diagnostic
+                .code
+                .as_deref()
+                .is_some_and(|code| self.codes.iter().any(|c| &**c == code))
+    }
+}
+
+/// Collect the suppression comments of a parsed file.
+pub(crate) fn collect_suppressions(root: &SyntaxNode, line_index: &LineIndex) -> Vec<Suppression> {
+    root.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter_map(|token| suppression(&token, line_index))
+        .collect()
+}
+
+fn suppression(token: &SyntaxToken, line_index: &LineIndex) -> Option<Suppression> {
+    let text = match token.kind() {
+        SyntaxKind::LINE_COMMENT => token.text().strip_prefix("//")?,
+        SyntaxKind::BLOCK_COMMENT => {
+            let text = token.text().strip_prefix("/*")?;
+            text.strip_suffix("*/").unwrap_or(text)
+        }
+        _ => return None,
+    };
+    let text = text.trim_start_matches(['/', '*']).trim_start();
+    let (next_line, rest) = if let Some(rest) = text.strip_prefix(IGNORE_NEXT_LINE) {
+        (true, rest)
+    } else {
+        (false, text.strip_prefix(IGNORE)?)
+    };
+    // `syster-ignored` isn't a directive
+    if rest.starts_with(|c: char| !c.is_whitespace() && c != ',') {
+        return None;
+    }
+    let codes_text = rest.split("--").next().unwrap_or_default();
+    let codes = codes_text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|code| !code.is_empty())
+        .map(Arc::from)
+        .collect();
+
+    let range = token.text_range();
+    let start = line_index.line_col(range.start());
+    let end = line_index.line_col(range.end());
+    let scope = if next_line {
+        Some(((end.line + 1, 0), (end.line + 1, u32::MAX)))
+    } else {
+        following_element(token).map(|node| {
+            let range = node.text_range();
+            let start = line_index.line_col(range.start());
+            let end = line_index.line_col(range.end());
+            ((start.line, start.col), (end.line, end.col))
+        })
+    };
+    Some(Suppression {
+        codes,
+        line: start.line,
+        col: start.col,
+        end_line: end.line,
+        end_col: end.col,
+        scope,
+    })
+}
+
+/// The element after `token`, skipping whitespace and other comments.
+fn following_element(token: &SyntaxToken) -> Option<SyntaxNode> {
+    let mut next = token.next_sibling_or_token();
+    while let Some(element) = next {
+        if !element.kind().is_trivia() {
+            return element.into_node();
+        }
+        next = element.next_sibling_or_token();
+    }
+    None
+}
+
+impl<'a> SemanticChecker<'a> {
+    /// Drop suppressed diagnostics and report the unused suppressions of the
+    /// checked files.
+    pub(super) fn apply_suppressions(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let mut used: HashMap<_, Vec<bool>> = HashMap::new();
+        let mut kept: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                let suppressions = self.index.suppressions(diagnostic.file);
+                if suppressions.is_empty() {
+                    return true;
+                }
+                let used = used
+                    .entry(diagnostic.file)
+                    .or_insert_with(|| vec![false; suppressions.len()]);
+                let mut suppressed = false;
+                for (suppression, used) in suppressions.iter().zip(used.iter_mut()) {
+                    if suppression.suppresses(diagnostic) {
+                        *used = true;
+                        suppressed = true;
+                    }
+                }
+                !suppressed
+            })
+            .collect();
+
+        let mut files: Vec<_> = self.checked_files.iter().copied().collect();
+        files.sort();
+        for file in files {
+            let used = used.get(&file);
+            for (i, suppression) in self.index.suppressions(file).iter().enumerate() {
+                if used.is_some_and(|used| used[i]) {
+                    continue;
+                }
+                // An allowed lint has nothing to report, whether or not its
+                // problems were fixed
+                let codes: Vec<&str> = suppression
+                    .codes
+                    .iter()
+                    .map(|code| &**code)
+                    .filter(|code| self.lints.level(code) != Some(LintLevel::Allow))
+                    .collect();
+                let message = if suppression.codes.is_empty() {
+                    "unused suppression: no diagnostics to suppress".to_string()
+                } else if codes.is_empty() {
+                    continue;
+                } else {
+                    format!(
+                        "unused suppression: no {} diagnostics to suppress",
+                        codes.join(", ")
+                    )
+                };
+                kept.push(
+                    Diagnostic::warning(file, suppression.line, suppression.col, message)
+                        .with_span(suppression.end_line, suppression.end_col)
+                        .with_code(codes::UNUSED_SUPPRESSION),
+                );
+            }
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::FileId;
+    use crate::hir::diagnostics::{LintLevel, LintRegistry, index_for};
+    use crate::syntax::parser::parse_content;
+
+    fn diagnostics(source: &str) -> Vec<Diagnostic> {
+        diagnostics_with_lints(source, LintRegistry::default())
+    }
+
+    fn diagnostics_with_lints(source: &str, lints: LintRegistry) -> Vec<Diagnostic> {
+        let index = index_for(&[("test.sysml", source)]);
+        let mut checker = SemanticChecker::new(&index).with_lints(Arc::new(lints));
+        checker.check_file(FileId::new(0)).unwrap();
+        checker.finish()
+    }
+
+    fn found(diagnostics: &[Diagnostic]) -> Vec<(&str, u32)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.code.as_deref().unwrap_or_default(), d.start_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_directives() {
+        let source = "// syster-ignore E0001, W0003 -- legacy\n\
+                      /* syster-ignore-next-line */\n\
+                      // syster-ignored E0001\n\
+                      part def A;";
+        let syntax = parse_content(source, std::path::Path::new("test.sysml")).unwrap();
+        let suppressions = collect_suppressions(&syntax.parse().syntax(), &LineIndex::new(source));
+        assert_eq!(suppressions.len(), 2);
+        assert_eq!(
+            suppressions[0].codes,
+            [Arc::from("E0001"), Arc::from("W0003")]
+        );
+        assert_eq!(suppressions[0].scope, Some(((3, 0), (3, 11))));
+        assert!(suppressions[1].codes.is_empty());
+        assert_eq!(suppressions[1].scope, Some(((2, 0), (2, u32::MAX))));
+    }
+
+    #[test]
+    fn test_ignore_following_element() {
+        let diagnostics = diagnostics(
+            "package P {\n\
+             \x20   // syster-ignore E0001\n\
+             \x20   part def A :> Missing { part x : AlsoMissing; }\n\
+             \x20   part def B :> Missing;\n\
+             }",
+        );
+        assert_eq!(found(&diagnostics), [(codes::UNDEFINED_REFERENCE, 3)]);
+    }
+
+    #[test]
+    fn test_ignore_package() {
+        let diagnostics = diagnostics(
+            "/* syster-ignore */\n\
+             package Legacy {\n\
+             \x20   part def old_engine :> Missing;\n\
+             }\n\
+             package P { part def B :> Missing; }",
+        );
+        assert_eq!(found(&diagnostics), [(codes::UNDEFINED_REFERENCE, 4)]);
+    }
+
+    #[test]
+    fn test_ignore_next_line_by_code() {
+        let mut lints = LintRegistry::default();
+        lints.set_level("naming-convention", LintLevel::Warning);
+        let diagnostics = diagnostics_with_lints(
+            "package P {\n\
+             \x20   // syster-ignore-next-line W0003\n\
+             \x20   part def fuel_tank :> Missing;\n\
+             \x20   part def other_tank;\n\
+             }",
+            lints,
+        );
+        assert_eq!(
+            found(&diagnostics),
+            [
+                (codes::UNDEFINED_REFERENCE, 2),
+                (codes::NAMING_CONVENTION, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unused_suppressions() {
+        let diagnostics = diagnostics(
+            "package P {\n\
+             \x20   // syster-ignore E0001\n\
+             \x20   part def Engine;\n\
+             \x20   // syster-ignore\n\
+             }",
+        );
+        assert_eq!(
+            found(&diagnostics),
+            [
+                (codes::UNUSED_SUPPRESSION, 1),
+                (codes::UNUSED_SUPPRESSION, 3)
+            ]
+        );
+        assert_eq!(
+            &*diagnostics[0].message,
+            "unused suppression: no E0001 diagnostics to suppress"
+        );
+    }
+
+    #[test]
+    fn test_suppressions_of_allowed_lints_are_not_unused() {
+        // `naming-convention` is allowed by default
+        let source = "package P {\n\
+                      \x20   // syster-ignore W0003 -- legacy names\n\
+                      \x20   part def fuel_tank;\n\
+                      \x20   // syster-ignore W0003, E0001\n\
+                      \x20   part def Engine;\n\
+                      }";
+        let diagnostics = diagnostics(source);
+        assert_eq!(found(&diagnostics), [(codes::UNUSED_SUPPRESSION, 3)]);
+        assert_eq!(
+            &*diagnostics[0].message,
+            "unused suppression: no E0001 diagnostics to suppress"
+        );
+
+        // Once the lint runs, the first suppression is used and the second isn't
+        let mut lints = LintRegistry::default();
+        lints.set_level("naming-convention", LintLevel::Warning);
+        let diagnostics = diagnostics_with_lints(source, lints);
+        assert_eq!(found(&diagnostics), [(codes::UNUSED_SUPPRESSION, 3)]);
+        assert_eq!(
+            &*diagnostics[0].message,
+            "unused suppression: no W0003, E0001 diagnostics to suppress"
+        );
+    }
+}
@@ -0,0 +1,782 @@
+//! Constant evaluation of value expressions.
+//!
+//! The [`Evaluator`] folds feature values such as `mass = 2 * wheelMass + 10`
+//! by resolving the names they reference through the [`SymbolIndex`] and
+//! evaluating those features' own values in turn. Anything that isn't a
+//! compile-time constant (an unbound feature, an unknown function, a cycle)
+//! evaluates to `None`.
+//!
+//! Each feature's value is computed once per evaluator, so features sharing
+//! references cost linear time, and the work done for one evaluator is capped
+//! by a step budget.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use super::{HirSymbol, Resolver, SymbolIndex};
+use crate::parser::{
+    Argument, BinaryOp, ClassificationOp, Expr, Literal, UnaryOp, ValueExpression,
+};
+
+/// Longest chain of features referencing each other's values that is followed.
+const MAX_DEPTH: usize = 64;
+
+/// Largest sequence a range expression like `1..n` expands to.
+const MAX_RANGE_LEN: i64 = 10_000;
+
+/// Most expressions one evaluator evaluates before giving up.
+const MAX_STEPS: usize = 1_000_000;
+
+/// Steps between two checks of the cancellation token.
+const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// The value of a constant expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    /// Several values, e.g. `(1, 2, 3)`; never empty or a single value
+    Sequence(Vec<Value>),
+    /// A number with its unit, e.g. `5 [kg]`
+    Quantity {
+        value: Box<Value>,
+        unit: String,
+    },
+}
+
+impl Value {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        Some(match literal {
+            Literal::Integer(v) => Value::Integer(*v),
+            Literal::Real(v) => Value::Real(*v),
+            Literal::String(v) => Value::String(v.clone()),
+            Literal::Boolean(v) => Value::Boolean(*v),
+            Literal::Null => Value::Null,
+            Literal::Infinity => return None,
+        })
+    }
+
+    /// Build a value from its items, flattening nested sequences.
+    fn from_items(items: Vec<Value>) -> Self {
+        let mut flat: Vec<Value> = items.into_iter().flat_map(Value::into_items).collect();
+        match flat.len() {
+            0 => Value::Null,
+            1 => flat.pop().unwrap_or(Value::Null),
+            _ => Value::Sequence(flat),
+        }
+    }
+
+    /// The items of this value, treating a single value as a one-element sequence.
+    fn into_items(self) -> Vec<Value> {
+        match self {
+            Value::Null => Vec::new(),
+            Value::Sequence(items) => items,
+            value => vec![value],
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(v) => Some(*v as f64),
+            Value::Real(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Value::Integer(_) | Value::Real(_))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(v) => write!(f, "{v}"),
+            Value::Real(v) => write!(f, "{v:?}"),
+            Value::String(v) => write!(f, "\"{v}\""),
+            Value::Boolean(v) => write!(f, "{v}"),
+            Value::Null => f.write_str("null"),
+            Value::Sequence(items) => {
+                f.write_str("(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str(")")
+            }
+            Value::Quantity { value, unit } => write!(f, "{value} [{unit}]"),
+        }
+    }
+}
+
+/// Evaluates value expressions against a symbol index.
+pub struct Evaluator<'a> {
+    index: &'a SymbolIndex,
+    /// Features whose values are being evaluated, to stop at reference cycles
+    in_progress: Vec<Arc<str>>,
+    /// Values of the features evaluated so far, by qualified name
+    values: HashMap<Arc<str>, Option<Value>>,
+    /// Whether a value was cut off by [`MAX_DEPTH`], and so must not be cached
+    truncated: bool,
+    /// Expressions evaluated so far, bounded by [`MAX_STEPS`]
+    steps: usize,
+    /// Checked every [`CANCEL_CHECK_INTERVAL`] steps
+    cancel: CancellationToken,
+    /// Parameters bound while evaluating the body of a collection operation
+    locals: Vec<(String, Value)>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Create an evaluator over `index`.
+    pub fn new(index: &'a SymbolIndex) -> Self {
+        Self {
+            index,
+            in_progress: Vec::new(),
+            values: HashMap::new(),
+            truncated: false,
+            steps: 0,
+            cancel: CancellationToken::new(),
+            locals: Vec::new(),
+        }
+    }
+
+    /// Stop evaluating (and evaluate to `None`) once `cancel` is signalled.
+    pub fn with_cancellation(mut self, cancel: &CancellationToken) -> Self {
+        self.cancel = cancel.clone();
+        self
+    }
+
+    /// Evaluate the value assigned to a feature.
+    ///
+    /// Names in the value resolve from the feature's owning namespace.
+    pub fn evaluate_symbol(&mut self, symbol: &HirSymbol) -> Option<Value> {
+        match symbol.value.as_ref()? {
+            ValueExpression::LiteralInteger(v) => Some(Value::Integer(*v)),
+            ValueExpression::LiteralReal(v) => Some(Value::Real(*v)),
+            ValueExpression::LiteralString(v) => Some(Value::String(v.clone())),
+            ValueExpression::LiteralBoolean(v) => Some(Value::Boolean(*v)),
+            ValueExpression::Null => Some(Value::Null),
+            ValueExpression::Expression { expr, .. } => {
+                if let Some(value) = self.values.get(&symbol.qualified_name) {
+                    return value.clone();
+                }
+                if self.in_progress.len() >= MAX_DEPTH {
+                    self.truncated = true;
+                    return None;
+                }
+                if self.in_progress.contains(&symbol.qualified_name) {
+                    return None;
+                }
+                let scope = symbol
+                    .qualified_name
+                    .rsplit_once("::")
+                    .map_or("", |(owner, _)| owner);
+
+                // Locals of an enclosing body aren't visible in another feature's value
+                let locals = std::mem::take(&mut self.locals);
+                let truncated = std::mem::take(&mut self.truncated);
+                self.in_progress.push(symbol.qualified_name.clone());
+                let value = self.evaluate(expr, scope);
+                self.in_progress.pop();
+                self.locals = locals;
+
+                // A value cut off by the depth limit may still evaluate when
+                // reached through a shorter chain
+                if !self.truncated {
+                    self.values
+                        .insert(symbol.qualified_name.clone(), value.clone());
+                }
+                self.truncated |= truncated;
+                value
+            }
+        }
+    }
+
+    /// Evaluate an expression, resolving names from `scope`.
+    pub fn evaluate(&mut self, expr: &Expr, scope: &str) -> Option<Value> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS
+            || (self.steps % CANCEL_CHECK_INTERVAL == 0 && self.cancel.is_cancelled())
+        {
+            // Stay exhausted: every later expression evaluates to `None` too
+            self.steps = MAX_STEPS + 1;
+            return None;
+        }
+        match expr {
+            Expr::Literal(literal) => Value::from_literal(literal),
+            Expr::Name(name) => {
+                if let Some((_, value)) = self.locals.iter().rev().find(|(local, _)| local == name)
+                {
+                    return Some(value.clone());
+                }
+                let symbol = self.resolve(expr, scope)?;
+                self.evaluate_symbol(&symbol)
+            }
+            Expr::Member { .. } => {
+                let symbol = self.resolve(expr, scope)?;
+                self.evaluate_symbol(&symbol)
+            }
+            Expr::Unary { op, operand } => {
+                let operand = self.evaluate(operand, scope)?;
+                unary(*op, operand)
+            }
+            Expr::Binary { op, lhs, rhs } => self.binary(*op, lhs, rhs, scope),
+            Expr::Classification {
+                op: ClassificationOp::As,
+                operand: Some(operand),
+                ..
+            } => self.evaluate(operand, scope),
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => match self.evaluate(condition, scope)? {
+                Value::Boolean(true) => self.evaluate(then_branch, scope),
+                Value::Boolean(false) => match else_branch {
+                    Some(else_branch) => self.evaluate(else_branch, scope),
+                    None => Some(Value::Null),
+                },
+                _ => None,
+            },
+            Expr::Invocation { target, args } => {
+                let Expr::Name(name) = target.as_ref() else {
+                    return None;
+                };
+                let function = name.rsplit("::").next().unwrap_or(name);
+                self.call(function, None, args, scope)
+            }
+            Expr::CollectionCall { target, name, args } => {
+                let receiver = self.evaluate(target, scope)?;
+                self.call(name, Some(receiver), args, scope)
+            }
+            Expr::Sequence(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.evaluate(item, scope))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Value::from_items(items))
+            }
+            Expr::Index { target, indices } => {
+                let [index] = indices.as_slice() else {
+                    return None;
+                };
+                let items = self.evaluate(target, scope)?.into_items();
+                match self.evaluate(index, scope)? {
+                    Value::Integer(i) if i >= 1 => items.into_iter().nth(i as usize - 1),
+                    _ => None,
+                }
+            }
+            Expr::Bracket { target, index } => {
+                let Expr::Name(unit) = index.as_ref() else {
+                    return None;
+                };
+                let value = self.evaluate(target, scope)?;
+                value.is_number().then(|| Value::Quantity {
+                    value: Box::new(value),
+                    unit: unit.clone(),
+                })
+            }
+            Expr::Classification { .. }
+            | Expr::Exists { .. }
+            | Expr::Body { .. }
+            | Expr::New { .. }
+            | Expr::Error => None,
+        }
+    }
+
+    /// Resolve a name or feature chain to the feature it references.
+    fn resolve(&self, expr: &Expr, scope: &str) -> Option<HirSymbol> {
+        let mut parts = Vec::new();
+        let mut current = expr;
+        loop {
+            match current {
+                Expr::Name(name) => {
+                    parts.push(Arc::from(name.as_str()));
+                    break;
+                }
+                Expr::Member { target, feature } => {
+                    parts.push(Arc::from(feature.as_str()));
+                    current = target;
+                }
+                _ => return None,
+            }
+        }
+        parts.reverse();
+
+        if parts.len() == 1 {
+            return Resolver::new(self.index)
+                .with_scope(scope)
+                .resolve(&parts[0])
+                .symbol()
+                .cloned();
+        }
+        let qualified_name =
+            self.index
+                .resolve_feature_chain_member(scope, &parts, parts.len() - 1)?;
+        self.index.lookup_qualified(&qualified_name).cloned()
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: &Expr, rhs: &Expr, scope: &str) -> Option<Value> {
+        let lhs = self.evaluate(lhs, scope)?;
+
+        // Conditional operators only evaluate their right-hand side when needed
+        match (op, &lhs) {
+            (BinaryOp::And, Value::Boolean(false)) => return Some(Value::Boolean(false)),
+            (BinaryOp::Or, Value::Boolean(true)) => return Some(Value::Boolean(true)),
+            (BinaryOp::Implies, Value::Boolean(false)) => return Some(Value::Boolean(true)),
+            (BinaryOp::NullCoalesce, Value::Null) => return self.evaluate(rhs, scope),
+            (BinaryOp::NullCoalesce, _) => return Some(lhs),
+            _ => {}
+        }
+
+        let rhs = self.evaluate(rhs, scope)?;
+        binary(op, lhs, rhs)
+    }
+
+    /// Call a built-in function, with `receiver` as the first argument of a
+    /// `->` invocation.
+    fn call(
+        &mut self,
+        name: &str,
+        receiver: Option<Value>,
+        args: &[Argument],
+        scope: &str,
+    ) -> Option<Value> {
+        // Collection operations taking a body: `xs->select { in x; x > 1 }`
+        if let [
+            Argument {
+                value: Expr::Body { params, result },
+                ..
+            },
+        ] = args
+        {
+            let items = receiver?.into_items();
+            let param = params.first()?;
+            let mut selected = Vec::new();
+            let mut results = Vec::new();
+            for item in items {
+                self.locals.push((param.clone(), item.clone()));
+                let result = self.evaluate(result, scope);
+                self.locals.pop();
+                match name {
+                    "collect" => results.push(result?),
+                    _ => match result? {
+                        Value::Boolean(keep) => {
+                            results.push(Value::Boolean(keep));
+                            if keep == (name != "reject") {
+                                selected.push(item);
+                            }
+                        }
+                        _ => return None,
+                    },
+                }
+            }
+            let all = |value: bool| results.iter().all(|r| *r == Value::Boolean(value));
+            return match name {
+                "select" | "reject" => Some(Value::from_items(selected)),
+                "collect" => Some(Value::from_items(results)),
+                "forAll" => Some(Value::Boolean(all(true))),
+                "exists" => Some(Value::Boolean(!all(false))),
+                _ => None,
+            };
+        }
+
+        let mut values = Vec::with_capacity(args.len() + 1);
+        values.extend(receiver);
+        for arg in args {
+            values.push(self.evaluate(&arg.value, scope)?);
+        }
+
+        match (name, values.as_slice()) {
+            ("size", [seq]) => Some(Value::Integer(seq.clone().into_items().len() as i64)),
+            ("isEmpty", [seq]) => Some(Value::Boolean(*seq == Value::Null)),
+            ("notEmpty", [seq]) => Some(Value::Boolean(*seq != Value::Null)),
+            ("first", [seq]) => seq.clone().into_items().into_iter().next(),
+            ("last", [seq]) => seq.clone().into_items().pop(),
+            ("includes", [seq, value]) => Some(Value::Boolean(
+                seq.clone()
+                    .into_items()
+                    .iter()
+                    .any(|item| values_equal(item, value)),
+            )),
+            ("excludes", [seq, value]) => Some(Value::Boolean(
+                !seq.clone()
+                    .into_items()
+                    .iter()
+                    .any(|item| values_equal(item, value)),
+            )),
+            ("abs", [value]) => match value {
+                Value::Integer(v) => v.checked_abs().map(Value::Integer),
+                Value::Real(v) => Some(Value::Real(v.abs())),
+                _ => None,
+            },
+            ("sum", [seq]) => fold(seq, Value::Integer(0), BinaryOp::Add),
+            ("product", [seq]) => fold(seq, Value::Integer(1), BinaryOp::Mul),
+            ("max" | "min", _) => {
+                let items: Vec<_> = values.iter().cloned().flat_map(Value::into_items).collect();
+                let wanted = if name == "max" {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                };
+                let mut items = items.into_iter();
+                let mut best = items.next()?;
+                for item in items {
+                    if compare(&item, &best)? == wanted {
+                        best = item;
+                    }
+                }
+                Some(best)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn fold(seq: &Value, init: Value, op: BinaryOp) -> Option<Value> {
+    seq.clone()
+        .into_items()
+        .into_iter()
+        .try_fold(init, |acc, item| binary(op, acc, item))
+}
+
+fn unary(op: UnaryOp, operand: Value) -> Option<Value> {
+    match (op, operand) {
+        (UnaryOp::Plus, value) if value.is_number() => Some(value),
+        (UnaryOp::Minus, Value::Integer(v)) => v.checked_neg().map(Value::Integer),
+        (UnaryOp::Minus, Value::Real(v)) => Some(Value::Real(-v)),
+        (UnaryOp::Minus, Value::Quantity { value, unit }) => Some(Value::Quantity {
+            value: Box::new(unary(UnaryOp::Minus, *value)?),
+            unit,
+        }),
+        (UnaryOp::Not, Value::Boolean(v)) => Some(Value::Boolean(!v)),
+        _ => None,
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value) -> Option<Value> {
+    match op {
+        BinaryOp::Add
+        | BinaryOp::Sub
+        | BinaryOp::Mul
+        | BinaryOp::Div
+        | BinaryOp::Rem
+        | BinaryOp::Pow => arithmetic(op, lhs, rhs),
+        BinaryOp::Eq | BinaryOp::Same => Some(Value::Boolean(values_equal(&lhs, &rhs))),
+        BinaryOp::NotEq | BinaryOp::NotSame => Some(Value::Boolean(!values_equal(&lhs, &rhs))),
+        BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+            let ordering = compare(&lhs, &rhs)?;
+            Some(Value::Boolean(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::LtEq => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Xor | BinaryOp::Implies => {
+            let (Value::Boolean(a), Value::Boolean(b)) = (lhs, rhs) else {
+                return None;
+            };
+            Some(Value::Boolean(match op {
+                BinaryOp::And => a && b,
+                BinaryOp::Or => a || b,
+                BinaryOp::Xor => a != b,
+                _ => !a || b,
+            }))
+        }
+        BinaryOp::NullCoalesce => Some(if lhs == Value::Null { rhs } else { lhs }),
+        BinaryOp::Union => {
+            let mut items = lhs.into_items();
+            for item in rhs.into_items() {
+                if !items.iter().any(|existing| values_equal(existing, &item)) {
+                    items.push(item);
+                }
+            }
+            Some(Value::from_items(items))
+        }
+        BinaryOp::Range => {
+            let (Value::Integer(start), Value::Integer(end)) = (lhs, rhs) else {
+                return None;
+            };
+            if end.checked_sub(start)? >= MAX_RANGE_LEN {
+                return None;
+            }
+            Some(Value::from_items(
+                (start..=end).map(Value::Integer).collect(),
+            ))
+        }
+    }
+}
+
+fn arithmetic(op: BinaryOp, lhs: Value, rhs: Value) -> Option<Value> {
+    match (lhs, rhs) {
+        (Value::Integer(a), Value::Integer(b)) => match op {
+            BinaryOp::Add => a.checked_add(b).map(Value::Integer),
+            BinaryOp::Sub => a.checked_sub(b).map(Value::Integer),
+            BinaryOp::Mul => a.checked_mul(b).map(Value::Integer),
+            // Integer division is exact (rational); it only stays an integer
+            // when there's no remainder
+            BinaryOp::Div => match a.checked_rem(b)? {
+                0 => a.checked_div(b).map(Value::Integer),
+                _ => Some(Value::Real(a as f64 / b as f64)),
+            },
+            BinaryOp::Rem => a.checked_rem(b).map(Value::Integer),
+            _ => match u32::try_from(b) {
+                Ok(exp) => a.checked_pow(exp).map(Value::Integer),
+                Err(_) => Some(Value::Real((a as f64).powf(b as f64))),
+            },
+        },
+        (Value::String(a), Value::String(b)) if op == BinaryOp::Add => Some(Value::String(a + &b)),
+        (
+            Value::Quantity { value: a, unit },
+            Value::Quantity {
+                value: b,
+                unit: other,
+            },
+        ) if matches!(op, BinaryOp::Add | BinaryOp::Sub) && unit == other => {
+            Some(Value::Quantity {
+                value: Box::new(arithmetic(op, *a, *b)?),
+                unit,
+            })
+        }
+        (Value::Quantity { value, unit }, scalar)
+            if scalar.is_number() && matches!(op, BinaryOp::Mul | BinaryOp::Div) =>
+        {
+            Some(Value::Quantity {
+                value: Box::new(arithmetic(op, *value, scalar)?),
+                unit,
+            })
+        }
+        (scalar, Value::Quantity { value, unit }) if scalar.is_number() && op == BinaryOp::Mul => {
+            Some(Value::Quantity {
+                value: Box::new(arithmetic(op, scalar, *value)?),
+                unit,
+            })
+        }
+        (lhs, rhs) => {
+            let (a, b) = (lhs.as_f64()?, rhs.as_f64()?);
+            let result = match op {
+                BinaryOp::Add => a + b,
+                BinaryOp::Sub => a - b,
+                BinaryOp::Mul => a * b,
+                BinaryOp::Div => a / b,
+                BinaryOp::Rem => a % b,
+                _ => a.powf(b),
+            };
+            result.is_finite().then_some(Value::Real(result))
+        }
+    }
+}
+
+fn values_equal(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs.as_f64(), rhs.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => lhs == rhs,
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (
+            Value::Quantity { value: a, unit },
+            Value::Quantity {
+                value: b,
+                unit: other,
+            },
+        ) if unit == other => compare(a, b),
+        _ => lhs.as_f64()?.partial_cmp(&rhs.as_f64()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::diagnostics::index_for;
+
+    fn build_index(source: &str) -> SymbolIndex {
+        index_for(&[("test.sysml", source)])
+    }
+
+    fn value_of(index: &SymbolIndex, qualified_name: &str) -> Option<Value> {
+        let symbol = index.lookup_qualified(qualified_name).unwrap();
+        Evaluator::new(index).evaluate_symbol(symbol)
+    }
+
+    #[test]
+    fn test_folds_attribute_references() {
+        let index = build_index(
+            r#"
+            package Cars {
+                part def Car {
+                    attribute wheelMass = 5;
+                    attribute mass = 2 * wheelMass + 10;
+                    attribute ratio = mass / 4;
+                    attribute heavy = mass > 15 and not (wheelMass == 0);
+                    attribute label = "car-" + "1";
+                }
+            }
+            "#,
+        );
+        assert_eq!(
+            value_of(&index, "Cars::Car::mass"),
+            Some(Value::Integer(20))
+        );
+        assert_eq!(
+            value_of(&index, "Cars::Car::ratio"),
+            Some(Value::Integer(5))
+        );
+        assert_eq!(
+            value_of(&index, "Cars::Car::heavy"),
+            Some(Value::Boolean(true))
+        );
+        assert_eq!(
+            value_of(&index, "Cars::Car::label"),
+            Some(Value::String("car-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_precedence_and_conditionals() {
+        let index = build_index(
+            r#"
+            package P {
+                attribute a = 1 + 2 * 3 ** 2;
+                attribute b = 2 ** 3 ** 2;
+                attribute c = if a > 10 then 7 / 2 else 0;
+                attribute d = a < 0 ? 1 : 2;
+                attribute e = null ?? 4;
+            }
+            "#,
+        );
+        assert_eq!(value_of(&index, "P::a"), Some(Value::Integer(19)));
+        assert_eq!(value_of(&index, "P::b"), Some(Value::Integer(512)));
+        assert_eq!(value_of(&index, "P::c"), Some(Value::Real(3.5)));
+        assert_eq!(value_of(&index, "P::d"), Some(Value::Integer(2)));
+        assert_eq!(value_of(&index, "P::e"), Some(Value::Integer(4)));
+    }
+
+    #[test]
+    fn test_sequences_and_collection_calls() {
+        let index = build_index(
+            r#"
+            package P {
+                attribute xs = (1, 2, 3, 4);
+                attribute big = xs->select { in x; x > 2 };
+                attribute total = xs->sum();
+                attribute count = (1..3 union 5)->size();
+                attribute second = xs#(2);
+                attribute biggest = max(3, 9, 4);
+            }
+            "#,
+        );
+        assert_eq!(
+            value_of(&index, "P::big"),
+            Some(Value::Sequence(vec![Value::Integer(3), Value::Integer(4)]))
+        );
+        assert_eq!(value_of(&index, "P::total"), Some(Value::Integer(10)));
+        assert_eq!(value_of(&index, "P::count"), Some(Value::Integer(4)));
+        assert_eq!(value_of(&index, "P::second"), Some(Value::Integer(2)));
+        assert_eq!(value_of(&index, "P::biggest"), Some(Value::Integer(9)));
+    }
+
+    #[test]
+    fn test_feature_chains_and_quantities() {
+        let index = build_index(
+            r#"
+            package P {
+                part def Wheel {
+                    attribute mass = 5 [kg];
+                }
+                part def Car {
+                    part wheel : Wheel;
+                    attribute mass = 4 * wheel.mass + 10 [kg];
+                }
+            }
+            "#,
+        );
+        let mass = value_of(&index, "P::Car::mass").unwrap();
+        assert_eq!(mass.to_string(), "30 [kg]");
+    }
+
+    #[test]
+    fn test_non_constant_values() {
+        let index = build_index(
+            r#"
+            package P {
+                attribute a = b + 1;
+                attribute b = a + 1;
+                attribute c = unknown + 1;
+                attribute d = 1 / 0;
+            }
+            "#,
+        );
+        assert_eq!(value_of(&index, "P::a"), None);
+        assert_eq!(value_of(&index, "P::c"), None);
+        assert_eq!(value_of(&index, "P::d"), None);
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let index = build_index(
+            r#"
+            package P {
+                attribute min = -9223372036854775807 - 1;
+                attribute a = min / -1;
+                attribute b = min % -1;
+                attribute c = min - 1;
+            }
+            "#,
+        );
+        assert_eq!(value_of(&index, "P::min"), Some(Value::Integer(i64::MIN)));
+        assert_eq!(value_of(&index, "P::a"), None);
+        assert_eq!(value_of(&index, "P::b"), None);
+        assert_eq!(value_of(&index, "P::c"), None);
+    }
+
+    #[test]
+    fn test_shared_references_are_evaluated_once() {
+        // Each feature references the previous one twice: without caching,
+        // evaluating the last one takes 2^60 steps
+        let mut source = String::from("package P {\n    attribute x0 = 1;\n");
+        for i in 1..=60 {
+            source.push_str(&format!("    attribute x{i} = x{} + x{};\n", i - 1, i - 1));
+        }
+        source.push_str("}\n");
+        let index = build_index(&source);
+
+        assert_eq!(value_of(&index, "P::x60"), Some(Value::Integer(1 << 60)));
+    }
+
+    #[test]
+    fn test_cancelled_evaluation_gives_up() {
+        let index = build_index(
+            r#"
+            package P {
+                attribute total = (1..5000)->collect { in x; x * 2 }->size();
+            }
+            "#,
+        );
+        assert_eq!(value_of(&index, "P::total"), Some(Value::Integer(5000)));
+
+        let symbol = index.lookup_qualified("P::total").unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert_eq!(
+            Evaluator::new(&index)
+                .with_cancellation(&cancel)
+                .evaluate_symbol(symbol),
+            None
+        );
+    }
+}
@@ -12,6 +12,7 @@
 //! - [`HirSymbol`] — A symbol extracted from the AST
 //! - [`SymbolIndex`] — Workspace-wide symbol index for name resolution
 //! - [`Resolver`] — Name resolver with import handling
+//! - [`Evaluator`] — Constant folding of feature value expressions
 //!
 //! ## Query Layers
 //!
@@ -36,6 +37,7 @@
 
 mod db;
 mod diagnostics;
+mod eval;
 mod ids;
 mod input;
 mod resolve;
@@ -44,12 +46,15 @@ mod symbols;
 mod views;
 
 pub use db::{
-    FileText, ParseResult, RootDatabase, SourceRootInput, file_symbols, file_symbols_from_text,
-    parse_file,
+    FileText, ParseResult, RootDatabase, SourceFiles, SourceRootInput, build_workspace_index,
+    file_extraction, file_fingerprint, file_symbols, file_symbols_from_text, parse_file,
+    workspace_index,
 };
 pub use diagnostics::{
-    Diagnostic, DiagnosticCollector, RelatedInfo, SemanticChecker, Severity, check_file,
+    Diagnostic, DiagnosticCollector, Fix, Lint, LintContext, LintLevel, LintRegistry, RelatedInfo,
+    SemanticChecker, Severity, Suppression, check_file,
 };
+pub use eval::{Evaluator, Value};
 pub use ids::{DefId, LocalDefId};
 pub use input::SourceRoot;
 pub use resolve::{ResolveResult, Resolver, SymbolIndex};
@@ -21,12 +21,35 @@ use indexmap::IndexMap;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
-use super::symbols::{HirSymbol, RefKind, SymbolKind, TypeRefKind};
+use super::diagnostics::Suppression;
+use super::symbols::{HirSymbol, RefKind, RelationshipKind, SymbolKind, TypeRefKind};
 use crate::base::FileId;
+use crate::syntax::file::FileExtension;
 
 /// Type alias for resolution cache: (name, starting_scope) -> resolved_qname
 type ResolutionCache = HashMap<(Arc<str>, Arc<str>), Option<Arc<str>>>;
 
+/// A type ref (or one part of a feature chain) waiting to be resolved.
+#[derive(Clone, Debug)]
+struct TypeRefItem {
+    sym_idx: SymbolIdx,
+    trk_idx: usize,
+    part_idx: usize,
+    target: Arc<str>,
+    /// The full chain and this part's position in it, for chain parts
+    chain_context: Option<(Arc<Vec<Arc<str>>>, usize)>,
+    kind: RefKind,
+}
+
+/// Collected type refs, split by resolution pass.
+#[derive(Debug, Default)]
+struct TypeRefWork {
+    /// Simple refs and chain first-parts
+    pass1: Vec<TypeRefItem>,
+    /// Chain parts after the first (resolved against the first part's type)
+    pass2: Vec<TypeRefItem>,
+}
+
 // ============================================================================
 // SCOPE VISIBILITY (Pre-computed at index time)
 // ============================================================================
@@ -42,6 +65,7 @@ type ResolutionCache = HashMap<(Arc<str>, Arc<str>), Option<Arc<str>>>;
 /// - `imports` contains symbols from ISQSpaceTime (via the wildcard import)
 /// - `public_reexports` tracks that ISQSpaceTime's symbols are re-exported
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScopeVisibility {
     /// The scope this visibility applies to (e.g., "ISQ", "Automotive::Torque").
     scope: Arc<str>,
@@ -184,6 +208,7 @@ pub type SymbolIdx = usize;
 /// from all other maps. This ensures consistency when symbols are mutated
 /// (e.g., when resolving type references).
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct SymbolIndex {
     /// The single source of truth for all symbols.
     symbols: Vec<HirSymbol>,
@@ -209,11 +234,32 @@ pub struct SymbolIndex {
     /// Filters for specific imports (import qualified name -> metadata names)
     /// These come from bracket syntax: `import X::*[@Filter]`
     import_filters: HashMap<Arc<str>, Vec<Arc<str>>>,
+    /// Reverse dependencies: resolved qualified name -> files with a TypeRef resolved to it.
+    /// Lets an edit re-resolve exactly the files that pointed at a renamed or deleted symbol.
+    dependents: HashMap<Arc<str>, HashSet<FileId>>,
+    /// Files with a TypeRef, resolved or not, keyed by each segment of the target name.
+    /// Lets an edit that adds a matching symbol re-resolve the files it may satisfy or shadow.
+    name_dependents: HashMap<Arc<str>, HashSet<FileId>>,
+    /// Import symbols keyed by each segment of the path they import (e.g., `ISQ`
+    /// for `import ISQ::*`), so an edit finds the scopes importing a namespace.
+    importers: HashMap<Arc<str>, HashSet<SymbolIdx>>,
+    /// Symbols keyed by each segment of their supertype names, so an edit finds
+    /// the scopes that inherit members from a type.
+    inheritors: HashMap<Arc<str>, HashSet<SymbolIdx>>,
+    /// Reverse type hierarchy: supertype qualified name -> symbols that specialize,
+    /// subset, redefine or are typed by it (see [`SymbolIndex::subtypes_of`]).
+    subtypes: HashMap<Arc<str>, HashSet<SymbolIdx>>,
+    /// Files added from KerML sources, whose symbol kinds only approximate
+    /// the KerML metaclasses (see [`ExtractionResult::is_kerml`](crate::hir::ExtractionResult)).
+    kerml_files: HashSet<FileId>,
+    /// `syster-ignore` comments of each file.
+    suppressions: HashMap<FileId, Vec<Suppression>>,
     /// Flag to track if parent scope index needs rebuilding.
     parent_index_dirty: bool,
     /// Cache for SemanticMetadata baseType resolution (with interior mutability for lazy population).
     /// Maps annotation short name (e.g., "systemdd") -> resolved baseType qualified name (e.g., "AHFProfileLib::SysDD").
     /// None value means "already looked up, no baseType found".
+    #[cfg_attr(feature = "cache", serde(skip))]
     metadata_basetype_cache: RwLock<HashMap<Arc<str>, Option<Arc<str>>>>,
 }
 
@@ -231,6 +277,13 @@ impl Clone for SymbolIndex {
             by_parent_scope: self.by_parent_scope.clone(),
             scope_filters: self.scope_filters.clone(),
             import_filters: self.import_filters.clone(),
+            dependents: self.dependents.clone(),
+            name_dependents: self.name_dependents.clone(),
+            importers: self.importers.clone(),
+            inheritors: self.inheritors.clone(),
+            subtypes: self.subtypes.clone(),
+            kerml_files: self.kerml_files.clone(),
+            suppressions: self.suppressions.clone(),
             parent_index_dirty: self.parent_index_dirty,
             // Clone the cache contents, not the lock
             metadata_basetype_cache: RwLock::new(
@@ -253,7 +306,15 @@ impl SymbolIndex {
         result: crate::hir::symbols::ExtractionResult,
     ) {
         // Add symbols
-        self.add_file(file, result.symbols);
+        let kind = if result.is_kerml {
+            FileExtension::KerML
+        } else {
+            FileExtension::SysML
+        };
+        self.add_file_with_kind(file, result.symbols, kind);
+        if !result.suppressions.is_empty() {
+            self.suppressions.insert(file, result.suppressions);
+        }
 
         // Add scope filters (from `filter @X;` statements)
         for (scope, metadata_names) in result.scope_filters {
@@ -275,6 +336,17 @@ impl SymbolIndex {
 
     /// Add symbols from a file to the index.
     pub fn add_file(&mut self, file: FileId, symbols: Vec<HirSymbol>) {
+        self.add_file_with_kind(file, symbols, FileExtension::SysML);
+    }
+
+    /// Add symbols from a file to the index, recording whether it's a KerML
+    /// source (see [`is_kerml_file()`](Self::is_kerml_file)).
+    pub fn add_file_with_kind(
+        &mut self,
+        file: FileId,
+        symbols: Vec<HirSymbol>,
+        kind: FileExtension,
+    ) {
         // Remove existing symbols from this file first
         self.remove_file(file);
 
@@ -284,6 +356,10 @@ impl SymbolIndex {
         // Clear visibility maps for affected scopes (they'll be rebuilt lazily)
         // We don't clear ALL visibility maps - just mark that parent index needs rebuild
 
+        if kind == FileExtension::KerML {
+            self.kerml_files.insert(file);
+        }
+
         let mut file_indices = Vec::with_capacity(symbols.len());
 
         for symbol in symbols {
@@ -317,6 +393,7 @@ impl SymbolIndex {
 
             // Store the symbol
             self.symbols.push(symbol);
+            self.link_scope_dependencies(idx);
         }
 
         // Index by file
@@ -376,7 +453,16 @@ impl SymbolIndex {
     ///
     /// Note: This marks indices as invalid but doesn't compact the symbols vec
     /// to avoid invalidating other indices. For a full cleanup, rebuild the index.
+    ///
+    /// The visibility maps are left as they are, since a re-added file mostly
+    /// declares the same scopes; see [`Self::update_visibility_for_changes`].
     pub fn remove_file(&mut self, file: FileId) {
+        // Drop the file's reverse-dependency entries while its symbols are still reachable
+        self.forget_dependencies(file);
+        self.forget_subtypes(file);
+        self.kerml_files.remove(&file);
+        self.suppressions.remove(&file);
+
         if let Some(indices) = self.by_file.remove(&file) {
             // Mark parent index as dirty
             self.parent_index_dirty = true;
@@ -385,6 +471,7 @@ impl SymbolIndex {
             self.metadata_basetype_cache.write().unwrap().clear();
 
             for &idx in &indices {
+                self.unlink_scope_dependencies(idx);
                 if let Some(symbol) = self.symbols.get(idx) {
                     let qname = symbol.qualified_name.clone();
                     let sname = symbol.name.clone();
@@ -393,9 +480,6 @@ impl SymbolIndex {
                     self.by_qualified_name.shift_remove(&qname);
                     self.definitions.remove(&qname);
 
-                    // Clear visibility map for this scope (will be rebuilt lazily)
-                    self.visibility_map.remove(&qname);
-
                     // Remove from simple name index
                     if let Some(list) = self.by_simple_name.get_mut(&sname) {
                         list.retain(|&i| i != idx);
@@ -447,6 +531,11 @@ impl SymbolIndex {
         }
     }
 
+    /// Every simple name in the index.
+    pub fn simple_names(&self) -> impl Iterator<Item = &Arc<str>> {
+        self.by_simple_name.keys()
+    }
+
     /// Look up all symbols with a simple name (also checks short names/aliases).
     pub fn lookup_simple(&self, name: &str) -> Vec<&HirSymbol> {
         let mut results = Vec::new();
@@ -591,6 +680,26 @@ impl SymbolIndex {
             .and_then(|&idx| self.symbols.get(idx))
     }
 
+    /// Whether `file` was added from a KerML source.
+    pub fn is_kerml_file(&self, file: FileId) -> bool {
+        self.kerml_files.contains(&file)
+    }
+
+    /// The `syster-ignore` comments of `file`.
+    pub fn suppressions(&self, file: FileId) -> &[Suppression] {
+        self.suppressions
+            .get(&file)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Metadata filters of an import (`import X::*[@Filter];`), by the import's qualified name.
+    pub fn import_filters(&self, import_qname: &str) -> &[Arc<str>] {
+        self.import_filters
+            .get(import_qname)
+            .map_or(&[], |filters| filters.as_slice())
+    }
+
     /// Get all symbols in a file.
     pub fn symbols_in_file(&self, file: FileId) -> Vec<&HirSymbol> {
         self.by_file
@@ -681,6 +790,7 @@ impl SymbolIndex {
 
         // Store the symbol
         self.symbols.push(symbol);
+        self.link_scope_dependencies(idx);
 
         // Mark parent index as dirty
         self.parent_index_dirty = true;
@@ -700,6 +810,16 @@ impl SymbolIndex {
         self.visibility_map.clear();
     }
 
+    /// Indices of the symbols still in the index, in insertion order.
+    ///
+    /// A removed symbol stays in the symbols vec, and when its file is re-added
+    /// the new symbols reuse its qualified name, so only `by_file` tells them apart.
+    fn live_symbol_indices(&self) -> Vec<SymbolIdx> {
+        let mut indices: Vec<SymbolIdx> = self.by_file.values().flatten().copied().collect();
+        indices.sort_unstable();
+        indices
+    }
+
     /// Ensure the parent scope index is built (needed for lazy visibility lookups).
     fn ensure_parent_index(&mut self) {
         if !self.parent_index_dirty {
@@ -708,15 +828,10 @@ impl SymbolIndex {
 
         self.by_parent_scope.clear();
 
-        // Only include symbols that are still valid (in by_qualified_name)
-        // This handles the case where remove_file marks symbols as invalid
-        // but doesn't remove them from the symbols vec
-        for (idx, symbol) in self.symbols.iter().enumerate() {
-            // Skip symbols that have been removed (not in by_qualified_name lookup)
-            if !self.by_qualified_name.contains_key(&symbol.qualified_name) {
-                continue;
-            }
-
+        // Only include symbols that are still valid: remove_file marks symbols
+        // as invalid but doesn't remove them from the symbols vec
+        for idx in self.live_symbol_indices() {
+            let symbol = &self.symbols[idx];
             let parent_scope: Arc<str> = Self::parent_scope(&symbol.qualified_name)
                 .map(Arc::from)
                 .unwrap_or_else(|| Arc::from(""));
@@ -778,6 +893,19 @@ impl SymbolIndex {
         }
     }
 
+    /// Update the visibility maps after the symbols with the given qualified
+    /// names were added, removed or modified, rebuilding only the scopes whose
+    /// visibility they can reach.
+    pub fn update_visibility_for_changes(&mut self, qualified_names: &[Arc<str>]) {
+        self.ensure_parent_index();
+        if self.visibility_map.is_empty() {
+            self.build_visibility_maps();
+            return;
+        }
+        let scopes = self.scopes_affected_by(qualified_names);
+        self.rebuild_visibility_for_scopes(&scopes);
+    }
+
     /// Resolve all type references in all symbols.
     ///
     /// This is called after visibility maps are built to fill in `resolved_target`
@@ -787,59 +915,97 @@ impl SymbolIndex {
     /// Feature chains (like `takePicture.focus`) are now preserved explicitly
     /// as TypeRefKind::Chain from the parser. Simple refs use TypeRefKind::Simple.
     pub fn resolve_all_type_refs(&mut self) {
-        use crate::hir::symbols::TypeRefKind;
+        // Ensure visibility maps are built first
+        self.ensure_visibility_maps();
+
+        // Every file is re-resolved, so the reverse dependencies are rebuilt from scratch
+        self.dependents.clear();
+        self.name_dependents.clear();
+        self.subtypes.clear();
+
+        let work = self.collect_type_ref_work(0..self.symbols.len());
+        // For Redefines refs, try context resolution FIRST before normal scope walk.
+        // This handles cases like `requirement X :>> X` where X redefines a member
+        // from the parent/satisfy context, not itself in the current scope.
+        self.resolve_type_ref_work(work, true);
+
+        let files: Vec<FileId> = self.by_file.keys().copied().collect();
+        for file in files {
+            self.record_dependencies(file);
+            self.record_subtypes(file);
+        }
+    }
 
+    /// Resolve type references only for symbols in specific files.
+    /// This is used for incremental updates to avoid re-resolving the entire workspace.
+    pub fn resolve_type_refs_for_files(&mut self, files: &[FileId]) {
         // Ensure visibility maps are built first
         self.ensure_visibility_maps();
 
-        // Memoization cache for scope walk results: (name, starting_scope) -> resolved_qname
-        // This avoids re-resolving the same name from the same scope multiple times
-        let mut resolution_cache: ResolutionCache = HashMap::new();
-
-        // Two-pass resolution to handle dependencies:
-        // Pass 1: Resolve simple refs and chain first-parts (they don't depend on other refs)
-        // Pass 2: Resolve chain subsequent parts (they depend on the first part's resolved type)
-
-        use std::rc::Rc;
-
-        // Collect work items, separating first-parts from subsequent chain parts
-        // Each item: (sym_idx, trk_idx, part_idx, target, chain_context, ref_kind)
-        type WorkItem = (
-            SymbolIdx,
-            usize,
-            usize,
-            Arc<str>,
-            Option<(Rc<Vec<Arc<str>>>, usize)>,
-            RefKind,
-        );
-        let mut pass1_work: Vec<WorkItem> = Vec::new();
-        let mut pass2_work: Vec<WorkItem> = Vec::new();
+        // The previous resolution results of these files are about to be replaced
+        for &file in files {
+            self.forget_dependencies(file);
+            self.forget_subtypes(file);
+        }
 
-        for (sym_idx, sym) in self.symbols.iter().enumerate() {
+        // Collect symbol indices for the specified files
+        let symbol_indices: Vec<SymbolIdx> = files
+            .iter()
+            .filter_map(|file| self.by_file.get(file))
+            .flat_map(|indices| indices.iter().copied())
+            .collect();
+
+        let work = self.collect_type_ref_work(symbol_indices);
+        self.resolve_type_ref_work(work, false);
+
+        for &file in files {
+            self.record_dependencies(file);
+            self.record_subtypes(file);
+        }
+    }
+
+    /// Collect the type refs of the given symbols, in symbol order.
+    fn collect_type_ref_work(
+        &self,
+        symbol_indices: impl IntoIterator<Item = SymbolIdx>,
+    ) -> TypeRefWork {
+        let mut work = TypeRefWork::default();
+
+        for sym_idx in symbol_indices {
+            let Some(sym) = self.symbols.get(sym_idx) else {
+                continue;
+            };
             for (trk_idx, trk) in sym.type_refs.iter().enumerate() {
                 match trk {
                     TypeRefKind::Simple(tr) => {
                         // Simple refs go in pass 1
-                        pass1_work.push((sym_idx, trk_idx, 0, tr.target.clone(), None, tr.kind));
+                        work.pass1.push(TypeRefItem {
+                            sym_idx,
+                            trk_idx,
+                            part_idx: 0,
+                            target: tr.target.clone(),
+                            chain_context: None,
+                            kind: tr.kind,
+                        });
                     }
                     TypeRefKind::Chain(chain) => {
-                        let chain_parts: Rc<Vec<Arc<str>>> =
-                            Rc::new(chain.parts.iter().map(|p| p.target.clone()).collect());
+                        let chain_parts: Arc<Vec<Arc<str>>> =
+                            Arc::new(chain.parts.iter().map(|p| p.target.clone()).collect());
                         for (part_idx, part) in chain.parts.iter().enumerate() {
-                            let item = (
+                            let item = TypeRefItem {
                                 sym_idx,
                                 trk_idx,
                                 part_idx,
-                                part.target.clone(),
-                                Some((Rc::clone(&chain_parts), part_idx)),
-                                part.kind,
-                            );
+                                target: part.target.clone(),
+                                chain_context: Some((Arc::clone(&chain_parts), part_idx)),
+                                kind: part.kind,
+                            };
                             if part_idx == 0 {
                                 // First part of chain - pass 1
-                                pass1_work.push(item);
+                                work.pass1.push(item);
                             } else {
                                 // Subsequent parts - pass 2 (depend on first part's type)
-                                pass2_work.push(item);
+                                work.pass2.push(item);
                             }
                         }
                     }
@@ -847,183 +1013,446 @@ impl SymbolIndex {
             }
         }
 
-        // Pass 1: Resolve simple refs and chain first-parts
-        for (sym_idx, trk_idx, part_idx, target, chain_context, ref_kind) in pass1_work {
-            let symbol_qname = self.symbols[sym_idx].qualified_name.clone();
+        work
+    }
 
-            // For Redefines refs, try context resolution FIRST before normal scope walk.
-            // This handles cases like `requirement X :>> X` where X redefines a member
-            // from the parent/satisfy context, not itself in the current scope.
-            let mut resolved = if ref_kind == RefKind::Redefines {
-                self.resolve_redefines_in_context(&symbol_qname, &target)
-            } else {
-                None
-            };
+    /// Resolve collected type refs.
+    ///
+    /// Two-pass resolution to handle dependencies:
+    /// Pass 1: Resolve simple refs and chain first-parts (they don't depend on other refs)
+    /// Pass 2: Resolve chain subsequent parts (they depend on the first part's resolved type)
+    ///
+    /// Most pass 1 refs only consult the visibility maps, so they are resolved in
+    /// parallel, one task per file, against the unchanged index. Redefines refs also look at the
+    /// resolutions of their parent and siblings, which are only meaningful for
+    /// refs stored before them; those and pass 2 are resolved in order while
+    /// the parallel results are written back, exactly as a sequential pass would.
+    fn resolve_type_ref_work(&mut self, work: TypeRefWork, redefines_context_first: bool) {
+        use rayon::prelude::*;
+
+        let resolved_in_parallel: Vec<Option<Option<Arc<str>>>> = {
+            let index = &*self;
+            let file_of = |item: &TypeRefItem| index.symbols[item.sym_idx].file;
+            work.pass1
+                .par_chunk_by(|a, b| file_of(a) == file_of(b))
+                .flat_map_iter(|items| {
+                    // Memoization cache for scope walk results: (name, starting_scope) -> resolved_qname
+                    let mut cache = ResolutionCache::new();
+                    items
+                        .iter()
+                        .map(|item| {
+                            (item.kind != RefKind::Redefines).then(|| {
+                                let symbol_qname = &index.symbols[item.sym_idx].qualified_name;
+                                index.resolve_type_ref_cached(
+                                    symbol_qname,
+                                    &item.target,
+                                    &item.chain_context,
+                                    &mut cache,
+                                )
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        };
 
-            // If context resolution didn't find anything (or wasn't a Redefines), try normal resolution
-            if resolved.is_none() {
-                resolved = self.resolve_type_ref_cached(
-                    &symbol_qname,
-                    &target,
-                    &chain_context,
-                    &mut resolution_cache,
-                );
-            }
+        let mut resolution_cache = ResolutionCache::new();
 
-            // For unresolved Redefines refs (when context resolution was skipped or failed),
-            // try one more time with context resolution as fallback
-            if resolved.is_none() && ref_kind == RefKind::Redefines {
-                resolved = self.resolve_redefines_in_context(&symbol_qname, &target);
-            }
+        // Pass 1: Store the parallel results, resolving Redefines refs in between
+        for (item, resolved) in work.pass1.iter().zip(resolved_in_parallel) {
+            let resolved = match resolved {
+                Some(resolved) => resolved,
+                None => {
+                    let symbol_qname = self.symbols[item.sym_idx].qualified_name.clone();
 
-            if let Some(trk) = self.symbols[sym_idx].type_refs.get_mut(trk_idx) {
-                match trk {
-                    TypeRefKind::Simple(tr) => {
-                        tr.resolved_target = resolved;
+                    let mut resolved = if redefines_context_first {
+                        self.resolve_redefines_in_context(&symbol_qname, &item.target)
+                    } else {
+                        None
+                    };
+
+                    // If context resolution didn't find anything, try normal resolution
+                    if resolved.is_none() {
+                        resolved = self.resolve_type_ref_cached(
+                            &symbol_qname,
+                            &item.target,
+                            &item.chain_context,
+                            &mut resolution_cache,
+                        );
                     }
-                    TypeRefKind::Chain(chain) => {
-                        if let Some(part) = chain.parts.get_mut(part_idx) {
-                            part.resolved_target = resolved;
-                        }
+
+                    // When context resolution was skipped or failed, try it once more as fallback
+                    if resolved.is_none() {
+                        resolved = self.resolve_redefines_in_context(&symbol_qname, &item.target);
                     }
+                    resolved
                 }
-            }
+            };
+            self.set_resolved_target(item, resolved);
         }
 
         // Pass 2: Resolve chain subsequent parts (can now use resolved types from pass 1)
-        for (sym_idx, trk_idx, part_idx, target, chain_context, _ref_kind) in pass2_work {
-            let symbol_qname = self.symbols[sym_idx].qualified_name.clone();
-
+        for item in &work.pass2 {
+            let symbol_qname = self.symbols[item.sym_idx].qualified_name.clone();
             let resolved = self.resolve_type_ref_cached(
                 &symbol_qname,
-                &target,
-                &chain_context,
+                &item.target,
+                &item.chain_context,
                 &mut resolution_cache,
             );
+            self.set_resolved_target(item, resolved);
+        }
+    }
 
-            if let Some(TypeRefKind::Chain(chain)) =
-                self.symbols[sym_idx].type_refs.get_mut(trk_idx)
-            {
-                if let Some(part) = chain.parts.get_mut(part_idx) {
+    /// Store the resolution of one type ref.
+    fn set_resolved_target(&mut self, item: &TypeRefItem, resolved: Option<Arc<str>>) {
+        let Some(trk) = self.symbols[item.sym_idx].type_refs.get_mut(item.trk_idx) else {
+            return;
+        };
+        match trk {
+            TypeRefKind::Simple(tr) => tr.resolved_target = resolved,
+            TypeRefKind::Chain(chain) => {
+                if let Some(part) = chain.parts.get_mut(item.part_idx) {
                     part.resolved_target = resolved;
                 }
             }
         }
     }
 
-    /// Resolve type references only for symbols in specific files.
-    /// This is used for incremental updates to avoid re-resolving the entire workspace.
-    pub fn resolve_type_refs_for_files(&mut self, files: &[FileId]) {
-        use crate::hir::symbols::TypeRefKind;
-
-        // Ensure visibility maps are built first
-        self.ensure_visibility_maps();
+    // ========================================================================
+    // REVERSE DEPENDENCIES
+    // ========================================================================
 
-        // Memoization cache for scope walk results
-        let mut resolution_cache: ResolutionCache = HashMap::new();
+    /// Get the files with a TypeRef resolved to `qualified_name`, ordered by FileId.
+    pub fn dependents_of(&self, qualified_name: &str) -> Vec<FileId> {
+        let mut files: Vec<FileId> = self
+            .dependents
+            .get(qualified_name)
+            .map(|files| files.iter().copied().collect())
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
 
-        // Collect symbol indices for the specified files
-        let symbol_indices: Vec<SymbolIdx> = files
-            .iter()
-            .filter_map(|file| self.by_file.get(file))
-            .flat_map(|indices| indices.iter().copied())
-            .collect();
+    /// Get the files whose resolution may change when the given symbols are
+    /// added, removed or modified, ordered by FileId.
+    ///
+    /// This covers files with a TypeRef resolved to one of the symbols (which
+    /// may now dangle or point elsewhere) and files with a TypeRef naming one
+    /// of the symbols' simple names (which may now resolve, or resolve to a
+    /// symbol shadowing the old target).
+    ///
+    /// An import among the symbols changes what its namespace makes visible,
+    /// so it also affects every file that resolves names from within that
+    /// namespace, through it, or through the namespaces importing it.
+    ///
+    /// Expects the visibility maps to be up to date (see
+    /// [`Self::update_visibility_for_changes`]).
+    pub fn files_affected_by(&self, qualified_names: &[Arc<str>]) -> Vec<FileId> {
+        let mut files: HashSet<FileId> = HashSet::new();
+        let mut namespaces: Vec<&str> = Vec::new();
+        for qname in qualified_names {
+            if let Some(dependents) = self.dependents.get(qname) {
+                files.extend(dependents);
+            }
+            if let Some(naming) = self.name_dependents.get(Self::last_segment(qname)) {
+                files.extend(naming);
+            }
+            if Self::is_import_name(qname) {
+                namespaces.extend(Self::parent_scope(qname));
+            }
+        }
+        for namespace in self.importing_namespaces(namespaces) {
+            files.extend(self.files_resolving_in(namespace));
+            if let Some(naming) = self.name_dependents.get(Self::last_segment(namespace)) {
+                files.extend(naming);
+            }
+        }
+        let mut files: Vec<FileId> = files.into_iter().collect();
+        files.sort();
+        files
+    }
 
-        use std::rc::Rc;
+    /// Whether a qualified name is that of an import (e.g., `P::import:Q::*`).
+    fn is_import_name(qualified_name: &str) -> bool {
+        qualified_name.starts_with("import:") || qualified_name.contains("::import:")
+    }
 
-        // Collect work items for these symbols only
-        type WorkItem = (
-            SymbolIdx,
-            usize,
-            usize,
-            Arc<str>,
-            Option<(Rc<Vec<Arc<str>>>, usize)>,
-            RefKind,
-        );
-        let mut pass1_work: Vec<WorkItem> = Vec::new();
-        let mut pass2_work: Vec<WorkItem> = Vec::new();
-
-        for &sym_idx in &symbol_indices {
-            if let Some(sym) = self.symbols.get(sym_idx) {
-                for (trk_idx, trk) in sym.type_refs.iter().enumerate() {
-                    match trk {
-                        TypeRefKind::Simple(tr) => {
-                            pass1_work.push((
-                                sym_idx,
-                                trk_idx,
-                                0,
-                                tr.target.clone(),
-                                None,
-                                tr.kind,
-                            ));
-                        }
-                        TypeRefKind::Chain(chain) => {
-                            let chain_parts: Rc<Vec<Arc<str>>> =
-                                Rc::new(chain.parts.iter().map(|p| p.target.clone()).collect());
-                            for (part_idx, part) in chain.parts.iter().enumerate() {
-                                let item = (
-                                    sym_idx,
-                                    trk_idx,
-                                    part_idx,
-                                    part.target.clone(),
-                                    Some((Rc::clone(&chain_parts), part_idx)),
-                                    part.kind,
-                                );
-                                if part_idx == 0 {
-                                    pass1_work.push(item);
-                                } else {
-                                    pass2_work.push(item);
-                                }
-                            }
-                        }
-                    }
+    /// The given namespaces plus those that import any of them, transitively.
+    ///
+    /// Imports are matched by the simple names in their written path, which
+    /// may take in a few unrelated namespaces but never misses one.
+    fn importing_namespaces<'s>(&'s self, namespaces: Vec<&'s str>) -> HashSet<&'s str> {
+        let mut found: HashSet<&str> = namespaces.iter().copied().collect();
+        let mut pending = namespaces;
+        while let Some(namespace) = pending.pop() {
+            for scope in self.importing_scopes(Self::last_segment(namespace)) {
+                if found.insert(scope) {
+                    pending.push(scope);
                 }
             }
         }
+        found
+    }
 
-        // Pass 1: Resolve simple refs and chain first-parts
-        for (sym_idx, trk_idx, part_idx, target, chain_context, ref_kind) in pass1_work {
-            let symbol_qname = self.symbols[sym_idx].qualified_name.clone();
-            let mut resolved = self.resolve_type_ref_cached(
-                &symbol_qname,
-                &target,
-                &chain_context,
-                &mut resolution_cache,
-            );
+    /// The scopes with an import whose written path names `name`.
+    fn importing_scopes(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.importers
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter_map(|&idx| Self::parent_scope(&self.symbols[idx].qualified_name))
+    }
 
-            if resolved.is_none() && ref_kind == RefKind::Redefines {
-                resolved = self.resolve_redefines_in_context(&symbol_qname, &target);
+    /// The files with a TypeRef resolved from within `namespace`.
+    ///
+    /// Walks the parent scope index, which must be up to date.
+    fn files_resolving_in(&self, namespace: &str) -> HashSet<FileId> {
+        let mut files = HashSet::new();
+        if let Some(symbol) = self.lookup_qualified(namespace) {
+            if !symbol.type_refs.is_empty() {
+                files.insert(symbol.file);
             }
+        }
+        let mut pending = vec![namespace];
+        while let Some(scope) = pending.pop() {
+            for &idx in self.by_parent_scope.get(scope).into_iter().flatten() {
+                let symbol = &self.symbols[idx];
+                if !symbol.type_refs.is_empty() {
+                    files.insert(symbol.file);
+                }
+                pending.push(&symbol.qualified_name);
+            }
+        }
+        files
+    }
 
-            if let Some(trk) = self.symbols[sym_idx].type_refs.get_mut(trk_idx) {
-                match trk {
-                    TypeRefKind::Simple(tr) => {
-                        tr.resolved_target = resolved;
+    /// Record the resolution results of a file's TypeRefs in the reverse-dependency maps.
+    fn record_dependencies(&mut self, file: FileId) {
+        let Some(indices) = self.by_file.get(&file) else {
+            return;
+        };
+        for &idx in indices {
+            for trk in &self.symbols[idx].type_refs {
+                for tr in trk.as_refs() {
+                    if let Some(target) = &tr.resolved_target {
+                        self.dependents
+                            .entry(target.clone())
+                            .or_default()
+                            .insert(file);
                     }
-                    TypeRefKind::Chain(chain) => {
-                        if let Some(part) = chain.parts.get_mut(part_idx) {
-                            part.resolved_target = resolved;
-                        }
+                    for segment in tr.target.split("::") {
+                        self.name_dependents
+                            .entry(Arc::from(segment))
+                            .or_default()
+                            .insert(file);
                     }
                 }
             }
         }
+    }
 
-        // Pass 2: Resolve chain subsequent parts
-        for (sym_idx, trk_idx, part_idx, target, chain_context, _ref_kind) in pass2_work {
-            let symbol_qname = self.symbols[sym_idx].qualified_name.clone();
-            let resolved = self.resolve_type_ref_cached(
-                &symbol_qname,
-                &target,
-                &chain_context,
-                &mut resolution_cache,
-            );
+    /// Remove a file's entries from the reverse-dependency maps.
+    ///
+    /// Must run before the file's symbols are replaced, since the entries are
+    /// found through the `resolved_target`s they were recorded from.
+    fn forget_dependencies(&mut self, file: FileId) {
+        let Some(indices) = self.by_file.get(&file) else {
+            return;
+        };
+        for &idx in indices {
+            for trk in &self.symbols[idx].type_refs {
+                for tr in trk.as_refs() {
+                    if let Some(target) = &tr.resolved_target {
+                        Self::unlink(&mut self.dependents, target, file);
+                    }
+                    for segment in tr.target.split("::") {
+                        Self::unlink(&mut self.name_dependents, segment, file);
+                    }
+                }
+            }
+        }
+    }
 
-            if let Some(TypeRefKind::Chain(chain)) =
-                self.symbols[sym_idx].type_refs.get_mut(trk_idx)
+    /// Remove `value` from the values recorded under `key`.
+    fn unlink<T: Eq + std::hash::Hash>(
+        map: &mut HashMap<Arc<str>, HashSet<T>>,
+        key: &str,
+        value: T,
+    ) {
+        if let Some(values) = map.get_mut(key) {
+            values.remove(&value);
+            if values.is_empty() {
+                map.remove(key);
+            }
+        }
+    }
+
+    /// Record an import in `importers`, or a symbol with supertypes in `inheritors`.
+    fn link_scope_dependencies(&mut self, idx: SymbolIdx) {
+        let symbol = &self.symbols[idx];
+        let map = if symbol.kind == SymbolKind::Import {
+            &mut self.importers
+        } else {
+            &mut self.inheritors
+        };
+        for segment in Self::scope_dependency_keys(symbol) {
+            map.entry(Arc::from(segment)).or_default().insert(idx);
+        }
+    }
+
+    /// Remove a symbol's entries from `importers` or `inheritors`.
+    fn unlink_scope_dependencies(&mut self, idx: SymbolIdx) {
+        let symbol = &self.symbols[idx];
+        let map = if symbol.kind == SymbolKind::Import {
+            &mut self.importers
+        } else {
+            &mut self.inheritors
+        };
+        for segment in Self::scope_dependency_keys(symbol) {
+            Self::unlink(map, segment, idx);
+        }
+    }
+
+    /// The simple names in the path an import imports, or in a symbol's
+    /// supertype names (including each part of a feature chain).
+    fn scope_dependency_keys(symbol: &HirSymbol) -> Vec<&str> {
+        let names: Vec<&str> = if symbol.kind == SymbolKind::Import {
+            vec![&symbol.name]
+        } else {
+            symbol.supertypes.iter().map(|s| s.as_ref()).collect()
+        };
+        names
+            .into_iter()
+            .flat_map(|name| name.split([':', '.']))
+            .filter(|segment| !segment.is_empty() && *segment != "*" && *segment != "**")
+            .collect()
+    }
+
+    /// The simple name a (possibly qualified) reference ends in.
+    fn last_segment(name: &str) -> &str {
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    // ========================================================================
+    // TYPE HIERARCHY
+    // ========================================================================
+
+    /// Get the direct supertypes of `symbol` with the relationship to each, in
+    /// source order.
+    ///
+    /// These are the resolved targets of its specialization, subsetting,
+    /// redefinition and typing, or the implicit library supertype it gets
+    /// without any (e.g., `Parts::Part` for a `part def`).
+    pub fn supertypes_of(&self, symbol: &HirSymbol) -> Vec<(RelationshipKind, &HirSymbol)> {
+        let mut supertypes: Vec<(RelationshipKind, &HirSymbol)> = Vec::new();
+        for (kind, target) in Self::hierarchy_edges(symbol) {
+            let Some(supertype) = self.lookup_qualified(&target) else {
+                continue;
+            };
+            if supertypes
+                .iter()
+                .any(|(_, s)| s.qualified_name == supertype.qualified_name)
             {
-                if let Some(part) = chain.parts.get_mut(part_idx) {
-                    part.resolved_target = resolved;
+                continue;
+            }
+            let kind = kind.unwrap_or(if symbol.kind.is_definition() {
+                RelationshipKind::Specializes
+            } else if supertype.kind.is_definition() {
+                RelationshipKind::TypedBy
+            } else {
+                RelationshipKind::Redefines
+            });
+            supertypes.push((kind, supertype));
+        }
+        supertypes
+    }
+
+    /// Get the symbols that have `qualified_name` as a direct supertype, with
+    /// their relationship to it, ordered by file and position.
+    pub fn subtypes_of(&self, qualified_name: &str) -> Vec<(RelationshipKind, &HirSymbol)> {
+        let Some(indices) = self.subtypes.get(qualified_name) else {
+            return Vec::new();
+        };
+        let mut subtypes: Vec<_> = indices
+            .iter()
+            .map(|&idx| &self.symbols[idx])
+            .filter_map(|symbol| {
+                self.supertypes_of(symbol)
+                    .into_iter()
+                    .find(|(_, supertype)| *supertype.qualified_name == *qualified_name)
+                    .map(|(kind, _)| (kind, symbol))
+            })
+            .collect();
+        subtypes.sort_by_key(|(_, symbol)| (symbol.file, symbol.start_line, symbol.start_col));
+        subtypes
+    }
+
+    /// The supertype names of a symbol: resolved type refs with their
+    /// relationship, then the implicit supertypes the extractor added, which
+    /// are already qualified and have no relationship of their own.
+    fn hierarchy_edges(symbol: &HirSymbol) -> Vec<(Option<RelationshipKind>, Arc<str>)> {
+        let mut edges = Vec::new();
+        for trk in &symbol.type_refs {
+            // A chain (`:>> engine.power`) names the feature its last part resolves to
+            let tr = match trk {
+                TypeRefKind::Simple(tr) => tr,
+                TypeRefKind::Chain(chain) => match chain.parts.last() {
+                    Some(tr) => tr,
+                    None => continue,
+                },
+            };
+            let kind = match tr.kind {
+                RefKind::Specializes => RelationshipKind::Specializes,
+                RefKind::TypedBy => RelationshipKind::TypedBy,
+                RefKind::Redefines => RelationshipKind::Redefines,
+                RefKind::Subsets => RelationshipKind::Subsets,
+                _ => continue,
+            };
+            if let Some(target) = &tr.resolved_target {
+                edges.push((Some(kind), target.clone()));
+            }
+        }
+        for supertype in &symbol.supertypes {
+            let named = symbol
+                .type_refs
+                .iter()
+                .any(|trk| trk.as_refs().iter().any(|tr| tr.target == *supertype));
+            // The library root itself (`Parts::Part`) is given its own name
+            if !named && supertype.contains("::") && *supertype != symbol.qualified_name {
+                edges.push((None, supertype.clone()));
+            }
+        }
+        edges
+    }
+
+    /// Record a file's symbols in the reverse type hierarchy.
+    fn record_subtypes(&mut self, file: FileId) {
+        let Some(indices) = self.by_file.get(&file) else {
+            return;
+        };
+        for &idx in indices {
+            for (_, target) in Self::hierarchy_edges(&self.symbols[idx]) {
+                self.subtypes.entry(target).or_default().insert(idx);
+            }
+        }
+    }
+
+    /// Remove a file's symbols from the reverse type hierarchy.
+    ///
+    /// Like [`Self::forget_dependencies`], must run before the file's symbols
+    /// are replaced.
+    fn forget_subtypes(&mut self, file: FileId) {
+        let Some(indices) = self.by_file.get(&file) else {
+            return;
+        };
+        for &idx in indices {
+            for (_, target) in Self::hierarchy_edges(&self.symbols[idx]) {
+                if let Some(subtypes) = self.subtypes.get_mut(&target) {
+                    subtypes.remove(&idx);
+                    if subtypes.is_empty() {
+                        self.subtypes.remove(&target);
+                    }
                 }
             }
         }
@@ -1037,7 +1466,7 @@ impl SymbolIndex {
         &self,
         containing_symbol: &str,
         target: &str,
-        chain_context: &Option<(std::rc::Rc<Vec<Arc<str>>>, usize)>,
+        chain_context: &Option<(Arc<Vec<Arc<str>>>, usize)>,
         cache: &mut ResolutionCache,
     ) -> Option<Arc<str>> {
         // Get the scope for resolution
@@ -2090,11 +2519,9 @@ impl SymbolIndex {
         self.visibility_map
             .insert(Arc::from(""), ScopeVisibility::new(""));
 
-        for symbol in &self.symbols {
-            // Skip symbols that have been removed (not in by_qualified_name lookup)
-            if !self.by_qualified_name.contains_key(&symbol.qualified_name) {
-                continue;
-            }
+        // Skip symbols that have been removed
+        for idx in self.live_symbol_indices() {
+            let symbol = &self.symbols[idx];
 
             // Ensure this symbol's scope exists (for namespace-creating symbols)
             // Include usages too - they can have nested members and need inherited members from their type
@@ -2123,55 +2550,7 @@ impl SymbolIndex {
                 .visibility_map
                 .entry(parent_scope.clone())
                 .or_insert_with(|| ScopeVisibility::new(parent_scope.clone()));
-
-            vis.add_direct(symbol.name.clone(), symbol.qualified_name.clone());
-
-            // Also register by short_name if available
-            if let Some(ref short_name) = symbol.short_name {
-                vis.add_direct(short_name.clone(), symbol.qualified_name.clone());
-            }
-
-            // Register anonymous redefining symbols under their base name.
-            // Pattern: `<:>>speedSensor#77@L789>` should be accessible as `speedSensor`
-            // This enables chains like `speedSensor.speedSensorPort.sensedSpeedSent` to resolve
-            // through the local redefining symbol rather than the inherited definition.
-            if symbol.name.starts_with("<:>>") {
-                // Extract base name: `<:>>speedSensor#77@L789>` -> `speedSensor`
-                if let Some(hash_pos) = symbol.name.find('#') {
-                    let base_name: Arc<str> = Arc::from(&symbol.name[4..hash_pos]);
-                    vis.add_direct(base_name, symbol.qualified_name.clone());
-                }
-            }
-
-            // Register ANONYMOUS symbols with explicit `redefines` relationships under the redefined name.
-            // Pattern: `perform ActionTree::providePower redefines providePower` creates a symbol
-            // named `<perform:ActionTree::providePower#24@L568>` that should be accessible as `providePower`.
-            // IMPORTANT: Only do this for anonymous symbols (name starts with '<') to avoid shadowing
-            // legitimate qualified references in named symbols.
-            if symbol.name.starts_with('<') {
-                for type_ref in &symbol.type_refs {
-                    for tr in type_ref.as_refs() {
-                        if tr.kind == RefKind::Redefines {
-                            // The redefines target is the name we should be visible as
-                            vis.add_direct(tr.target.clone(), symbol.qualified_name.clone());
-                        }
-                    }
-
-                    // For perform actions with chain references like `perform startVehicle.turnVehicleOn`,
-                    // register the LAST part of the chain as the visible name.
-                    // This allows `driver.turnVehicleOn` to find the perform inside driver.
-                    if symbol.name.starts_with("<perform:") {
-                        if let crate::hir::TypeRefKind::Chain(chain) = type_ref {
-                            if let Some(last_part) = chain.parts.last() {
-                                vis.add_direct(
-                                    last_part.target.clone(),
-                                    symbol.qualified_name.clone(),
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+            Self::register_direct(vis, symbol);
 
             // If the parent scope is anonymous (contains `<` which indicates generated names),
             // also add this symbol to the grandparent scope so it's accessible from siblings.
@@ -2184,17 +2563,7 @@ impl SymbolIndex {
                         .visibility_map
                         .entry(grandparent_arc.clone())
                         .or_insert_with(|| ScopeVisibility::new(grandparent_arc));
-                    gp_vis.add_direct(symbol.name.clone(), symbol.qualified_name.clone());
-                    if let Some(ref short_name) = symbol.short_name {
-                        gp_vis.add_direct(short_name.clone(), symbol.qualified_name.clone());
-                    }
-                    // Also register anonymous redefining symbols in grandparent
-                    if symbol.name.starts_with("<:>>") {
-                        if let Some(hash_pos) = symbol.name.find('#') {
-                            let base_name: Arc<str> = Arc::from(&symbol.name[4..hash_pos]);
-                            gp_vis.add_direct(base_name, symbol.qualified_name.clone());
-                        }
-                    }
+                    Self::register_promoted(gp_vis, symbol);
                 }
             }
         }
@@ -2205,13 +2574,13 @@ impl SymbolIndex {
         let scope_keys: Vec<_> = self.visibility_map.keys().cloned().collect();
 
         for scope in &scope_keys {
-            self.process_imports_recursive(scope, &mut visited);
+            self.process_imports_recursive(scope, &mut visited, None);
         }
         let t_imports = t_imports_start.elapsed();
 
         // 4. Propagate inherited members from supertypes (can now resolve types via imports)
         let t_inherit_start = std::time::Instant::now();
-        self.propagate_inherited_members();
+        self.propagate_inherited_members(None);
         let t_inherit = t_inherit_start.elapsed();
 
         tracing::info!(
@@ -2222,22 +2591,318 @@ impl SymbolIndex {
         );
     }
 
+    /// Register a symbol in its parent scope's direct definitions.
+    fn register_direct(vis: &mut ScopeVisibility, symbol: &HirSymbol) {
+        vis.add_direct(symbol.name.clone(), symbol.qualified_name.clone());
+
+        // Also register by short_name if available
+        if let Some(ref short_name) = symbol.short_name {
+            vis.add_direct(short_name.clone(), symbol.qualified_name.clone());
+        }
+
+        // Register anonymous redefining symbols under their base name.
+        // Pattern: `<:>>speedSensor#77@L789>` should be accessible as `speedSensor`
+        // This enables chains like `speedSensor.speedSensorPort.sensedSpeedSent` to resolve
+        // through the local redefining symbol rather than the inherited definition.
+        if symbol.name.starts_with("<:>>") {
+            // Extract base name: `<:>>speedSensor#77@L789>` -> `speedSensor`
+            if let Some(hash_pos) = symbol.name.find('#') {
+                let base_name: Arc<str> = Arc::from(&symbol.name[4..hash_pos]);
+                vis.add_direct(base_name, symbol.qualified_name.clone());
+            }
+        }
+
+        // Register ANONYMOUS symbols with explicit `redefines` relationships under the redefined name.
+        // Pattern: `perform ActionTree::providePower redefines providePower` creates a symbol
+        // named `<perform:ActionTree::providePower#24@L568>` that should be accessible as `providePower`.
+        // IMPORTANT: Only do this for anonymous symbols (name starts with '<') to avoid shadowing
+        // legitimate qualified references in named symbols.
+        if symbol.name.starts_with('<') {
+            for type_ref in &symbol.type_refs {
+                for tr in type_ref.as_refs() {
+                    if tr.kind == RefKind::Redefines {
+                        // The redefines target is the name we should be visible as
+                        vis.add_direct(tr.target.clone(), symbol.qualified_name.clone());
+                    }
+                }
+
+                // For perform actions with chain references like `perform startVehicle.turnVehicleOn`,
+                // register the LAST part of the chain as the visible name.
+                // This allows `driver.turnVehicleOn` to find the perform inside driver.
+                if symbol.name.starts_with("<perform:") {
+                    if let crate::hir::TypeRefKind::Chain(chain) = type_ref {
+                        if let Some(last_part) = chain.parts.last() {
+                            vis.add_direct(last_part.target.clone(), symbol.qualified_name.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Register a symbol of an anonymous scope in the direct definitions of
+    /// the scope enclosing it.
+    fn register_promoted(vis: &mut ScopeVisibility, symbol: &HirSymbol) {
+        vis.add_direct(symbol.name.clone(), symbol.qualified_name.clone());
+        if let Some(ref short_name) = symbol.short_name {
+            vis.add_direct(short_name.clone(), symbol.qualified_name.clone());
+        }
+        // Also register anonymous redefining symbols in grandparent
+        if symbol.name.starts_with("<:>>") {
+            if let Some(hash_pos) = symbol.name.find('#') {
+                let base_name: Arc<str> = Arc::from(&symbol.name[4..hash_pos]);
+                vis.add_direct(base_name, symbol.qualified_name.clone());
+            }
+        }
+    }
+
+    /// Whether `qualified_name` is registered in the direct definitions of
+    /// `scope` by the scope itself, rather than inherited from a supertype.
+    fn is_declared_in(scope: &str, qualified_name: &str) -> bool {
+        let parent = Self::parent_scope(qualified_name).unwrap_or("");
+        parent == scope
+            || (parent.contains('<') && Self::parent_scope(parent).unwrap_or("") == scope)
+    }
+
+    /// The scopes whose visibility maps may change when the given symbols are
+    /// added, removed or modified.
+    ///
+    /// These are the symbols' own scopes and their parents, plus the scopes
+    /// importing or inheriting from any affected scope, transitively. When an
+    /// import or a supertype changes, a scope's names change wholesale, so its
+    /// nested scopes with supertypes (which may now resolve elsewhere) and the
+    /// scopes importing it recursively are affected as well.
+    fn scopes_affected_by(&self, qualified_names: &[Arc<str>]) -> HashSet<Arc<str>> {
+        // (scope, whether its names may change wholesale)
+        let mut pending: Vec<(Arc<str>, bool)> = Vec::new();
+        for qname in qualified_names {
+            let parent = Self::parent_scope(qname).unwrap_or("");
+            if Self::is_import_name(qname) {
+                pending.push((Arc::from(parent), true));
+                continue;
+            }
+            pending.push((qname.clone(), true));
+            // The names an anonymous symbol is registered under aren't its own
+            let anonymous = Self::last_segment(qname).starts_with('<') || parent.contains('<');
+            pending.push((Arc::from(parent), anonymous));
+            if parent.contains('<') {
+                let grandparent = Self::parent_scope(parent).unwrap_or("");
+                pending.push((Arc::from(grandparent), true));
+            }
+            if let Some(short_name) = self
+                .lookup_qualified(qname)
+                .and_then(|symbol| symbol.short_name.as_deref())
+            {
+                pending.extend(
+                    self.importing_scopes(short_name)
+                        .map(|s| (Arc::from(s), true)),
+                );
+                pending.extend(
+                    self.inheriting_scopes(short_name)
+                        .map(|s| (s.clone(), true)),
+                );
+            }
+        }
+
+        let mut affected: HashSet<Arc<str>> = HashSet::new();
+        let mut wholesale: HashSet<Arc<str>> = HashSet::new();
+        while let Some((scope, is_wholesale)) = pending.pop() {
+            let first_visit = affected.insert(scope.clone());
+            let upgraded = is_wholesale && wholesale.insert(scope.clone());
+            if !first_visit && !upgraded {
+                continue;
+            }
+
+            if !scope.is_empty() {
+                let name = Self::last_segment(&scope);
+                pending.extend(
+                    self.importing_scopes(name)
+                        .map(|s| (Arc::from(s), is_wholesale)),
+                );
+                pending.extend(
+                    self.inheriting_scopes(name)
+                        .map(|s| (s.clone(), is_wholesale)),
+                );
+            }
+            if !is_wholesale {
+                continue;
+            }
+
+            // Recursive imports of any enclosing namespace take in this scope's names
+            let mut ancestor = Self::parent_scope(&scope);
+            while let Some(namespace) = ancestor.filter(|a| !a.is_empty()) {
+                for idx in self
+                    .importers
+                    .get(Self::last_segment(namespace))
+                    .into_iter()
+                    .flatten()
+                {
+                    let import = &self.symbols[*idx];
+                    if import.name.ends_with("::**") {
+                        let importing = Self::parent_scope(&import.qualified_name).unwrap_or("");
+                        pending.push((Arc::from(importing), true));
+                    }
+                }
+                ancestor = Self::parent_scope(namespace);
+            }
+
+            let mut nested = vec![scope];
+            while let Some(namespace) = nested.pop() {
+                for &idx in self.by_parent_scope.get(&namespace).into_iter().flatten() {
+                    let symbol = &self.symbols[idx];
+                    if !symbol.supertypes.is_empty() {
+                        pending.push((symbol.qualified_name.clone(), true));
+                    }
+                    nested.push(symbol.qualified_name.clone());
+                }
+            }
+        }
+        affected
+    }
+
+    /// The symbols whose supertype names mention `name`.
+    fn inheriting_scopes(&self, name: &str) -> impl Iterator<Item = &Arc<str>> {
+        self.inheritors
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|&idx| &self.symbols[idx].qualified_name)
+    }
+
+    /// Rebuild the visibility maps of `scopes`, taking the maps of every other
+    /// scope as up to date.
+    ///
+    /// Runs the same phases as [`Self::build_visibility_maps`], restricted to
+    /// the given scopes, so both produce the same maps.
+    fn rebuild_visibility_for_scopes(&mut self, scopes: &HashSet<Arc<str>>) {
+        // Symbols of anonymous scopes are also registered in the enclosing scope
+        let mut promoted: HashMap<&str, Vec<SymbolIdx>> = HashMap::new();
+        for (parent, indices) in &self.by_parent_scope {
+            if !parent.contains('<') {
+                continue;
+            }
+            if let Some(grandparent) = Self::parent_scope(parent) {
+                if scopes.contains(grandparent) {
+                    promoted.entry(grandparent).or_default().extend(indices);
+                }
+            }
+        }
+
+        // 1. Direct definitions, registered in index order like the full build
+        let mut rebuilt: Vec<(Arc<str>, ScopeVisibility)> = Vec::new();
+        for scope in scopes {
+            let mut members: Vec<(SymbolIdx, bool)> = self
+                .by_parent_scope
+                .get(scope)
+                .into_iter()
+                .flatten()
+                .map(|&idx| (idx, false))
+                .chain(
+                    promoted
+                        .get(scope.as_ref())
+                        .into_iter()
+                        .flatten()
+                        .map(|&idx| (idx, true)),
+                )
+                .filter(|&(idx, _)| self.symbols[idx].kind != SymbolKind::Import)
+                .collect();
+            members.sort_unstable();
+
+            let is_namespace = self.lookup_qualified(scope).is_some_and(|symbol| {
+                symbol.kind == SymbolKind::Package
+                    || symbol.kind.is_definition()
+                    || symbol.kind.is_usage()
+            });
+            if !scope.is_empty() && !is_namespace && members.is_empty() {
+                continue;
+            }
+
+            let mut vis = ScopeVisibility::new(scope.clone());
+            for (idx, is_promoted) in members {
+                if is_promoted {
+                    Self::register_promoted(&mut vis, &self.symbols[idx]);
+                } else {
+                    Self::register_direct(&mut vis, &self.symbols[idx]);
+                }
+            }
+            rebuilt.push((scope.clone(), vis));
+        }
+        for scope in scopes {
+            self.visibility_map.remove(scope);
+        }
+        self.visibility_map.extend(rebuilt);
+
+        // 2. Imports
+        let mut visited: HashSet<(Arc<str>, Arc<str>)> = HashSet::new();
+        for scope in scopes {
+            if self.visibility_map.contains_key(scope) {
+                self.process_imports_recursive(scope, &mut visited, Some(scopes));
+            }
+        }
+
+        // 3. Inherited members. Which supertype a member is inherited from
+        // depends on the order supertypes gain their own inherited members,
+        // so the supertypes start over too and are propagated in the same order.
+        let lineage = self.supertype_closure(scopes);
+        for scope in lineage.difference(scopes) {
+            if let Some(vis) = self.visibility_map.get_mut(scope) {
+                vis.direct_defs
+                    .retain(|_, qname| Self::is_declared_in(scope, qname));
+            }
+        }
+        self.propagate_inherited_members(Some(&lineage));
+    }
+
+    /// The given scopes plus their supertypes, transitively.
+    fn supertype_closure(&self, scopes: &HashSet<Arc<str>>) -> HashSet<Arc<str>> {
+        let mut found = scopes.clone();
+        let mut pending: Vec<Arc<str>> = scopes.iter().cloned().collect();
+        while let Some(scope) = pending.pop() {
+            let Some(symbol) = self.lookup_qualified(&scope) else {
+                continue;
+            };
+            let parent = Self::parent_scope(&scope).unwrap_or("");
+            for supertype in &symbol.supertypes {
+                if let Some(resolved) =
+                    self.resolve_supertype_for_inheritance(supertype, parent, Some(&scope))
+                {
+                    if found.insert(resolved.clone()) {
+                        pending.push(resolved);
+                    }
+                }
+            }
+        }
+        found
+    }
+
     /// Propagate inherited members from supertypes into scope visibility maps.
     /// When `Shape :> Path`, members of `Path` become visible in `Shape`.
     ///
     /// Uses topological ordering by scope depth: shallower scopes are processed first.
     /// This ensures that when processing `Shape::tfe` (which inherits from `edges`),
     /// `Shape` has already inherited `edges` from `Path`.
-    fn propagate_inherited_members(&mut self) {
+    ///
+    /// With `only`, just the scopes in it inherit members. It must include the
+    /// supertypes of its scopes for them to inherit what a full build would.
+    fn propagate_inherited_members(&mut self, only: Option<&HashSet<Arc<str>>>) {
         // Collect symbols with their unresolved supertypes and parent scope for later resolution
         // Format: (qualified_name, parent_scope, unresolved_supertype_name)
         let mut inheritance_edges: Vec<(Arc<str>, Arc<str>, Arc<str>)> = Vec::new();
 
-        for symbol in &self.symbols {
-            // Skip symbols that have been removed
-            if !self.by_qualified_name.contains_key(&symbol.qualified_name) {
-                continue;
+        // Skip symbols that have been removed
+        let indices = match only {
+            Some(scopes) => {
+                let mut indices: Vec<SymbolIdx> = scopes
+                    .iter()
+                    .filter_map(|scope| self.by_qualified_name.get(scope).copied())
+                    .collect();
+                indices.sort_unstable();
+                indices
             }
+            None => self.live_symbol_indices(),
+        };
+        for idx in indices {
+            let symbol = &self.symbols[idx];
 
             if !symbol.supertypes.is_empty() {
                 let scope = &symbol.qualified_name;
@@ -2406,11 +3071,17 @@ impl SymbolIndex {
     }
 
     /// Process imports for a scope recursively, handling transitive public re-exports.
+    ///
+    /// With `only`, the scopes outside it are taken as already processed.
     fn process_imports_recursive(
         &mut self,
         scope: &str,
         visited: &mut HashSet<(Arc<str>, Arc<str>)>,
+        only: Option<&HashSet<Arc<str>>>,
     ) {
+        if only.is_some_and(|scopes| !scopes.contains(scope)) {
+            return;
+        }
         let scope_arc: Arc<str> = Arc::from(scope);
 
         // Find import symbols in this scope using the parent index (much faster than scanning all symbols)
@@ -2449,7 +3120,7 @@ impl SymbolIndex {
                     if !visited.contains(&marker) {
                         visited.insert(marker);
                         // Recursively process the parent package first
-                        self.process_imports_recursive(parent_pkg, visited);
+                        self.process_imports_recursive(parent_pkg, visited, only);
                     }
                 }
             }
@@ -2467,7 +3138,7 @@ impl SymbolIndex {
                 visited.insert(key);
 
                 // Recursively process the target's imports first (to get transitive symbols)
-                self.process_imports_recursive(&resolved_target, visited);
+                self.process_imports_recursive(&resolved_target, visited, only);
 
                 // Get filter info - both scope filters and import-specific filters
                 let scope_filters = self.scope_filters.get(scope).cloned();
@@ -2480,8 +3151,11 @@ impl SymbolIndex {
                 if let Some(target_vis) = self.visibility_map.get(&resolved_target as &str).cloned()
                 {
                     // Collect symbols to import (applying filter)
+                    // Inherited members aren't imported. The full build imports
+                    // before inheriting, but an incremental one may see them.
                     let direct_defs_to_import: Vec<_> = target_vis
                         .direct_defs()
+                        .filter(|(_, qname)| Self::is_declared_in(&resolved_target, qname))
                         .filter(|(_, qname)| {
                             // Apply filter if present
                             if let Some(ref filters) = active_filters {
@@ -3347,6 +4021,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -3526,6 +4201,242 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reverse_dependencies() {
+        use crate::hir::symbols::extract_symbols_unified;
+        use crate::syntax::SyntaxFile;
+
+        let lib = FileId::new(0);
+        let user = FileId::new(1);
+        let mut index = SymbolIndex::new();
+        index.add_file(
+            lib,
+            extract_symbols_unified(lib, &SyntaxFile::sysml("package Lib { part def Engine; }")),
+        );
+        index.add_file(user, extract_symbols_unified(
+                user,
+                &SyntaxFile::sysml(
+                    "package App { private import Lib::*; part engine : Engine; part wheel : Wheel; }",
+                ),
+            ));
+        index.resolve_all_type_refs();
+
+        assert_eq!(index.dependents_of("Lib::Engine"), vec![user]);
+        assert!(index.dependents_of("Lib::Missing").is_empty());
+
+        // A new `Wheel` anywhere may satisfy the unresolved ref in `user`
+        assert_eq!(
+            index.files_affected_by(&[Arc::from("Lib::Wheel")]),
+            vec![user]
+        );
+        // ...and a new `Engine` may shadow the one its resolved ref points at
+        assert_eq!(
+            index.files_affected_by(&[Arc::from("App::Engine")]),
+            vec![user]
+        );
+
+        // Re-resolving replaces the file's old entries
+        index.add_file(
+            user,
+            extract_symbols_unified(user, &SyntaxFile::sysml("package App;")),
+        );
+        index.resolve_type_refs_for_files(&[user]);
+        assert!(index.dependents_of("Lib::Engine").is_empty());
+        assert!(
+            index
+                .files_affected_by(&[Arc::from("Lib::Wheel")])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parallel_resolution_is_deterministic() {
+        use crate::hir::symbols::extract_symbols_unified;
+        use crate::syntax::SyntaxFile;
+
+        // Each package imports the previous one and shadows some of its names
+        let mut index = SymbolIndex::new();
+        for i in 0..32u32 {
+            let source = format!(
+                "package P{i} {{ public import P{prev}::*; part def Engine; part def Wheel{i} :> Wheel{prev}; \
+                 part car {{ part engine : Engine; part wheel : Wheel{prev}; part spare :> wheel; }} }}",
+                prev = i.saturating_sub(1),
+            );
+            let file = FileId::new(i);
+            index.add_file(
+                file,
+                extract_symbols_unified(file, &SyntaxFile::sysml(&source)),
+            );
+        }
+
+        let resolved_targets = |index: &SymbolIndex| -> Vec<Option<Arc<str>>> {
+            index
+                .all_symbols()
+                .flat_map(|symbol| symbol.type_refs.iter().flat_map(|trk| trk.as_refs()))
+                .map(|type_ref| type_ref.resolved_target.clone())
+                .collect()
+        };
+        let resolve_with_threads = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let mut index = index.clone();
+            pool.install(|| index.resolve_all_type_refs());
+            resolved_targets(&index)
+        };
+
+        let sequential = resolve_with_threads(1);
+        assert!(sequential.iter().any(Option::is_some));
+        for _ in 0..8 {
+            assert_eq!(resolve_with_threads(4), sequential);
+        }
+    }
+
+    #[test]
+    fn test_update_visibility_for_changes_matches_full_build() {
+        use crate::hir::symbols::extract_symbols_unified;
+        use crate::syntax::SyntaxFile;
+
+        let lib = FileId::new(0);
+        let app = FileId::new(1);
+        let user = FileId::new(2);
+        let mut index = SymbolIndex::new();
+        let sources = [
+            (lib, "package Lib { part def Engine { part piston; } }"),
+            (
+                app,
+                "package App { public import Lib::*; part def V8 :> Engine; }",
+            ),
+            (
+                user,
+                "package User { private import App::*; part def V12 :> V8; }",
+            ),
+        ];
+        for (file, source) in sources {
+            index.add_file(
+                file,
+                extract_symbols_unified(file, &SyntaxFile::sysml(source)),
+            );
+        }
+        index.ensure_visibility_maps();
+
+        index.add_file(
+            lib,
+            extract_symbols_unified(
+                lib,
+                &SyntaxFile::sysml(
+                    "package Lib { part def Engine { part piston; part crank; } part def Wheel; }",
+                ),
+            ),
+        );
+        index.update_visibility_for_changes(&[
+            Arc::from("Lib::Engine::crank"),
+            Arc::from("Lib::Wheel"),
+        ]);
+
+        // The new members reach the scopes inheriting or importing them...
+        let visible = |index: &SymbolIndex, scope: &str, name: &str| {
+            let vis = &index.visibility_maps()[scope];
+            vis.lookup_direct(name)
+                .or_else(|| vis.imports.get(name))
+                .cloned()
+        };
+        assert_eq!(
+            visible(&index, "User::V12", "crank").as_deref(),
+            Some("Lib::Engine::crank")
+        );
+        assert_eq!(
+            visible(&index, "User", "Wheel").as_deref(),
+            Some("Lib::Wheel")
+        );
+
+        // ...and every map is the one a full build makes
+        let mut full = index.clone();
+        full.mark_visibility_dirty();
+        full.ensure_visibility_maps();
+        assert_eq!(index.visibility_maps().len(), full.visibility_maps().len());
+        for (scope, vis) in full.visibility_maps() {
+            let updated = &index.visibility_maps()[scope];
+            assert_eq!(updated.direct_defs, vis.direct_defs, "{scope}");
+            assert_eq!(updated.imports, vis.imports, "{scope}");
+            assert_eq!(updated.public_reexports, vis.public_reexports, "{scope}");
+        }
+    }
+
+    #[test]
+    fn test_reverse_type_hierarchy() {
+        use crate::hir::symbols::extract_symbols_unified;
+        use crate::syntax::SyntaxFile;
+
+        let lib = FileId::new(0);
+        let user = FileId::new(1);
+        let mut index = SymbolIndex::new();
+        index.add_file(
+            lib,
+            extract_symbols_unified(lib, &SyntaxFile::sysml("package Lib { part def Engine; }")),
+        );
+        index.add_file(
+            user,
+            extract_symbols_unified(
+                user,
+                &SyntaxFile::sysml(
+                    "package App { private import Lib::*; part def V8 :> Engine; part e : V8; }",
+                ),
+            ),
+        );
+        index.resolve_all_type_refs();
+
+        let names = |subtypes: Vec<(RelationshipKind, &HirSymbol)>| -> Vec<String> {
+            subtypes
+                .iter()
+                .map(|(kind, s)| format!("{} {}", kind.display(), s.qualified_name))
+                .collect()
+        };
+        assert_eq!(
+            names(index.subtypes_of("Lib::Engine")),
+            ["Specializes App::V8"]
+        );
+        assert_eq!(names(index.subtypes_of("App::V8")), ["Typed by App::e"]);
+        let v8 = index.lookup_qualified("App::V8").unwrap();
+        assert_eq!(names(index.supertypes_of(v8)), ["Specializes Lib::Engine"]);
+
+        // Removing the file removes its symbols from the reverse hierarchy
+        index.remove_file(user);
+        assert!(index.subtypes_of("Lib::Engine").is_empty());
+    }
+
+    #[test]
+    fn test_add_file_records_kerml_files() {
+        use crate::hir::symbols::{extract_symbols_unified, extract_with_filters};
+        use crate::syntax::SyntaxFile;
+
+        let kerml = FileId::new(0);
+        let sysml = FileId::new(1);
+        let empty_kerml = FileId::new(2);
+        let mut index = SymbolIndex::new();
+        index.add_file_with_kind(
+            kerml,
+            extract_symbols_unified(kerml, &SyntaxFile::kerml("package Lib { classifier A; }")),
+            FileExtension::KerML,
+        );
+        index.add_file(
+            sysml,
+            extract_symbols_unified(sysml, &SyntaxFile::sysml("package App { part def B; }")),
+        );
+        // A KerML file without symbols is still recorded as KerML
+        index.add_extraction_result(
+            empty_kerml,
+            extract_with_filters(empty_kerml, &SyntaxFile::kerml("")),
+        );
+        assert!(index.is_kerml_file(kerml));
+        assert!(!index.is_kerml_file(sysml));
+        assert!(index.is_kerml_file(empty_kerml));
+
+        index.remove_file(kerml);
+        assert!(!index.is_kerml_file(kerml));
+    }
+
     #[test]
     fn test_debug_message_chain_resolution() {
         use crate::hir::symbols::extract_symbols_unified;
@@ -10,17 +10,27 @@ use crate::base::FileId;
 /// Manages the mapping between file paths and FileIds.
 ///
 /// This is the "file database" that assigns stable IDs to paths
-/// and tracks file contents.
+/// and tracks file contents. A path keeps its FileId for the lifetime of
+/// the set, even across removal, and ids are never reused for another path.
 #[derive(Debug, Default)]
 pub struct FileSet {
     inner: RwLock<FileSetInner>,
 }
 
-#[derive(Debug, Default)]
+// Manual Clone implementation because RwLock doesn't implement Clone
+impl Clone for FileSet {
+    fn clone(&self) -> Self {
+        Self {
+            inner: RwLock::new(self.inner.read().clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 struct FileSetInner {
-    /// Path → FileId mapping
+    /// Path → FileId mapping for every path ever seen (survives removal)
     path_to_id: IndexMap<PathBuf, FileId>,
-    /// FileId → Path mapping (reverse lookup)
+    /// FileId → Path mapping (reverse lookup) for files currently in the set
     id_to_path: IndexMap<FileId, PathBuf>,
     /// FileId → Contents
     contents: IndexMap<FileId, Arc<str>>,
@@ -36,22 +46,25 @@ impl FileSet {
 
     /// Get or create a FileId for a path.
     ///
-    /// If the path already has a FileId, returns it.
-    /// Otherwise, assigns a new FileId.
+    /// If the path already has a FileId, returns it (re-adding the file if it
+    /// was removed). Otherwise, assigns a new FileId.
     pub fn file_id(&self, path: &Path) -> FileId {
         // Fast path: read lock
         {
             let inner = self.inner.read();
             if let Some(&id) = inner.path_to_id.get(path) {
-                return id;
+                if inner.id_to_path.contains_key(&id) {
+                    return id;
+                }
             }
         }
 
         // Slow path: write lock
         let mut inner = self.inner.write();
 
-        // Double-check
+        // Double-check, and bring a removed path back under its old id
         if let Some(&id) = inner.path_to_id.get(path) {
+            inner.id_to_path.insert(id, path.to_owned());
             return id;
         }
 
@@ -62,6 +75,13 @@ impl FileSet {
         id
     }
 
+    /// Get the FileId of a file in the set, without assigning one.
+    pub fn lookup(&self, path: &Path) -> Option<FileId> {
+        let inner = self.inner.read();
+        let id = *inner.path_to_id.get(path)?;
+        inner.id_to_path.contains_key(&id).then_some(id)
+    }
+
     /// Get the path for a FileId.
     pub fn path(&self, file: FileId) -> Option<PathBuf> {
         self.inner.read().id_to_path.get(&file).cloned()
@@ -78,17 +98,18 @@ impl FileSet {
     }
 
     /// Remove a file from the set.
+    ///
+    /// Other files keep their ids, and the path keeps its id reserved so
+    /// adding it again hands out the same FileId.
     pub fn remove(&self, file: FileId) {
         let mut inner = self.inner.write();
-        if let Some(path) = inner.id_to_path.swap_remove(&file) {
-            inner.path_to_id.swap_remove(&path);
-        }
-        inner.contents.swap_remove(&file);
+        inner.id_to_path.shift_remove(&file);
+        inner.contents.shift_remove(&file);
     }
 
     /// Get the number of files.
     pub fn len(&self) -> usize {
-        self.inner.read().path_to_id.len()
+        self.inner.read().id_to_path.len()
     }
 
     /// Check if the file set is empty.
@@ -138,4 +159,28 @@ mod tests {
 
         assert_eq!(files.path(id).as_deref(), Some(path));
     }
+
+    #[test]
+    fn test_file_set_remove_keeps_ids_stable() {
+        let files = FileSet::new();
+        let a = files.file_id(Path::new("/a.sysml"));
+        let b = files.file_id(Path::new("/b.sysml"));
+        let c = files.file_id(Path::new("/c.sysml"));
+
+        files.remove(a);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.files(), vec![b, c]);
+        assert!(files.path(a).is_none());
+        assert!(files.lookup(Path::new("/a.sysml")).is_none());
+
+        // Remaining files keep their ids, new paths get fresh ones
+        assert_eq!(files.file_id(Path::new("/c.sysml")), c);
+        let d = files.file_id(Path::new("/d.sysml"));
+        assert!(d != a && d != b && d != c);
+
+        // A removed path comes back under its original id
+        assert_eq!(files.file_id(Path::new("/a.sysml")), a);
+        assert_eq!(files.lookup(Path::new("/a.sysml")), Some(a));
+        assert_eq!(files.len(), 4);
+    }
 }
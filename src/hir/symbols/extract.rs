@@ -1,6 +1,7 @@
 //! Unified extraction entry points and AST member dispatch.
 
 use crate::base::FileId;
+use crate::hir::diagnostics::collect_suppressions;
 use crate::parser::NamespaceMember;
 
 use super::context::ExtractionContext;
@@ -32,7 +33,10 @@ pub fn extract_symbols_unified(file: FileId, syntax: &crate::syntax::SyntaxFile)
 ///
 /// Returns both symbols and scope filter information for import filtering.
 pub fn extract_with_filters(file: FileId, syntax: &crate::syntax::SyntaxFile) -> ExtractionResult {
-    let mut result = ExtractionResult::default();
+    let mut result = ExtractionResult {
+        is_kerml: syntax.is_kerml(),
+        ..Default::default()
+    };
     let line_index = syntax.line_index();
     let mut context = ExtractionContext {
         file,
@@ -48,6 +52,7 @@ pub fn extract_with_filters(file: FileId, syntax: &crate::syntax::SyntaxFile) ->
             extract_from_ast_member(&mut result, &mut context, &member);
         }
     }
+    result.suppressions = collect_suppressions(&syntax.parse().syntax(), &context.line_index);
 
     result
 }
@@ -20,6 +20,7 @@ fn extract_definition_rels_from_ast(def: &AstDefinition) -> Vec<ExtractedRel> {
     let mut rels = Vec::new();
 
     // Specializations
+    let mut previous = None;
     for spec in def.specializations() {
         let rel_kind = match spec.kind() {
             Some(SpecializationKind::Specializes) => RelKind::Specializes,
@@ -28,8 +29,12 @@ fn extract_definition_rels_from_ast(def: &AstDefinition) -> Vec<ExtractedRel> {
             Some(SpecializationKind::References) => RelKind::References,
             Some(SpecializationKind::Conjugates) => RelKind::Specializes,
             Some(SpecializationKind::FeatureChain) => RelKind::Specializes,
+            Some(SpecializationKind::Other) => RelKind::Other,
+            // Comma-continuation of `unions A, B` etc.
+            None if previous == Some(RelKind::Other) => RelKind::Other,
             None => RelKind::Specializes, // Comma-continuation
         };
+        previous = Some(rel_kind);
         if let Some(target) = spec.target() {
             rels.push(ExtractedRel {
                 kind: rel_kind,
@@ -226,6 +231,7 @@ pub(super) fn extract_definition_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
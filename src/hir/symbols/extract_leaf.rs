@@ -96,6 +96,7 @@ pub(super) fn extract_comment_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -176,6 +177,7 @@ pub(super) fn extract_alias_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -278,6 +280,7 @@ pub(super) fn extract_import_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -371,6 +374,7 @@ pub(super) fn extract_dependency_from_ast(
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -65,6 +65,7 @@ pub(super) fn extract_package_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -136,6 +137,7 @@ pub(super) fn extract_library_package_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -224,6 +226,7 @@ pub(super) fn extract_filter_from_ast(
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -112,6 +112,7 @@ fn push_special_usage_symbol(
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -238,6 +239,7 @@ fn push_special_usage_symbol(
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -623,8 +625,11 @@ pub(super) fn extract_connect_usage_from_ast(
                             is_ordered: false,
                             is_nonunique: false,
                             is_portion: false,
+                            is_conjugated: false,
                             direction: None,
-                            multiplicity: None,
+                            multiplicity: end
+                                .multiplicity()
+                                .map(|(lower, upper)| parser::Multiplicity { lower, upper }),
                             value: None,
                         });
                     }
@@ -829,6 +834,7 @@ pub(super) fn extract_accept_action_from_ast(
                             is_ordered: false,
                             is_nonunique: false,
                             is_portion: false,
+                            is_conjugated: false,
                             direction: None,
                             multiplicity: None,
                             value: None,
@@ -859,6 +865,27 @@ pub(super) fn extract_state_subaction_from_ast(
         }
     }
 
+    // Specializations (`do action doAction : Action :>> 'do';`)
+    for spec in subaction
+        .syntax()
+        .children()
+        .filter_map(parser::Specialization::cast)
+    {
+        let kind = match spec.kind() {
+            Some(parser::SpecializationKind::Specializes) => RelKind::Specializes,
+            Some(parser::SpecializationKind::Subsets) => RelKind::Subsets,
+            Some(parser::SpecializationKind::Redefines) => RelKind::Redefines,
+            _ => continue,
+        };
+        if let Some(target) = spec.target() {
+            rels.push(ExtractedRel {
+                kind,
+                target: RelTarget::Simple(target.to_string()),
+                range: Some(target.syntax().text_range()),
+            });
+        }
+    }
+
     let body_members: Vec<NamespaceMember> = subaction
         .body()
         .map(|body| body.members().collect())
@@ -1011,6 +1038,7 @@ pub(super) fn extract_for_loop_from_ast(
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -29,7 +29,8 @@ fn extract_usage_rels_from_ast(usage: &Usage) -> Vec<ExtractedRel> {
         .typing()
         .or_else(|| usage.perform_action_usage().and_then(|p| p.typing()));
     if let Some(typing) = typing {
-        if let Some(target) = typing.target() {
+        // `part p : A, B;` is typed by both
+        for target in typing.targets() {
             rels.push(ExtractedRel {
                 kind: RelKind::TypedBy,
                 target: RelTarget::Simple(target.to_string()),
@@ -59,6 +60,7 @@ fn extract_usage_rels_from_ast(usage: &Usage) -> Vec<ExtractedRel> {
     }
 
     // Specializations
+    let mut previous = None;
     for spec in usage.specializations() {
         let rel_kind = match spec.kind() {
             Some(SpecializationKind::Specializes) => RelKind::Specializes,
@@ -67,6 +69,9 @@ fn extract_usage_rels_from_ast(usage: &Usage) -> Vec<ExtractedRel> {
             Some(SpecializationKind::References) => RelKind::References,
             Some(SpecializationKind::Conjugates) => RelKind::Specializes,
             Some(SpecializationKind::FeatureChain) => RelKind::FeatureChain,
+            Some(SpecializationKind::Other) => RelKind::Other,
+            // Comma-continuation of `unions a, b` etc.
+            None if previous == Some(RelKind::Other) => RelKind::Other,
             None => {
                 // A SPECIALIZATION with no operator but a scope-qualified target (X::Y)
                 // is a references relationship. Plain comma-continuation items are
@@ -78,6 +83,7 @@ fn extract_usage_rels_from_ast(usage: &Usage) -> Vec<ExtractedRel> {
                 }
             }
         };
+        previous = Some(rel_kind);
         if let Some(target) = spec.target() {
             let target_str = target.to_string();
             let target_range = target.syntax().text_range();
@@ -521,8 +527,12 @@ fn collect_endpoint_children_from_ast(
                     is_ordered: false,
                     is_nonunique: false,
                     is_portion: false,
+                    is_conjugated: false,
                     direction: None,
-                    multiplicity: None,
+                    multiplicity: end.multiplicity().map(|(lo, hi)| Multiplicity {
+                        lower: lo,
+                        upper: hi,
+                    }),
                     value: None,
                 });
             }
@@ -609,6 +619,7 @@ fn collect_transition_payload_from_ast(
                     is_ordered: false,
                     is_nonunique: false,
                     is_portion: false,
+                    is_conjugated: false,
                     direction: None,
                     multiplicity: None,
                     value: None,
@@ -901,6 +912,7 @@ pub(super) fn extract_usage_from_ast(
                     is_ordered: usage.is_ordered(),
                     is_nonunique: usage.is_nonunique(),
                     is_portion: usage.is_portion(),
+                    is_conjugated: usage.is_conjugated(),
                     direction: usage.direction(),
                     multiplicity: usage.multiplicity().map(|(lo, hi)| Multiplicity {
                         lower: lo,
@@ -1075,6 +1087,7 @@ pub(super) fn extract_usage_from_ast(
         is_ordered: usage.is_ordered(),
         is_nonunique: usage.is_nonunique(),
         is_portion: usage.is_portion(),
+        is_conjugated: usage.is_conjugated(),
         direction: usage.direction(),
         multiplicity: usage.multiplicity().map(|(lo, hi)| Multiplicity {
             lower: lo,
@@ -1220,6 +1233,7 @@ pub(super) fn extract_metadata_member_from_ast(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -331,6 +331,7 @@ pub(super) fn rel_kind_to_anon_prefix(kind: RelKind) -> &'static str {
         RelKind::References => "ref:",
         RelKind::Meta => "meta:",
         RelKind::Crosses => "crosses:",
+        RelKind::Other => "rel:",
         RelKind::Expression => "~",
         RelKind::FeatureChain => "chain:",
         RelKind::Conjugates => "~:",
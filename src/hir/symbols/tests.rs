@@ -110,6 +110,65 @@ fn test_direction_and_multiplicity_extraction() {
     );
 }
 
+#[test]
+fn test_conjugated_port_extraction() {
+    use crate::syntax::parser::parse_content;
+
+    let source = r#"part def Engine {
+            port fuelIn : ~FuelPort;
+            port fuelOut : FuelPort;
+        }"#;
+
+    let syntax = parse_content(source, std::path::Path::new("test.sysml")).unwrap();
+    let symbols = extract_symbols_unified(FileId::new(0), &syntax);
+
+    let fuel_in = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "fuelIn")
+        .unwrap();
+    assert!(fuel_in.is_conjugated);
+    assert_eq!(fuel_in.supertypes[0].as_ref(), "FuelPort");
+
+    let fuel_out = symbols
+        .iter()
+        .find(|s| s.name.as_ref() == "fuelOut")
+        .unwrap();
+    assert!(!fuel_out.is_conjugated);
+}
+
+#[test]
+fn test_expression_and_endpoint_multiplicity_extraction() {
+    use crate::syntax::parser::parse_content;
+
+    let source = r#"part def Vehicle {
+            part axles[1..n-1];
+            part hubs[size(axles)];
+            part wheels[4];
+            connect [2] w ::> wheels to hubs;
+        }"#;
+
+    let syntax = parse_content(source, std::path::Path::new("test.sysml")).unwrap();
+    let symbols = extract_symbols_unified(FileId::new(0), &syntax);
+    let multiplicity = |name: &str| {
+        symbols
+            .iter()
+            .find(|s| s.name.as_ref() == name)
+            .unwrap()
+            .multiplicity
+    };
+
+    // Bounds given by an expression aren't known statically
+    assert_eq!(multiplicity("axles"), None);
+    assert_eq!(multiplicity("hubs"), None);
+    assert_eq!(
+        multiplicity("w"),
+        Some(Multiplicity {
+            lower: Some(2),
+            upper: Some(2)
+        })
+    );
+}
+
 /// Regression: `flow of <PayloadType> from a to b` must extract a reference to
 /// the payload type. The first-class flow-payload grammar wraps the type in a
 /// `PAYLOAD_FEATURE` node, which the `of_type()` accessor previously did not
@@ -119,6 +119,8 @@ pub(crate) enum RelKind {
     DependencyTarget,
     // Other
     Crosses,
+    /// Set operations, disjoining, inversion and featuring
+    Other,
 }
 
 /// A relationship extracted from an AST node during symbol extraction.
@@ -182,6 +184,7 @@ pub fn new_element_id() -> Arc<str> {
 /// Type references (TypedBy, Specializes) resolve via scope walking.
 /// Feature references (Redefines, Subsets, References) resolve via inheritance hierarchy.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum RefKind {
     /// `: Type` - type annotation, resolves via scope
     TypedBy,
@@ -246,6 +249,7 @@ impl RefKind {
 
 /// The kind of relationship between symbols.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum RelationshipKind {
     /// `:>` - specialization (for definitions)
     Specializes,
@@ -320,6 +324,7 @@ impl RelationshipKind {
 
 /// A relationship from this symbol to another.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct HirRelationship {
     /// The kind of relationship
     pub kind: RelationshipKind,
@@ -381,6 +386,7 @@ impl HirRelationship {
 /// by checking if TypeRefs are adjacent (separated by a dot). This avoids
 /// storing chain metadata in the HIR layer.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeRef {
     /// The target type name as written in source (e.g., "Car", "focus")
     pub target: Arc<str>,
@@ -447,6 +453,7 @@ impl TypeRef {
 
 /// A type reference that can be either a simple reference or a chain.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeRefKind {
     /// A simple reference like `Vehicle`
     Simple(TypeRef),
@@ -500,6 +507,7 @@ impl TypeRefKind {
 
 /// A chain of type references like `engine.power.value`
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeRefChain {
     /// The parts of the chain, each with its own span
     pub parts: Vec<TypeRef>,
@@ -521,6 +529,7 @@ impl TypeRefChain {
 /// This is a simplified symbol type for the new HIR layer.
 /// It captures the essential information needed for IDE features.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct HirSymbol {
     /// The simple name of the symbol
     pub name: Arc<str>,
@@ -590,6 +599,8 @@ pub struct HirSymbol {
     pub is_nonunique: bool,
     /// Whether this symbol is a portion (slice of occurrence)
     pub is_portion: bool,
+    /// Whether this symbol is typed by a conjugated port definition (`~FuelPort`)
+    pub is_conjugated: bool,
     /// Direction (in, out, inout) for ports and parameters
     pub direction: Option<Direction>,
     /// Multiplicity bounds [lower..upper]
@@ -619,6 +630,7 @@ impl HirSymbol {
 
 /// The kind of a symbol.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum SymbolKind {
     Package,
     // Definitions
@@ -717,7 +729,7 @@ impl SymbolKind {
             Some(DefinitionKind::Rendering) => Self::RenderingDefinition,
             Some(DefinitionKind::Enum) => Self::EnumerationDefinition,
             Some(DefinitionKind::Flow) => Self::Other,
-            Some(DefinitionKind::Metadata) => Self::Other,
+            Some(DefinitionKind::Metadata) => Self::MetadataDefinition,
             Some(DefinitionKind::Occurrence) => Self::OccurrenceDefinition,
             Some(DefinitionKind::Actor) => Self::Other,
             // KerML mappings
@@ -851,7 +863,7 @@ impl SymbolKind {
 }
 
 /// Result of symbol extraction, including both symbols and scope filters.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ExtractionResult {
     /// Extracted symbols.
     pub symbols: Vec<HirSymbol>,
@@ -862,6 +874,12 @@ pub struct ExtractionResult {
     /// Filters for specific imports (import qualified name -> metadata names).
     /// These come from bracket syntax: `import X::*[@Filter]`
     pub import_filters: Vec<(Arc<str>, Vec<String>)>,
+    /// Whether the symbols come from a KerML file. KerML elements are extracted
+    /// with their closest SysML kinds (e.g. a `classifier` as a part definition,
+    /// a `feature` as an attribute usage).
+    pub is_kerml: bool,
+    /// `syster-ignore` comments, applied when the file's diagnostics are collected.
+    pub suppressions: Vec<crate::hir::Suppression>,
 }
 
 /// Span information extracted from an AST node.
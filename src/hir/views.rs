@@ -12,6 +12,7 @@ pub type QualifiedName = Arc<str>;
 
 /// View-specific data attached to HirSymbol.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum ViewData {
     /// A ViewDefinition (defines what elements to show).
     ViewDefinition(ViewDefinition),
@@ -40,6 +41,7 @@ pub enum ViewData {
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewDefinition {
     /// Elements exposed (made visible) by this view.
     pub expose: Vec<ExposeRelationship>,
@@ -60,6 +62,7 @@ pub struct ViewDefinition {
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewUsage {
     /// The ViewDefinition this view is typed by.
     pub view_def: Option<QualifiedName>,
@@ -83,6 +86,7 @@ pub struct ViewUsage {
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewpointDefinition {
     /// Required stakeholders for this viewpoint.
     pub stakeholders: Vec<QualifiedName>,
@@ -94,6 +98,7 @@ pub struct ViewpointDefinition {
 
 /// A ViewpointUsage is an instance of a ViewpointDefinition.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewpointUsage {
     /// The ViewpointDefinition this is typed by.
     pub viewpoint_def: Option<QualifiedName>,
@@ -110,6 +115,7 @@ pub struct ViewpointUsage {
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct RenderingDefinition {
     /// Layout algorithm (e.g., "layered", "tree", "force-directed").
     pub layout: Option<String>,
@@ -119,6 +125,7 @@ pub struct RenderingDefinition {
 
 /// A RenderingUsage is an instance of a RenderingDefinition.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct RenderingUsage {
     /// The RenderingDefinition this is typed by.
     pub rendering_def: Option<QualifiedName>,
@@ -133,6 +140,7 @@ pub struct RenderingUsage {
 /// - `expose Model::Vehicle::*;` (namespace expose - direct children)
 /// - `expose Model::Vehicle::**;` (recursive expose - all descendants)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExposeRelationship {
     /// The import path being exposed.
     pub import_path: ImportPath,
@@ -149,6 +157,7 @@ pub struct ExposeRelationship {
 /// - `Model::Vehicle::*` (all direct children)
 /// - `Model::Vehicle::**` (all descendants recursively)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImportPath {
     /// The qualified name of the target element.
     pub target: QualifiedName,
@@ -158,6 +167,7 @@ pub struct ImportPath {
 
 /// Wildcard kinds for import paths.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum WildcardKind {
     /// No wildcard - specific element.
     None,
@@ -173,6 +183,7 @@ pub enum WildcardKind {
 /// - `filter @SysML::PartUsage;` (metadata check)
 /// - `filter element.type == "PartDef";` (property check - DEFERRED in Phase 4)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum FilterCondition {
     /// Filter by metadata annotation (e.g., `@SysML::PartUsage`).
     Metadata(MetadataFilter),
@@ -184,6 +195,7 @@ pub enum FilterCondition {
 ///
 /// Example: `@SysML::PartUsage` checks if element has that metadata.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct MetadataFilter {
     /// The qualified name of the metadata annotation.
     pub annotation: QualifiedName,
@@ -195,6 +207,7 @@ pub struct MetadataFilter {
 ///
 /// Example: `render Views::asTreeDiagram;`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct RenderingSpec {
     /// The qualified name of the rendering definition.
     pub rendering: QualifiedName,
@@ -3,6 +3,10 @@
 //! The `AnalysisHost` owns all mutable state and provides `Analysis` snapshots
 //! for querying. This pattern ensures consistent reads across multiple queries.
 //!
+//! Snapshots are owned and `Send + Sync`, so they can be handed to worker
+//! threads. Any change applied to the host cancels the snapshots taken before
+//! it; see [`Analysis::is_cancelled()`].
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -13,25 +17,33 @@
 //!
 //! // Get a snapshot for queries
 //! let analysis = host.analysis();
-//! let hover = analysis.hover(file_id, line, col);
-//! let symbols = analysis.document_symbols(file_id);
+//! let hover = analysis.hover(file_id, line, col).unwrap();
+//! let symbols = analysis.document_symbols(file_id).unwrap();
 //! ```
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::base::FileId;
-use crate::hir::{HirSymbol, SymbolIndex, extract_with_filters};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId, TextRange, WorkspaceEdit};
+use crate::hir::{
+    Diagnostic, FileSet, FileText, HirSymbol, LintRegistry, RefKind, RootDatabase, SemanticChecker,
+    SymbolIndex, SymbolKind, build_workspace_index, file_extraction, file_fingerprint, parse_file,
+};
 use crate::syntax::SyntaxFile;
+use crate::syntax::file::FileExtension;
 
 // ModelFormat trait needed for .write() on Xmi/JsonLd/Yaml
 #[cfg(feature = "interchange")]
 use crate::interchange::ModelFormat;
 
 use super::{
-    CompletionItem, DocumentLink, FoldingRange, GotoResult, HoverResult, InlayHint,
-    ReferenceResult, SelectionRange, SemanticToken, SymbolInfo,
+    CodeAction, CompletionItem, DocumentLink, FoldingRange, GotoResult, HoverResult, InlayHint,
+    ReferenceResult, RenameError, RenameTarget, SelectionRange, SemanticToken, SignatureHelp,
+    SymbolInfo, TypeHierarchyItem,
 };
 
 /// Owns all mutable state for the IDE layer.
@@ -39,30 +51,45 @@ use super::{
 /// Apply changes via `set_file_content()` and `remove_file()`,
 /// then get a consistent snapshot via `analysis()`.
 ///
+/// File texts live in a Salsa [`RootDatabase`]; parsing and symbol extraction
+/// are memoized queries over it, so rebuilding the index only re-extracts
+/// files whose text actually changed.
+///
 /// When the `interchange` feature is enabled, `AnalysisHost` also
 /// provides a lazily-cached [`Model`](crate::interchange::Model)
 /// projection via [`model()`](Self::model), navigation delegates,
 /// and format export methods — eliminating the need for a separate
 /// `ModelHost` when working from SysML text.
-#[derive(Clone)]
 pub struct AnalysisHost {
-    /// Parsed files stored directly (no Workspace dependency)
-    files: HashMap<PathBuf, SyntaxFile>,
-    /// HIR-based symbol index built from parsed files
-    symbol_index: SymbolIndex,
+    /// Salsa database holding one `FileText` input per file.
+    /// Behind a mutex only so the host stays `Sync`; `&mut self` paths use `get_mut()`.
+    db: Mutex<RootDatabase>,
+    /// HIR-based symbol index built from the memoized extraction results.
+    /// Shared with `Analysis` snapshots; cloned on write while a snapshot holds it.
+    /// That clone copies the symbols of every file, so an edit made while a
+    /// snapshot is alive costs time proportional to the whole workspace.
+    symbol_index: Arc<SymbolIndex>,
     /// Map from file path to FileId
-    file_id_map: HashMap<String, FileId>,
+    file_id_map: Arc<HashMap<String, FileId>>,
     /// Reverse map from FileId to file path
-    file_path_map: HashMap<FileId, String>,
-    /// Files that have been modified and need re-extraction
-    dirty_files: HashSet<PathBuf>,
-    /// Files that have been removed (need to be removed from index)
-    removed_files: HashSet<PathBuf>,
+    file_path_map: Arc<HashMap<FileId, String>>,
+    /// Token shared by all snapshots of the current state, cancelled on the next change
+    cancellation: CancellationToken,
+    /// Assigns each path its FileId once for the lifetime of the host
+    file_set: FileSet,
+    /// Input fingerprint of every file as of the last index build
+    indexed_files: HashMap<FileId, u64>,
+    /// Database revision the index was last built at
+    indexed_revision: Option<salsa::Revision>,
     /// Whether we need a full rebuild (e.g., first build)
     needs_full_rebuild: bool,
     /// Persistent cache: qualified_name → element_id
     /// Preserves IDs even when symbols are temporarily removed
     element_id_cache: HashMap<Arc<str>, Arc<str>>,
+    /// Lints run with the semantic checks, at the workspace's levels
+    lints: Arc<LintRegistry>,
+    /// Syntax tree of every file as last set, for queries that edit source
+    syntax_files: Arc<HashMap<FileId, Arc<SyntaxFile>>>,
     /// Lazily-cached interchange `Model`, built from the `SymbolIndex`.
     /// Invalidated whenever file content changes.
     #[cfg(feature = "interchange")]
@@ -75,18 +102,69 @@ impl Default for AnalysisHost {
     }
 }
 
+// Cloning a Salsa database shares its storage, and every write then blocks until
+// all other clones are dropped. A cloned host therefore gets a fresh database
+// seeded with the same inputs, and keeps the already-built index so it does not
+// re-extract anything until it is edited.
+impl Clone for AnalysisHost {
+    fn clone(&self) -> Self {
+        let source_db = self.db.lock();
+        let source_files = source_db.file_texts();
+        let mut db = RootDatabase::new();
+        db.set_file_texts(source_files.iter().map(|file_text| {
+            (
+                file_text.file(&*source_db),
+                file_text.text(&*source_db).clone(),
+            )
+        }));
+        for file_text in source_files {
+            db.set_file_extension(
+                file_text.file(&*source_db),
+                file_text.extension(&*source_db),
+            );
+        }
+
+        // Only carry the revision over if the index was up to date
+        let up_to_date =
+            self.indexed_revision == Some(salsa::plumbing::current_revision(&*source_db));
+        let indexed_revision = up_to_date.then(|| salsa::plumbing::current_revision(&db));
+        drop(source_db);
+
+        Self {
+            db: Mutex::new(db),
+            symbol_index: self.symbol_index.clone(),
+            file_id_map: self.file_id_map.clone(),
+            file_path_map: self.file_path_map.clone(),
+            file_set: self.file_set.clone(),
+            indexed_files: self.indexed_files.clone(),
+            indexed_revision,
+            cancellation: CancellationToken::new(),
+            needs_full_rebuild: self.needs_full_rebuild,
+            element_id_cache: self.element_id_cache.clone(),
+            lints: self.lints.clone(),
+            syntax_files: self.syntax_files.clone(),
+            #[cfg(feature = "interchange")]
+            model_cache: self.model_cache.clone(),
+        }
+    }
+}
+
 impl AnalysisHost {
     /// Create a new empty AnalysisHost.
     pub fn new() -> Self {
         Self {
-            files: HashMap::new(),
-            symbol_index: SymbolIndex::new(),
-            file_id_map: HashMap::new(),
-            file_path_map: HashMap::new(),
-            dirty_files: HashSet::new(),
-            removed_files: HashSet::new(),
+            db: Mutex::new(RootDatabase::new()),
+            symbol_index: Arc::new(SymbolIndex::new()),
+            file_id_map: Arc::new(HashMap::new()),
+            file_path_map: Arc::new(HashMap::new()),
+            file_set: FileSet::new(),
+            indexed_files: HashMap::new(),
+            indexed_revision: None,
+            cancellation: CancellationToken::new(),
             needs_full_rebuild: true, // First analysis needs full build
             element_id_cache: HashMap::new(),
+            lints: Arc::new(LintRegistry::default()),
+            syntax_files: Arc::new(HashMap::new()),
             #[cfg(feature = "interchange")]
             model_cache: None,
         }
@@ -100,34 +178,99 @@ impl AnalysisHost {
         path: &str,
         content: &str,
     ) -> Vec<crate::syntax::parser::ParseError> {
-        use crate::syntax::parser::parse_with_result;
-        use std::path::Path;
-
-        let path_buf = PathBuf::from(path);
+        use crate::syntax::parser::{file_extension, syntax_errors};
+
+        let extension = match file_extension(Path::new(path)) {
+            Ok(extension) => extension,
+            Err(e) => return vec![e],
+        };
+
+        self.request_cancellation();
+        let file_text = self.set_file_text(Path::new(path), content, extension);
+
+        // Parse eagerly to report errors; the result is memoized for extraction
+        let db = self.db.get_mut();
+        let file_id = file_text.file(&*db);
+        let syntax_file = parse_file(&*db, file_text).syntax_file;
+        let errors = match &syntax_file {
+            Some(syntax_file) => syntax_errors(syntax_file, content),
+            None => Vec::new(),
+        };
+        self.set_syntax_file(file_id, syntax_file);
 
-        // Parse the content
-        let result = parse_with_result(content, Path::new(path));
+        // Invalidate cached Model — symbols changed
+        #[cfg(feature = "interchange")]
+        {
+            self.model_cache = None;
+        }
+        errors
+    }
 
-        if let Some(syntax_file) = result.content {
-            self.files.insert(path_buf.clone(), syntax_file);
+    /// Edit a loaded file, replacing `range` (byte offsets into its current
+    /// text) with `new_text`.
+    ///
+    /// Unlike [`set_file_content()`](Self::set_file_content), only the
+    /// innermost member containing the edit is reparsed where possible;
+    /// see [`SyntaxFile::reparse()`].
+    ///
+    /// Returns parse errors if any, or `None` if the file is not loaded or
+    /// `range` is not a valid range of its text.
+    pub fn apply_text_edit(
+        &mut self,
+        path: &str,
+        range: TextRange,
+        new_text: &str,
+    ) -> Option<Vec<crate::syntax::parser::ParseError>> {
+        use crate::syntax::parser::syntax_errors;
+
+        let file_id = *self.file_id_map.get(path)?;
+        let db = self.db.get_mut();
+        let file_text = db.file_text(file_id)?;
+
+        let text = file_text.text(&*db);
+        let (start, end) = (usize::from(range.start()), usize::from(range.end()));
+        if end > text.len() || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            return None;
         }
+        let mut content = text.clone();
+        content.replace_range(start..end, new_text);
+
+        let syntax_file = parse_file(&*db, file_text)
+            .get_syntax_file()?
+            .reparse(range, new_text);
+        let errors = syntax_errors(&syntax_file, &content);
+
+        let syntax_file = Arc::new(syntax_file);
+        self.request_cancellation();
+        self.set_syntax_file(file_id, Some(Arc::clone(&syntax_file)));
+        self.db
+            .get_mut()
+            .set_file_syntax(file_id, content, syntax_file);
 
-        // Mark this file as dirty (needs re-extraction)
-        self.dirty_files.insert(path_buf);
         // Invalidate cached Model — symbols changed
         #[cfg(feature = "interchange")]
         {
             self.model_cache = None;
         }
-        result.errors
+        Some(errors)
     }
 
     /// Remove a file from storage.
     pub fn remove_file(&mut self, path: &str) {
-        let path_buf = PathBuf::from(path);
-        self.files.remove(&path_buf);
-        self.dirty_files.remove(&path_buf);
-        self.removed_files.insert(path_buf);
+        self.remove_file_path(&PathBuf::from(path));
+    }
+
+    /// Remove a file from storage using PathBuf.
+    #[allow(clippy::ptr_arg)] // Public signature predates `&Path`
+    pub fn remove_file_path(&mut self, path: &PathBuf) {
+        self.request_cancellation();
+        let path_str = path.to_string_lossy().to_string();
+        if let Some(file_id) = Arc::make_mut(&mut self.file_id_map).remove(&path_str) {
+            Arc::make_mut(&mut self.file_path_map).remove(&file_id);
+            Arc::make_mut(&mut self.syntax_files).remove(&file_id);
+            self.file_set.remove(file_id);
+            self.db.get_mut().remove_file(file_id);
+        }
         // Invalidate cached Model — symbols changed
         #[cfg(feature = "interchange")]
         {
@@ -135,43 +278,148 @@ impl AnalysisHost {
         }
     }
 
-    /// Remove a file from storage using PathBuf.
-    pub fn remove_file_path(&mut self, path: &PathBuf) {
-        self.files.remove(path);
-        self.dirty_files.remove(path);
-        self.removed_files.insert(path.clone());
-    }
-
     /// Check if a file exists in storage.
     pub fn has_file(&self, path: &str) -> bool {
-        let path_buf = PathBuf::from(path);
-        self.files.contains_key(&path_buf)
+        self.has_file_path(Path::new(path))
     }
 
     /// Check if a file exists in storage using Path.
-    pub fn has_file_path(&self, path: &std::path::Path) -> bool {
-        self.files.contains_key(path)
+    pub fn has_file_path(&self, path: &Path) -> bool {
+        self.file_id_map
+            .contains_key(path.to_string_lossy().as_ref())
     }
 
     /// Update or add a file with pre-parsed content.
-    /// Used when caller already has parsed SyntaxFile.
-    pub fn set_file(&mut self, path: PathBuf, file: SyntaxFile) {
-        self.dirty_files.insert(path.clone());
-        self.files.insert(path, file);
+    ///
+    /// The tree is stored with the file's text, so the index is built from it
+    /// without parsing the text again.
+    pub fn set_file(&mut self, path: PathBuf, file: impl Into<Arc<SyntaxFile>>) {
+        self.set_files([(path, file)]);
+    }
+
+    /// Update or add several files with pre-parsed content at once.
+    ///
+    /// Like calling [`set_file()`](Self::set_file) for each of them, but the
+    /// database registers the whole batch in one go, so loading a workspace
+    /// costs time linear in its size.
+    pub fn set_files<F: Into<Arc<SyntaxFile>>>(
+        &mut self,
+        files: impl IntoIterator<Item = (PathBuf, F)>,
+    ) {
+        let files = files.into_iter().map(|(path, file)| {
+            let file = file.into();
+            (path, file.source_text(), file)
+        });
+        self.set_parsed_files(files);
+    }
+
+    /// Add every file of `other`, sharing its texts and parsed trees.
+    ///
+    /// Unlike calling [`set_file()`](Self::set_file) with each of
+    /// [`files()`](Self::files), this doesn't rebuild any text from its tree.
+    pub fn copy_files_from(&mut self, other: &AnalysisHost) {
+        let files: Vec<_> = {
+            let db = other.db.lock();
+            db.file_texts()
+                .into_iter()
+                .filter_map(|file_text| {
+                    let path = other.file_path_map.get(&file_text.file(&*db))?;
+                    let syntax_file = parse_file(&*db, file_text).syntax_file?;
+                    Some((path.clone(), file_text.text(&*db).clone(), syntax_file))
+                })
+                .collect()
+        };
+        self.set_parsed_files(
+            files
+                .into_iter()
+                .map(|(path, text, syntax_file)| (PathBuf::from(path), text, syntax_file)),
+        );
+    }
+
+    /// Get the parsed files keyed by path, sharing the trees the host holds.
+    pub fn files(&self) -> HashMap<PathBuf, Arc<SyntaxFile>> {
+        self.syntax_files
+            .iter()
+            .filter_map(|(file_id, syntax_file)| {
+                let path = self.file_path_map.get(file_id)?;
+                Some((PathBuf::from(path), Arc::clone(syntax_file)))
+            })
+            .collect()
     }
 
-    /// Get access to the parsed files.
-    pub fn files(&self) -> &HashMap<PathBuf, SyntaxFile> {
-        &self.files
+    /// Register each `syntax_file`, parsed from `text`, for its `path`.
+    fn set_parsed_files(
+        &mut self,
+        files: impl IntoIterator<Item = (PathBuf, String, Arc<SyntaxFile>)>,
+    ) {
+        self.request_cancellation();
+        let mut trees = Vec::new();
+        let files: Vec<_> = files
+            .into_iter()
+            .map(|(path, text, syntax_file)| {
+                let file_id = self.file_id_for_path(&path);
+                trees.push((file_id, Arc::clone(&syntax_file)));
+                (file_id, text, syntax_file)
+            })
+            .collect();
+        self.db.get_mut().set_file_syntaxes(files);
+        let syntax_files = Arc::make_mut(&mut self.syntax_files);
+        syntax_files.extend(trees);
+        #[cfg(feature = "interchange")]
+        {
+            self.model_cache = None;
+        }
     }
 
     /// Get the number of files loaded.
     pub fn file_count(&self) -> usize {
-        self.files.len()
+        self.file_id_map.len()
+    }
+
+    /// Register `text` for `path` in the database under the path's FileId.
+    fn set_file_text(&mut self, path: &Path, text: &str, extension: FileExtension) -> FileText {
+        let file_id = self.file_id_for_path(path);
+        let db = self.db.get_mut();
+        let file_text = db.set_file_text(file_id, text);
+        db.set_file_extension(file_id, extension);
+        file_text
+    }
+
+    /// The FileId of `path`, allocating one for a new path.
+    fn file_id_for_path(&mut self, path: &Path) -> FileId {
+        let path_str = path.to_string_lossy().to_string();
+        match self.file_id_map.get(&path_str) {
+            Some(&id) => id,
+            None => {
+                let id = self.file_set.file_id(Path::new(&path_str));
+                Arc::make_mut(&mut self.file_id_map).insert(path_str.clone(), id);
+                Arc::make_mut(&mut self.file_path_map).insert(id, path_str);
+                id
+            }
+        }
+    }
+
+    /// Record the syntax tree snapshots see for `file_id`.
+    fn set_syntax_file(&mut self, file_id: FileId, syntax_file: Option<Arc<SyntaxFile>>) {
+        let syntax_files = Arc::make_mut(&mut self.syntax_files);
+        match syntax_file {
+            Some(syntax_file) => syntax_files.insert(file_id, syntax_file),
+            None => syntax_files.remove(&file_id),
+        };
+    }
+
+    /// Cancel every outstanding `Analysis` snapshot.
+    ///
+    /// Called automatically before any change is applied; queries still running
+    /// on an old snapshot observe it through [`Analysis::is_cancelled()`].
+    pub fn request_cancellation(&mut self) {
+        self.cancellation.cancel();
+        self.cancellation = CancellationToken::new();
     }
 
     /// Mark the index as needing full rebuild (call after external changes).
     pub fn mark_dirty(&mut self) {
+        self.request_cancellation();
         self.needs_full_rebuild = true;
         // Invalidate cached Model — symbols will change on next rebuild
         #[cfg(feature = "interchange")]
@@ -181,8 +429,12 @@ impl AnalysisHost {
     }
 
     /// Check if the index needs updating.
-    fn needs_update(&self) -> bool {
-        self.needs_full_rebuild || !self.dirty_files.is_empty() || !self.removed_files.is_empty()
+    ///
+    /// Any input change bumps the database revision, so an unchanged
+    /// revision means the index is current without looking at any file.
+    fn needs_update(&mut self) -> bool {
+        self.needs_full_rebuild
+            || self.indexed_revision != Some(salsa::plumbing::current_revision(self.db.get_mut()))
     }
 
     /// Rebuild the symbol index from the current files.
@@ -194,161 +446,233 @@ impl AnalysisHost {
         } else {
             self.incremental_rebuild();
         }
+        self.indexed_revision = Some(salsa::plumbing::current_revision(self.db.get_mut()));
     }
 
     /// Full rebuild - used on first load or when structure changes significantly
+    ///
+    /// Files are read through the memoized [`file_extraction`] query, so only
+    /// those edited since they were last extracted are extracted again. The
+    /// index is built outside the database, leaving the host its only owner,
+    /// so later incremental rebuilds update it in place.
     fn full_rebuild(&mut self) {
         // First, update cache with all current symbols' IDs
         for symbol in self.symbol_index.all_symbols() {
-            if !symbol.element_id.as_ref().is_empty()
-                && !symbol.element_id.starts_with("00000000-0000-0000-0000")
-            {
-                self.element_id_cache
-                    .insert(symbol.qualified_name.clone(), symbol.element_id.clone());
-            }
-        }
-
-        // Build file ID map from file paths
-        self.file_id_map.clear();
-        self.file_path_map.clear();
-
-        for (i, path) in self.files.keys().enumerate() {
-            let path_str = path.to_string_lossy().to_string();
-            let file_id = FileId::new(i as u32);
-            self.file_id_map.insert(path_str.clone(), file_id);
-            self.file_path_map.insert(file_id, path_str);
+            cache_element_id(&mut self.element_id_cache, symbol);
         }
 
-        // Build symbol index directly from parsed files
-        let mut new_index = SymbolIndex::new();
-
-        for (path, syntax_file) in &self.files {
-            let path_str = path.to_string_lossy().to_string();
-            if let Some(&file_id) = self.file_id_map.get(&path_str) {
-                // Extract symbols and filters using unified extraction (handles both SysML and KerML)
-                let mut result = extract_with_filters(file_id, syntax_file);
-
-                // Preserve element IDs from cache (survives removal/re-add)
-                for symbol in &mut result.symbols {
-                    if let Some(cached_id) = self.element_id_cache.get(&symbol.qualified_name) {
-                        symbol.element_id = cached_id.clone();
-                    }
-                }
+        let db = self.db.get_mut();
+        let mut new_index = build_workspace_index(db);
+        self.indexed_files = db
+            .file_texts()
+            .into_iter()
+            .map(|file_text| (file_text.file(&*db), file_fingerprint(&*db, file_text)))
+            .collect();
 
-                new_index.add_extraction_result(file_id, result);
+        // Preserve element IDs from cache (survives removal/re-add)
+        let cache = &self.element_id_cache;
+        new_index.update_all_symbols(|symbol| {
+            if let Some(cached_id) = cache.get(&symbol.qualified_name) {
+                symbol.element_id = cached_id.clone();
             }
-        }
-
-        // Build visibility maps for import resolution
-        new_index.ensure_visibility_maps();
-
-        // Resolve all type references (pre-compute resolved_target)
-        new_index.resolve_all_type_refs();
+        });
 
-        self.symbol_index = new_index;
+        self.symbol_index = Arc::new(new_index);
         self.needs_full_rebuild = false;
-        self.dirty_files.clear();
-        self.removed_files.clear();
     }
 
     /// Incremental rebuild - only re-extract changed files
     fn incremental_rebuild(&mut self) {
         use std::time::Instant;
 
+        let db = self.db.get_mut();
+        let file_texts = db.file_texts();
+        let symbol_index = Arc::make_mut(&mut self.symbol_index);
+
         // Collect files that need type ref resolution
         let mut files_to_resolve: Vec<FileId> = Vec::new();
+        // Qualified names whose declarations were added, removed or changed
+        let mut changed_names: Vec<Arc<str>> = Vec::new();
 
         // Handle removed files first - cache their element IDs before removal
         let t0 = Instant::now();
-        for path in self.removed_files.drain() {
-            let path_str = path.to_string_lossy().to_string();
-            if let Some(&file_id) = self.file_id_map.get(&path_str) {
-                // Cache element IDs before removing
-                for symbol in self.symbol_index.symbols_in_file(file_id) {
-                    if !symbol.element_id.as_ref().is_empty()
-                        && !symbol.element_id.starts_with("00000000-0000-0000-0000")
-                    {
-                        self.element_id_cache
-                            .insert(symbol.qualified_name.clone(), symbol.element_id.clone());
-                    }
-                }
-                self.symbol_index.remove_file(file_id);
+        let live: HashSet<FileId> = file_texts.iter().map(|ft| ft.file(&*db)).collect();
+        let removed: Vec<FileId> = self
+            .indexed_files
+            .keys()
+            .filter(|file_id| !live.contains(file_id))
+            .copied()
+            .collect();
+        for file_id in removed {
+            for symbol in symbol_index.symbols_in_file(file_id) {
+                cache_element_id(&mut self.element_id_cache, symbol);
+                changed_names.push(symbol.qualified_name.clone());
             }
+            symbol_index.remove_file(file_id);
+            self.indexed_files.remove(&file_id);
         }
 
-        // Re-extract only dirty files and track which need resolution
-        for path in self.dirty_files.drain() {
-            let path_str = path.to_string_lossy().to_string();
-
-            let file_id = if let Some(&id) = self.file_id_map.get(&path_str) {
-                // Cache element IDs before re-extraction (so modified symbols keep their IDs)
-                for symbol in self.symbol_index.symbols_in_file(id) {
-                    if !symbol.element_id.as_ref().is_empty()
-                        && !symbol.element_id.starts_with("00000000-0000-0000-0000")
-                    {
-                        self.element_id_cache
-                            .insert(symbol.qualified_name.clone(), symbol.element_id.clone());
-                    }
-                }
-                id
-            } else {
-                let new_id = FileId::new(self.file_id_map.len() as u32);
-                self.file_id_map.insert(path_str.clone(), new_id);
-                self.file_path_map.insert(new_id, path_str.clone());
-                new_id
-            };
-
-            if let Some(syntax_file) = self.files.get(&path) {
-                let mut result = extract_with_filters(file_id, syntax_file);
-
-                // Preserve element IDs from cache (survives removal/re-add)
-                for symbol in &mut result.symbols {
-                    if let Some(cached_id) = self.element_id_cache.get(&symbol.qualified_name) {
-                        symbol.element_id = cached_id.clone();
-                    }
-                }
+        // Re-extract only files whose input changed since the last build
+        for file_text in file_texts {
+            let file_id = file_text.file(&*db);
+            let fingerprint = file_fingerprint(&*db, file_text);
+            if self.indexed_files.get(&file_id) == Some(&fingerprint) {
+                continue;
+            }
 
-                self.symbol_index.add_extraction_result(file_id, result);
-                files_to_resolve.push(file_id);
+            // Cache element IDs before re-extraction (so modified symbols keep their IDs)
+            let old_symbols = symbol_index.symbols_in_file(file_id);
+            for symbol in &old_symbols {
+                cache_element_id(&mut self.element_id_cache, symbol);
+            }
+
+            let mut result = file_extraction(&*db, file_text).clone();
+
+            // Preserve element IDs from cache (survives removal/re-add)
+            for symbol in &mut result.symbols {
+                if let Some(cached_id) = self.element_id_cache.get(&symbol.qualified_name) {
+                    symbol.element_id = cached_id.clone();
+                }
             }
+            changed_names.extend(changed_declarations(&old_symbols, &result.symbols));
+
+            symbol_index.add_extraction_result(file_id, result);
+            self.indexed_files.insert(file_id, fingerprint);
+            files_to_resolve.push(file_id);
         }
         let t1 = Instant::now();
 
-        // Rebuild visibility maps (needed for correct resolution)
-        self.symbol_index.mark_visibility_dirty();
-        self.symbol_index.ensure_visibility_maps();
+        // Rebuild the visibility maps of the scopes the changes can reach
+        symbol_index.update_visibility_for_changes(&changed_names);
         let t2 = Instant::now();
 
-        // Only resolve type refs for changed files (not the entire workspace)
+        // Resolve type refs for changed files plus the files that depend on
+        // a changed declaration (not the entire workspace)
+        let changed_count = files_to_resolve.len();
+        for file_id in symbol_index.files_affected_by(&changed_names) {
+            if !files_to_resolve.contains(&file_id) {
+                files_to_resolve.push(file_id);
+            }
+        }
         if !files_to_resolve.is_empty() {
-            self.symbol_index
-                .resolve_type_refs_for_files(&files_to_resolve);
+            symbol_index.resolve_type_refs_for_files(&files_to_resolve);
         }
         let t3 = Instant::now();
 
         tracing::info!(
-            "Incremental rebuild: extract={:?}, visibility={:?}, resolve={:?}",
+            "Incremental rebuild: extract={:?}, visibility={:?}, resolve={:?} ({} changed, {} dependent files)",
             t1.duration_since(t0),
             t2.duration_since(t1),
-            t3.duration_since(t2)
+            t3.duration_since(t2),
+            changed_count,
+            files_to_resolve.len() - changed_count
         );
     }
 
     /// Get a consistent snapshot for querying.
     ///
-    /// If the index is dirty, it will be rebuilt first.
-    pub fn analysis(&mut self) -> Analysis<'_> {
+    /// If the index is dirty, it will be rebuilt first. The snapshot does not
+    /// borrow the host and stays valid (though cancelled) after later edits.
+    ///
+    /// An incremental rebuild while a snapshot is still alive first copies the
+    /// whole index, so drop snapshots once their queries are answered.
+    pub fn analysis(&mut self) -> Analysis {
         if self.needs_update() {
             self.rebuild_index();
         }
 
         Analysis {
-            symbol_index: &self.symbol_index,
-            file_id_map: &self.file_id_map,
-            file_path_map: &self.file_path_map,
+            symbol_index: Arc::clone(&self.symbol_index),
+            file_id_map: Arc::clone(&self.file_id_map),
+            file_path_map: Arc::clone(&self.file_path_map),
+            cancellation: self.cancellation.clone(),
+            lints: Arc::clone(&self.lints),
+            syntax_files: Arc::clone(&self.syntax_files),
+        }
+    }
+
+    /// Hash of every file's path and input, independent of the order files were added.
+    ///
+    /// Two hosts with the same content hash most likely hold the same sources.
+    /// The hash uses a fixed algorithm, so it is stable across builds and
+    /// toolchains and can key on-disk caches. Being 64 bits, it may collide,
+    /// so it only selects an entry: [`restore_index_snapshot()`](Self::restore_index_snapshot)
+    /// still checks each file's length and fingerprint.
+    pub fn content_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = rustc_hash::FxHasher::default();
+        for stamp in self.file_stamps() {
+            stamp.path.hash(&mut hasher);
+            stamp.len.hash(&mut hasher);
+            stamp.fingerprint.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Path, FileId, text length and input fingerprint of every file, sorted by path.
+    fn file_stamps(&self) -> Vec<FileStamp> {
+        let db = self.db.lock();
+        let mut files: Vec<_> = db
+            .file_texts()
+            .into_iter()
+            .filter_map(|file_text| {
+                let file_id = file_text.file(&*db);
+                Some(FileStamp {
+                    path: self.file_path_map.get(&file_id)?.clone(),
+                    file: file_id,
+                    len: file_text.text(&*db).len(),
+                    fingerprint: file_fingerprint(&*db, file_text),
+                })
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Capture the built index so it can be restored without re-extracting.
+    ///
+    /// The index is rebuilt first if it is out of date.
+    #[cfg(feature = "cache")]
+    pub fn index_snapshot(&mut self) -> IndexSnapshot {
+        if self.needs_update() {
+            self.rebuild_index();
+        }
+
+        IndexSnapshot {
+            files: self.file_stamps(),
+            index: Arc::clone(&self.symbol_index),
         }
     }
 
+    /// Adopt a previously captured index instead of building one.
+    ///
+    /// The snapshot is only accepted if it was taken from exactly the files
+    /// this host holds now: same paths, same FileIds and same contents (same
+    /// length and fingerprint).
+    /// Returns `false` (leaving the host untouched) otherwise.
+    #[cfg(feature = "cache")]
+    pub fn restore_index_snapshot(&mut self, snapshot: IndexSnapshot) -> bool {
+        let files = self.file_stamps();
+        if snapshot.files != files {
+            return false;
+        }
+
+        self.request_cancellation();
+        self.symbol_index = snapshot.index;
+        self.indexed_files = files
+            .into_iter()
+            .map(|stamp| (stamp.file, stamp.fingerprint))
+            .collect();
+        self.indexed_revision = Some(salsa::plumbing::current_revision(self.db.get_mut()));
+        self.needs_full_rebuild = false;
+        #[cfg(feature = "interchange")]
+        {
+            self.model_cache = None;
+        }
+        true
+    }
+
     /// Get the FileId for a path, if it exists.
     pub fn get_file_id(&self, path: &str) -> Option<FileId> {
         self.file_id_map.get(path).copied()
@@ -375,7 +699,30 @@ impl AnalysisHost {
     ///
     /// Returns a list of diagnostics (errors and warnings) found during semantic analysis.
     pub fn diagnostics(&self, file_id: FileId) -> Vec<crate::hir::Diagnostic> {
-        crate::hir::check_file(&self.symbol_index, file_id)
+        // Nothing can cancel the host's own token while it's borrowed
+        check_file(
+            &self.symbol_index,
+            file_id,
+            &self.lints,
+            &self.syntax_files,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap_or_default()
+    }
+
+    /// Set the lints run with the semantic checks, and their levels.
+    ///
+    /// Like a file change, this cancels the snapshots taken before it, whose
+    /// diagnostics were computed with the old levels.
+    pub fn set_lints(&mut self, lints: LintRegistry) {
+        self.request_cancellation();
+        self.lints = Arc::new(lints);
+    }
+
+    /// The lints run with the semantic checks.
+    pub fn lints(&self) -> &LintRegistry {
+        &self.lints
     }
 
     /// Get all semantic diagnostics for all loaded files.
@@ -383,7 +730,7 @@ impl AnalysisHost {
     /// Returns a map from file path to diagnostics for that file.
     pub fn all_diagnostics(&self) -> HashMap<String, Vec<crate::hir::Diagnostic>> {
         let mut result = HashMap::new();
-        for (path, &file_id) in &self.file_id_map {
+        for (path, &file_id) in self.file_id_map.iter() {
             let diags = self.diagnostics(file_id);
             if !diags.is_empty() {
                 result.insert(path.clone(), diags);
@@ -397,7 +744,7 @@ impl AnalysisHost {
     /// Returns a vec of (file_path, diagnostic) pairs for errors only.
     pub fn all_errors(&self) -> Vec<(String, crate::hir::Diagnostic)> {
         let mut result = Vec::new();
-        for (path, &file_id) in &self.file_id_map {
+        for (path, &file_id) in self.file_id_map.iter() {
             for diag in self.diagnostics(file_id) {
                 if diag.severity == crate::hir::Severity::Error {
                     result.push((path.clone(), diag));
@@ -424,7 +771,8 @@ impl AnalysisHost {
     where
         F: FnMut(&mut HirSymbol),
     {
-        self.symbol_index.update_symbols(f);
+        self.request_cancellation();
+        Arc::make_mut(&mut self.symbol_index).update_symbols(f);
         // Invalidate cached Model — symbol metadata changed
         #[cfg(feature = "interchange")]
         {
@@ -704,40 +1052,202 @@ impl AnalysisHost {
     }
 }
 
+/// Identifies the input a file was indexed from.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+struct FileStamp {
+    path: String,
+    file: FileId,
+    /// Length of the text, so that a fingerprint collision alone can't pass
+    len: usize,
+    fingerprint: u64,
+}
+
+/// A built symbol index together with the files it was built from.
+///
+/// Produced by [`AnalysisHost::index_snapshot()`] and serializable, so a fully
+/// resolved index (visibility maps and resolved type refs included) can be
+/// written to disk and handed back via [`AnalysisHost::restore_index_snapshot()`].
+#[cfg(feature = "cache")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct IndexSnapshot {
+    /// Every indexed file, sorted by path
+    files: Vec<FileStamp>,
+    /// The index as built from those files
+    index: Arc<SymbolIndex>,
+}
+
+#[cfg(feature = "cache")]
+impl IndexSnapshot {
+    /// Number of files the index was built from.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The captured symbol index.
+    pub fn symbol_index(&self) -> &SymbolIndex {
+        &self.index
+    }
+}
+
+/// Remember a symbol's element ID so it survives re-extraction or removal.
+fn cache_element_id(cache: &mut HashMap<Arc<str>, Arc<str>>, symbol: &HirSymbol) {
+    if !symbol.element_id.as_ref().is_empty()
+        && !symbol.element_id.starts_with("00000000-0000-0000-0000")
+    {
+        cache.insert(symbol.qualified_name.clone(), symbol.element_id.clone());
+    }
+}
+
+/// The parts of a symbol that name resolution in other files can observe.
+///
+/// Spans, docs and bodies without references are left out, so an edit that
+/// only touches those doesn't re-resolve the files depending on the symbol.
+#[derive(PartialEq)]
+struct Declaration<'a> {
+    kind: SymbolKind,
+    short_name: Option<&'a str>,
+    supertypes: &'a [Arc<str>],
+    is_public: bool,
+    metadata_annotations: &'a [Arc<str>],
+    type_refs: Vec<(&'a str, RefKind)>,
+}
+
+impl<'a> Declaration<'a> {
+    fn of(symbol: &'a HirSymbol) -> Self {
+        Self {
+            kind: symbol.kind,
+            short_name: symbol.short_name.as_deref(),
+            supertypes: &symbol.supertypes,
+            is_public: symbol.is_public,
+            metadata_annotations: &symbol.metadata_annotations,
+            type_refs: symbol
+                .type_refs
+                .iter()
+                .flat_map(|trk| trk.as_refs())
+                .map(|tr| (tr.target.as_ref(), tr.kind))
+                .collect(),
+        }
+    }
+}
+
+/// The qualified names declared by a file's `old` or `new` symbols, but not
+/// the same way by both: added, removed, or with a changed [`Declaration`].
+fn changed_declarations(old: &[&HirSymbol], new: &[HirSymbol]) -> Vec<Arc<str>> {
+    let mut declarations: HashMap<&Arc<str>, (Vec<Declaration<'_>>, Vec<Declaration<'_>>)> =
+        HashMap::new();
+    for symbol in old {
+        let entry = declarations.entry(&symbol.qualified_name).or_default();
+        entry.0.push(Declaration::of(symbol));
+    }
+    for symbol in new {
+        let entry = declarations.entry(&symbol.qualified_name).or_default();
+        entry.1.push(Declaration::of(symbol));
+    }
+    declarations
+        .into_iter()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(qname, _)| qname.clone())
+        .collect()
+}
+
+/// Run the semantic checks and `lints` on one file, with fixes built from
+/// `syntax_files` and "did you mean" fixes for the references in `suggestions`,
+/// giving up as soon as `cancel` is signalled.
+fn check_file(
+    index: &SymbolIndex,
+    file: FileId,
+    lints: &Arc<LintRegistry>,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    suggestions: Option<(u32, u32, u32, u32)>,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<Diagnostic>> {
+    let mut checker = SemanticChecker::new(index)
+        .with_cancellation(cancel.clone())
+        .with_lints(Arc::clone(lints))
+        .with_syntax(|file| syntax_files.get(&file).cloned());
+    if let Some(range) = suggestions {
+        checker = checker.with_suggestions(range);
+    }
+    checker.check_file(file)?;
+    Ok(checker.finish())
+}
+
 /// An immutable snapshot of the analysis state.
 ///
 /// All IDE queries go through this struct to ensure consistent results.
-pub struct Analysis<'a> {
-    symbol_index: &'a SymbolIndex,
-    file_id_map: &'a HashMap<String, FileId>,
-    file_path_map: &'a HashMap<FileId, String>,
+/// Snapshots share their data with the host via `Arc`, so they are cheap to
+/// clone and can be sent to other threads. A snapshot is cancelled as soon as
+/// the host applies a change; its results are still consistent, but stale.
+#[derive(Clone)]
+pub struct Analysis {
+    symbol_index: Arc<SymbolIndex>,
+    file_id_map: Arc<HashMap<String, FileId>>,
+    file_path_map: Arc<HashMap<FileId, String>>,
+    cancellation: CancellationToken,
+    lints: Arc<LintRegistry>,
+    syntax_files: Arc<HashMap<FileId, Arc<SyntaxFile>>>,
 }
 
-impl<'a> Analysis<'a> {
+impl Analysis {
+    // Every query returns `Err(Cancelled)` if the host changed before it
+    // started; workspace-wide scans also give up midway.
+
     // ==================== Symbol-based features ====================
 
     /// Get hover information at a position.
-    pub fn hover(&self, file_id: FileId, line: u32, col: u32) -> Option<HoverResult> {
-        super::hover(self.symbol_index, file_id, line, col)
+    pub fn hover(&self, file_id: FileId, line: u32, col: u32) -> Cancellable<Option<HoverResult>> {
+        super::hover_cancellable(&self.symbol_index, file_id, line, col, &self.cancellation)
     }
 
     /// Get type information at a position.
     ///
     /// Returns info if cursor is on a type annotation (`:`, `:>`, `::>`, etc.).
-    pub fn type_info_at(&self, file_id: FileId, line: u32, col: u32) -> Option<super::TypeInfo> {
-        super::type_info_at(self.symbol_index, file_id, line, col)
+    pub fn type_info_at(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Option<super::TypeInfo>> {
+        self.with_cancellation(|index| super::type_info_at(index, file_id, line, col))
     }
 
     /// Go to definition at a position.
-    pub fn goto_definition(&self, file_id: FileId, line: u32, col: u32) -> GotoResult {
-        super::goto_definition(self.symbol_index, file_id, line, col)
+    pub fn goto_definition(&self, file_id: FileId, line: u32, col: u32) -> Cancellable<GotoResult> {
+        self.with_cancellation(|index| super::goto_definition(index, file_id, line, col))
     }
 
     /// Go to type definition at a position.
     ///
     /// Navigates from a usage to its type definition (e.g., from `engine : Engine` to `part def Engine`).
-    pub fn goto_type_definition(&self, file_id: FileId, line: u32, col: u32) -> GotoResult {
-        super::goto_type_definition(self.symbol_index, file_id, line, col)
+    pub fn goto_type_definition(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<GotoResult> {
+        self.with_cancellation(|index| super::goto_type_definition(index, file_id, line, col))
+    }
+
+    /// Get the direct supertypes of the symbol at a position.
+    pub fn type_hierarchy_supertypes(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Vec<TypeHierarchyItem>> {
+        super::type_hierarchy_supertypes(&self.symbol_index, file_id, line, col, &self.cancellation)
+    }
+
+    /// Get the direct subtypes of the symbol at a position: what specializes,
+    /// subsets, redefines or is typed by it.
+    pub fn type_hierarchy_subtypes(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Vec<TypeHierarchyItem>> {
+        super::type_hierarchy_subtypes(&self.symbol_index, file_id, line, col, &self.cancellation)
     }
 
     /// Find all references to a symbol at a position.
@@ -747,8 +1257,47 @@ impl<'a> Analysis<'a> {
         line: u32,
         col: u32,
         include_declaration: bool,
-    ) -> ReferenceResult {
-        super::find_references(self.symbol_index, file_id, line, col, include_declaration)
+    ) -> Cancellable<ReferenceResult> {
+        super::find_references_cancellable(
+            &self.symbol_index,
+            file_id,
+            line,
+            col,
+            include_declaration,
+            &self.cancellation,
+        )
+    }
+
+    /// Check that the symbol at a position can be renamed, and get the name
+    /// a rename would change.
+    pub fn prepare_rename(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Result<RenameTarget, RenameError>> {
+        self.with_cancellation(|index| {
+            super::prepare_rename(index, &self.syntax_files, file_id, line, col)
+        })
+    }
+
+    /// Rename the symbol at a position, returning the edits of every affected file.
+    pub fn rename(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+        new_name: &str,
+    ) -> Cancellable<Result<WorkspaceEdit, RenameError>> {
+        super::rename(
+            &self.symbol_index,
+            &self.syntax_files,
+            file_id,
+            line,
+            col,
+            new_name,
+            &self.cancellation,
+        )
     }
 
     /// Get completions at a position.
@@ -758,35 +1307,129 @@ impl<'a> Analysis<'a> {
         line: u32,
         col: u32,
         trigger: Option<char>,
-    ) -> Vec<CompletionItem> {
-        super::completions(self.symbol_index, file_id, line, col, trigger)
+    ) -> Cancellable<Vec<CompletionItem>> {
+        super::scoped_completions(
+            &self.symbol_index,
+            &self.syntax_files,
+            file_id,
+            line,
+            col,
+            trigger,
+            &self.cancellation,
+        )
+    }
+
+    /// Get the signature of the calculation, action or function invoked
+    /// around a position, with the parameter of the argument at it.
+    pub fn signature_help(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Option<SignatureHelp>> {
+        super::signature_help(
+            &self.symbol_index,
+            &self.syntax_files,
+            file_id,
+            line,
+            col,
+            &self.cancellation,
+        )
     }
 
     /// Get all symbols in a document.
-    pub fn document_symbols(&self, file_id: FileId) -> Vec<SymbolInfo> {
-        super::document_symbols(self.symbol_index, file_id)
+    pub fn document_symbols(&self, file_id: FileId) -> Cancellable<Vec<SymbolInfo>> {
+        self.with_cancellation(|index| super::document_symbols(index, file_id))
     }
 
     /// Search for symbols across the workspace.
-    pub fn workspace_symbols(&self, query: Option<&str>) -> Vec<SymbolInfo> {
-        super::workspace_symbols(self.symbol_index, query)
+    pub fn workspace_symbols(&self, query: Option<&str>) -> Cancellable<Vec<SymbolInfo>> {
+        super::workspace_symbols_cancellable(&self.symbol_index, query, &self.cancellation)
     }
 
     /// Get document links (import paths, etc.).
-    pub fn document_links(&self, file_id: FileId) -> Vec<DocumentLink> {
-        super::document_links(self.symbol_index, file_id)
+    pub fn document_links(&self, file_id: FileId) -> Cancellable<Vec<DocumentLink>> {
+        self.with_cancellation(|index| super::document_links(index, file_id))
+    }
+
+    /// Get semantic diagnostics for a file.
+    pub fn diagnostics(&self, file_id: FileId) -> Cancellable<Vec<crate::hir::Diagnostic>> {
+        check_file(
+            &self.symbol_index,
+            file_id,
+            &self.lints,
+            &self.syntax_files,
+            None,
+            &self.cancellation,
+        )
+    }
+
+    /// Get semantic diagnostics for the whole workspace.
+    pub fn workspace_diagnostics(&self) -> Cancellable<Vec<crate::hir::Diagnostic>> {
+        let mut checker = SemanticChecker::new(&self.symbol_index)
+            .with_cancellation(self.cancellation.clone())
+            .with_lints(Arc::clone(&self.lints))
+            .with_syntax(|file| self.syntax_files.get(&file).cloned());
+        checker.check_all()?;
+        Ok(checker.finish())
+    }
+
+    /// Get the code actions offered at a range (start_line, start_col, end_line, end_col):
+    /// quick fixes for the diagnostics overlapping it, then refactorings.
+    pub fn code_actions(
+        &self,
+        file_id: FileId,
+        range: (u32, u32, u32, u32),
+    ) -> Cancellable<Vec<CodeAction>> {
+        let diagnostics = check_file(
+            &self.symbol_index,
+            file_id,
+            &self.lints,
+            &self.syntax_files,
+            Some(range),
+            &self.cancellation,
+        )?;
+        let mut actions = super::quick_fixes(&diagnostics, range);
+        actions.extend(super::refactorings(
+            &self.symbol_index,
+            &self.syntax_files,
+            file_id,
+            range,
+            &self.cancellation,
+        )?);
+        Ok(actions)
+    }
+
+    /// Build the edits of a code action offered without them (one with
+    /// [`data`](CodeAction::data)); `None` if it no longer applies. Other
+    /// actions are returned as they are.
+    pub fn resolve_code_action(&self, action: CodeAction) -> Cancellable<Option<CodeAction>> {
+        let Some(data) = &action.data else {
+            return Ok(Some(action));
+        };
+        super::resolve_refactoring(
+            &self.symbol_index,
+            &self.syntax_files,
+            data,
+            &self.cancellation,
+        )
     }
 
     // ==================== AST-based features ====================
 
     /// Get folding ranges for a file.
-    pub fn folding_ranges(&self, file_id: FileId) -> Vec<FoldingRange> {
-        super::folding_ranges(self.symbol_index, file_id)
+    pub fn folding_ranges(&self, file_id: FileId) -> Cancellable<Vec<FoldingRange>> {
+        self.with_cancellation(|index| super::folding_ranges(index, file_id))
     }
 
     /// Get selection ranges at positions.
-    pub fn selection_ranges(&self, file_id: FileId, line: u32, col: u32) -> Vec<SelectionRange> {
-        super::selection_ranges(self.symbol_index, file_id, line, col)
+    pub fn selection_ranges(
+        &self,
+        file_id: FileId,
+        line: u32,
+        col: u32,
+    ) -> Cancellable<Vec<SelectionRange>> {
+        self.with_cancellation(|index| super::selection_ranges(index, file_id, line, col))
     }
 
     /// Get inlay hints for a file (optionally within a range).
@@ -794,25 +1437,53 @@ impl<'a> Analysis<'a> {
         &self,
         file_id: FileId,
         range: Option<(u32, u32, u32, u32)>,
-    ) -> Vec<InlayHint> {
-        super::inlay_hints(self.symbol_index, file_id, range)
+    ) -> Cancellable<Vec<InlayHint>> {
+        self.with_cancellation(|index| super::inlay_hints(index, file_id, range))
     }
 
     /// Get semantic tokens for a file.
-    pub fn semantic_tokens(&self, file_id: FileId) -> Vec<SemanticToken> {
-        super::semantic_tokens(self.symbol_index, file_id)
+    pub fn semantic_tokens(&self, file_id: FileId) -> Cancellable<Vec<SemanticToken>> {
+        super::semantic_tokens_cancellable(&self.symbol_index, file_id, &self.cancellation)
+    }
+
+    /// Run a per-file query unless the snapshot is already cancelled.
+    fn with_cancellation<T>(&self, query: impl FnOnce(&SymbolIndex) -> T) -> Cancellable<T> {
+        Cancelled::check(&self.cancellation)?;
+        Ok(query(&self.symbol_index))
     }
 
     // ==================== Accessors ====================
 
     /// Get the symbol index.
     pub fn symbol_index(&self) -> &SymbolIndex {
-        self.symbol_index
+        &self.symbol_index
     }
 
     /// Get the file ID map.
     pub fn file_id_map(&self) -> &HashMap<String, FileId> {
-        self.file_id_map
+        &self.file_id_map
+    }
+
+    /// Get the syntax tree of a file.
+    pub fn syntax(&self, file_id: FileId) -> Option<&SyntaxFile> {
+        self.syntax_files
+            .get(&file_id)
+            .map(|syntax_file| &**syntax_file)
+    }
+
+    // ==================== Cancellation ====================
+
+    /// Check whether the host has changed since this snapshot was taken.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Get the token that is cancelled when the host changes.
+    ///
+    /// Useful to wire a snapshot into cancellable APIs such as
+    /// [`format_async()`](crate::syntax::formatter::format_async).
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
     }
 
     /// Get the file path for a FileId.
@@ -857,6 +1528,555 @@ mod tests {
         assert!(analysis.get_file_id("test.sysml").is_none());
     }
 
+    #[test]
+    fn test_edit_updates_index() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        host.set_file_content("b.sysml", "package B { part def Wheel; }");
+        assert!(
+            host.analysis()
+                .symbol_index()
+                .lookup_qualified("A::Engine")
+                .is_some()
+        );
+
+        host.set_file_content("a.sysml", "package A { part def Motor; }");
+        let analysis = host.analysis();
+        let index = analysis.symbol_index();
+        assert!(index.lookup_qualified("A::Engine").is_none());
+        assert!(index.lookup_qualified("A::Motor").is_some());
+        assert!(index.lookup_qualified("B::Wheel").is_some());
+    }
+
+    #[test]
+    fn test_only_changed_files_are_reindexed() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        host.set_file_content("b.sysml", "package B { part def Wheel; }");
+        let _ = host.analysis();
+        let revision = host.indexed_revision;
+
+        // No changes: the index is not touched
+        assert!(!host.needs_update());
+
+        // Setting identical content does not bump the revision
+        host.set_file_content("b.sysml", "package B { part def Wheel; }");
+        assert!(!host.needs_update());
+        assert_eq!(host.indexed_revision, revision);
+
+        host.set_file_content("b.sysml", "package B { part def Tire; }");
+        assert!(host.needs_update());
+        let b = host.get_file_id("b.sysml").unwrap();
+        let a = host.get_file_id("a.sysml").unwrap();
+        let a_fingerprint = host.indexed_files[&a];
+        let b_fingerprint = host.indexed_files[&b];
+
+        let _ = host.analysis();
+        assert_eq!(host.indexed_files[&a], a_fingerprint);
+        assert_ne!(host.indexed_files[&b], b_fingerprint);
+    }
+
+    /// The resolved target of the first type ref of `qualified_name`.
+    fn resolved_type(host: &mut AnalysisHost, qualified_name: &str) -> Option<Arc<str>> {
+        let analysis = host.analysis();
+        let symbol = analysis
+            .symbol_index()
+            .lookup_qualified(qualified_name)
+            .expect("symbol should exist");
+        symbol.type_refs[0].as_refs()[0].resolved_target.clone()
+    }
+
+    #[test]
+    fn test_edit_re_resolves_dependent_files() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("lib.sysml", "package Lib { part def Engine; }");
+        host.set_file_content(
+            "app.sysml",
+            "package App { private import Lib::*; part engine : Engine; }",
+        );
+        assert_eq!(
+            resolved_type(&mut host, "App::engine").as_deref(),
+            Some("Lib::Engine")
+        );
+
+        // Renaming the definition leaves the reference in the other file dangling
+        host.set_file_content("lib.sysml", "package Lib { part def Motor; }");
+        assert_eq!(resolved_type(&mut host, "App::engine"), None);
+
+        // Restoring it resolves the waiting reference again
+        host.set_file_content("lib.sysml", "package Lib { part def Engine; }");
+        assert_eq!(
+            resolved_type(&mut host, "App::engine").as_deref(),
+            Some("Lib::Engine")
+        );
+
+        // Deleting the defining file leaves it dangling too
+        host.remove_file("lib.sysml");
+        assert_eq!(resolved_type(&mut host, "App::engine"), None);
+    }
+
+    #[test]
+    fn test_edit_re_resolves_shadowed_references() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("lib.sysml", "package Lib { part def Engine; }");
+        host.set_file_content(
+            "app.sysml",
+            "package App { private import Lib::*; part engine : Engine; }",
+        );
+        assert_eq!(
+            resolved_type(&mut host, "App::engine").as_deref(),
+            Some("Lib::Engine")
+        );
+
+        // A member of `App` takes precedence over the imported definition
+        host.set_file_content("engine.sysml", "package App { part def Engine; }");
+        assert_eq!(
+            resolved_type(&mut host, "App::engine").as_deref(),
+            Some("App::Engine")
+        );
+    }
+
+    #[test]
+    fn test_edit_re_resolves_references_through_changed_imports() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("lib.sysml", "package Lib { part def Engine; }");
+        host.set_file_content("mid.sysml", "package Mid { public import Lib::*; }");
+        // `Mid` continued in another file, resolving through its import
+        host.set_file_content("more.sysml", "package Mid { part engine : Engine; }");
+        // Re-exported again, then imported from a namespace not naming `Mid`
+        host.set_file_content("app.sysml", "package App { public import Mid::*; }");
+        host.set_file_content(
+            "top.sysml",
+            "package Top { private import App::*; part engine : Engine; }",
+        );
+        host.set_file_content("car.sysml", "package Car { part engine : Mid::Engine; }");
+        for user in ["Mid::engine", "Top::engine", "Car::engine"] {
+            assert_eq!(
+                resolved_type(&mut host, user).as_deref(),
+                Some("Lib::Engine"),
+                "{user}"
+            );
+        }
+
+        // A private import no longer re-exports `Engine` to importing namespaces
+        host.set_file_content("mid.sysml", "package Mid { private import Lib::*; }");
+        assert_eq!(resolved_type(&mut host, "Top::engine"), None);
+
+        // Without the import, the rest of `Mid` and qualified references
+        // through it dangle too
+        host.set_file_content("mid.sysml", "package Mid;");
+        assert_eq!(resolved_type(&mut host, "Mid::engine"), None);
+        assert_eq!(resolved_type(&mut host, "Car::engine"), None);
+
+        host.set_file_content("mid.sysml", "package Mid { public import Lib::*; }");
+        for user in ["Mid::engine", "Top::engine", "Car::engine"] {
+            assert_eq!(
+                resolved_type(&mut host, user).as_deref(),
+                Some("Lib::Engine"),
+                "{user}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_edit_updates_goto_definition_in_dependent_file() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("lib.sysml", "package Lib {\n    part def Engine;\n}");
+        host.set_file_content(
+            "app.sysml",
+            "package App {\n    private import Lib::*;\n    part engine : Engine;\n}",
+        );
+        let app = host.get_file_id("app.sysml").unwrap();
+        assert!(
+            !host
+                .analysis()
+                .goto_definition(app, 2, 19)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Moving the definition down a line is picked up without touching app.sysml
+        host.set_file_content(
+            "lib.sysml",
+            "package Lib {\n    part def Other;\n    part def Engine;\n}",
+        );
+        let result = host.analysis().goto_definition(app, 2, 19).unwrap();
+        assert_eq!(result.targets.len(), 1);
+        assert_eq!(result.targets[0].start_line, 2);
+    }
+
+    #[test]
+    fn test_file_ids_survive_removal() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A;");
+        host.set_file_content("b.sysml", "package B;");
+        host.set_file_content("c.sysml", "package C;");
+        let c = host.get_file_id("c.sysml").unwrap();
+
+        host.remove_file("a.sysml");
+        host.mark_dirty();
+        let analysis = host.analysis();
+        assert_eq!(analysis.get_file_id("c.sysml"), Some(c));
+        assert_eq!(
+            analysis.symbol_index().lookup_qualified("C").unwrap().file,
+            c
+        );
+        assert!(analysis.symbol_index().lookup_qualified("A").is_none());
+    }
+
+    #[test]
+    fn test_file_ids_stable_across_rebuilds_and_re_adds() {
+        let mut host = AnalysisHost::new();
+        for name in ["a", "b", "c", "d"] {
+            host.set_file_content(&format!("{name}.sysml"), &format!("package {name};"));
+        }
+        let ids: Vec<FileId> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| host.get_file_id(&format!("{name}.sysml")).unwrap())
+            .collect();
+
+        for _ in 0..3 {
+            host.mark_dirty();
+            let analysis = host.analysis();
+            for (name, &id) in ["a", "b", "c", "d"].iter().zip(&ids) {
+                assert_eq!(analysis.get_file_id(&format!("{name}.sysml")), Some(id));
+                assert_eq!(
+                    analysis.symbol_index().lookup_qualified(name).unwrap().file,
+                    id
+                );
+            }
+        }
+
+        // Removing and re-adding a path gives it back its original id
+        host.remove_file("b.sysml");
+        assert_eq!(host.get_file_id("b.sysml"), None);
+        host.set_file_content("e.sysml", "package e;");
+        host.set_file_content("b.sysml", "package b;");
+        assert_eq!(host.get_file_id("b.sysml"), Some(ids[1]));
+        assert!(!ids.contains(&host.get_file_id("e.sysml").unwrap()));
+        assert_eq!(
+            host.analysis()
+                .symbol_index()
+                .lookup_qualified("b")
+                .unwrap()
+                .file,
+            ids[1]
+        );
+    }
+
+    #[test]
+    fn test_apply_text_edit() {
+        use crate::base::TextSize;
+
+        let mut host = AnalysisHost::new();
+        let text =
+            "package P {\n    part def Engine { attribute power : Real; }\n    part e : Engine;\n}";
+        host.set_file_content("test.sysml", text);
+
+        let range = |needle: &str| {
+            let start = text.find(needle).unwrap() as u32;
+            TextRange::at(start.into(), (needle.len() as u32).into())
+        };
+
+        let errors = host
+            .apply_text_edit("test.sysml", range("power"), "torque")
+            .unwrap();
+        assert!(errors.is_empty());
+        let analysis = host.analysis();
+        assert!(
+            analysis
+                .symbol_index()
+                .lookup_qualified("P::Engine::torque")
+                .is_some()
+        );
+        assert!(
+            analysis
+                .symbol_index()
+                .lookup_qualified("P::Engine::power")
+                .is_none()
+        );
+        // The edited text is what a full parse of the new content sees
+        let edited = host.files()[&PathBuf::from("test.sysml")].clone();
+        assert_eq!(*edited, SyntaxFile::sysml(&text.replace("power", "torque")));
+
+        assert!(
+            host.apply_text_edit("missing.sysml", range("P"), "Q")
+                .is_none()
+        );
+        let past_end = TextRange::empty(TextSize::from(text.len() as u32 + 10));
+        assert!(host.apply_text_edit("test.sysml", past_end, "x").is_none());
+    }
+
+    #[test]
+    fn test_kerml_file_parsed_as_kerml() {
+        let mut host = AnalysisHost::new();
+        let errors = host.set_file_content("lib.kerml", "package Lib { classifier Thing; }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let files = host.files();
+        assert!(files[&PathBuf::from("lib.kerml")].is_kerml());
+        assert!(
+            host.analysis()
+                .symbol_index()
+                .lookup_qualified("Lib::Thing")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_mark_dirty_rebuilds_the_index() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        drop(host.analysis());
+
+        // No input changed, but the index was edited outside the database
+        host.update_symbols(|symbol| symbol.doc = Some(Arc::from("edited")));
+        host.mark_dirty();
+        let analysis = host.analysis();
+        let engine = analysis
+            .symbol_index()
+            .lookup_qualified("A::Engine")
+            .unwrap();
+        assert_eq!(engine.doc, None);
+    }
+
+    #[test]
+    fn test_incremental_rebuild_updates_the_index_in_place() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        host.set_file_content("b.sysml", "package B { part def Wheel; }");
+        drop(host.analysis());
+
+        // Once snapshots are dropped, the host is the only owner of the index
+        // built by the full rebuild, so an edit doesn't copy it
+        assert_eq!(Arc::strong_count(&host.symbol_index), 1);
+        let before = Arc::as_ptr(&host.symbol_index);
+        host.set_file_content("b.sysml", "package B { part def Tire; }");
+        host.rebuild_index();
+        assert_eq!(Arc::as_ptr(&host.symbol_index), before);
+        assert!(host.symbol_index.lookup_qualified("B::Tire").is_some());
+    }
+
+    #[test]
+    fn test_edit_without_declaration_changes_resolves_no_other_file() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content(
+            "lib.sysml",
+            "package Lib { part def Engine { attribute mass = 1; } }",
+        );
+        host.set_file_content(
+            "app.sysml",
+            "package App { private import Lib::*; part engine : Engine; }",
+        );
+        drop(host.analysis());
+
+        // Overwrite the resolved ref in app.sysml; only re-resolving the file restores it
+        fn engine_target(host: &mut AnalysisHost) -> &mut Option<Arc<str>> {
+            let index = Arc::make_mut(&mut host.symbol_index);
+            let engine = index.lookup_qualified_mut("App::engine").unwrap();
+            match &mut engine.type_refs[0] {
+                crate::hir::TypeRefKind::Simple(type_ref) => &mut type_ref.resolved_target,
+                crate::hir::TypeRefKind::Chain(_) => unreachable!(),
+            }
+        }
+        *engine_target(&mut host) = Some(Arc::from("Stale"));
+
+        // Changing a value declares nothing new
+        host.set_file_content(
+            "lib.sysml",
+            "package Lib { part def Engine { attribute mass = 2; } }",
+        );
+        host.rebuild_index();
+        assert_eq!(engine_target(&mut host).as_deref(), Some("Stale"));
+
+        // Specializing `Engine` changes its declaration
+        host.set_file_content(
+            "lib.sysml",
+            "package Lib { part def Base; part def Engine :> Base { attribute mass = 2; } }",
+        );
+        host.rebuild_index();
+        assert_eq!(engine_target(&mut host).as_deref(), Some("Lib::Engine"));
+    }
+
+    #[test]
+    fn test_set_file_reuses_the_given_tree() {
+        let syntax_file = Arc::new(SyntaxFile::sysml("package P { part def Engine; }"));
+        let mut host = AnalysisHost::new();
+        host.set_file(PathBuf::from("p.sysml"), Arc::clone(&syntax_file));
+        assert!(
+            host.analysis()
+                .symbol_index()
+                .lookup_qualified("P::Engine")
+                .is_some()
+        );
+        assert!(Arc::ptr_eq(
+            &host.files()[&PathBuf::from("p.sysml")],
+            &syntax_file
+        ));
+
+        // Copying files to another host shares the trees too
+        let mut copy = AnalysisHost::new();
+        copy.copy_files_from(&host);
+        assert!(Arc::ptr_eq(
+            &copy.files()[&PathBuf::from("p.sysml")],
+            &syntax_file
+        ));
+        assert!(
+            copy.analysis()
+                .symbol_index()
+                .lookup_qualified("P::Engine")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_unsupported_extension_is_rejected() {
+        let mut host = AnalysisHost::new();
+        let errors = host.set_file_content("notes.txt", "package P;");
+        assert_eq!(errors.len(), 1);
+        assert!(!host.has_file("notes.txt"));
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        let _ = host.analysis();
+
+        // Editing a clone must neither block on nor affect the original
+        let mut clone = host.clone();
+        assert!(!clone.needs_update());
+        clone.set_file_content("a.sysml", "package A { part def Motor; }");
+        clone.set_file_content("b.sysml", "package B;");
+
+        let index = clone.analysis().symbol_index().clone();
+        assert!(index.lookup_qualified("A::Motor").is_some());
+        assert!(index.lookup_qualified("B").is_some());
+
+        let analysis = host.analysis();
+        assert!(
+            analysis
+                .symbol_index()
+                .lookup_qualified("A::Engine")
+                .is_some()
+        );
+        assert!(analysis.get_file_id("b.sysml").is_none());
+    }
+
+    #[test]
+    fn test_analysis_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+        assert_send_sync::<Analysis>();
+        assert_send_sync::<AnalysisHost>();
+    }
+
+    #[test]
+    fn test_edit_cancels_snapshots() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def Engine; }");
+        let before = host.analysis();
+        let same = host.analysis();
+        assert!(!before.is_cancelled());
+
+        host.set_file_content("a.sysml", "package A { part def Motor; }");
+        assert!(before.is_cancelled());
+        assert!(same.is_cancelled());
+
+        let after = host.analysis();
+        assert!(!after.is_cancelled());
+
+        // The old snapshot still answers consistently from its own state
+        assert!(
+            before
+                .symbol_index()
+                .lookup_qualified("A::Engine")
+                .is_some()
+        );
+        assert!(after.symbol_index().lookup_qualified("A::Motor").is_some());
+    }
+
+    #[test]
+    fn test_set_lints_cancels_snapshots() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A { part def fuel_tank; }");
+        let file = host.get_file_id("a.sysml").unwrap();
+        let before = host.analysis();
+
+        let mut lints = LintRegistry::default();
+        lints.set_level("naming-convention", crate::hir::LintLevel::Warning);
+        host.set_lints(lints);
+        assert!(before.is_cancelled());
+        assert!(before.workspace_diagnostics().is_err());
+
+        let after = host.analysis();
+        assert!(!after.is_cancelled());
+        assert!(
+            after
+                .diagnostics(file)
+                .unwrap()
+                .iter()
+                .any(|d| d.message.contains("naming convention"))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_queries_on_worker_thread() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content("a.sysml", "package A {\n    part def Engine;\n}");
+        let file = host.get_file_id("a.sysml").unwrap();
+        let snapshot = host.analysis();
+
+        let (answered, answer) = std::sync::mpsc::channel();
+        let (edited, wait_for_edit) = std::sync::mpsc::channel();
+        let worker = std::thread::spawn(move || {
+            answered
+                .send(snapshot.document_symbols(file).unwrap().len())
+                .unwrap();
+
+            // The host edits while this snapshot is still alive
+            wait_for_edit.recv().unwrap();
+            assert!(snapshot.is_cancelled());
+            assert!(snapshot.document_symbols(file).is_err());
+            // The snapshot keeps the view it was taken with
+            let index = snapshot.symbol_index();
+            assert!(index.lookup_qualified("A::Engine").is_some());
+            assert!(index.lookup_qualified("A::Motor").is_none());
+        });
+        assert!(answer.recv().unwrap() > 0);
+
+        host.set_file_content("a.sysml", "package A {\n    part def Motor;\n}");
+        let fresh = host.analysis();
+        assert!(fresh.symbol_index().lookup_qualified("A::Motor").is_some());
+        assert!(fresh.symbol_index().lookup_qualified("A::Engine").is_none());
+        edited.send(()).unwrap();
+
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_snapshot_queries_return_err() {
+        let mut host = AnalysisHost::new();
+        host.set_file_content(
+            "a.sysml",
+            "package A {\n    part def Engine;\n    part e : Engine;\n}",
+        );
+        let file = host.get_file_id("a.sysml").unwrap();
+        let snapshot = host.analysis();
+        assert!(snapshot.hover(file, 1, 14).is_ok());
+        assert!(snapshot.workspace_diagnostics().is_ok());
+
+        host.set_file_content("a.sysml", "package A {}");
+        assert!(matches!(snapshot.hover(file, 1, 14), Err(Cancelled)));
+        assert!(snapshot.find_references(file, 1, 14, true).is_err());
+        assert!(snapshot.workspace_symbols(Some("Engine")).is_err());
+        assert!(snapshot.semantic_tokens(file).is_err());
+        assert!(snapshot.workspace_diagnostics().is_err());
+        assert!(snapshot.diagnostics(file).is_err());
+        assert!(snapshot.code_actions(file, (2, 10, 2, 10)).is_err());
+
+        assert!(host.analysis().document_symbols(file).is_ok());
+    }
+
     // ── Interchange model projection tests ──────────────────────────
 
     #[test]
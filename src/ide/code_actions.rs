@@ -0,0 +1,81 @@
+//! Code actions — edits offered at a range of a file.
+//!
+//! Quick fixes come from the [`Fix`](crate::hir::Fix)es attached to the
+//! semantic diagnostics overlapping the range; refactorings (extract,
+//! inline, move, …) from the syntax tree at the range. Moves are offered
+//! without edits: they're built when the action is resolved.
+
+use std::sync::Arc;
+
+use crate::base::{FileId, TextEdit};
+use crate::hir::Diagnostic;
+
+/// The kind of a code action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CodeActionKind {
+    /// Fixes a diagnostic.
+    QuickFix,
+    /// Extracts part of a declaration into a new one.
+    RefactorExtract,
+    /// Inlines a declaration into its use.
+    RefactorInline,
+    /// Rewrites a declaration in place.
+    RefactorRewrite,
+    /// Moves a declaration to another namespace.
+    RefactorMove,
+}
+
+/// An edit offered at a range of a file.
+#[derive(Clone, Debug)]
+pub struct CodeAction {
+    /// Title shown to the user.
+    pub title: Arc<str>,
+    /// The kind of action.
+    pub kind: CodeActionKind,
+    /// The edits to apply, possibly across files.
+    pub edits: Vec<TextEdit>,
+    /// The diagnostic a quick fix resolves.
+    pub diagnostic: Option<Diagnostic>,
+    /// Whether this is the obvious choice (the only fix of its diagnostic).
+    pub is_preferred: bool,
+    /// What to build `edits` from, for an action offered without them; see
+    /// [`Analysis::resolve_code_action`](super::Analysis::resolve_code_action).
+    pub data: Option<CodeActionData>,
+}
+
+/// What a code action offered without edits is resolved from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CodeActionData {
+    /// Move the definition whose name is at (line, col) of `file` to the end
+    /// of `package` (a qualified name), which may be any package of the
+    /// workspace outside libraries.
+    MoveDefinition {
+        file: FileId,
+        line: u32,
+        col: u32,
+        package: Arc<str>,
+    },
+}
+
+/// Get the quick fixes of the `diagnostics` overlapping `range`
+/// (start_line, start_col, end_line, end_col).
+pub fn quick_fixes(diagnostics: &[Diagnostic], range: (u32, u32, u32, u32)) -> Vec<CodeAction> {
+    let (start_line, start_col, end_line, end_col) = range;
+    diagnostics
+        .iter()
+        .filter(|d| {
+            (d.start_line, d.start_col) <= (end_line, end_col)
+                && (d.end_line, d.end_col) >= (start_line, start_col)
+        })
+        .flat_map(|d| {
+            d.fixes.iter().map(|fix| CodeAction {
+                title: fix.label.clone(),
+                kind: CodeActionKind::QuickFix,
+                edits: fix.edits.clone(),
+                diagnostic: Some(d.clone()),
+                is_preferred: d.fixes.len() == 1,
+                data: None,
+            })
+        })
+        .collect()
+}
@@ -1,9 +1,30 @@
 //! Completion suggestions implementation.
+//!
+//! The text before the cursor tells what's expected there:
+//! - after `::`, the members of the namespace before it;
+//! - after `.`, the features of the feature chain before it;
+//! - after `:`, `:>`, `specializes`, …, the types visible from the cursor;
+//! - after `:>>` or `redefines`, the features the owner inherits;
+//! - elsewhere, the keywords valid at the position and the visible names.
+//!
+//! Visible names come from the visibility maps of the cursor's scope and its
+//! parents, with the members they inherit from their supertypes. Definitions
+//! not visible yet are offered with an edit importing them.
 
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
-use crate::base::FileId;
-use crate::hir::{HirSymbol, SymbolIndex, SymbolKind};
+use rowan::Direction;
+use tokio_util::sync::CancellationToken;
+
+use super::refactor::{display_qualified, within};
+use super::rename::{parent_scope, strip_quotes};
+use crate::base::{
+    Cancellable, Cancelled, FileId, LineCol, LineIndex, TextEdit, TextRange, TextSize,
+};
+use crate::hir::{HirSymbol, Resolver, SymbolIndex, SymbolKind};
+use crate::parser::{Lexer, SyntaxKind, SyntaxNode};
+use crate::syntax::SyntaxFile;
 
 /// Kind of completion item.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -43,6 +64,8 @@ pub struct CompletionItem {
     pub insert_text: Option<Arc<str>>,
     /// Sort priority (lower = higher priority).
     pub sort_priority: u32,
+    /// Edits applied along with the completion (e.g., importing the element).
+    pub additional_edits: Vec<TextEdit>,
 }
 
 impl CompletionItem {
@@ -55,6 +78,7 @@ impl CompletionItem {
             documentation: None,
             insert_text: None,
             sort_priority: 100,
+            additional_edits: Vec::new(),
         }
     }
 
@@ -112,6 +136,11 @@ impl CompletionItem {
 
 /// Get completion suggestions at a position.
 ///
+/// Without the file's text the cursor's scope is guessed from the
+/// declarations before it, and only the names visible from there are
+/// suggested. See [`scoped_completions()`] for suggestions driven by the
+/// cursor's scope and position.
+///
 /// # Arguments
 /// * `index` - The symbol index to search
 /// * `file` - The file containing the cursor
@@ -128,120 +157,804 @@ pub fn completions(
     col: u32,
     trigger: Option<char>,
 ) -> Vec<CompletionItem> {
-    let mut items = Vec::new();
-
-    // Determine context
-    let context = determine_context(index, file, line, col, trigger);
-
-    match context {
-        CompletionContext::TypeReference => {
-            // Suggest definitions (types)
-            for symbol in index.all_definitions() {
-                if symbol.kind.is_definition() {
-                    let mut item = CompletionItem::from_symbol(symbol);
-                    item.sort_priority = 10;
-                    items.push(item);
+    completions_cancellable(index, file, line, col, trigger, &CancellationToken::new())
+        .unwrap_or_default()
+}
+
+/// Get completion suggestions at a position, giving up as soon as `cancel` is signalled.
+///
+/// Same as [`completions()`], but returns `Err(Cancelled)` instead of
+/// finishing the workspace scan once the token is cancelled.
+pub fn completions_cancellable(
+    index: &SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    trigger: Option<char>,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<CompletionItem>> {
+    scoped_completions(index, &HashMap::new(), file, line, col, trigger, cancel)
+}
+
+/// Get completion suggestions at a position from the text before it,
+/// giving up as soon as `cancel` is signalled.
+///
+/// Only the names visible from the cursor's scope are suggested, with
+/// definitions not imported yet offered along with an edit importing them.
+/// If `file` isn't parsed, the scope is guessed from the declarations before
+/// the cursor and only the names visible from it are suggested.
+pub fn scoped_completions(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    line: u32,
+    col: u32,
+    trigger: Option<char>,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<CompletionItem>> {
+    Cancelled::check(cancel)?;
+    let Some(cursor) = syntax_files
+        .get(&file)
+        .and_then(|syntax| Cursor::new(index, file, syntax, line, col))
+    else {
+        let scope = scope_before(index, file, line, col);
+        let mut completer = Completer::new(index, syntax_files, scope, cancel);
+        let types_only = trigger == Some(':');
+        if !types_only {
+            completer.items.extend(keyword_completions(Place::Member {
+                in_package: true,
+                after_prefix: false,
+            }));
+        }
+        completer.visible(|symbol| {
+            !types_only || symbol.kind.is_definition() || is_namespace(symbol.kind)
+        })?;
+        return Ok(completer.finish());
+    };
+    let mut completer = Completer::new(index, syntax_files, cursor.scope.clone(), cancel);
+
+    match cursor.context() {
+        CompletionContext::TypeReference { types_only } => {
+            completer.visible(|symbol| {
+                !types_only || symbol.kind.is_definition() || is_namespace(symbol.kind)
+            })?;
+            completer.importable(&cursor)?;
+        }
+        CompletionContext::Redefinition => completer.redefinable()?,
+        CompletionContext::MemberAccess(path) => completer.members(&path)?,
+        CompletionContext::FeatureChain(chain) => completer.features(&chain)?,
+        CompletionContext::General(place) => {
+            completer.items.extend(keyword_completions(place));
+            completer.visible(|_| true)?;
+            completer.importable(&cursor)?;
+        }
+    }
+
+    Ok(completer.finish())
+}
+
+/// Completion context.
+#[derive(Debug, PartialEq, Eq)]
+enum CompletionContext {
+    /// After `:`, `:>`, `specializes`, … — expecting a type (or, for
+    /// specializations, any element)
+    TypeReference { types_only: bool },
+    /// After `:>>` or `redefines` — expecting an inherited feature
+    Redefinition,
+    /// After `::` — expecting a member of the namespace before it
+    MemberAccess(String),
+    /// After `.` — expecting a feature of the chain before it
+    FeatureChain(Vec<String>),
+    /// General completion
+    General(Place),
+}
+
+/// Where the cursor is in a declaration, to know which keywords are valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Place {
+    /// At the start of a member of a package (or the file) or of another
+    /// element, possibly after some of its prefixes.
+    Member {
+        in_package: bool,
+        after_prefix: bool,
+    },
+    /// After the name of a definition.
+    DefinitionHeader,
+    /// After the name or type of a usage.
+    UsageHeader,
+    /// Anywhere else, e.g. in an expression.
+    Other,
+}
+
+/// Sort priorities of the suggestions (lower = higher priority).
+const KEYWORD_PRIORITY: u32 = 5;
+const LOCAL_PRIORITY: u32 = 10;
+const INHERITED_PRIORITY: u32 = 12;
+const IMPORTED_PRIORITY: u32 = 14;
+const AUTO_IMPORT_PRIORITY: u32 = 50;
+
+/// The text before the cursor.
+struct Cursor {
+    file: FileId,
+    root: SyntaxNode,
+    text: String,
+    line_index: LineIndex,
+    offset: TextSize,
+    /// The tokens before the word being typed, trivia excluded.
+    tokens: Vec<(SyntaxKind, TextRange)>,
+    /// Qualified name of the namespace whose body holds the cursor.
+    scope: Arc<str>,
+    /// The part of the word being typed before the cursor.
+    prefix: String,
+}
+
+impl Cursor {
+    fn new(
+        index: &SymbolIndex,
+        file: FileId,
+        syntax: &SyntaxFile,
+        line: u32,
+        col: u32,
+    ) -> Option<Self> {
+        let root = syntax.parse().syntax();
+        let text = syntax.source_text();
+        let line_index = LineIndex::new(&text);
+        let offset = line_index
+            .offset(LineCol::new(line, col))?
+            .min(TextSize::of(text.as_str()));
+        if !text.is_char_boundary(usize::from(offset)) {
+            return None;
+        }
+
+        let mut tokens: Vec<_> = Lexer::new(&text[..usize::from(offset)])
+            .filter(|token| !token.kind.is_trivia())
+            .map(|token| {
+                (
+                    token.kind,
+                    TextRange::at(token.offset, TextSize::of(token.text)),
+                )
+            })
+            .collect();
+        let prefix = match tokens.last() {
+            Some(&(kind, range))
+                if range.end() == offset && (kind == SyntaxKind::IDENT || kind.is_keyword()) =>
+            {
+                tokens.pop();
+                text[range].to_string()
+            }
+            _ => String::new(),
+        };
+        let scope = scope_at(index, file, &root, &line_index, offset);
+
+        Some(Self {
+            file,
+            root,
+            text,
+            line_index,
+            offset,
+            tokens,
+            scope,
+            prefix,
+        })
+    }
+
+    fn context(&self) -> CompletionContext {
+        let Some(&(last, _)) = self.tokens.last() else {
+            return CompletionContext::General(self.place());
+        };
+        match last {
+            SyntaxKind::COLON_COLON => CompletionContext::MemberAccess(
+                self.path_before(SyntaxKind::COLON_COLON).join("::"),
+            ),
+            SyntaxKind::DOT => CompletionContext::FeatureChain(self.path_before(SyntaxKind::DOT)),
+            SyntaxKind::COLON
+            | SyntaxKind::BY_KW
+            | SyntaxKind::TILDE
+            | SyntaxKind::CONJUGATES_KW => CompletionContext::TypeReference { types_only: true },
+            SyntaxKind::COLON_GT
+            | SyntaxKind::SPECIALIZES_KW
+            | SyntaxKind::SUBSETS_KW
+            | SyntaxKind::REFERENCES_KW
+            | SyntaxKind::COLON_COLON_GT
+            | SyntaxKind::IMPORT_KW
+            | SyntaxKind::FOR_KW => CompletionContext::TypeReference { types_only: false },
+            SyntaxKind::COLON_GT_GT | SyntaxKind::REDEFINES_KW => CompletionContext::Redefinition,
+            _ => CompletionContext::General(self.place()),
+        }
+    }
+
+    /// The names separated by `separator` before the trailing one.
+    fn path_before(&self, separator: SyntaxKind) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut tokens = self.tokens[..self.tokens.len() - 1].iter().rev();
+        while let Some(&(SyntaxKind::IDENT, range)) = tokens.next() {
+            path.push(strip_quotes(&self.text[range]).to_string());
+            if tokens.next().map(|&(kind, _)| kind) != Some(separator) {
+                break;
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    /// Where the cursor is in the statement it's typed in.
+    fn place(&self) -> Place {
+        // The statement starts after the last `;`, `{` or `}`; braces
+        // tell whether it's in a package
+        let mut in_package = vec![true];
+        let mut start = 0;
+        for (i, &(kind, _)) in self.tokens.iter().enumerate() {
+            match kind {
+                SyntaxKind::L_BRACE => {
+                    in_package.push(self.tokens[start..i].iter().any(|&(kind, _)| {
+                        matches!(kind, SyntaxKind::PACKAGE_KW | SyntaxKind::NAMESPACE_KW)
+                    }));
+                    start = i + 1;
+                }
+                SyntaxKind::R_BRACE => {
+                    if in_package.len() > 1 {
+                        in_package.pop();
+                    }
+                    start = i + 1;
                 }
+                SyntaxKind::SEMICOLON => start = i + 1,
+                _ => {}
             }
         }
-        CompletionContext::MemberAccess(scope) => {
-            // Suggest members of the scope
-            for symbol in index.all_symbols() {
-                if symbol.qualified_name.starts_with(&format!("{}::", scope)) {
-                    let depth = symbol.qualified_name.matches("::").count();
-                    let scope_depth = scope.matches("::").count() + 1;
-                    // Only direct children
-                    if depth == scope_depth + 1 {
-                        items.push(CompletionItem::from_symbol(symbol));
+        let statement: Vec<_> = self.tokens[start..].iter().map(|&(kind, _)| kind).collect();
+        let in_package = in_package.last().copied().unwrap_or(true);
+
+        if statement.iter().all(|&kind| is_prefix(kind)) {
+            return Place::Member {
+                in_package,
+                after_prefix: statement.iter().any(|&kind| !is_visibility(kind)),
+            };
+        }
+        if statement.last() != Some(&SyntaxKind::IDENT) {
+            return Place::Other;
+        }
+        let mut declaration = statement
+            .iter()
+            .copied()
+            .skip_while(|&kind| is_prefix(kind));
+        if statement.contains(&SyntaxKind::DEF_KW) {
+            Place::DefinitionHeader
+        } else if declaration.next().is_some_and(|kind| {
+            kind.is_keyword()
+                && !matches!(
+                    kind,
+                    SyntaxKind::PACKAGE_KW
+                        | SyntaxKind::LIBRARY_KW
+                        | SyntaxKind::NAMESPACE_KW
+                        | SyntaxKind::IMPORT_KW
+                        | SyntaxKind::ALIAS_KW
+                        | SyntaxKind::DOC_KW
+                        | SyntaxKind::COMMENT_KW
+                )
+        }) {
+            Place::UsageHeader
+        } else {
+            Place::Other
+        }
+    }
+
+    /// An edit importing `qualified_name` where the cursor can see it: before
+    /// the first member of the innermost package, or of the file.
+    fn import_edit(&self, qualified_name: &str) -> TextEdit {
+        let package_member = self
+            .root
+            .token_at_offset(self.offset)
+            .left_biased()
+            .and_then(|token| {
+                token.parent_ancestors().find(|node| {
+                    matches!(
+                        node.kind(),
+                        SyntaxKind::PACKAGE | SyntaxKind::LIBRARY_PACKAGE
+                    )
+                })
+            })
+            .and_then(|package| {
+                package
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::NAMESPACE_BODY)
+            })
+            .and_then(|body| body.children().next());
+        let anchor = package_member.or_else(|| self.root.children().next());
+        let import = format!("import {};", display_qualified(qualified_name));
+        let Some(anchor) = anchor else {
+            return self.insert(TextSize::from(0), format!("{import}\n"));
+        };
+        let offset = anchor.text_range().start();
+        let line_start = self.text[..usize::from(offset)]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let indent = &self.text[line_start..usize::from(offset)];
+        let new_text = if indent.chars().all(char::is_whitespace) {
+            format!("{import}\n{indent}")
+        } else {
+            format!("{import} ")
+        };
+        self.insert(offset, new_text)
+    }
+
+    fn insert(&self, offset: TextSize, new_text: String) -> TextEdit {
+        let LineCol { line, col } = self.line_index.line_col(offset);
+        TextEdit::insert(self.file, (line, col), new_text)
+    }
+}
+
+/// The qualified name of the innermost named element whose body contains `offset`.
+pub(super) fn scope_at(
+    index: &SymbolIndex,
+    file: FileId,
+    root: &SyntaxNode,
+    line_index: &LineIndex,
+    offset: TextSize,
+) -> Arc<str> {
+    let Some(token) = root.token_at_offset(offset).left_biased() else {
+        return Arc::from("");
+    };
+    let symbols = index.symbols_in_file(file);
+    token
+        .parent_ancestors()
+        .filter(|node| node.kind() == SyntaxKind::NAMESPACE_BODY && in_braces(node, offset))
+        .filter_map(|body| {
+            let name = body
+                .parent()?
+                .children()
+                .find(|child| child.kind() == SyntaxKind::NAME)?;
+            let LineCol { line, col } = line_index.line_col(name.text_range().start());
+            symbols.iter().find(|symbol| {
+                symbol.kind != SymbolKind::Import
+                    && (symbol.start_line, symbol.start_col) == (line, col)
+            })
+        })
+        .map(|symbol| symbol.qualified_name.clone())
+        .next()
+        .unwrap_or_else(|| Arc::from(""))
+}
+
+/// The scope of a position of `file` guessed from the last declaration before
+/// it: the declaration itself if it's a package or definition, else its owner.
+fn scope_before(index: &SymbolIndex, file: FileId, line: u32, col: u32) -> Arc<str> {
+    index
+        .symbols_in_file(file)
+        .into_iter()
+        .filter(|symbol| {
+            symbol.kind != SymbolKind::Import
+                && (symbol.start_line, symbol.start_col) <= (line, col)
+        })
+        .max_by_key(|symbol| (symbol.start_line, symbol.start_col))
+        .map_or_else(
+            || Arc::from(""),
+            |symbol| {
+                if symbol.kind == SymbolKind::Package || symbol.kind.is_definition() {
+                    symbol.qualified_name.clone()
+                } else {
+                    Arc::from(parent_scope(&symbol.qualified_name))
+                }
+            },
+        )
+}
+
+/// Whether `offset` is between the braces of a namespace body.
+fn in_braces(body: &SyntaxNode, offset: TextSize) -> bool {
+    let opened = body.first_token().is_some_and(|token| {
+        token.kind() == SyntaxKind::L_BRACE && token.text_range().end() <= offset
+    });
+    let closed = body
+        .last_token()
+        .filter(|token| token.kind() == SyntaxKind::R_BRACE)
+        .is_some_and(|token| token.text_range().start() < offset);
+    opened && !closed
+}
+
+/// Collects the suggestions for a scope.
+struct Completer<'a> {
+    index: &'a SymbolIndex,
+    syntax_files: &'a HashMap<FileId, Arc<SyntaxFile>>,
+    scope: Arc<str>,
+    cancel: &'a CancellationToken,
+    items: Vec<CompletionItem>,
+    /// Names visible from the scope, which shadow further ones.
+    visible_names: HashSet<Arc<str>>,
+    /// Line indexes of the files read to check visibility keywords.
+    line_indexes: HashMap<FileId, LineIndex>,
+}
+
+impl<'a> Completer<'a> {
+    fn new(
+        index: &'a SymbolIndex,
+        syntax_files: &'a HashMap<FileId, Arc<SyntaxFile>>,
+        scope: Arc<str>,
+        cancel: &'a CancellationToken,
+    ) -> Self {
+        Self {
+            index,
+            syntax_files,
+            scope,
+            cancel,
+            items: Vec::new(),
+            visible_names: HashSet::new(),
+            line_indexes: HashMap::new(),
+        }
+    }
+
+    /// Suggest the names visible from the scope: declared or imported in it
+    /// or its parents, or inherited from their supertypes.
+    fn visible(&mut self, filter: impl Fn(&HirSymbol) -> bool) -> Cancellable<()> {
+        let index = self.index;
+        let scope = self.scope.clone();
+        let mut scope: &str = &scope;
+        loop {
+            Cancelled::check(self.cancel)?;
+            let mut found = Vec::new();
+            if let Some(vis) = index.visibility_for_scope(scope) {
+                for (name, qualified_name) in vis.direct_defs() {
+                    found.push((name.clone(), qualified_name.clone(), LOCAL_PRIORITY));
+                }
+                for (name, qualified_name) in vis.imports() {
+                    found.push((name.clone(), qualified_name.clone(), IMPORTED_PRIORITY));
+                }
+            } else if index.visibility_maps().is_empty() {
+                // Without visibility maps only the scope's own declarations are known
+                for symbol in index.all_symbols() {
+                    if symbol.kind != SymbolKind::Import
+                        && parent_scope(&symbol.qualified_name) == scope
+                    {
+                        found.push((
+                            symbol.name.clone(),
+                            symbol.qualified_name.clone(),
+                            LOCAL_PRIORITY,
+                        ));
                     }
                 }
             }
+            if let Some(owner) = index.lookup_qualified(scope) {
+                for (name, member) in self.inherited(owner) {
+                    found.push((name, member.qualified_name.clone(), INHERITED_PRIORITY));
+                }
+            }
+            for (name, qualified_name, priority) in found {
+                if self.visible_names.contains(&name) {
+                    continue;
+                }
+                let Some(symbol) = index.lookup_qualified(&qualified_name) else {
+                    continue;
+                };
+                if priority != LOCAL_PRIORITY && self.is_private(symbol) {
+                    continue;
+                }
+                self.visible_names.insert(name.clone());
+                if filter(symbol) {
+                    self.offer(&name, symbol, priority);
+                }
+            }
+            if scope.is_empty() {
+                return Ok(());
+            }
+            scope = parent_scope(scope);
         }
-        CompletionContext::General => {
-            // Suggest keywords
-            items.extend(keyword_completions());
-
-            // Suggest all visible symbols
-            for symbol in index.all_definitions() {
-                let mut item = CompletionItem::from_symbol(symbol);
-                item.sort_priority = 50;
-                items.push(item);
+    }
+
+    /// Suggest the definitions of other packages matching the word being
+    /// typed, with an edit importing them.
+    fn importable(&mut self, cursor: &Cursor) -> Cancellable<()> {
+        if cursor.prefix.is_empty() {
+            return Ok(());
+        }
+        let prefix = cursor.prefix.to_lowercase();
+        for symbol in self.index.all_definitions() {
+            Cancelled::check(self.cancel)?;
+            if !symbol.name.to_lowercase().starts_with(&prefix)
+                || self.visible_names.contains(&symbol.name)
+            {
+                continue;
+            }
+            let package = parent_scope(&symbol.qualified_name);
+            let in_package = self
+                .index
+                .lookup_qualified(package)
+                .is_some_and(|package| package.kind == SymbolKind::Package);
+            if !in_package || self.is_private(symbol) {
+                continue;
             }
+            let import = cursor.import_edit(&symbol.qualified_name);
+            let mut item = CompletionItem::from_symbol(symbol)
+                .with_detail(format!("import {}", symbol.qualified_name))
+                .with_priority(AUTO_IMPORT_PRIORITY);
+            item.additional_edits.push(import);
+            self.items.push(item);
+        }
+        Ok(())
+    }
 
-            // Suggest symbols in the same file with higher priority
-            for symbol in index.symbols_in_file(file) {
-                let mut item = CompletionItem::from_symbol(symbol);
-                item.sort_priority = 20;
-                items.push(item);
+    /// Suggest the features inherited by the scope, which it can redefine.
+    fn redefinable(&mut self) -> Cancellable<()> {
+        Cancelled::check(self.cancel)?;
+        let Some(owner) = self.index.lookup_qualified(&self.scope) else {
+            return Ok(());
+        };
+        for (name, member) in self.inherited(owner) {
+            if member.kind.is_usage() && !self.is_private(member) {
+                self.offer(&name, member, LOCAL_PRIORITY);
             }
         }
+        Ok(())
     }
 
-    // Sort by priority
-    items.sort_by_key(|item| item.sort_priority);
+    /// Suggest the members of the namespace named `path` from the scope.
+    fn members(&mut self, path: &str) -> Cancellable<()> {
+        Cancelled::check(self.cancel)?;
+        let Some(namespace) = self.resolve(&self.scope.clone(), path) else {
+            return Ok(());
+        };
+        let namespace = match namespace.kind {
+            SymbolKind::Alias => {
+                let target = namespace.supertypes.first();
+                match target.and_then(|target| {
+                    self.resolve(parent_scope(&namespace.qualified_name), target)
+                }) {
+                    Some(target) => target,
+                    None => return Ok(()),
+                }
+            }
+            _ => namespace,
+        };
+        let inside = within(&self.scope, &namespace.qualified_name);
+        self.members_of(namespace, inside, |_| true)
+    }
 
-    // Deduplicate by label
-    items.dedup_by(|a, b| a.label == b.label);
+    /// Suggest the features of the last element of a feature chain.
+    fn features(&mut self, chain: &[String]) -> Cancellable<()> {
+        Cancelled::check(self.cancel)?;
+        let Some((first, rest)) = chain.split_first() else {
+            return Ok(());
+        };
+        let Some(mut feature) = self.resolve(&self.scope.clone(), first) else {
+            return Ok(());
+        };
+        for name in rest {
+            let member = self
+                .index
+                .find_member_in_scope(&feature.qualified_name, name)
+                .and_then(|member| self.index.lookup_qualified(&member.qualified_name));
+            match member {
+                Some(member) => feature = member,
+                None => return Ok(()),
+            }
+        }
+        let inside = within(&self.scope, &feature.qualified_name);
+        self.members_of(feature, inside, |symbol| symbol.kind.is_usage())
+    }
 
-    items
+    /// Suggest the members of `namespace`: declared in it, re-exported by
+    /// it, or inherited. Private ones are only visible from `inside` it.
+    fn members_of(
+        &mut self,
+        namespace: &'a HirSymbol,
+        inside: bool,
+        filter: impl Fn(&HirSymbol) -> bool,
+    ) -> Cancellable<()> {
+        let index = self.index;
+        let mut found = Vec::new();
+        if let Some(vis) = index.visibility_for_scope(&namespace.qualified_name) {
+            for (name, qualified_name) in vis.direct_defs() {
+                found.push((name.clone(), qualified_name.clone(), LOCAL_PRIORITY));
+            }
+            // Only public imports are visible through the namespace
+            for (name, qualified_name) in vis.imports() {
+                if vis
+                    .public_reexports()
+                    .iter()
+                    .any(|namespace| within(qualified_name, namespace))
+                {
+                    found.push((name.clone(), qualified_name.clone(), IMPORTED_PRIORITY));
+                }
+            }
+        }
+        for (name, member) in self.inherited(namespace) {
+            found.push((name, member.qualified_name.clone(), INHERITED_PRIORITY));
+        }
+        for (name, qualified_name, priority) in found {
+            Cancelled::check(self.cancel)?;
+            let Some(symbol) = index.lookup_qualified(&qualified_name) else {
+                continue;
+            };
+            if filter(symbol) && (inside || !self.is_private(symbol)) {
+                self.offer(&name, symbol, priority);
+            }
+        }
+        Ok(())
+    }
+
+    /// The members `owner` inherits from its supertypes, nearest first.
+    fn inherited(&self, owner: &HirSymbol) -> Vec<(Arc<str>, &'a HirSymbol)> {
+        let mut members = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<_> = self.supertypes(owner).into();
+        while let Some(supertype) = queue.pop_front() {
+            if !visited.insert(supertype.qualified_name.clone()) {
+                continue;
+            }
+            if let Some(vis) = self.index.visibility_for_scope(&supertype.qualified_name) {
+                for (name, qualified_name) in vis.direct_defs() {
+                    if let Some(member) = self.index.lookup_qualified(qualified_name) {
+                        members.push((name.clone(), member));
+                    }
+                }
+            }
+            queue.extend(self.supertypes(supertype));
+        }
+        members
+    }
+
+    fn supertypes(&self, symbol: &HirSymbol) -> Vec<&'a HirSymbol> {
+        symbol
+            .supertypes
+            .iter()
+            .filter_map(|supertype| self.resolve(&symbol.qualified_name, supertype))
+            .filter(|supertype| supertype.qualified_name != symbol.qualified_name)
+            .collect()
+    }
+
+    fn resolve(&self, scope: &str, name: &str) -> Option<&'a HirSymbol> {
+        let resolved = Resolver::new(self.index).with_scope(scope).resolve(name);
+        self.index
+            .lookup_qualified(&resolved.symbol()?.qualified_name)
+    }
+
+    /// Whether `symbol` is declared `private`.
+    fn is_private(&mut self, symbol: &HirSymbol) -> bool {
+        let Some(syntax) = self.syntax_files.get(&symbol.file) else {
+            return false;
+        };
+        let line_index = self
+            .line_indexes
+            .entry(symbol.file)
+            .or_insert_with(|| syntax.line_index());
+        syntax
+            .member_at(line_index, symbol.start_line, symbol.start_col)
+            .and_then(|member| {
+                member
+                    .siblings_with_tokens(Direction::Prev)
+                    .skip(1)
+                    .find(|element| !element.kind().is_trivia())
+            })
+            .is_some_and(|element| element.kind() == SyntaxKind::PRIVATE_KW)
+    }
+
+    fn offer(&mut self, name: &Arc<str>, symbol: &HirSymbol, priority: u32) {
+        if matches!(
+            symbol.kind,
+            SymbolKind::Import
+                | SymbolKind::Comment
+                | SymbolKind::Dependency
+                | SymbolKind::ExposeRelationship
+        ) || name.is_empty()
+        {
+            return;
+        }
+        let mut item = CompletionItem::from_symbol(symbol).with_priority(priority);
+        item.label = name.clone();
+        self.items.push(item);
+    }
+
+    /// The suggestions by priority, without repeated labels.
+    fn finish(self) -> Vec<CompletionItem> {
+        let mut items = self.items;
+        items.sort_by_key(|item| item.sort_priority);
+        let mut labels = HashSet::new();
+        items.retain(|item| labels.insert(item.label.clone()));
+        items
+    }
 }
 
-/// Completion context.
-#[derive(Debug)]
-enum CompletionContext {
-    /// After `:` or `:>` — expecting a type
-    TypeReference,
-    /// After `::` or `.` — expecting a member
-    MemberAccess(String),
-    /// General completion
-    General,
+/// Whether `kind` can prefix a member declaration.
+fn is_prefix(kind: SyntaxKind) -> bool {
+    is_visibility(kind)
+        || matches!(
+            kind,
+            SyntaxKind::ABSTRACT_KW
+                | SyntaxKind::VARIATION_KW
+                | SyntaxKind::INDIVIDUAL_KW
+                | SyntaxKind::IN_KW
+                | SyntaxKind::OUT_KW
+                | SyntaxKind::INOUT_KW
+                | SyntaxKind::REF_KW
+                | SyntaxKind::READONLY_KW
+                | SyntaxKind::DERIVED_KW
+                | SyntaxKind::END_KW
+                | SyntaxKind::CONST_KW
+        )
 }
 
-fn determine_context(
-    _index: &SymbolIndex,
-    _file: FileId,
-    _line: u32,
-    _col: u32,
-    trigger: Option<char>,
-) -> CompletionContext {
-    // Simple heuristic based on trigger character
-    match trigger {
-        Some(':') => CompletionContext::TypeReference,
-        Some('.') => CompletionContext::MemberAccess(String::new()),
-        _ => CompletionContext::General,
-    }
+fn is_visibility(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::PUBLIC_KW | SyntaxKind::PRIVATE_KW | SyntaxKind::PROTECTED_KW
+    )
 }
 
-/// Get keyword completions.
-fn keyword_completions() -> Vec<CompletionItem> {
-    let keywords = [
-        ("part def", "part def ${1:Name} {\n\t$0\n}"),
-        ("part", "part ${1:name} : ${2:Type};"),
-        ("action def", "action def ${1:Name} {\n\t$0\n}"),
-        ("action", "action ${1:name};"),
-        ("item def", "item def ${1:Name} {\n\t$0\n}"),
-        ("item", "item ${1:name} : ${2:Type};"),
-        ("port def", "port def ${1:Name} {\n\t$0\n}"),
-        ("port", "port ${1:name} : ${2:Type};"),
-        ("attribute", "attribute ${1:name} : ${2:Type};"),
-        ("requirement def", "requirement def ${1:Name} {\n\t$0\n}"),
-        ("requirement", "requirement ${1:name} {\n\t$0\n}"),
-        ("package", "package ${1:Name} {\n\t$0\n}"),
-        ("import", "import ${1:path}::*;"),
-        ("specializes", ":> ${1:Type}"),
-        ("redefines", ":>> ${1:name}"),
-        ("subsets", ":> ${1:name}"),
-    ];
-
-    keywords
-        .iter()
-        .enumerate()
-        .map(|(i, (label, snippet))| {
-            CompletionItem::new(*label, CompletionKind::Keyword)
-                .with_insert_text(*snippet)
-                .with_priority(i as u32)
-        })
-        .collect()
+/// Whether elements can be named through a symbol of `kind`.
+fn is_namespace(kind: SymbolKind) -> bool {
+    matches!(kind, SymbolKind::Package | SymbolKind::Alias)
+}
+
+/// Declarations valid in any namespace body, with their snippets.
+const MEMBER_SNIPPETS: &[(&str, &str)] = &[
+    ("part def", "part def ${1:Name} {\n\t$0\n}"),
+    ("part", "part ${1:name} : ${2:Type};"),
+    ("attribute def", "attribute def ${1:Name};"),
+    ("attribute", "attribute ${1:name} : ${2:Type};"),
+    ("item def", "item def ${1:Name} {\n\t$0\n}"),
+    ("item", "item ${1:name} : ${2:Type};"),
+    ("port def", "port def ${1:Name} {\n\t$0\n}"),
+    ("port", "port ${1:name} : ${2:Type};"),
+    ("action def", "action def ${1:Name} {\n\t$0\n}"),
+    ("action", "action ${1:name};"),
+    ("state def", "state def ${1:Name} {\n\t$0\n}"),
+    ("state", "state ${1:name};"),
+    ("calc def", "calc def ${1:Name} {\n\t$0\n}"),
+    ("constraint def", "constraint def ${1:Name} {\n\t$0\n}"),
+    ("requirement def", "requirement def ${1:Name} {\n\t$0\n}"),
+    ("requirement", "requirement ${1:name} {\n\t$0\n}"),
+    ("connection def", "connection def ${1:Name} {\n\t$0\n}"),
+    ("interface def", "interface def ${1:Name} {\n\t$0\n}"),
+    ("enum def", "enum def ${1:Name} {\n\t$0\n}"),
+];
+
+/// Declarations that can't follow prefixes like `abstract`, with their snippets.
+const UNPREFIXED_SNIPPETS: &[(&str, &str)] = &[
+    ("import", "import ${1:path}::*;"),
+    ("alias", "alias ${1:Name} for ${2:Target};"),
+    ("doc", "doc /* $0 */"),
+    ("comment", "comment /* $0 */"),
+];
+
+/// Keywords completing the declaration of a definition or usage.
+const DEFINITION_HEADER_SNIPPETS: &[(&str, &str)] = &[("specializes", "specializes ${1:Type}")];
+const USAGE_HEADER_SNIPPETS: &[(&str, &str)] = &[
+    ("defined by", "defined by ${1:Type}"),
+    ("subsets", "subsets ${1:feature}"),
+    ("redefines", "redefines ${1:feature}"),
+    ("references", "references ${1:feature}"),
+];
+
+/// Get the keywords valid at `place`, as snippets where they start a declaration.
+fn keyword_completions(place: Place) -> Vec<CompletionItem> {
+    let snippet = |(label, snippet): &(&str, &str)| {
+        CompletionItem::new(*label, CompletionKind::Snippet)
+            .with_insert_text(*snippet)
+            .with_priority(KEYWORD_PRIORITY)
+    };
+    let keyword = |label: &&str| {
+        CompletionItem::new(*label, CompletionKind::Keyword).with_priority(KEYWORD_PRIORITY)
+    };
+
+    match place {
+        Place::Member {
+            in_package,
+            after_prefix,
+        } => {
+            let mut items: Vec<_> = MEMBER_SNIPPETS.iter().map(snippet).collect();
+            if !after_prefix {
+                if in_package {
+                    items.push(snippet(&("package", "package ${1:Name} {\n\t$0\n}")));
+                }
+                items.extend(UNPREFIXED_SNIPPETS.iter().map(snippet));
+                items.extend(["public", "private"].iter().map(keyword));
+            }
+            items.extend(["abstract", "variation"].iter().map(keyword));
+            if !in_package {
+                items.extend(["in", "out", "inout", "ref"].iter().map(keyword));
+            }
+            items
+        }
+        Place::DefinitionHeader => DEFINITION_HEADER_SNIPPETS.iter().map(snippet).collect(),
+        Place::UsageHeader => USAGE_HEADER_SNIPPETS.iter().map(snippet).collect(),
+        Place::Other => Vec::new(),
+    }
 }
 
 #[cfg(test)]
@@ -284,6 +997,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -306,10 +1020,22 @@ mod tests {
 
     #[test]
     fn test_keyword_completions() {
-        let keywords = keyword_completions();
+        let keywords = keyword_completions(Place::Member {
+            in_package: true,
+            after_prefix: false,
+        });
         assert!(!keywords.is_empty());
         assert!(keywords.iter().any(|k| k.label.as_ref() == "part def"));
         assert!(keywords.iter().any(|k| k.label.as_ref() == "package"));
+
+        // Packages can't be declared in definitions, nor imported after `abstract`
+        let keywords = keyword_completions(Place::Member {
+            in_package: false,
+            after_prefix: true,
+        });
+        assert!(keywords.iter().any(|k| k.label.as_ref() == "in"));
+        assert!(!keywords.iter().any(|k| k.label.as_ref() == "package"));
+        assert!(!keywords.iter().any(|k| k.label.as_ref() == "import"));
     }
 
     #[test]
@@ -2,9 +2,14 @@
 
 use std::sync::Arc;
 
-use crate::base::FileId;
-use crate::hir::{HirRelationship, HirSymbol, RelationshipKind, SymbolIndex, SymbolKind};
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId};
+use crate::hir::{
+    Evaluator, HirRelationship, HirSymbol, RelationshipKind, SymbolIndex, SymbolKind,
+};
 use crate::ide::type_info::{find_type_ref_at_position, resolve_type_ref_with_chain};
+use crate::parser::ValueExpression;
 
 /// A resolved relationship with target location info for building links.
 #[derive(Clone, Debug)]
@@ -107,13 +112,31 @@ fn resolve_relationships(
 /// # Returns
 /// Hover information, or None if nothing to show.
 pub fn hover(index: &SymbolIndex, file: FileId, line: u32, col: u32) -> Option<HoverResult> {
+    hover_cancellable(index, file, line, col, &CancellationToken::new()).unwrap_or(None)
+}
+
+/// Get hover information for a position, giving up as soon as `cancel` is
+/// signalled.
+///
+/// Same as [`hover()`], but returns `Err(Cancelled)` instead of finishing the
+/// evaluation of the symbol's value once the token is cancelled.
+pub fn hover_cancellable(
+    index: &SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Cancellable<Option<HoverResult>> {
+    Cancelled::check(cancel)?;
+
     // First, check if cursor is on a type reference (e.g., ::>, :, :>)
     if let Some(ctx) = find_type_ref_at_position(index, file, line, col) {
         // Try to resolve and show hover for the target type
         if let Some(target_symbol) = resolve_type_ref_with_chain(index, &ctx) {
-            let contents = build_hover_content(&target_symbol, index);
+            let contents = build_hover_content(&target_symbol, index, cancel);
+            Cancelled::check(cancel)?;
             // Return with the type_ref's span (where the cursor is)
-            return Some(HoverResult {
+            return Ok(Some(HoverResult {
                 contents,
                 qualified_name: Some(target_symbol.qualified_name.clone()),
                 is_definition: target_symbol.kind.is_definition(),
@@ -122,7 +145,7 @@ pub fn hover(index: &SymbolIndex, file: FileId, line: u32, col: u32) -> Option<H
                 start_col: ctx.type_ref.start_col,
                 end_line: ctx.type_ref.end_line,
                 end_col: ctx.type_ref.end_col,
-            });
+            }));
         } else {
             // Type reference found but couldn't be resolved - show unresolved message
             // This happens when the referenced symbol is not visible (e.g., import was removed)
@@ -131,7 +154,7 @@ pub fn hover(index: &SymbolIndex, file: FileId, line: u32, col: u32) -> Option<H
                  You may need to add an import statement.",
                 ctx.target_name, ctx.target_name
             );
-            return Some(HoverResult {
+            return Ok(Some(HoverResult {
                 contents,
                 qualified_name: None,
                 is_definition: false,
@@ -140,21 +163,28 @@ pub fn hover(index: &SymbolIndex, file: FileId, line: u32, col: u32) -> Option<H
                 start_col: ctx.type_ref.start_col,
                 end_line: ctx.type_ref.end_line,
                 end_col: ctx.type_ref.end_col,
-            });
+            }));
         }
     }
 
     // Otherwise, find the symbol at the cursor position
-    let symbol = find_symbol_at_position(index, file, line, col)?;
+    let Some(symbol) = find_symbol_at_position(index, file, line, col) else {
+        return Ok(None);
+    };
 
     // Build hover content
-    let contents = build_hover_content(symbol, index);
+    let contents = build_hover_content(symbol, index, cancel);
+    Cancelled::check(cancel)?;
 
-    Some(HoverResult::new(contents, symbol, index))
+    Ok(Some(HoverResult::new(contents, symbol, index)))
 }
 
 /// Build markdown hover content for a symbol.
-fn build_hover_content(symbol: &HirSymbol, _index: &SymbolIndex) -> String {
+fn build_hover_content(
+    symbol: &HirSymbol,
+    index: &SymbolIndex,
+    cancel: &CancellationToken,
+) -> String {
     let mut content = String::new();
 
     // Symbol signature
@@ -169,6 +199,18 @@ fn build_hover_content(symbol: &HirSymbol, _index: &SymbolIndex) -> String {
         content.push('\n');
     }
 
+    // Computed value of a non-literal value expression (e.g. `= 2 * wheelMass + 10`)
+    if let Some(ValueExpression::Expression { .. }) = symbol.value {
+        if let Some(value) = Evaluator::new(index)
+            .with_cancellation(cancel)
+            .evaluate_symbol(symbol)
+        {
+            content.push_str("\n**Value:** `");
+            content.push_str(&value.to_string());
+            content.push_str("`\n");
+        }
+    }
+
     // Note: Relationships are formatted at the LSP layer with clickable links.
 
     // Qualified name for context
@@ -394,6 +436,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -439,6 +482,44 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_hover_shows_computed_value() {
+        use crate::parser::{BinaryOp, Expr, Literal};
+
+        let mut index = SymbolIndex::new();
+        let mut wheel_mass =
+            make_symbol("wheelMass", "Car::wheelMass", SymbolKind::AttributeUsage, 2);
+        wheel_mass.value = Some(ValueExpression::LiteralInteger(5));
+        let mut mass = make_symbol("mass", "Car::mass", SymbolKind::AttributeUsage, 3);
+        mass.value = Some(ValueExpression::Expression {
+            text: "2 * wheelMass".to_string(),
+            expr: Expr::Binary {
+                op: BinaryOp::Mul,
+                lhs: Box::new(Expr::Literal(Literal::Integer(2))),
+                rhs: Box::new(Expr::Name("wheelMass".to_string())),
+            },
+        });
+        index.add_file(FileId::new(0), vec![wheel_mass, mass]);
+        index.ensure_visibility_maps();
+
+        let hover = hover(&index, FileId::new(0), 3, 5).unwrap();
+        assert!(hover.contents.contains("**Value:** `10`"));
+    }
+
+    #[test]
+    fn test_hover_cancelled() {
+        let mut index = SymbolIndex::new();
+        let def = make_symbol("Car", "Vehicle::Car", SymbolKind::PartDefinition, 5);
+        index.add_file(FileId::new(0), vec![def]);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert!(matches!(
+            hover_cancellable(&index, FileId::new(0), 5, 5, &cancel),
+            Err(Cancelled)
+        ));
+    }
+
     #[test]
     fn test_build_signature_package() {
         let symbol = make_symbol("Vehicle", "Vehicle", SymbolKind::Package, 0);
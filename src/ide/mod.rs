@@ -20,33 +20,48 @@
 //! host.set_file_content("test.sysml", "package Test {}");
 //!
 //! let analysis = host.analysis();
-//! let symbols = analysis.document_symbols(file_id);
+//! let symbols = analysis.document_symbols(file_id).unwrap();
 //! ```
 
 mod analysis;
+mod code_actions;
 mod completion;
 mod document_links;
 mod folding;
 mod goto;
 mod hover;
 mod inlay_hints;
+mod refactor;
 mod references;
+mod rename;
 mod selection;
 mod semantic_tokens;
+mod signature_help;
 mod symbols;
 pub mod text_utils;
+mod type_hierarchy;
 mod type_info;
 
+pub use crate::base::{Cancellable, Cancelled};
+#[cfg(feature = "cache")]
+pub use analysis::IndexSnapshot;
 pub use analysis::{Analysis, AnalysisHost};
-pub use completion::{CompletionItem, CompletionKind, completions};
+pub use code_actions::{CodeAction, CodeActionData, CodeActionKind, quick_fixes};
+pub use completion::{
+    CompletionItem, CompletionKind, completions, completions_cancellable, scoped_completions,
+};
 pub use document_links::{DocumentLink, document_links};
 pub use folding::{FoldingRange, folding_ranges};
 pub use goto::{GotoResult, GotoTarget, goto_definition, goto_type_definition};
-pub use hover::{HoverResult, ResolvedRelationship, hover};
+pub use hover::{HoverResult, ResolvedRelationship, hover, hover_cancellable};
 pub use inlay_hints::{InlayHint, InlayHintKind, inlay_hints};
-pub use references::{Reference, ReferenceResult, find_references};
+pub use refactor::{refactorings, resolve_refactoring};
+pub use references::{Reference, ReferenceResult, find_references, find_references_cancellable};
+pub use rename::{RenameError, RenameTarget, prepare_rename, rename};
 pub use selection::{SelectionRange, selection_ranges};
-pub use semantic_tokens::{SemanticToken, TokenType, semantic_tokens};
-pub use symbols::{SymbolInfo, document_symbols, workspace_symbols};
+pub use semantic_tokens::{SemanticToken, TokenType, semantic_tokens, semantic_tokens_cancellable};
+pub use signature_help::{ParameterInfo, SignatureHelp, signature_help};
+pub use symbols::{SymbolInfo, document_symbols, workspace_symbols, workspace_symbols_cancellable};
 pub use text_utils::{extract_qualified_name_at_cursor, extract_word_at_cursor};
+pub use type_hierarchy::{TypeHierarchyItem, type_hierarchy_subtypes, type_hierarchy_supertypes};
 pub use type_info::{TypeInfo, find_type_ref_at_position, resolve_type_ref, type_info_at};
@@ -0,0 +1,1037 @@
+//! Refactorings — structural code actions built on the syntax tree.
+//!
+//! At a usage:
+//! - extract its body into a new definition typing it;
+//! - inline the definition typing it, if nothing else uses that definition;
+//! - convert it into a definition specializing its types;
+//! - name it, if it's anonymous.
+//!
+//! A definition can be moved into another package of its file, or of the
+//! workspace if the client names one (library packages aside), with the
+//! references to it rewritten. A selection of package members can be
+//! wrapped in a new package.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use super::rename::{Sources, parent_scope, parse_name, qualify, reference_scope, strip_quotes};
+use super::{CodeAction, CodeActionData, CodeActionKind};
+use crate::base::{
+    Cancellable, Cancelled, FileId, LineCol, LineIndex, TextEdit, TextRange, TextSize,
+};
+use crate::hir::{HirSymbol, RefKind, ResolveResult, Resolver, SymbolIndex, TypeRef};
+use crate::parser::{
+    AstNode, Definition, NamespaceMember, SpecializationKind, SyntaxKind, SyntaxNode, Usage,
+    UsageKind, declaration_range, declaration_removal_range, member_at, trimmed_range,
+};
+use crate::syntax::SyntaxFile;
+
+/// Indentation of one nesting level in generated code.
+const INDENT: &str = "    ";
+
+/// Name of a package created by wrapping a selection.
+const NEW_PACKAGE: &str = "NewPackage";
+
+/// How many symbols a workspace scan visits between cancellation checks.
+const CANCEL_CHECK_INTERVAL: usize = 256;
+
+/// Get the refactorings offered at a range (start_line, start_col, end_line, end_col)
+/// of `file`, giving up as soon as `cancel` is signalled.
+///
+/// Returns `Err(Cancelled)` if the token was cancelled before the workspace
+/// scans the refactorings depend on finished.
+pub fn refactorings(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    range: (u32, u32, u32, u32),
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<CodeAction>> {
+    Cancelled::check(cancel)?;
+    let Some(source) = Source::new(syntax_files, file) else {
+        return Ok(Vec::new());
+    };
+    let (start_line, start_col, end_line, end_col) = range;
+    let (Some(start), Some(end)) = (
+        source.offset(start_line, start_col),
+        source.offset(end_line, end_col),
+    ) else {
+        return Ok(Vec::new());
+    };
+    let refactor = Refactor {
+        index,
+        syntax_files,
+        source,
+        cancel,
+    };
+
+    let mut actions = Vec::new();
+    if let Some(member) = member_at(&refactor.source.root, start) {
+        if let Some(usage) = Usage::cast(member.clone()) {
+            actions.extend(refactor.extract_definition(&usage));
+            actions.extend(refactor.inline_definition(&usage));
+            actions.extend(refactor.convert_to_definition(&usage));
+            actions.extend(refactor.name_anonymous_usage(&usage));
+        } else if let Some(definition) = Definition::cast(member) {
+            actions.extend(refactor.move_definition(&definition));
+        }
+    }
+    if start < end {
+        actions.extend(refactor.wrap_in_package(TextRange::new(start, end)));
+    }
+    // A cancelled scan stops early, so what it found can't be trusted
+    Cancelled::check(cancel)?;
+    Ok(actions)
+}
+
+/// Build the edits of a refactoring offered without them, from its `data`,
+/// giving up as soon as `cancel` is signalled. `None` if it no longer applies.
+pub fn resolve_refactoring(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    data: &CodeActionData,
+    cancel: &CancellationToken,
+) -> Cancellable<Option<CodeAction>> {
+    Cancelled::check(cancel)?;
+    let action = resolve(index, syntax_files, data, cancel);
+    Cancelled::check(cancel)?;
+    Ok(action)
+}
+
+fn resolve(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    data: &CodeActionData,
+    cancel: &CancellationToken,
+) -> Option<CodeAction> {
+    match data {
+        CodeActionData::MoveDefinition {
+            file,
+            line,
+            col,
+            package,
+        } => {
+            let source = Source::new(syntax_files, *file)?;
+            let definition =
+                Definition::cast(member_at(&source.root, source.offset(*line, *col)?)?)?;
+            let refactor = Refactor {
+                index,
+                syntax_files,
+                source,
+                cancel,
+            };
+            refactor.move_to_package(&definition, package)
+        }
+    }
+}
+
+/// A parsed file with its text.
+struct Source {
+    file: FileId,
+    root: SyntaxNode,
+    text: String,
+    line_index: LineIndex,
+}
+
+impl Source {
+    fn new(syntax_files: &HashMap<FileId, Arc<SyntaxFile>>, file: FileId) -> Option<Self> {
+        let syntax = syntax_files.get(&file)?;
+        let root = syntax.parse().syntax();
+        let text = syntax.source_text();
+        let line_index = LineIndex::new(&text);
+        Some(Self {
+            file,
+            root,
+            text,
+            line_index,
+        })
+    }
+
+    fn position(&self, offset: TextSize) -> (u32, u32) {
+        let LineCol { line, col } = self.line_index.line_col(offset);
+        (line, col)
+    }
+
+    fn offset(&self, line: u32, col: u32) -> Option<TextSize> {
+        let offset = self.line_index.offset(LineCol::new(line, col))?;
+        (offset <= self.root.text_range().end()).then_some(offset)
+    }
+
+    fn text_at(&self, range: TextRange) -> &str {
+        &self.text[range]
+    }
+
+    fn replace(&self, range: TextRange, new_text: impl Into<Arc<str>>) -> TextEdit {
+        TextEdit::replace(
+            self.file,
+            self.position(range.start()),
+            self.position(range.end()),
+            new_text,
+        )
+    }
+
+    /// The whitespace at the start of the line of `offset`.
+    fn line_indent(&self, offset: TextSize) -> &str {
+        let offset = usize::from(offset);
+        let line_start = self.text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.text[line_start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// Whether only whitespace precedes `offset` on its line.
+    fn starts_line(&self, offset: TextSize) -> bool {
+        let offset = usize::from(offset);
+        let line_start = self.text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        self.text[line_start..offset]
+            .chars()
+            .all(char::is_whitespace)
+    }
+
+    /// The member node declaring `symbol`.
+    fn declaration_of(&self, symbol: &HirSymbol) -> Option<SyntaxNode> {
+        let offset = self.offset(symbol.start_line, symbol.start_col)?;
+        member_at(&self.root, offset)
+    }
+}
+
+struct Refactor<'a> {
+    index: &'a SymbolIndex,
+    syntax_files: &'a HashMap<FileId, Arc<SyntaxFile>>,
+    source: Source,
+    cancel: &'a CancellationToken,
+}
+
+impl<'a> Refactor<'a> {
+    /// Extract the body of a usage into a new definition, placed before the
+    /// outermost member containing the usage.
+    fn extract_definition(&self, usage: &Usage) -> Option<CodeAction> {
+        let source = &self.source;
+        let name = usage.name()?;
+        let keyword = definition_keyword(usage.usage_kind()?)?;
+        let body = usage.body()?;
+        body.members().next()?;
+        let typing = usage.typing();
+        if typing.as_ref().is_some_and(|typing| typing.is_conjugated()) {
+            return None;
+        }
+
+        let anchor = usage
+            .syntax()
+            .ancestors()
+            .filter(|node| NamespaceMember::can_cast(node.kind()))
+            .take_while(|node| !is_package(node))
+            .last()?;
+        let scope = self.scope_of(&anchor);
+        let base_name = upper_first(strip_quotes(&name.text()?));
+        let definition_name = display_name(&self.unique_name(&scope, &base_name));
+
+        let anchor_start = declaration_range(&anchor).start();
+        let indent = source.line_indent(anchor_start);
+        let body_text = reindent(
+            source.text_at(trimmed_range(body.syntax())),
+            source.line_indent(trimmed_range(usage.syntax()).start()),
+            indent,
+        );
+        let supertypes = typing
+            .as_ref()
+            .map(|typing| {
+                let targets: Vec<_> = typing
+                    .targets()
+                    .map(|target| source.text_at(trimmed_range(target.syntax())))
+                    .collect();
+                format!(" :> {}", targets.join(", "))
+            })
+            .unwrap_or_default();
+        let definition = format!("{keyword} def {definition_name}{supertypes} {body_text}");
+        let definition = if source.starts_line(anchor_start) {
+            format!("{definition}\n\n{indent}")
+        } else {
+            format!("{definition} ")
+        };
+
+        let mut edits = vec![source.replace(TextRange::empty(anchor_start), definition)];
+        let body_start = previous_end(body.syntax())?;
+        let body_range = TextRange::new(body_start, trimmed_range(body.syntax()).end());
+        match typing
+            .as_ref()
+            .and_then(|typing| targets_range(typing.syntax()))
+        {
+            Some(targets) => {
+                edits.push(source.replace(targets, definition_name.clone()));
+                edits.push(source.replace(body_range, ";"));
+            }
+            None if body_start == trimmed_range(name.syntax()).end() => {
+                edits.push(source.replace(body_range, format!(" : {definition_name};")));
+            }
+            None => {
+                let name_end = TextRange::empty(trimmed_range(name.syntax()).end());
+                edits.push(source.replace(name_end, format!(" : {definition_name}")));
+                edits.push(source.replace(body_range, ";"));
+            }
+        }
+
+        Some(refactoring(
+            format!("extract definition `{definition_name}`"),
+            CodeActionKind::RefactorExtract,
+            edits,
+        ))
+    }
+
+    /// Replace a usage's type by the body and supertypes of its definition,
+    /// and remove the definition, if this usage is its only user.
+    fn inline_definition(&self, usage: &Usage) -> Option<CodeAction> {
+        let source = &self.source;
+        let keyword = definition_keyword(usage.usage_kind()?)?;
+        let typing = usage.typing()?;
+        if typing.is_conjugated() || typing.targets().count() != 1 {
+            return None;
+        }
+        let body = usage.body()?;
+        if body.syntax().first_token()?.kind() != SyntaxKind::SEMICOLON {
+            return None;
+        }
+
+        let symbol = self.symbol_of(usage.syntax())?;
+        let target = symbol
+            .type_refs
+            .iter()
+            .flat_map(|type_ref_kind| type_ref_kind.as_refs())
+            .find(|type_ref| type_ref.kind == RefKind::TypedBy)?
+            .resolved_target
+            .clone()?;
+        let definition = self.index.lookup_qualified(&target)?;
+        if !definition.kind.is_definition() || definition.is_abstract {
+            return None;
+        }
+        let uses = self
+            .references(&definition.qualified_name)
+            .filter(|(user, _)| !within(&user.qualified_name, &definition.qualified_name))
+            .count();
+        if uses != 1 {
+            return None;
+        }
+
+        let other_source;
+        let definition_source = if definition.file == source.file {
+            source
+        } else {
+            other_source = Source::new(self.syntax_files, definition.file)?;
+            &other_source
+        };
+        let node = definition_source.declaration_of(definition)?;
+        if node
+            .text_range()
+            .contains_range(usage.syntax().text_range())
+            && definition.file == source.file
+        {
+            return None;
+        }
+        let definition_node = Definition::cast(node.clone())?;
+        if definition_keywords(&node) != keyword {
+            return None;
+        }
+        let mut supertypes = Vec::new();
+        for specialization in definition_node.specializations() {
+            if !matches!(
+                specialization.kind(),
+                None | Some(SpecializationKind::Specializes)
+            ) {
+                return None;
+            }
+            let target = specialization.target()?;
+            supertypes.push(definition_source.text_at(trimmed_range(target.syntax())));
+        }
+
+        let targets = targets_range(typing.syntax())?;
+        let mut edits = vec![if supertypes.is_empty() {
+            // Drop `: T`, keeping the multiplicity
+            source.replace(
+                TextRange::new(previous_end(typing.syntax())?, targets.end()),
+                "",
+            )
+        } else {
+            source.replace(targets, supertypes.join(", "))
+        }];
+        let definition_body = definition_node.body()?;
+        if definition_body.syntax().first_token()?.kind() == SyntaxKind::L_BRACE {
+            let body_text = reindent(
+                definition_source.text_at(trimmed_range(definition_body.syntax())),
+                definition_source.line_indent(node.text_range().start()),
+                source.line_indent(trimmed_range(usage.syntax()).start()),
+            );
+            edits.push(source.replace(
+                TextRange::new(
+                    previous_end(body.syntax())?,
+                    trimmed_range(body.syntax()).end(),
+                ),
+                format!(" {body_text}"),
+            ));
+        }
+        edits.push(definition_source.replace(
+            declaration_removal_range(&node, &definition_source.text),
+            "",
+        ));
+
+        Some(refactoring(
+            format!("inline definition `{}`", definition.name),
+            CodeActionKind::RefactorInline,
+            edits,
+        ))
+    }
+
+    /// Turn `part x : T { ... }` into `part def x :> T { ... }`, if nothing
+    /// refers to the usage.
+    fn convert_to_definition(&self, usage: &Usage) -> Option<CodeAction> {
+        let source = &self.source;
+        let name = usage.name()?;
+        let keyword = definition_keyword(usage.usage_kind()?)?;
+        let typing = usage.typing()?;
+        if typing.is_conjugated()
+            || typing
+                .syntax()
+                .children()
+                .any(|child| child.kind() == SyntaxKind::MULTIPLICITY)
+        {
+            return None;
+        }
+        // Only prefixes definitions have too
+        let mut keyword_end = None;
+        for element in usage.syntax().children_with_tokens() {
+            match element.kind() {
+                kind if kind.is_trivia() => {}
+                SyntaxKind::NAME | SyntaxKind::TYPING | SyntaxKind::NAMESPACE_BODY => {}
+                SyntaxKind::ABSTRACT_KW | SyntaxKind::VARIATION_KW | SyntaxKind::INDIVIDUAL_KW => {}
+                kind if kind.is_keyword()
+                    && keyword.split(' ').any(|k| k == element_text(&element)) =>
+                {
+                    keyword_end = Some(element.text_range().end());
+                }
+                _ => return None,
+            }
+        }
+        if definition_keywords(usage.syntax()) != keyword {
+            return None;
+        }
+
+        let symbol = self.symbol_of(usage.syntax())?;
+        if self
+            .references(&symbol.qualified_name)
+            .any(|(user, _)| !within(&user.qualified_name, &symbol.qualified_name))
+        {
+            return None;
+        }
+        let colon = typing
+            .syntax()
+            .children_with_tokens()
+            .find(|element| element.kind() == SyntaxKind::COLON)?;
+        let targets = source.text_at(targets_range(typing.syntax())?);
+
+        Some(refactoring(
+            format!(
+                "convert `{}` to a definition specializing `{}`",
+                strip_quotes(&name.text()?),
+                targets
+            ),
+            CodeActionKind::RefactorRewrite,
+            vec![
+                source.replace(TextRange::empty(keyword_end?), " def"),
+                source.replace(colon.text_range(), ":>"),
+            ],
+        ))
+    }
+
+    /// Give an anonymous usage a name, derived from its type.
+    fn name_anonymous_usage(&self, usage: &Usage) -> Option<CodeAction> {
+        let source = &self.source;
+        if usage.name().is_some()
+            || usage
+                .specializations()
+                .any(|specialization| specialization.kind() == Some(SpecializationKind::Redefines))
+        {
+            return None;
+        }
+        // The name goes after the keywords
+        let mut keyword = None;
+        let mut insert_at = None;
+        for element in usage.syntax().children_with_tokens() {
+            if element.kind().is_trivia() {
+                continue;
+            }
+            if element.kind().is_keyword() {
+                keyword = Some(element_text(&element).to_string());
+                continue;
+            }
+            insert_at = Some(element.text_range().start());
+            break;
+        }
+        let (keyword, insert_at) = (keyword?, insert_at?);
+
+        let type_name = usage
+            .typing()
+            .and_then(|typing| typing.target())
+            .or_else(|| {
+                usage
+                    .specializations()
+                    .find_map(|specialization| specialization.target())
+            })
+            .and_then(|target| target.segments().pop());
+        let base_name = match &type_name {
+            Some(type_name) => lower_first(strip_quotes(type_name)),
+            None => keyword,
+        };
+        let name = display_name(&self.unique_name(&self.scope_of(usage.syntax()), &base_name));
+
+        Some(refactoring(
+            format!("name usage `{name}`"),
+            CodeActionKind::RefactorRewrite,
+            vec![source.replace(TextRange::empty(insert_at), format!("{name} "))],
+        ))
+    }
+
+    /// Offer to move a definition into each other package of its file; its
+    /// edits are built when the action is resolved.
+    fn move_definition(&self, definition: &Definition) -> Vec<CodeAction> {
+        let node = definition.syntax();
+        let Some((symbol, parent)) = self.movable(node) else {
+            return Vec::new();
+        };
+        user_packages(&self.source.root)
+            .into_iter()
+            .filter_map(|package| {
+                let package_symbol = self.move_target(symbol, &parent, &self.source, &package)?;
+                Some(CodeAction {
+                    data: Some(CodeActionData::MoveDefinition {
+                        file: self.source.file,
+                        line: symbol.start_line,
+                        col: symbol.start_col,
+                        package: package_symbol.qualified_name.clone(),
+                    }),
+                    ..refactoring(
+                        format!(
+                            "move `{}` to `{}`",
+                            symbol.name, package_symbol.qualified_name
+                        ),
+                        CodeActionKind::RefactorMove,
+                        Vec::new(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Move `definition` to the end of the package named `package`, with
+    /// the references to it rewritten.
+    fn move_to_package(&self, definition: &Definition, package: &str) -> Option<CodeAction> {
+        let node = definition.syntax();
+        let (symbol, parent) = self.movable(node)?;
+        let package_symbol = self.index.lookup_qualified(package)?;
+        if package_symbol.kind != crate::hir::SymbolKind::Package
+            || self.index.is_kerml_file(package_symbol.file)
+        {
+            return None;
+        }
+        let other_source;
+        let target = if package_symbol.file == self.source.file {
+            &self.source
+        } else {
+            other_source = Source::new(self.syntax_files, package_symbol.file)?;
+            &other_source
+        };
+        let package_node = target
+            .declaration_of(package_symbol)
+            .filter(|node| user_packages(&target.root).contains(node))?;
+        self.move_target(symbol, &parent, target, &package_node)?;
+
+        // The references the move may have to rewrite
+        let moved = &symbol.qualified_name;
+        let refs: Vec<_> = self
+            .type_refs()
+            .filter(|(user, type_ref, _)| {
+                within(&user.qualified_name, moved) || within(type_ref.effective_target(), moved)
+            })
+            .collect();
+        let mut sources = Sources::new(self.syntax_files);
+        self.move_to(
+            &mut sources,
+            &refs,
+            node,
+            symbol,
+            target,
+            &package_node,
+            &package_symbol.qualified_name,
+        )
+    }
+
+    /// The symbol of a definition that can be moved, with the package (or
+    /// file) it's declared in.
+    fn movable(&self, node: &SyntaxNode) -> Option<(&'a HirSymbol, SyntaxNode)> {
+        let symbol = self.symbol_of(node)?;
+        let parent = node.parent().and_then(|body| match body.kind() {
+            SyntaxKind::SOURCE_FILE => Some(body),
+            _ => body.parent().filter(is_package),
+        })?;
+        Some((symbol, parent))
+    }
+
+    /// The symbol of `package`, in `target`, if `symbol` declared in
+    /// `parent` can move there: it's another package, without a member of
+    /// the same name.
+    fn move_target(
+        &self,
+        symbol: &HirSymbol,
+        parent: &SyntaxNode,
+        target: &Source,
+        package: &SyntaxNode,
+    ) -> Option<&'a HirSymbol> {
+        if package == parent {
+            return None;
+        }
+        let package_symbol = self.symbol_in(target, package)?;
+        let taken = self
+            .index
+            .lookup_qualified(&qualify(&package_symbol.qualified_name, &symbol.name))
+            .is_some();
+        (!taken).then_some(package_symbol)
+    }
+
+    /// Move `node`, declaring `symbol`, to the end of `package` in `target`.
+    #[allow(clippy::too_many_arguments)]
+    fn move_to(
+        &self,
+        sources: &mut Sources<'_>,
+        refs: &[(&'a HirSymbol, &'a TypeRef, usize)],
+        node: &SyntaxNode,
+        symbol: &HirSymbol,
+        target: &Source,
+        package: &SyntaxNode,
+        package_name: &str,
+    ) -> Option<CodeAction> {
+        let source = &self.source;
+        let moved = &symbol.qualified_name;
+        let mut edited = HashSet::new();
+        let mut edits = Vec::new();
+        // Edits to the moved text, as (range, new text)
+        let mut inner_edits = Vec::new();
+
+        for &(user, type_ref, part) in refs {
+            if within(&user.qualified_name, moved) {
+                // References out of the definition must still resolve from the package
+                if part != 0 || user.file != source.file {
+                    continue;
+                }
+                let Some(resolved) = &type_ref.resolved_target else {
+                    continue;
+                };
+                if within(resolved, moved) {
+                    continue;
+                }
+                let segments = sources.names(user.file, type_ref);
+                let Some(first) = segments.first() else {
+                    continue;
+                };
+                let resolved: Vec<_> = resolved.split("::").collect();
+                let Some(len) = (resolved.len() + 1).checked_sub(segments.len()) else {
+                    continue;
+                };
+                let first_target = resolved[..len].join("::");
+                let still_resolves = matches!(
+                    Resolver::new(self.index).with_scope(package_name).resolve(&first.name),
+                    ResolveResult::Found(found) if *found.qualified_name == first_target
+                );
+                if !still_resolves && edited.insert((user.file, first.start)) {
+                    let range = TextRange::new(
+                        source.offset(first.start.0, first.start.1)?,
+                        source.offset(first.end.0, first.end.1)?,
+                    );
+                    inner_edits.push((range, display_qualified(&first_target)));
+                }
+            } else if let Some((i, segments)) = sources.segment_naming(user.file, type_ref, moved) {
+                let segment = &segments[i];
+                if segment.name != *symbol.name || !edited.insert((user.file, segment.start)) {
+                    continue;
+                }
+                let path = if within(reference_scope(&user.qualified_name), package_name) {
+                    display_name(&symbol.name)
+                } else {
+                    display_qualified(&qualify(package_name, &symbol.name))
+                };
+                edits.push(TextEdit::replace(
+                    user.file,
+                    segments[0].start,
+                    segment.end,
+                    path,
+                ));
+            }
+        }
+
+        // The definition's text, with its references fixed up
+        let range = declaration_range(node);
+        inner_edits.sort_by_key(|(range, _)| range.start());
+        let mut text = String::new();
+        let mut copied = range.start();
+        for (edit_range, new_text) in inner_edits {
+            if !range.contains_range(edit_range) || edit_range.start() < copied {
+                continue;
+            }
+            text.push_str(source.text_at(TextRange::new(copied, edit_range.start())));
+            text.push_str(&new_text);
+            copied = edit_range.end();
+        }
+        text.push_str(source.text_at(TextRange::new(copied, range.end())));
+
+        let package_indent = target.line_indent(package.text_range().start());
+        let member_indent = format!("{package_indent}{INDENT}");
+        let text = reindent(&text, source.line_indent(range.start()), &member_indent);
+        let closing = package
+            .children()
+            .find(|child| child.kind() == SyntaxKind::NAMESPACE_BODY)?
+            .last_token()
+            .filter(|token| token.kind() == SyntaxKind::R_BRACE)?;
+        let insert_from = closing
+            .siblings_with_tokens(rowan::Direction::Prev)
+            .skip(1)
+            .find(|element| !element.kind().is_trivia())?;
+        let insert_from = match insert_from {
+            rowan::NodeOrToken::Node(node) => trimmed_range(&node).end(),
+            rowan::NodeOrToken::Token(token) => token.text_range().end(),
+        };
+        edits.push(target.replace(
+            TextRange::new(insert_from, closing.text_range().start()),
+            format!("\n{member_indent}{text}\n{package_indent}"),
+        ));
+        edits.push(source.replace(declaration_removal_range(node, &source.text), ""));
+
+        Some(refactoring(
+            format!("move `{}` to `{}`", symbol.name, package_name),
+            CodeActionKind::RefactorMove,
+            edits,
+        ))
+    }
+
+    /// Wrap the members of a package (or the file) in `selection` in a new
+    /// package, imported where they were if they're used elsewhere.
+    fn wrap_in_package(&self, selection: TextRange) -> Option<CodeAction> {
+        let source = &self.source;
+        let container = match source.root.covering_element(selection) {
+            rowan::NodeOrToken::Node(node) => node,
+            rowan::NodeOrToken::Token(token) => token.parent()?,
+        }
+        .ancestors()
+        .find(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::NAMESPACE_BODY | SyntaxKind::SOURCE_FILE
+            )
+        })?;
+        let scope = match container.kind() {
+            SyntaxKind::SOURCE_FILE => Arc::from(""),
+            _ => self
+                .symbol_of(&container.parent().filter(is_package)?)?
+                .qualified_name
+                .clone(),
+        };
+        let members: Vec<_> = container
+            .children()
+            .filter(|child| NamespaceMember::can_cast(child.kind()))
+            .filter(|member| {
+                let range = declaration_range(member);
+                range.start() < selection.end() && selection.start() < range.end()
+            })
+            .collect();
+        let (first, last) = (members.first()?, members.last()?);
+
+        let mut start = declaration_range(first).start();
+        let end = trimmed_range(last).end();
+        let indent = source.line_indent(start).to_string();
+        let prefix = if source.starts_line(start) {
+            start -= TextSize::of(indent.as_str());
+            indent.as_str()
+        } else {
+            ""
+        };
+        let name = display_name(&self.unique_name(&scope, NEW_PACKAGE));
+        let body: Vec<_> = source
+            .text_at(TextRange::new(start, end))
+            .lines()
+            .map(|line| match line.trim() {
+                "" => String::new(),
+                _ => format!("{INDENT}{line}"),
+            })
+            .collect();
+        let mut text = format!("{prefix}package {name} {{\n{}\n{indent}}}", body.join("\n"));
+
+        // Keep the wrapped elements visible where they were
+        let range = TextRange::new(start, end);
+        let wrapped: Vec<_> = self
+            .index
+            .symbols_in_file(source.file)
+            .into_iter()
+            .filter(|symbol| *parent_scope(&symbol.qualified_name) == *scope)
+            .filter(|symbol| {
+                source
+                    .offset(symbol.start_line, symbol.start_col)
+                    .is_some_and(|offset| range.contains(offset))
+            })
+            .map(|symbol| symbol.qualified_name.clone())
+            .collect();
+        let used_elsewhere = self.type_refs().any(|(user, type_ref, _)| {
+            type_ref.resolved_target.as_ref().is_some_and(|target| {
+                wrapped.iter().any(|wrapped| within(target, wrapped))
+                    && !wrapped
+                        .iter()
+                        .any(|wrapped| within(&user.qualified_name, wrapped))
+            })
+        });
+        if used_elsewhere {
+            text.push_str(&format!("\n{indent}public import {name}::*;"));
+        }
+
+        Some(refactoring(
+            "wrap in package",
+            CodeActionKind::RefactorRewrite,
+            vec![source.replace(range, text)],
+        ))
+    }
+
+    /// The symbol declared by a member node, if it's named.
+    fn symbol_of(&self, node: &SyntaxNode) -> Option<&'a HirSymbol> {
+        self.symbol_in(&self.source, node)
+    }
+
+    /// The symbol declared by a member node of `source`, if it's named.
+    fn symbol_in(&self, source: &Source, node: &SyntaxNode) -> Option<&'a HirSymbol> {
+        let name = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::NAME)?;
+        let (line, col) = source.position(name.text_range().start());
+        self.index
+            .symbols_in_file(source.file)
+            .into_iter()
+            .find(|symbol| {
+                symbol.kind != crate::hir::SymbolKind::Import
+                    && (symbol.start_line, symbol.start_col) == (line, col)
+            })
+    }
+
+    /// Qualified name of the namespace `node` is declared in.
+    fn scope_of(&self, node: &SyntaxNode) -> Arc<str> {
+        node.ancestors()
+            .skip(1)
+            .filter(|ancestor| NamespaceMember::can_cast(ancestor.kind()))
+            .find_map(|ancestor| self.symbol_of(&ancestor))
+            .map_or_else(|| Arc::from(""), |symbol| symbol.qualified_name.clone())
+    }
+
+    /// `base`, or `base` with the first free number appended, to be unique in `scope`.
+    fn unique_name(&self, scope: &str, base: &str) -> String {
+        let taken = |name: &str| self.index.lookup_qualified(&qualify(scope, name)).is_some();
+        let mut name = base.to_string();
+        let mut n = 1;
+        while taken(&name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+
+    /// Every type reference, with its symbol and its index in its chain.
+    ///
+    /// Stops early once the cancellation token is signalled.
+    fn type_refs(&self) -> impl Iterator<Item = (&'a HirSymbol, &'a TypeRef, usize)> {
+        let cancel = self.cancel;
+        let symbols = self.index.all_symbols().enumerate();
+        symbols
+            .take_while(move |(i, _)| i % CANCEL_CHECK_INTERVAL != 0 || !cancel.is_cancelled())
+            .flat_map(|(_, symbol)| {
+                symbol.type_refs.iter().flat_map(move |type_ref_kind| {
+                    type_ref_kind
+                        .as_refs()
+                        .into_iter()
+                        .enumerate()
+                        .map(move |(part, type_ref)| (symbol, type_ref, part))
+                })
+            })
+    }
+
+    /// The references resolved to `qualified_name` or one of its members.
+    fn references(
+        &self,
+        qualified_name: &str,
+    ) -> impl Iterator<Item = (&'a HirSymbol, &'a TypeRef)> {
+        self.type_refs().filter_map(move |(symbol, type_ref, _)| {
+            within(type_ref.resolved_target.as_deref()?, qualified_name)
+                .then_some((symbol, type_ref))
+        })
+    }
+}
+
+fn refactoring(
+    title: impl Into<Arc<str>>,
+    kind: CodeActionKind,
+    edits: Vec<TextEdit>,
+) -> CodeAction {
+    CodeAction {
+        title: title.into(),
+        kind,
+        edits,
+        diagnostic: None,
+        is_preferred: false,
+        data: None,
+    }
+}
+
+/// The keywords declaring a definition of the usage kind, before `def`.
+fn definition_keyword(kind: UsageKind) -> Option<&'static str> {
+    Some(match kind {
+        UsageKind::Part => "part",
+        UsageKind::Attribute => "attribute",
+        UsageKind::Port => "port",
+        UsageKind::Item => "item",
+        UsageKind::Action => "action",
+        UsageKind::State => "state",
+        UsageKind::Constraint => "constraint",
+        UsageKind::Requirement => "requirement",
+        UsageKind::UseCase => "use case",
+        UsageKind::Case => "case",
+        UsageKind::Calc => "calc",
+        UsageKind::Connection => "connection",
+        UsageKind::Interface => "interface",
+        UsageKind::Allocation => "allocation",
+        UsageKind::Occurrence => "occurrence",
+        UsageKind::Analysis => "analysis case",
+        UsageKind::Verification => "verification case",
+        _ => return None,
+    })
+}
+
+/// The kind keywords of a usage or definition (`use case`), without its
+/// prefixes and `def`.
+fn definition_keywords(node: &SyntaxNode) -> String {
+    node.children_with_tokens()
+        .filter(|element| element.kind().is_keyword())
+        .take_while(|element| element.kind() != SyntaxKind::DEF_KW)
+        .filter(|element| {
+            !matches!(
+                element.kind(),
+                SyntaxKind::ABSTRACT_KW | SyntaxKind::VARIATION_KW | SyntaxKind::INDIVIDUAL_KW
+            )
+        })
+        .map(|element| element_text(&element).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn element_text(element: &crate::parser::SyntaxElement) -> &str {
+    element.as_token().map_or("", |token| token.text())
+}
+
+/// The packages under `node` a definition can be moved into: all but
+/// library packages and their contents.
+fn user_packages(node: &SyntaxNode) -> Vec<SyntaxNode> {
+    let mut packages = Vec::new();
+    for child in node.children() {
+        match child.kind() {
+            SyntaxKind::PACKAGE => {
+                packages.push(child.clone());
+                for body in child.children() {
+                    if body.kind() == SyntaxKind::NAMESPACE_BODY {
+                        packages.extend(user_packages(&body));
+                    }
+                }
+            }
+            SyntaxKind::LIBRARY_PACKAGE => {}
+            _ if NamespaceMember::can_cast(child.kind()) => {}
+            _ => packages.extend(user_packages(&child)),
+        }
+    }
+    packages
+}
+
+fn is_package(node: &SyntaxNode) -> bool {
+    matches!(
+        node.kind(),
+        SyntaxKind::PACKAGE | SyntaxKind::LIBRARY_PACKAGE
+    )
+}
+
+/// The range of the qualified names of a typing (`Engine, Motor` in `: Engine, Motor[2]`).
+fn targets_range(typing: &SyntaxNode) -> Option<TextRange> {
+    let mut targets = typing
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::QUALIFIED_NAME);
+    let first = trimmed_range(&targets.next()?);
+    let last = targets.last().map_or(first, |last| trimmed_range(&last));
+    Some(first.cover(last))
+}
+
+/// The end of whatever precedes `node` in its parent, trivia excluded.
+fn previous_end(node: &SyntaxNode) -> Option<TextSize> {
+    let previous = node
+        .siblings_with_tokens(rowan::Direction::Prev)
+        .skip(1)
+        .find(|element| !element.kind().is_trivia())?;
+    Some(match previous {
+        rowan::NodeOrToken::Node(node) => trimmed_range(&node).end(),
+        rowan::NodeOrToken::Token(token) => token.text_range().end(),
+    })
+}
+
+/// Whether `name` is `scope` or declared in it.
+pub(super) fn within(name: &str, scope: &str) -> bool {
+    scope.is_empty()
+        || name
+            .strip_prefix(scope)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Move the lines after the first from indentation `from` to `to`.
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    let mut lines = text.split('\n');
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        match line.strip_prefix(from) {
+            Some(rest) => {
+                result.push_str(to);
+                result.push_str(rest);
+            }
+            None => result.push_str(line),
+        }
+    }
+    result
+}
+
+/// `name` as written in source, quoted unless it's an identifier.
+fn display_name(name: &str) -> String {
+    if parse_name(name).is_some() && !name.starts_with('\'') {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "\\'"))
+    }
+}
+
+pub(super) fn display_qualified(qualified_name: &str) -> String {
+    qualified_name
+        .split("::")
+        .map(display_name)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn upper_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
-use crate::base::FileId;
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId};
 use crate::hir::{HirSymbol, SymbolIndex, SymbolKind, TypeRef};
 
 /// Result of a find-references request.
@@ -99,15 +101,41 @@ pub fn find_references(
     col: u32,
     include_declaration: bool,
 ) -> ReferenceResult {
+    find_references_cancellable(
+        index,
+        file,
+        line,
+        col,
+        include_declaration,
+        &CancellationToken::new(),
+    )
+    .unwrap_or_else(|Cancelled| ReferenceResult::empty())
+}
+
+/// Find all references to the symbol at the given position, giving up as
+/// soon as `cancel` is signalled.
+///
+/// Same as [`find_references()`], but returns `Err(Cancelled)` instead of
+/// finishing the workspace scan once the token is cancelled.
+pub fn find_references_cancellable(
+    index: &SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    include_declaration: bool,
+    cancel: &CancellationToken,
+) -> Cancellable<ReferenceResult> {
+    Cancelled::check(cancel)?;
+
     // First, check if cursor is on a type reference
     if let Some((target_name, _source_symbol)) = find_type_ref_at_position(index, file, line, col) {
-        return find_references_for_target(index, &target_name, include_declaration);
+        return find_references_for_target(index, &target_name, include_declaration, cancel);
     }
 
     // Find the symbol at the cursor position
     let symbol = match find_symbol_at_position(index, file, line, col) {
         Some(s) => s,
-        None => return ReferenceResult::empty(),
+        None => return Ok(ReferenceResult::empty()),
     };
 
     // Determine what we're looking for
@@ -123,7 +151,7 @@ pub fn find_references(
         }
     };
 
-    find_references_for_target(index, &target_name, include_declaration)
+    find_references_for_target(index, &target_name, include_declaration, cancel)
 }
 
 /// Find all references to a named target.
@@ -131,7 +159,8 @@ fn find_references_for_target(
     index: &SymbolIndex,
     target_name: &str,
     include_declaration: bool,
-) -> ReferenceResult {
+    cancel: &CancellationToken,
+) -> Cancellable<ReferenceResult> {
     let mut references = Vec::new();
 
     // Find the definition
@@ -146,24 +175,23 @@ fn find_references_for_target(
 
     // Collect all type references to this target (actual textual locations)
     // Use resolved_target for proper scoped matching
-    let type_refs: Vec<_> = index
-        .all_symbols()
-        .flat_map(|sym| {
-            sym.type_refs
-                .iter()
-                .flat_map(|trk| trk.as_refs()) // Flatten TypeRefKind to &TypeRef
-                .filter(|tr| {
-                    // Use effective_target (resolved if available) for accurate scoped matching
-                    tr.effective_target().as_ref() == target_name
-                })
-                .map(move |tr| Reference::from_type_ref(tr, sym.file))
-        })
-        .collect();
-
-    references.extend(type_refs);
+    for sym in index.all_symbols() {
+        Cancelled::check(cancel)?;
+        let type_refs = sym
+            .type_refs
+            .iter()
+            .flat_map(|trk| trk.as_refs()) // Flatten TypeRefKind to &TypeRef
+            .filter(|tr| {
+                // Use effective_target (resolved if available) for accurate scoped matching
+                tr.effective_target().as_ref() == target_name
+            })
+            .map(|tr| Reference::from_type_ref(tr, sym.file));
+        references.extend(type_refs);
+    }
 
     // Find direct name matches (for things like package references)
     for sym in index.all_symbols() {
+        Cancelled::check(cancel)?;
         if sym.name.as_ref() == target_name && !sym.kind.is_definition() {
             // Avoid duplicates
             if !references.iter().any(|r| {
@@ -174,10 +202,10 @@ fn find_references_for_target(
         }
     }
 
-    ReferenceResult {
+    Ok(ReferenceResult {
         references,
         include_declaration,
-    }
+    })
 }
 
 /// Find a type reference at a specific position.
@@ -323,6 +351,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
@@ -0,0 +1,703 @@
+//! Rename implementation.
+//!
+//! A rename edits the declaration of a symbol and every reference resolved to
+//! it: type annotations, relationship targets, feature chain parts, import
+//! paths and alias targets. Only the segment of a qualified name that names
+//! the symbol changes, so renaming `Engine` turns `Lib::Engine` into
+//! `Lib::Motor`. With the cursor on a short name (`<m>`), the short name is
+//! renamed instead, along with the references written with it.
+//!
+//! Symbols declared in library packages (the standard library included) can't
+//! be renamed. The rename is refused if the new name is taken in the symbol's namespace,
+//! or if it would change what a reference resolves to: either a renamed
+//! reference finds another symbol first, or the renamed symbol is found
+//! before the one an existing reference means. It's also refused when a file
+//! with a name to edit has no syntax tree, rather than leaving that name as is.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{
+    Cancellable, Cancelled, FileId, LineCol, LineIndex, TextEdit, TextSize, WorkspaceEdit,
+};
+use crate::hir::{HirSymbol, SymbolIndex, SymbolKind, TypeRef};
+use crate::parser::{Lexer, SyntaxKind};
+use crate::syntax::SyntaxFile;
+
+/// The name a rename at a position would change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenameTarget {
+    /// The file containing the name.
+    pub file: FileId,
+    /// Start line (0-indexed).
+    pub start_line: u32,
+    /// Start column (0-indexed).
+    pub start_col: u32,
+    /// End line (0-indexed).
+    pub end_line: u32,
+    /// End column (0-indexed).
+    pub end_col: u32,
+    /// The name as written, to edit into the new one.
+    pub placeholder: Arc<str>,
+    /// Qualified name of the renamed symbol.
+    pub qualified_name: Arc<str>,
+}
+
+/// Why a rename can't be done.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum RenameError {
+    /// The cursor isn't on the name of a symbol.
+    #[error("no symbol to rename at this position")]
+    NoSymbol,
+
+    /// The symbol has no name of its own to rename.
+    #[error("'{0}' can't be renamed")]
+    NotRenamable(Arc<str>),
+
+    /// The symbol is declared in a library package.
+    #[error("'{0}' is declared in a library and can't be renamed")]
+    Library(Arc<str>),
+
+    /// The new name isn't a valid (or quoted) name.
+    #[error("'{0}' is not a valid name")]
+    InvalidName(Arc<str>),
+
+    /// A file with a name to edit has no syntax tree to find it in.
+    #[error("{0} has no syntax tree, so its names can't be renamed")]
+    NoSyntaxTree(FileId),
+
+    /// The new name would collide with or shadow another symbol.
+    #[error("{message}")]
+    Conflict {
+        /// What the new name conflicts with.
+        message: Arc<str>,
+        /// The file of the conflicting declaration or reference.
+        file: FileId,
+        /// Line of the conflicting declaration or reference (0-indexed).
+        line: u32,
+        /// Column of the conflicting declaration or reference (0-indexed).
+        col: u32,
+    },
+}
+
+/// Find the name a rename at the given position would change.
+///
+/// # Arguments
+/// * `index` - The symbol index to search
+/// * `syntax_files` - Parsed files, to locate names in the source
+/// * `file` - The file containing the cursor
+/// * `line` - Cursor line (0-indexed)
+/// * `col` - Cursor column (0-indexed)
+pub fn prepare_rename(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    line: u32,
+    col: u32,
+) -> Result<RenameTarget, RenameError> {
+    let mut sources = Sources::new(syntax_files);
+    let target = find_target(index, &mut sources, file, line, col)?;
+    Ok(RenameTarget {
+        file: target.file,
+        start_line: target.name.start.0,
+        start_col: target.name.start.1,
+        end_line: target.name.end.0,
+        end_col: target.name.end.1,
+        placeholder: target.name.text,
+        qualified_name: target.symbol.qualified_name.clone(),
+    })
+}
+
+/// Rename the symbol at the given position to `new_name`, giving up as soon
+/// as `cancel` is signalled.
+///
+/// `new_name` is written as given, so names that aren't identifiers must be
+/// quoted (`'Fuel Tank'`).
+///
+/// # Returns
+/// The edits of every affected file, or why the rename can't be done;
+/// `Err(Cancelled)` if the token was cancelled before the workspace scan
+/// finished.
+pub fn rename(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    line: u32,
+    col: u32,
+    new_name: &str,
+    cancel: &CancellationToken,
+) -> Cancellable<Result<WorkspaceEdit, RenameError>> {
+    Cancelled::check(cancel)?;
+
+    let mut sources = Sources::new(syntax_files);
+    let target = match find_target(index, &mut sources, file, line, col) {
+        Ok(target) => target,
+        Err(err) => return Ok(Err(err)),
+    };
+    let Some(name) = parse_name(new_name) else {
+        return Ok(Err(RenameError::InvalidName(Arc::from(new_name))));
+    };
+    if *name == *target.old {
+        return Ok(Ok(WorkspaceEdit::new()));
+    }
+    if let Err(err) = check_collision(index, target.symbol, &name) {
+        return Ok(Err(err));
+    }
+
+    let qualified_name = &target.symbol.qualified_name;
+
+    let mut edit = WorkspaceEdit::new();
+    let mut edited = HashSet::new();
+    let symbol = target.symbol;
+    if !sources.has_syntax(symbol.file) {
+        return Ok(Err(RenameError::NoSyntaxTree(symbol.file)));
+    }
+    if let Some(declared) = sources.declared_name(symbol, target.short) {
+        edited.insert((symbol.file, declared.start));
+        edit.push(TextEdit::replace(
+            symbol.file,
+            declared.start,
+            declared.end,
+            new_name,
+        ));
+    }
+
+    for sym in index.all_symbols() {
+        Cancelled::check(cancel)?;
+        let refers = sym
+            .type_refs
+            .iter()
+            .flat_map(|type_ref_kind| type_ref_kind.as_refs())
+            .any(|type_ref| refers_to(type_ref, qualified_name));
+        if refers && !sources.has_syntax(sym.file) {
+            return Ok(Err(RenameError::NoSyntaxTree(sym.file)));
+        }
+        let scope = reference_scope(&sym.qualified_name);
+        for type_ref_kind in &sym.type_refs {
+            for (part, type_ref) in type_ref_kind.as_refs().into_iter().enumerate() {
+                // Only the first segment of a reference is looked up in scope
+                let in_scope = part == 0;
+
+                if let Some((i, segments)) =
+                    sources.segment_naming(sym.file, type_ref, qualified_name)
+                {
+                    let segment = &segments[i];
+                    if segment.name != *target.old || !edited.insert((sym.file, segment.start)) {
+                        continue;
+                    }
+                    if i == 0 && in_scope {
+                        if let Some(other) = binding_conflict(index, scope, &target, &name, true) {
+                            return Ok(Err(conflict(
+                                format!(
+                                    "'{name}' would refer to '{other}' here instead of '{qualified_name}'"
+                                ),
+                                sym.file,
+                                segment.start,
+                            )));
+                        }
+                    }
+                    edit.push(TextEdit::replace(
+                        sym.file,
+                        segment.start,
+                        segment.end,
+                        new_name,
+                    ));
+                } else if in_scope
+                    && type_ref.resolved_target.is_some()
+                    && type_ref.target.split("::").next().map(strip_quotes) == Some(name.as_str())
+                {
+                    if let Some(other) = binding_conflict(index, scope, &target, &name, false) {
+                        return Ok(Err(conflict(
+                            format!(
+                                "'{name}' would refer to '{qualified_name}' here instead of '{other}'"
+                            ),
+                            sym.file,
+                            (type_ref.start_line, type_ref.start_col),
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Ok(edit))
+}
+
+/// The symbol a rename applies to, and the name at the cursor.
+struct Target<'a> {
+    symbol: &'a HirSymbol,
+    /// Whether the short name is renamed rather than the name.
+    short: bool,
+    /// The renamed name.
+    old: Arc<str>,
+    /// The file and the name at the cursor.
+    file: FileId,
+    name: NameToken,
+}
+
+impl<'a> Target<'a> {
+    fn new(symbol: &'a HirSymbol, short: bool, file: FileId, name: NameToken) -> Self {
+        let old = symbol.short_name.clone().filter(|_| short);
+        Self {
+            symbol,
+            short,
+            old: old.unwrap_or_else(|| symbol.name.clone()),
+            file,
+            name,
+        }
+    }
+}
+
+/// A name in the source.
+pub(super) struct NameToken {
+    /// The name as written, quotes included.
+    pub(super) text: Arc<str>,
+    /// The name without quotes.
+    pub(super) name: String,
+    pub(super) start: (u32, u32),
+    pub(super) end: (u32, u32),
+}
+
+/// Source text of the files a rename looks at, read once per file.
+pub(super) struct Sources<'a> {
+    syntax_files: &'a HashMap<FileId, Arc<SyntaxFile>>,
+    texts: HashMap<FileId, Option<(String, LineIndex)>>,
+}
+
+impl<'a> Sources<'a> {
+    pub(super) fn new(syntax_files: &'a HashMap<FileId, Arc<SyntaxFile>>) -> Self {
+        Self {
+            syntax_files,
+            texts: HashMap::new(),
+        }
+    }
+
+    /// The names written in a type reference: the segments of a qualified name.
+    pub(super) fn names(&mut self, file: FileId, type_ref: &TypeRef) -> Vec<NameToken> {
+        self.names_between(
+            file,
+            (type_ref.start_line, type_ref.start_col),
+            (type_ref.end_line, type_ref.end_col),
+        )
+    }
+
+    /// The short name or name in a symbol's declaration, whose span covers
+    /// both (`<m> metre`).
+    pub(super) fn declared_name(&mut self, symbol: &HirSymbol, short: bool) -> Option<NameToken> {
+        let mut names = self.names_between(
+            symbol.file,
+            (symbol.start_line, symbol.start_col),
+            (symbol.end_line, symbol.end_col),
+        );
+        let i = if short {
+            let short_name = symbol.short_name.as_deref()?;
+            names.iter().position(|n| n.name == short_name)?
+        } else {
+            names.iter().rposition(|n| n.name == *symbol.name)?
+        };
+        Some(names.swap_remove(i))
+    }
+
+    /// The written segment of a reference to `qualified_name` or one of its
+    /// members that names `qualified_name`, with all the segments.
+    ///
+    /// The segments are lined up with the end of the resolved target, so
+    /// the segment is only a candidate; its name still has to be checked.
+    pub(super) fn segment_naming(
+        &mut self,
+        file: FileId,
+        type_ref: &TypeRef,
+        qualified_name: &str,
+    ) -> Option<(usize, Vec<NameToken>)> {
+        if !refers_to(type_ref, qualified_name) {
+            return None;
+        }
+        let resolved = type_ref.effective_target();
+        let segments = self.names(file, type_ref);
+        let depth = qualified_name.split("::").count();
+        let i = (depth + segments.len()).checked_sub(resolved.split("::").count() + 1)?;
+        (i < segments.len()).then_some((i, segments))
+    }
+
+    /// The names written between two positions of `file`.
+    pub(super) fn names_between(
+        &mut self,
+        file: FileId,
+        start: (u32, u32),
+        end: (u32, u32),
+    ) -> Vec<NameToken> {
+        let Some((text, line_index)) = self.text(file) else {
+            return Vec::new();
+        };
+
+        let offset = |(line, col)| line_index.offset(LineCol { line, col });
+        let (Some(start), Some(end)) = (offset(start), offset(end)) else {
+            return Vec::new();
+        };
+        let Some(range_text) = text.get(usize::from(start)..usize::from(end)) else {
+            return Vec::new();
+        };
+
+        let position = |offset: TextSize| {
+            let line_col = line_index.line_col(start + offset);
+            (line_col.line, line_col.col)
+        };
+        Lexer::new(range_text)
+            .filter(|token| token.kind == SyntaxKind::IDENT)
+            .map(|token| NameToken {
+                text: Arc::from(token.text),
+                name: strip_quotes(token.text).to_string(),
+                start: position(token.offset),
+                end: position(token.offset + TextSize::of(token.text)),
+            })
+            .collect()
+    }
+
+    /// Whether `symbol` is declared inside a library package.
+    pub(super) fn in_library(&mut self, symbol: &HirSymbol) -> bool {
+        let Some(syntax) = self.syntax_files.get(&symbol.file) else {
+            return false;
+        };
+        self.text(symbol.file)
+            .and_then(|(_, line_index)| {
+                syntax.member_at(line_index, symbol.start_line, symbol.start_col)
+            })
+            .is_some_and(|member| {
+                member
+                    .ancestors()
+                    .any(|node| node.kind() == SyntaxKind::LIBRARY_PACKAGE)
+            })
+    }
+
+    /// Whether `file` has a syntax tree to find names in.
+    pub(super) fn has_syntax(&self, file: FileId) -> bool {
+        self.syntax_files.contains_key(&file)
+    }
+
+    /// The text of `file` and its line index.
+    fn text(&mut self, file: FileId) -> Option<&(String, LineIndex)> {
+        let syntax_files = self.syntax_files;
+        self.texts
+            .entry(file)
+            .or_insert_with(|| {
+                let text = syntax_files.get(&file)?.source_text();
+                let line_index = LineIndex::new(&text);
+                Some((text, line_index))
+            })
+            .as_ref()
+    }
+}
+
+/// Find the symbol and name at the cursor: a reference, or a declaration.
+fn find_target<'a>(
+    index: &'a SymbolIndex,
+    sources: &mut Sources<'_>,
+    file: FileId,
+    line: u32,
+    col: u32,
+) -> Result<Target<'a>, RenameError> {
+    let symbols = index.symbols_in_file(file);
+    let at_cursor = |name: &NameToken| name.start <= (line, col) && (line, col) <= name.end;
+
+    // A reference names the symbol its resolved target ends in at the cursor's segment
+    for symbol in &symbols {
+        for type_ref_kind in &symbol.type_refs {
+            let Some((_, type_ref)) = type_ref_kind.part_at(line, col) else {
+                continue;
+            };
+            let segments = sources.names(file, type_ref);
+            let Some(i) = segments.iter().position(at_cursor) else {
+                continue;
+            };
+            let resolved: Vec<_> = type_ref.effective_target().split("::").collect();
+            let Some(len) = (resolved.len() + i + 1).checked_sub(segments.len()) else {
+                continue;
+            };
+            let Some(referenced) = index.lookup_qualified(&resolved[..len].join("::")) else {
+                continue;
+            };
+            let segment = segments.into_iter().nth(i).expect("segment at cursor");
+            let short = if *referenced.name == segment.name {
+                false
+            } else if referenced.short_name.as_deref() == Some(segment.name.as_str()) {
+                true
+            } else {
+                continue;
+            };
+            return renamable(sources, referenced)
+                .map(|symbol| Target::new(symbol, short, file, segment));
+        }
+    }
+
+    for symbol in symbols {
+        if symbol.kind == SymbolKind::Import {
+            continue;
+        }
+        for short in [true, false] {
+            if let Some(name) = sources.declared_name(symbol, short).filter(at_cursor) {
+                return renamable(sources, symbol)
+                    .map(|symbol| Target::new(symbol, short, file, name));
+            }
+        }
+    }
+
+    Err(RenameError::NoSymbol)
+}
+
+/// Refuse symbols named by the extractor rather than the source, and
+/// symbols declared in libraries.
+fn renamable<'a>(
+    sources: &mut Sources<'_>,
+    symbol: &'a HirSymbol,
+) -> Result<&'a HirSymbol, RenameError> {
+    if symbol.name.is_empty() || symbol.name.starts_with('<') {
+        return Err(RenameError::NotRenamable(symbol.qualified_name.clone()));
+    }
+    if sources.in_library(symbol) {
+        return Err(RenameError::Library(symbol.qualified_name.clone()));
+    }
+    Ok(symbol)
+}
+
+/// The name `new_name` declares, or `None` if it isn't a single name.
+pub(super) fn parse_name(new_name: &str) -> Option<String> {
+    let mut tokens = Lexer::new(new_name);
+    match (tokens.next(), tokens.next()) {
+        (Some(token), None) if token.kind == SyntaxKind::IDENT => {
+            Some(strip_quotes(new_name).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Refuse a name already declared in the symbol's namespace.
+fn check_collision(index: &SymbolIndex, symbol: &HirSymbol, name: &str) -> Result<(), RenameError> {
+    let parent = parent_scope(&symbol.qualified_name);
+    let sibling = index
+        .visibility_for_scope(parent)
+        .and_then(|vis| vis.lookup_direct(name))
+        .and_then(|qname| index.lookup_qualified(qname))
+        .or_else(|| index.lookup_qualified(&qualify(parent, name)));
+    match sibling {
+        Some(other) if other.qualified_name != symbol.qualified_name => {
+            let message = if parent.is_empty() {
+                format!("'{name}' is already defined at the top level")
+            } else {
+                format!("'{name}' is already defined in '{parent}'")
+            };
+            Err(conflict(
+                message,
+                other.file,
+                (other.start_line, other.start_col),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check a reference resolved from `scope` against the renamed symbol.
+///
+/// Returns the symbol `name` is bound to instead when, after the rename, a
+/// renamed reference (`renamed`) would find it first, or an existing
+/// reference to it would find the renamed symbol first.
+fn binding_conflict<'a>(
+    index: &'a SymbolIndex,
+    scope: &str,
+    target: &Target<'_>,
+    name: &str,
+    renamed: bool,
+) -> Option<&'a Arc<str>> {
+    let (renamed_at, bound) = binding(index, scope, &target.old)?;
+    if *bound != target.symbol.qualified_name {
+        return None;
+    }
+    let (other_at, other) = binding(index, scope, name)?;
+    let conflicts = if renamed {
+        other_at <= renamed_at
+    } else {
+        renamed_at <= other_at
+    };
+    (conflicts && *other != target.symbol.qualified_name).then_some(other)
+}
+
+/// How many scopes out from `scope` `name` is visible, and what it names there.
+fn binding<'a>(index: &'a SymbolIndex, scope: &str, name: &str) -> Option<(usize, &'a Arc<str>)> {
+    let mut current = scope;
+    let mut distance = 0;
+    loop {
+        if let Some(qname) = index
+            .visibility_for_scope(current)
+            .and_then(|vis| vis.lookup(name))
+        {
+            return Some((distance, qname));
+        }
+        if current.is_empty() {
+            return None;
+        }
+        current = parent_scope(current);
+        distance += 1;
+    }
+}
+
+/// The scope a symbol's references are resolved from.
+pub(super) fn reference_scope(qualified_name: &str) -> &str {
+    if let Some(at) = qualified_name.find("::import:") {
+        &qualified_name[..at]
+    } else if qualified_name.starts_with("import:") {
+        ""
+    } else {
+        qualified_name
+    }
+}
+
+/// Whether `type_ref` resolves to `qualified_name` or one of its members.
+fn refers_to(type_ref: &TypeRef, qualified_name: &str) -> bool {
+    type_ref
+        .effective_target()
+        .strip_prefix(qualified_name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+pub(super) fn parent_scope(qualified_name: &str) -> &str {
+    qualified_name
+        .rsplit_once("::")
+        .map_or("", |(parent, _)| parent)
+}
+
+pub(super) fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}::{name}")
+    }
+}
+
+pub(super) fn strip_quotes(name: &str) -> &str {
+    name.strip_prefix('\'')
+        .and_then(|name| name.strip_suffix('\''))
+        .unwrap_or(name)
+}
+
+fn conflict(message: String, file: FileId, (line, col): (u32, u32)) -> RenameError {
+    RenameError::Conflict {
+        message: Arc::from(message),
+        file,
+        line,
+        col,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::extract_with_filters;
+
+    /// The index and syntax trees of SysML `files`, numbered in order.
+    fn workspace(files: &[&str]) -> (SymbolIndex, HashMap<FileId, Arc<SyntaxFile>>) {
+        let mut index = SymbolIndex::new();
+        let mut syntax_files = HashMap::new();
+        for (i, source) in files.iter().enumerate() {
+            let file = FileId::new(i as u32);
+            let syntax = SyntaxFile::sysml(source);
+            index.add_extraction_result(file, extract_with_filters(file, &syntax));
+            syntax_files.insert(file, Arc::new(syntax));
+        }
+        index.ensure_visibility_maps();
+        index.resolve_all_type_refs();
+        (index, syntax_files)
+    }
+
+    #[test]
+    fn test_in_library() {
+        let (index, syntax_files) = workspace(&[
+            "library package Lib { part def Engine; }\npackage Model { part def Car; }",
+        ]);
+        let mut sources = Sources::new(&syntax_files);
+        let in_library = |sources: &mut Sources<'_>, name| {
+            sources.in_library(index.lookup_qualified(name).unwrap())
+        };
+
+        assert!(in_library(&mut sources, "Lib"));
+        assert!(in_library(&mut sources, "Lib::Engine"));
+        assert!(!in_library(&mut sources, "Model"));
+        assert!(!in_library(&mut sources, "Model::Car"));
+    }
+
+    #[test]
+    fn test_in_library_without_syntax_tree() {
+        let (index, _) = workspace(&["library package Lib { part def Engine; }"]);
+        let syntax_files = HashMap::new();
+        let mut sources = Sources::new(&syntax_files);
+
+        assert!(!sources.in_library(index.lookup_qualified("Lib::Engine").unwrap()));
+    }
+
+    #[test]
+    fn test_collision_with_sibling() {
+        let (index, _) = workspace(&["package P {\n    part def A;\n    part def B;\n}"]);
+        let b = index.lookup_qualified("P::B").unwrap();
+
+        assert_eq!(
+            check_collision(&index, b, "A"),
+            Err(RenameError::Conflict {
+                message: Arc::from("'A' is already defined in 'P'"),
+                file: FileId::new(0),
+                line: 1,
+                col: 13,
+            })
+        );
+        assert_eq!(check_collision(&index, b, "C"), Ok(()));
+    }
+
+    #[test]
+    fn test_rename_shadowing_reference_conflicts() {
+        let source = "package P {\n    part def A;\n    package Q {\n        part def B;\n        part x : A;\n    }\n}";
+        let (index, syntax_files) = workspace(&[source]);
+
+        let result = rename(
+            &index,
+            &syntax_files,
+            FileId::new(0),
+            3,
+            17,
+            "A",
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert!(
+            matches!(
+                result,
+                Err(RenameError::Conflict {
+                    line: 4,
+                    col: 17,
+                    ..
+                })
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_rename_refused_without_syntax_tree() {
+        let (index, mut syntax_files) = workspace(&[
+            "package Lib { part def Engine; }",
+            "package Car {\n    private import Lib::*;\n    part e : Engine;\n}",
+        ]);
+        syntax_files.remove(&FileId::new(1));
+
+        let result = rename(
+            &index,
+            &syntax_files,
+            FileId::new(0),
+            0,
+            24,
+            "Motor",
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(result, Err(RenameError::NoSyntaxTree(FileId::new(1))));
+    }
+}
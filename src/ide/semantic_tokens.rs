@@ -3,7 +3,9 @@
 //! This module provides semantic token extraction directly from the HIR layer,
 //! without depending on the legacy semantic layer.
 
-use crate::base::FileId;
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId};
 use crate::hir::{SymbolIndex, SymbolKind};
 
 /// Token type for semantic highlighting.
@@ -132,10 +134,24 @@ pub struct SemanticToken {
 ///
 /// Vector of semantic tokens sorted by position.
 pub fn semantic_tokens(index: &SymbolIndex, file: FileId) -> Vec<SemanticToken> {
+    semantic_tokens_cancellable(index, file, &CancellationToken::new()).unwrap_or_default()
+}
+
+/// Get semantic tokens for a file, giving up as soon as `cancel` is signalled.
+///
+/// Same as [`semantic_tokens()`], but returns `Err(Cancelled)` instead of
+/// finishing once the token is cancelled.
+pub fn semantic_tokens_cancellable(
+    index: &SymbolIndex,
+    file: FileId,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<SemanticToken>> {
     let mut tokens = Vec::new();
 
     // Add tokens for all symbols in this file
     for symbol in index.symbols_in_file(file) {
+        Cancelled::check(cancel)?;
+
         // Skip anonymous/synthetic symbols (names like `<:>>cyl#8@L7>`)
         // These are generated names for anonymous usages and shouldn't be highlighted
         if symbol.name.starts_with('<') {
@@ -190,7 +206,7 @@ pub fn semantic_tokens(index: &SymbolIndex, file: FileId) -> Vec<SemanticToken>
     // Sort tokens by position (line, then column)
     tokens.sort_by_key(|t| (t.line, t.col));
 
-    tokens
+    Ok(tokens)
 }
 
 #[cfg(test)]
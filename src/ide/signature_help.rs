@@ -0,0 +1,349 @@
+//! Signature help — the parameters of the calculation, action or function
+//! being invoked at the cursor.
+//!
+//! The invocation is found in the tokens before the cursor rather than in
+//! the syntax tree, which is usually incomplete while arguments are typed.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use super::completion::scope_at;
+use super::rename::{parent_scope, strip_quotes};
+use crate::base::{Cancellable, Cancelled, FileId, LineCol, LineIndex, TextRange, TextSize};
+use crate::hir::{HirSymbol, RefKind, Resolver, SymbolIndex, SymbolKind};
+use crate::parser::{Direction, Lexer, Multiplicity, SyntaxKind};
+use crate::syntax::SyntaxFile;
+
+/// The signature of an invoked definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureHelp {
+    /// The signature (e.g., `Compute(in a : Real, in b : Real[0..1]) : Real`).
+    pub label: Arc<str>,
+    /// Documentation of the invoked definition.
+    pub documentation: Option<Arc<str>>,
+    /// Qualified name of the invoked definition.
+    pub qualified_name: Arc<str>,
+    /// The parameters, in declaration order.
+    pub parameters: Vec<ParameterInfo>,
+    /// Index in `parameters` of the one the argument at the cursor is for.
+    pub active_parameter: Option<usize>,
+}
+
+/// A parameter of an invoked definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterInfo {
+    /// The parameter as shown in the signature (e.g., `in b : Real[0..1]`).
+    pub label: Arc<str>,
+    /// The parameter's name.
+    pub name: Arc<str>,
+    /// Direction of the parameter.
+    pub direction: Direction,
+    /// The parameter's type, as written.
+    pub type_name: Option<Arc<str>>,
+    /// Multiplicity of the parameter.
+    pub multiplicity: Option<Multiplicity>,
+}
+
+/// Get the signature of the invocation whose arguments contain the cursor,
+/// giving up as soon as `cancel` is signalled.
+///
+/// Positional arguments are matched to the `in` and `inout` parameters in
+/// order; a named argument (`target = p`) to the parameter it names.
+pub fn signature_help(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Cancellable<Option<SignatureHelp>> {
+    Cancelled::check(cancel)?;
+    let help = signature_help_at(index, syntax_files, file, line, col, cancel);
+    // A cancelled supertype walk stops early, so its parameters can't be trusted
+    Cancelled::check(cancel)?;
+    Ok(help)
+}
+
+fn signature_help_at(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Option<SignatureHelp> {
+    let syntax = syntax_files.get(&file)?;
+    let text = syntax.source_text();
+    let line_index = LineIndex::new(&text);
+    let offset = line_index
+        .offset(LineCol::new(line, col))?
+        .min(TextSize::of(text.as_str()));
+    if !text.is_char_boundary(usize::from(offset)) {
+        return None;
+    }
+    let tokens: Vec<_> = Lexer::new(&text[..usize::from(offset)])
+        .filter(|token| !token.kind.is_trivia())
+        .map(|token| {
+            (
+                token.kind,
+                TextRange::at(token.offset, TextSize::of(token.text)),
+            )
+        })
+        .collect();
+    let invocation = Invocation::before_cursor(&text, &tokens)?;
+
+    let root = syntax.parse().syntax();
+    let scope = scope_at(index, file, &root, &line_index, offset);
+    let resolved = Resolver::new(index)
+        .with_scope(scope)
+        .resolve(&invocation.name);
+    let invoked = index.lookup_qualified(&resolved.symbol()?.qualified_name)?;
+    let definition = behavior_of(index, invoked, cancel)?;
+
+    let parameters: Vec<_> = parameters(index, definition, cancel)
+        .into_iter()
+        .map(parameter_info)
+        .collect();
+    let active_parameter = match &invocation.argument {
+        Argument::Named(name) => parameters
+            .iter()
+            .position(|parameter| *parameter.name == **name),
+        Argument::Positional(position) => parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, parameter)| parameter.direction != Direction::Out)
+            .nth(*position)
+            .map(|(i, _)| i),
+    };
+
+    let labels: Vec<_> = parameters.iter().map(|p| &*p.label).collect();
+    let mut label = format!("{}({})", definition.name, labels.join(", "));
+    if let Some(result) = result_type(index, syntax_files, definition) {
+        label.push_str(" : ");
+        label.push_str(&result);
+    }
+
+    Some(SignatureHelp {
+        label: label.into(),
+        documentation: definition.doc.clone(),
+        qualified_name: definition.qualified_name.clone(),
+        parameters,
+        active_parameter,
+    })
+}
+
+/// An invocation whose argument list is open at the cursor.
+struct Invocation {
+    /// The invoked name, as written.
+    name: String,
+    /// The argument at the cursor.
+    argument: Argument,
+}
+
+enum Argument {
+    /// The nth positional argument.
+    Positional(usize),
+    /// An argument for the named parameter.
+    Named(String),
+}
+
+impl Invocation {
+    /// Find the innermost unclosed `name(` in `tokens`.
+    fn before_cursor(text: &str, tokens: &[(SyntaxKind, TextRange)]) -> Option<Self> {
+        let mut depth = 0usize;
+        let mut commas = 0;
+        let mut argument_start = tokens.len();
+        let mut open = None;
+        for (i, &(kind, _)) in tokens.iter().enumerate().rev() {
+            match kind {
+                SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET => depth += 1,
+                SyntaxKind::L_PAREN | SyntaxKind::L_BRACKET if depth > 0 => depth -= 1,
+                SyntaxKind::L_PAREN => {
+                    open = Some(i);
+                    break;
+                }
+                SyntaxKind::COMMA if depth == 0 => {
+                    if commas == 0 {
+                        argument_start = i + 1;
+                    }
+                    commas += 1;
+                }
+                SyntaxKind::L_BRACKET
+                | SyntaxKind::L_BRACE
+                | SyntaxKind::R_BRACE
+                | SyntaxKind::SEMICOLON => return None,
+                _ => {}
+            }
+        }
+        let open = open?;
+        if commas == 0 {
+            argument_start = open + 1;
+        }
+
+        // The invoked name: `Name` or `Qualified::Name` just before `(`
+        let mut segments = Vec::new();
+        let mut before = tokens[..open].iter().rev();
+        while let Some(&(SyntaxKind::IDENT, range)) = before.next() {
+            segments.push(strip_quotes(&text[range]));
+            if before.next().map(|&(kind, _)| kind) != Some(SyntaxKind::COLON_COLON) {
+                break;
+            }
+        }
+        if segments.is_empty() {
+            return None;
+        }
+        segments.reverse();
+
+        let argument = match tokens[argument_start..] {
+            [(SyntaxKind::IDENT, name), (SyntaxKind::EQ, _), ..] => {
+                Argument::Named(strip_quotes(&text[name]).to_string())
+            }
+            _ => Argument::Positional(commas),
+        };
+        Some(Self {
+            name: segments.join("::"),
+            argument,
+        })
+    }
+}
+
+/// The definition whose parameters an invocation of `symbol` takes: itself,
+/// or the type of a usage.
+fn behavior_of<'a>(
+    index: &'a SymbolIndex,
+    symbol: &'a HirSymbol,
+    cancel: &CancellationToken,
+) -> Option<&'a HirSymbol> {
+    if symbol.kind.is_definition() || !parameters(index, symbol, cancel).is_empty() {
+        return Some(symbol);
+    }
+    symbol
+        .type_refs
+        .iter()
+        .flat_map(|type_ref| type_ref.as_refs())
+        .filter(|type_ref| type_ref.kind == RefKind::TypedBy)
+        .find_map(|type_ref| index.lookup_qualified(type_ref.resolved_target.as_deref()?))
+}
+
+/// The directed features of `definition`, or of its first supertype that
+/// has some. Stops early once `cancel` is signalled.
+fn parameters<'a>(
+    index: &'a SymbolIndex,
+    definition: &HirSymbol,
+    cancel: &CancellationToken,
+) -> Vec<&'a HirSymbol> {
+    let mut visited = Vec::new();
+    let mut current = index.lookup_qualified(&definition.qualified_name);
+    while let Some(symbol) = current {
+        if visited.contains(&symbol.qualified_name) || cancel.is_cancelled() {
+            break;
+        }
+        visited.push(symbol.qualified_name.clone());
+
+        let mut own: Vec<_> = index
+            .symbols_in_file(symbol.file)
+            .into_iter()
+            .filter(|member| {
+                member.direction.is_some()
+                    && member
+                        .qualified_name
+                        .strip_prefix(&*symbol.qualified_name)
+                        .and_then(|rest| rest.strip_prefix("::"))
+                        .is_some_and(|rest| !rest.contains("::"))
+            })
+            .collect();
+        if !own.is_empty() {
+            own.sort_by_key(|member| (member.start_line, member.start_col));
+            return own;
+        }
+        let resolver = Resolver::new(index).with_scope(symbol.qualified_name.clone());
+        current = symbol
+            .supertypes
+            .first()
+            .and_then(|supertype| resolver.resolve(supertype).symbol().cloned())
+            .and_then(|supertype| index.lookup_qualified(&supertype.qualified_name));
+    }
+    Vec::new()
+}
+
+fn parameter_info(parameter: &HirSymbol) -> ParameterInfo {
+    let direction = parameter.direction.unwrap_or(Direction::In);
+    let type_name = type_name(parameter);
+    let mut label = format!("{} {}", direction_keyword(direction), parameter.name);
+    if let Some(type_name) = &type_name {
+        label.push_str(" : ");
+        label.push_str(type_name);
+    }
+    if let Some(multiplicity) = parameter.multiplicity {
+        label.push_str(&format_multiplicity(multiplicity));
+    }
+    ParameterInfo {
+        label: label.into(),
+        name: parameter.name.clone(),
+        direction,
+        type_name,
+        multiplicity: parameter.multiplicity,
+    }
+}
+
+/// The type a feature is declared with.
+fn type_name(feature: &HirSymbol) -> Option<Arc<str>> {
+    feature
+        .type_refs
+        .iter()
+        .flat_map(|type_ref| type_ref.as_refs())
+        .find(|type_ref| type_ref.kind == RefKind::TypedBy)
+        .map(|type_ref| type_ref.target.clone())
+        .or_else(|| feature.supertypes.first().cloned())
+}
+
+/// The type of the `return` parameter of a calculation, if it declares one.
+fn result_type(
+    index: &SymbolIndex,
+    syntax_files: &HashMap<FileId, Arc<SyntaxFile>>,
+    definition: &HirSymbol,
+) -> Option<Arc<str>> {
+    let syntax = syntax_files.get(&definition.file)?;
+    let line_index = syntax.line_index();
+    index
+        .symbols_in_file(definition.file)
+        .into_iter()
+        .filter(|member| {
+            member.direction.is_none()
+                && member.kind != SymbolKind::Import
+                && parent_scope(&member.qualified_name) == &*definition.qualified_name
+        })
+        .find(|member| {
+            syntax
+                .member_at(&line_index, member.start_line, member.start_col)
+                .and_then(|member| {
+                    member
+                        .descendants_with_tokens()
+                        .filter_map(|element| element.into_token())
+                        .find(|token| !token.kind().is_trivia())
+                })
+                .is_some_and(|first| first.kind() == SyntaxKind::RETURN_KW)
+        })
+        .and_then(type_name)
+}
+
+fn direction_keyword(direction: Direction) -> &'static str {
+    match direction {
+        Direction::In => "in",
+        Direction::Out => "out",
+        Direction::InOut => "inout",
+    }
+}
+
+/// `[lower..upper]`, with `*` for an unbounded upper bound.
+fn format_multiplicity(multiplicity: Multiplicity) -> String {
+    match (multiplicity.lower, multiplicity.upper) {
+        (Some(lower), Some(upper)) if lower == upper => format!("[{lower}]"),
+        (Some(lower), Some(upper)) => format!("[{lower}..{upper}]"),
+        (Some(lower), None) => format!("[{lower}..*]"),
+        (None, Some(upper)) => format!("[{upper}]"),
+        (None, None) => "[*]".to_string(),
+    }
+}
@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
-use crate::base::FileId;
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId};
 use crate::hir::{HirSymbol, SymbolIndex, SymbolKind};
 
 /// A symbol for the workspace symbol list or document outline.
@@ -57,30 +59,43 @@ impl SymbolInfo {
 /// # Returns
 /// List of matching symbols, sorted by name.
 pub fn workspace_symbols(index: &SymbolIndex, query: Option<&str>) -> Vec<SymbolInfo> {
+    workspace_symbols_cancellable(index, query, &CancellationToken::new()).unwrap_or_default()
+}
+
+/// Get all symbols in the workspace, giving up as soon as `cancel` is signalled.
+///
+/// Same as [`workspace_symbols()`], but returns `Err(Cancelled)` instead of
+/// finishing the scan once the token is cancelled.
+pub fn workspace_symbols_cancellable(
+    index: &SymbolIndex,
+    query: Option<&str>,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<SymbolInfo>> {
     let query_lower = query.map(|q| q.to_lowercase());
 
-    let mut results: Vec<SymbolInfo> = index
-        .all_symbols()
-        .filter(|sym| {
-            // Skip imports
-            if matches!(sym.kind, SymbolKind::Import) {
-                return false;
-            }
+    let mut results: Vec<SymbolInfo> = Vec::new();
+    for sym in index.all_symbols() {
+        Cancelled::check(cancel)?;
+
+        // Skip imports
+        if matches!(sym.kind, SymbolKind::Import) {
+            continue;
+        }
 
-            // Filter by query if provided
-            if let Some(ref q) = query_lower {
-                let name_lower = sym.name.to_lowercase();
-                let qname_lower = sym.qualified_name.to_lowercase();
-                name_lower.contains(q) || qname_lower.contains(q)
-            } else {
-                true
+        // Filter by query if provided
+        if let Some(ref q) = query_lower {
+            let name_lower = sym.name.to_lowercase();
+            let qname_lower = sym.qualified_name.to_lowercase();
+            if !name_lower.contains(q) && !qname_lower.contains(q) {
+                continue;
             }
-        })
-        .map(SymbolInfo::from_hir)
-        .collect();
+        }
+
+        results.push(SymbolInfo::from_hir(sym));
+    }
 
     results.sort_by(|a, b| a.name.cmp(&b.name));
-    results
+    Ok(results)
 }
 
 /// Get all symbols in a specific file for document outline.
@@ -152,6 +167,7 @@ mod tests {
             is_ordered: false,
             is_nonunique: false,
             is_portion: false,
+            is_conjugated: false,
             direction: None,
             multiplicity: None,
             value: None,
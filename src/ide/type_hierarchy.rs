@@ -0,0 +1,134 @@
+//! Type hierarchy — the direct supertypes and subtypes of the symbol at the cursor.
+//!
+//! The hierarchy follows specialization, subsetting, redefinition and typing,
+//! so the subtypes of a definition include both its specializations and the
+//! usages typed by it. Subtypes come from the index's reverse hierarchy rather
+//! than a scan of the workspace.
+
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use crate::base::{Cancellable, Cancelled, FileId};
+use crate::hir::{HirSymbol, RelationshipKind, SymbolIndex, SymbolKind};
+
+/// A supertype or subtype in a type hierarchy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeHierarchyItem {
+    /// The symbol name.
+    pub name: Arc<str>,
+    /// The qualified name, for requesting the next level of the hierarchy.
+    pub qualified_name: Arc<str>,
+    /// The symbol kind.
+    pub kind: SymbolKind,
+    /// How the subtype relates to the supertype (e.g., `Specializes`).
+    pub relationship: RelationshipKind,
+    /// The file containing the symbol.
+    pub file: FileId,
+    /// Start line (0-indexed).
+    pub start_line: u32,
+    /// Start column (0-indexed).
+    pub start_col: u32,
+    /// End line (0-indexed).
+    pub end_line: u32,
+    /// End column (0-indexed).
+    pub end_col: u32,
+}
+
+impl TypeHierarchyItem {
+    fn new(relationship: RelationshipKind, symbol: &HirSymbol) -> Self {
+        Self {
+            name: symbol.name.clone(),
+            qualified_name: symbol.qualified_name.clone(),
+            kind: symbol.kind,
+            relationship,
+            file: symbol.file,
+            start_line: symbol.start_line,
+            start_col: symbol.start_col,
+            end_line: symbol.end_line,
+            end_col: symbol.end_col,
+        }
+    }
+}
+
+/// Get the direct supertypes of the symbol at a position, including the
+/// implicit library ones (e.g., `Parts::Part`), giving up as soon as `cancel`
+/// is signalled.
+///
+/// The cursor may be on a declaration or on a reference to the symbol.
+pub fn type_hierarchy_supertypes(
+    index: &SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<TypeHierarchyItem>> {
+    let Some(symbol) = symbol_at(index, file, line, col, cancel)? else {
+        return Ok(Vec::new());
+    };
+    Ok(index
+        .supertypes_of(symbol)
+        .into_iter()
+        .map(|(kind, supertype)| TypeHierarchyItem::new(kind, supertype))
+        .collect())
+}
+
+/// Get the symbols that directly specialize, subset, redefine or are typed by
+/// the symbol at a position, giving up as soon as `cancel` is signalled.
+///
+/// The cursor may be on a declaration or on a reference to the symbol.
+pub fn type_hierarchy_subtypes(
+    index: &SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Cancellable<Vec<TypeHierarchyItem>> {
+    let Some(symbol) = symbol_at(index, file, line, col, cancel)? else {
+        return Ok(Vec::new());
+    };
+    let subtypes = index.subtypes_of(&symbol.qualified_name);
+    let mut items = Vec::with_capacity(subtypes.len());
+    for (kind, subtype) in subtypes {
+        Cancelled::check(cancel)?;
+        items.push(TypeHierarchyItem::new(kind, subtype));
+    }
+    Ok(items)
+}
+
+/// The symbol referenced at the cursor, or else the innermost one declared there.
+fn symbol_at<'a>(
+    index: &'a SymbolIndex,
+    file: FileId,
+    line: u32,
+    col: u32,
+    cancel: &CancellationToken,
+) -> Cancellable<Option<&'a HirSymbol>> {
+    let symbols = index.symbols_in_file(file);
+
+    for symbol in &symbols {
+        Cancelled::check(cancel)?;
+        let referenced = symbol.type_refs.iter().find_map(|type_ref| {
+            let (_, type_ref) = type_ref.part_at(line, col)?;
+            index.lookup_qualified(type_ref.resolved_target.as_deref()?)
+        });
+        if referenced.is_some() {
+            return Ok(referenced);
+        }
+    }
+
+    Ok(symbols
+        .into_iter()
+        .filter(|symbol| symbol.kind != SymbolKind::Import && contains_position(symbol, line, col))
+        .min_by_key(|symbol| {
+            (
+                symbol.end_line - symbol.start_line,
+                symbol.end_col.wrapping_sub(symbol.start_col),
+            )
+        }))
+}
+
+fn contains_position(symbol: &HirSymbol, line: u32, col: u32) -> bool {
+    (symbol.start_line, symbol.start_col) <= (line, col)
+        && (line, col) <= (symbol.end_line, symbol.end_col)
+}
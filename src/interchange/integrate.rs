@@ -11,7 +11,7 @@
 //! |----------|-----------|---------|
 //! | `model_from_symbols()` | HIR → Model | Export symbols to interchange Model |
 //! | `symbols_from_model()` | Model → HIR | Import Model elements as HirSymbols |
-//! | `model_from_database()` | RootDatabase → Model | Export Salsa DB to Model |
+//! | `model_from_database()` | RootDatabase → Model | Export registered Salsa files to Model |
 //! | `apply_metadata_to_host()` | Metadata → AnalysisHost | Restore element IDs after decompile |
 //!
 //! For bridging ChangeTracker edits into Salsa queries, use
@@ -32,9 +32,15 @@ use std::sync::Arc;
 ///
 /// This extracts all symbols and relationships from the database
 /// and builds an interchange Model that can be serialized to XMI, KPAR, etc.
-pub fn model_from_database(_db: &RootDatabase) -> Model {
-    // An empty database produces an empty model
-    Model::new()
+///
+/// Every file registered via [`RootDatabase::set_file_text()`] is parsed and
+/// extracted through the memoized Salsa queries, then indexed and resolved
+/// exactly like `AnalysisHost` does, so the result matches
+/// `model_from_symbols()` over the equivalent `SymbolIndex`.
+pub fn model_from_database(db: &RootDatabase) -> Model {
+    let index = crate::hir::workspace_index(db);
+    let symbols: Vec<HirSymbol> = index.all_symbols().cloned().collect();
+    model_from_symbols(&symbols)
 }
 
 /// Convert an interchange Model back to HIR symbols.
@@ -161,6 +167,7 @@ pub fn symbols_from_model(model: &Model) -> Result<Vec<HirSymbol>, InterchangeEr
             is_ordered: element.is_ordered,
             is_nonunique: element.is_nonunique,
             is_portion: element.is_portion,
+            is_conjugated: false,
             is_composite: if element.kind.is_feature_kind() {
                 Some(
                     element
@@ -847,7 +854,7 @@ pub fn model_from_symbols(symbols: &[HirSymbol]) -> Model {
                     ElementKind::NullExpression,
                     PropertyValue::String(Arc::from("null")),
                 ),
-                ValueExpression::Expression(text) => (
+                ValueExpression::Expression { text, .. } => (
                     ElementKind::FeatureReferenceExpression,
                     PropertyValue::String(Arc::from(text.as_str())),
                 ),
@@ -897,7 +904,7 @@ pub fn model_from_symbols(symbols: &[HirSymbol]) -> Model {
                     ElementKind::NullExpression,
                     PropertyValue::String(Arc::from("null")),
                 ),
-                ValueExpression::Expression(text) => (
+                ValueExpression::Expression { text, .. } => (
                     ElementKind::FeatureReferenceExpression,
                     PropertyValue::String(Arc::from(text.as_str())),
                 ),
@@ -1346,6 +1353,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_model_from_database_registered_files() {
+        let mut db = RootDatabase::new();
+        db.set_file_text(
+            FileId::new(0),
+            "package Types {\n    part def Vehicle;\n}",
+        );
+        db.set_file_text(
+            FileId::new(1),
+            "package Fleet {\n    import Types::*;\n    part def Car :> Vehicle;\n    part car : Car;\n}",
+        );
+
+        let model = model_from_database(&db);
+
+        assert_eq!(model.roots.len(), 2, "Should have both packages as roots");
+        assert_eq!(model.find_by_name("Vehicle").len(), 1);
+        assert_eq!(model.find_by_name("Car").len(), 1);
+        assert_eq!(model.find_by_name("car").len(), 1);
+
+        // The cross-file specialization resolves to the real Vehicle element
+        let specialization = model
+            .iter_relationship_elements()
+            .find(|e| e.kind == super::super::model::ElementKind::Specialization)
+            .expect("Should have a specialization");
+        let rel_data = specialization.relationship.as_ref().unwrap();
+        let target = model
+            .elements
+            .get(&rel_data.target[0])
+            .expect("Target element should exist");
+        assert_eq!(target.qualified_name.as_deref(), Some("Types::Vehicle"));
+    }
+
+    #[test]
+    fn test_model_from_database_matches_analysis_host() {
+        let source = "package P {\n    part def Engine;\n    part def Car {\n        part engine : Engine;\n    }\n}";
+
+        let mut db = RootDatabase::new();
+        db.set_file_text(FileId::new(0), source);
+        let from_db = model_from_database(&db);
+
+        let mut host = AnalysisHost::new();
+        host.set_file_content("model.sysml", source);
+        let from_host = host.model();
+
+        assert_eq!(from_db.element_count(), from_host.element_count());
+        assert_eq!(from_db.relationship_count(), from_host.relationship_count());
+        for name in ["P", "Engine", "Car", "engine"] {
+            assert_eq!(
+                from_db.find_by_name(name).len(),
+                from_host.find_by_name(name).len(),
+                "element count mismatch for '{}'",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_model_from_database_tracks_edits_and_removals() {
+        let mut db = RootDatabase::new();
+        db.set_file_text(FileId::new(0), "package A { part def X; }");
+        db.set_file_text(FileId::new(1), "package B { part def Y; }");
+        assert_eq!(model_from_database(&db).find_by_name("X").len(), 1);
+
+        // Edit: X is renamed to Z
+        db.set_file_text(FileId::new(0), "package A { part def Z; }");
+        let model = model_from_database(&db);
+        assert!(model.find_by_name("X").is_empty());
+        assert_eq!(model.find_by_name("Z").len(), 1);
+
+        // Removal: B disappears from the model
+        db.remove_file(FileId::new(1));
+        let model = model_from_database(&db);
+        assert!(model.find_by_name("Y").is_empty());
+        assert_eq!(model.roots.len(), 1);
+    }
+
     #[test]
     fn test_model_from_symbols_perform_chain_usage_uses_target_name_and_reference_subsetting() {
         let sysml = r#"
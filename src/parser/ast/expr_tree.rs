@@ -0,0 +1,798 @@
+use super::*;
+use crate::parser::SyntaxElement;
+
+// ============================================================================
+// Expr — typed expression tree lowered from an Expression CST node
+// ============================================================================
+//
+// The expression grammar doesn't build a node per precedence level: operators
+// and operands of one expression are flat children of a single EXPRESSION
+// node, with nested EXPRESSION nodes only where a sub-expression is parsed on
+// its own (arguments, branches, bodies, sequence items). Lowering re-applies
+// the precedence chain from `grammar::kerml_expressions` to those children.
+
+/// A literal value in an expression.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub enum Literal {
+    /// Integer literal (e.g., `100`)
+    Integer(i64),
+    /// Real/decimal literal (e.g., `0.75`)
+    Real(f64),
+    /// String literal — stored without quotes
+    String(String),
+    /// Boolean literal (`true` or `false`)
+    Boolean(bool),
+    /// `null` or `()`
+    Null,
+    /// `*`
+    Infinity,
+}
+
+// Same bit-pattern equality as `ValueExpression` so trees can be hashed.
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Literal::Integer(a), Literal::Integer(b)) => a == b,
+            (Literal::Real(a), Literal::Real(b)) => a.to_bits() == b.to_bits(),
+            (Literal::String(a), Literal::String(b)) => a == b,
+            (Literal::Boolean(a), Literal::Boolean(b)) => a == b,
+            (Literal::Null, Literal::Null) | (Literal::Infinity, Literal::Infinity) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Literal {}
+
+impl std::hash::Hash for Literal {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Literal::Integer(v) => v.hash(state),
+            Literal::Real(v) => v.to_bits().hash(state),
+            Literal::String(v) => v.hash(state),
+            Literal::Boolean(v) => v.hash(state),
+            Literal::Null | Literal::Infinity => {}
+        }
+    }
+}
+
+/// Prefix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOp {
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `~`
+    BitNot,
+    /// `not`
+    Not,
+    /// `all` (extent)
+    All,
+}
+
+/// Infix operators, from loosest to tightest binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOp {
+    /// `??`
+    NullCoalesce,
+    /// `implies`
+    Implies,
+    /// `|` or `or`
+    Or,
+    /// `xor`
+    Xor,
+    /// `&` or `and`
+    And,
+    /// `union`
+    Union,
+    /// `==`
+    Eq,
+    /// `!=`
+    NotEq,
+    /// `===`
+    Same,
+    /// `!==`
+    NotSame,
+    /// `<`
+    Lt,
+    /// `<=`
+    LtEq,
+    /// `>`
+    Gt,
+    /// `>=`
+    GtEq,
+    /// `..`
+    Range,
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+    /// `**` or `^`
+    Pow,
+}
+
+impl BinaryOp {
+    fn from_kind(kind: SyntaxKind) -> Option<Self> {
+        Some(match kind {
+            SyntaxKind::QUESTION_QUESTION => BinaryOp::NullCoalesce,
+            SyntaxKind::IMPLIES_KW => BinaryOp::Implies,
+            SyntaxKind::PIPE | SyntaxKind::OR_KW => BinaryOp::Or,
+            SyntaxKind::XOR_KW => BinaryOp::Xor,
+            SyntaxKind::AMP | SyntaxKind::AND_KW => BinaryOp::And,
+            SyntaxKind::UNION_KW => BinaryOp::Union,
+            SyntaxKind::EQ_EQ => BinaryOp::Eq,
+            SyntaxKind::BANG_EQ => BinaryOp::NotEq,
+            SyntaxKind::EQ_EQ_EQ => BinaryOp::Same,
+            SyntaxKind::BANG_EQ_EQ => BinaryOp::NotSame,
+            SyntaxKind::LT => BinaryOp::Lt,
+            SyntaxKind::LT_EQ => BinaryOp::LtEq,
+            SyntaxKind::GT => BinaryOp::Gt,
+            SyntaxKind::GT_EQ => BinaryOp::GtEq,
+            SyntaxKind::DOT_DOT => BinaryOp::Range,
+            SyntaxKind::PLUS => BinaryOp::Add,
+            SyntaxKind::MINUS => BinaryOp::Sub,
+            SyntaxKind::STAR => BinaryOp::Mul,
+            SyntaxKind::SLASH => BinaryOp::Div,
+            SyntaxKind::PERCENT => BinaryOp::Rem,
+            SyntaxKind::STAR_STAR | SyntaxKind::CARET => BinaryOp::Pow,
+            _ => return None,
+        })
+    }
+
+    /// Binding power; classification operators sit between equality (7) and
+    /// relational (9) at [`CLASSIFICATION_PRECEDENCE`].
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::NullCoalesce => 1,
+            BinaryOp::Implies => 2,
+            BinaryOp::Or => 3,
+            BinaryOp::Xor => 4,
+            BinaryOp::And => 5,
+            BinaryOp::Union => 6,
+            BinaryOp::Eq | BinaryOp::NotEq | BinaryOp::Same | BinaryOp::NotSame => 7,
+            BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => 9,
+            BinaryOp::Range => 10,
+            BinaryOp::Add | BinaryOp::Sub => 11,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 12,
+            BinaryOp::Pow => 13,
+        }
+    }
+}
+
+const CLASSIFICATION_PRECEDENCE: u8 = 8;
+
+/// Classification operators taking a type on their right-hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClassificationOp {
+    /// `hastype`
+    HasType,
+    /// `istype` or `@`
+    IsType,
+    /// `as`
+    As,
+    /// `meta`
+    Meta,
+    /// `@@`
+    MetaIsType,
+}
+
+impl ClassificationOp {
+    fn from_kind(kind: SyntaxKind) -> Option<Self> {
+        Some(match kind {
+            SyntaxKind::HASTYPE_KW => ClassificationOp::HasType,
+            SyntaxKind::ISTYPE_KW | SyntaxKind::AT => ClassificationOp::IsType,
+            SyntaxKind::AS_KW => ClassificationOp::As,
+            SyntaxKind::META_KW => ClassificationOp::Meta,
+            SyntaxKind::AT_AT => ClassificationOp::MetaIsType,
+            _ => return None,
+        })
+    }
+}
+
+/// An argument of an invocation, `name = value` when named.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub struct Argument {
+    pub name: Option<String>,
+    pub value: Expr,
+}
+
+/// A typed expression tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr {
+    /// A literal value
+    Literal(Literal),
+    /// A (possibly qualified) name, e.g. `wheelMass` or `ISQ::mass`
+    Name(String),
+    /// Feature chaining, e.g. `engine.mass`
+    Member { target: Box<Expr>, feature: String },
+    /// Prefix operator application
+    Unary { op: UnaryOp, operand: Box<Expr> },
+    /// Infix operator application
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    /// `x istype T`, `x as T`, ... (`operand` is `None` for the prefix form `istype T`)
+    Classification {
+        op: ClassificationOp,
+        operand: Option<Box<Expr>>,
+        type_name: String,
+    },
+    /// `if c then a else b`, `if c ? a else b` or `c ? a : b`
+    Conditional {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Option<Box<Expr>>,
+    },
+    /// `exists a, b : predicate`
+    Exists { names: Vec<String>, body: Box<Expr> },
+    /// `f(args)` or `C { a = value; }`
+    Invocation {
+        target: Box<Expr>,
+        args: Vec<Argument>,
+    },
+    /// `target->name(args)`, `target->name { body }` and the `.?{}` / `.{}` shorthands
+    CollectionCall {
+        target: Box<Expr>,
+        name: String,
+        args: Vec<Argument>,
+    },
+    /// `{ in x; result }` — a body with its `in` parameters
+    Body {
+        params: Vec<String>,
+        result: Box<Expr>,
+    },
+    /// `new T(args)`
+    New {
+        type_name: String,
+        args: Vec<Argument>,
+    },
+    /// `(a, b, c)`
+    Sequence(Vec<Expr>),
+    /// `target#(i)`
+    Index {
+        target: Box<Expr>,
+        indices: Vec<Expr>,
+    },
+    /// `target[index]`, e.g. a quantity with its unit `5 [kg]`
+    Bracket { target: Box<Expr>, index: Box<Expr> },
+    /// Text that couldn't be lowered
+    Error,
+}
+
+impl Expression {
+    /// Lower this expression into a typed [`Expr`] tree.
+    ///
+    /// Returns [`Expr::Error`] if the expression has syntax errors or uses a
+    /// form the lowering doesn't understand.
+    pub fn tree(&self) -> Expr {
+        lower_expression(&self.0)
+    }
+}
+
+fn lower_expression(node: &SyntaxNode) -> Expr {
+    let mut lowering = Lowering::new(node);
+    let expr = lowering.expression();
+    if lowering.at_end() { expr } else { Expr::Error }
+}
+
+/// Cursor over the non-trivia children of one EXPRESSION node.
+struct Lowering {
+    elements: Vec<SyntaxElement>,
+    pos: usize,
+}
+
+impl Lowering {
+    fn new(node: &SyntaxNode) -> Self {
+        Self {
+            elements: node
+                .children_with_tokens()
+                .filter(|el| !el.kind().is_trivia())
+                .collect(),
+            pos: 0,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.elements.len()
+    }
+
+    fn nth_kind(&self, n: usize) -> Option<SyntaxKind> {
+        self.elements.get(self.pos + n).map(|el| el.kind())
+    }
+
+    fn at(&self, kind: SyntaxKind) -> bool {
+        self.nth_kind(0) == Some(kind)
+    }
+
+    fn bump(&mut self) -> Option<SyntaxElement> {
+        let el = self.elements.get(self.pos).cloned();
+        self.pos += 1;
+        el
+    }
+
+    fn eat(&mut self, kind: SyntaxKind) -> bool {
+        if self.at(kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn node(&mut self, kind: SyntaxKind) -> Option<SyntaxNode> {
+        if self.at(kind) {
+            self.bump().and_then(|el| el.into_node())
+        } else {
+            None
+        }
+    }
+
+    /// A nested EXPRESSION node, lowered on its own.
+    fn nested_expression(&mut self) -> Expr {
+        match self.node(SyntaxKind::EXPRESSION) {
+            Some(node) => lower_expression(&node),
+            None => Expr::Error,
+        }
+    }
+
+    fn expression(&mut self) -> Expr {
+        if self.eat(SyntaxKind::IF_KW) {
+            let condition = self.binary(0);
+            if !self.eat(SyntaxKind::THEN_KW) && !self.eat(SyntaxKind::QUESTION) {
+                return Expr::Error;
+            }
+            return self.branches(condition);
+        }
+
+        if self.eat(SyntaxKind::EXISTS_KW) {
+            let mut names = Vec::new();
+            while let Some(el) = self.bump() {
+                match el.kind() {
+                    SyntaxKind::COMMA => {}
+                    SyntaxKind::COLON => break,
+                    _ => match el.into_token() {
+                        Some(token) => names.push(strip_unrestricted_name(token.text())),
+                        None => return Expr::Error,
+                    },
+                }
+            }
+            return Expr::Exists {
+                names,
+                body: Box::new(self.nested_expression()),
+            };
+        }
+
+        let expr = self.binary(0);
+        if self.eat(SyntaxKind::QUESTION) {
+            let then_branch = self.nested_expression();
+            if !self.eat(SyntaxKind::COLON) {
+                return Expr::Error;
+            }
+            return Expr::Conditional {
+                condition: Box::new(expr),
+                then_branch: Box::new(then_branch),
+                else_branch: Some(Box::new(self.nested_expression())),
+            };
+        }
+        expr
+    }
+
+    fn branches(&mut self, condition: Expr) -> Expr {
+        let then_branch = self.nested_expression();
+        let else_branch = if self.eat(SyntaxKind::ELSE_KW) {
+            Some(Box::new(self.nested_expression()))
+        } else {
+            None
+        };
+        Expr::Conditional {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
+        }
+    }
+
+    /// Precedence climbing over the flat operator/operand sequence.
+    fn binary(&mut self, min_precedence: u8) -> Expr {
+        let mut lhs = self.prefix();
+
+        while let Some(kind) = self.nth_kind(0) {
+            if let Some(op) = ClassificationOp::from_kind(kind) {
+                if CLASSIFICATION_PRECEDENCE < min_precedence {
+                    break;
+                }
+                self.bump();
+                let Some(type_name) = self.node(SyntaxKind::QUALIFIED_NAME) else {
+                    return Expr::Error;
+                };
+                lhs = Expr::Classification {
+                    op,
+                    operand: Some(Box::new(lhs)),
+                    type_name: qualified_name_text(&type_name),
+                };
+                continue;
+            }
+
+            let Some(op) = BinaryOp::from_kind(kind) else {
+                break;
+            };
+            let precedence = op.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            self.bump();
+            // `**` is right-associative; everything else folds left
+            let next_min = if op == BinaryOp::Pow {
+                precedence
+            } else {
+                precedence + 1
+            };
+            let rhs = self.binary(next_min);
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+
+        lhs
+    }
+
+    fn prefix(&mut self) -> Expr {
+        let Some(kind) = self.nth_kind(0) else {
+            return Expr::Error;
+        };
+
+        // Prefix classification with an implicit `self` operand: `istype T`, `@T`
+        if matches!(
+            kind,
+            SyntaxKind::HASTYPE_KW | SyntaxKind::ISTYPE_KW | SyntaxKind::AT
+        ) {
+            self.bump();
+            return match self.node(SyntaxKind::QUALIFIED_NAME) {
+                Some(type_name) => Expr::Classification {
+                    op: ClassificationOp::from_kind(kind).unwrap_or(ClassificationOp::IsType),
+                    operand: None,
+                    type_name: qualified_name_text(&type_name),
+                },
+                None => Expr::Error,
+            };
+        }
+
+        let op = match kind {
+            SyntaxKind::PLUS => UnaryOp::Plus,
+            SyntaxKind::MINUS => UnaryOp::Minus,
+            SyntaxKind::TILDE => UnaryOp::BitNot,
+            SyntaxKind::NOT_KW => UnaryOp::Not,
+            SyntaxKind::ALL_KW => UnaryOp::All,
+            _ => return self.primary(),
+        };
+        self.bump();
+        Expr::Unary {
+            op,
+            operand: Box::new(self.prefix()),
+        }
+    }
+
+    fn primary(&mut self) -> Expr {
+        let mut expr = self.base();
+
+        loop {
+            match self.nth_kind(0) {
+                Some(SyntaxKind::DOT) => match self.nth_kind(1) {
+                    Some(SyntaxKind::QUESTION) => {
+                        self.pos += 2;
+                        expr = self.shorthand_call(expr, "select");
+                    }
+                    Some(SyntaxKind::L_BRACE) => {
+                        self.pos += 1;
+                        expr = self.shorthand_call(expr, "collect");
+                    }
+                    _ => {
+                        self.bump();
+                        let Some(feature) = self.bump().and_then(|el| el.into_token()) else {
+                            return Expr::Error;
+                        };
+                        expr = Expr::Member {
+                            target: Box::new(expr),
+                            feature: strip_unrestricted_name(feature.text()),
+                        };
+                        if let Some(args) = self.node(SyntaxKind::ARGUMENT_LIST) {
+                            expr = Expr::Invocation {
+                                target: Box::new(expr),
+                                args: lower_arguments(&args),
+                            };
+                        }
+                    }
+                },
+                Some(SyntaxKind::ARROW) => {
+                    self.bump();
+                    let name = match self.bump().and_then(|el| el.into_token()) {
+                        Some(token) => strip_unrestricted_name(token.text()),
+                        None => return Expr::Error,
+                    };
+                    let args = match self.nth_kind(0) {
+                        Some(SyntaxKind::L_BRACE) => vec![Argument {
+                            name: None,
+                            value: self.body(),
+                        }],
+                        Some(SyntaxKind::ARGUMENT_LIST) => self
+                            .node(SyntaxKind::ARGUMENT_LIST)
+                            .map(|args| lower_arguments(&args))
+                            .unwrap_or_default(),
+                        Some(SyntaxKind::EXPRESSION) => vec![Argument {
+                            name: None,
+                            value: self.nested_expression(),
+                        }],
+                        _ => Vec::new(),
+                    };
+                    expr = Expr::CollectionCall {
+                        target: Box::new(expr),
+                        name,
+                        args,
+                    };
+                }
+                Some(SyntaxKind::HASH) => {
+                    self.bump();
+                    if !self.eat(SyntaxKind::L_PAREN) {
+                        return Expr::Error;
+                    }
+                    let mut indices = vec![self.nested_expression()];
+                    while self.eat(SyntaxKind::COMMA) {
+                        indices.push(self.nested_expression());
+                    }
+                    if !self.eat(SyntaxKind::R_PAREN) {
+                        return Expr::Error;
+                    }
+                    expr = Expr::Index {
+                        target: Box::new(expr),
+                        indices,
+                    };
+                }
+                Some(SyntaxKind::L_BRACKET) => {
+                    self.bump();
+                    let index = self.nested_expression();
+                    if !self.eat(SyntaxKind::R_BRACKET) {
+                        return Expr::Error;
+                    }
+                    expr = Expr::Bracket {
+                        target: Box::new(expr),
+                        index: Box::new(index),
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        expr
+    }
+
+    fn shorthand_call(&mut self, target: Expr, name: &str) -> Expr {
+        Expr::CollectionCall {
+            target: Box::new(target),
+            name: name.to_string(),
+            args: vec![Argument {
+                name: None,
+                value: self.body(),
+            }],
+        }
+    }
+
+    fn base(&mut self) -> Expr {
+        let Some(el) = self.bump() else {
+            return Expr::Error;
+        };
+
+        let token = match el {
+            rowan::NodeOrToken::Node(node) => {
+                return match node.kind() {
+                    SyntaxKind::QUALIFIED_NAME => {
+                        let expr = lower_qualified_name(&node);
+                        match self.node(SyntaxKind::ARGUMENT_LIST) {
+                            Some(args) => Expr::Invocation {
+                                target: Box::new(expr),
+                                args: lower_arguments(&args),
+                            },
+                            None => expr,
+                        }
+                    }
+                    SyntaxKind::EXPRESSION => lower_expression(&node),
+                    _ => Expr::Error,
+                };
+            }
+            rowan::NodeOrToken::Token(token) => token,
+        };
+
+        match token.kind() {
+            SyntaxKind::INTEGER => Expr::Literal(match token.text().parse::<i64>() {
+                Ok(v) => Literal::Integer(v),
+                Err(_) => match token.text().parse::<f64>() {
+                    Ok(v) => Literal::Real(v),
+                    Err(_) => return Expr::Error,
+                },
+            }),
+            SyntaxKind::DECIMAL => match token.text().parse::<f64>() {
+                Ok(v) => Expr::Literal(Literal::Real(v)),
+                Err(_) => Expr::Error,
+            },
+            SyntaxKind::STRING => Expr::Literal(Literal::String(unquote(token.text()))),
+            SyntaxKind::TRUE_KW => Expr::Literal(Literal::Boolean(true)),
+            SyntaxKind::FALSE_KW => Expr::Literal(Literal::Boolean(false)),
+            SyntaxKind::NULL_KW => Expr::Literal(Literal::Null),
+            // A `*` in operand position is the unbounded multiplicity, e.g. `0..*`
+            SyntaxKind::INFINITY_KW | SyntaxKind::STAR => Expr::Literal(Literal::Infinity),
+            SyntaxKind::NEW_KW => {
+                let Some(type_name) = self.node(SyntaxKind::QUALIFIED_NAME) else {
+                    return Expr::Error;
+                };
+                Expr::New {
+                    type_name: qualified_name_text(&type_name),
+                    args: self
+                        .node(SyntaxKind::ARGUMENT_LIST)
+                        .map(|args| lower_arguments(&args))
+                        .unwrap_or_default(),
+                }
+            }
+            SyntaxKind::L_PAREN => {
+                let mut items = Vec::new();
+                while !self.eat(SyntaxKind::R_PAREN) {
+                    if self.at_end() {
+                        return Expr::Error;
+                    }
+                    if !items.is_empty() && !self.eat(SyntaxKind::COMMA) {
+                        return Expr::Error;
+                    }
+                    items.push(self.nested_expression());
+                }
+                match items.len() {
+                    0 => Expr::Literal(Literal::Null),
+                    1 => items.pop().unwrap_or(Expr::Error),
+                    _ => Expr::Sequence(items),
+                }
+            }
+            SyntaxKind::L_BRACE => {
+                // Block expression `{ expr }`; step back so `body` sees the brace
+                self.pos -= 1;
+                self.body()
+            }
+            _ => Expr::Error,
+        }
+    }
+
+    /// A `{ ... }` body whose tokens are flat children of the expression.
+    ///
+    /// `in` parameters are collected by name; the last top-level expression
+    /// is the result.
+    fn body(&mut self) -> Expr {
+        if !self.eat(SyntaxKind::L_BRACE) {
+            return Expr::Error;
+        }
+
+        let mut params = Vec::new();
+        let mut result = None;
+        let mut depth = 0usize;
+        while let Some(el) = self.bump() {
+            match el.kind() {
+                SyntaxKind::L_BRACE => depth += 1,
+                SyntaxKind::R_BRACE if depth == 0 => {
+                    return Expr::Body {
+                        params,
+                        result: Box::new(result.unwrap_or(Expr::Error)),
+                    };
+                }
+                SyntaxKind::R_BRACE => depth -= 1,
+                SyntaxKind::IN_KW if depth == 0 && self.nth_kind(0).is_some_and(is_name_token) => {
+                    if let Some(name) = self.bump().and_then(|el| el.into_token()) {
+                        params.push(strip_unrestricted_name(name.text()));
+                    }
+                }
+                SyntaxKind::EXPRESSION if depth == 0 => {
+                    result = el.into_node().map(|node| lower_expression(&node));
+                }
+                _ => {}
+            }
+        }
+        Expr::Error
+    }
+}
+
+/// Lower a QUALIFIED_NAME node: `::` segments form the name, `.` segments
+/// are feature chaining on it.
+fn lower_qualified_name(node: &SyntaxNode) -> Expr {
+    let mut segments = Vec::new();
+    let mut members = Vec::new();
+    let mut in_chain = false;
+    for token in node
+        .children_with_tokens()
+        .filter_map(|el| el.into_token())
+        .filter(|t| !t.kind().is_trivia())
+    {
+        match token.kind() {
+            SyntaxKind::COLON_COLON => {}
+            SyntaxKind::DOT => in_chain = true,
+            _ if in_chain => members.push(strip_unrestricted_name(token.text())),
+            _ => segments.push(strip_unrestricted_name(token.text())),
+        }
+    }
+
+    let mut expr = Expr::Name(segments.join("::"));
+    for feature in members {
+        expr = Expr::Member {
+            target: Box::new(expr),
+            feature,
+        };
+    }
+    expr
+}
+
+fn qualified_name_text(node: &SyntaxNode) -> String {
+    node.children_with_tokens()
+        .filter_map(|el| el.into_token())
+        .filter(|t| !t.kind().is_trivia())
+        .map(|t| match t.kind() {
+            SyntaxKind::COLON_COLON | SyntaxKind::DOT => t.text().to_string(),
+            _ => strip_unrestricted_name(t.text()),
+        })
+        .collect()
+}
+
+/// Lower an ARGUMENT_LIST node, either `(a, name = b)` or `{ name = b; }`.
+fn lower_arguments(list: &SyntaxNode) -> Vec<Argument> {
+    let mut args = Vec::new();
+    for child in list.children() {
+        match child.kind() {
+            SyntaxKind::ARGUMENT_LIST => {
+                let mut tokens = child
+                    .children_with_tokens()
+                    .filter(|el| !el.kind().is_trivia());
+                let name = match (tokens.next(), tokens.next()) {
+                    (Some(rowan::NodeOrToken::Token(name)), Some(eq))
+                        if eq.kind() == SyntaxKind::EQ =>
+                    {
+                        Some(strip_unrestricted_name(name.text()))
+                    }
+                    _ => None,
+                };
+                let value = child
+                    .children()
+                    .find(|n| n.kind() == SyntaxKind::EXPRESSION)
+                    .map(|n| lower_expression(&n))
+                    .unwrap_or(Expr::Error);
+                args.push(Argument { name, value });
+            }
+            SyntaxKind::EXPRESSION => args.push(Argument {
+                name: None,
+                value: lower_expression(&child),
+            }),
+            _ => {}
+        }
+    }
+    args
+}
+
+/// Strip the quotes of a string literal.
+fn unquote(text: &str) -> String {
+    if text.len() >= 2
+        && ((text.starts_with('"') && text.ends_with('"'))
+            || (text.starts_with('\'') && text.ends_with('\'')))
+    {
+        text[1..text.len() - 1].to_string()
+    } else {
+        text.to_string()
+    }
+}
@@ -6,6 +6,7 @@ use super::*;
 
 /// A value expression assigned to a feature (e.g., `= 42`, `= "hello"`, `= true`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueExpression {
     /// Integer literal (e.g., `100`)
     LiteralInteger(i64),
@@ -17,8 +18,8 @@ pub enum ValueExpression {
     LiteralBoolean(bool),
     /// Null literal
     Null,
-    /// A non-literal expression, stored as raw source text
-    Expression(String),
+    /// A non-literal expression: its source text and typed tree
+    Expression { text: String, expr: Expr },
 }
 
 // Manual Eq impl because f64 doesn't implement Eq (NaN != NaN).
@@ -35,7 +36,10 @@ impl std::hash::Hash for ValueExpression {
             ValueExpression::LiteralString(v) => v.hash(state),
             ValueExpression::LiteralBoolean(v) => v.hash(state),
             ValueExpression::Null => {}
-            ValueExpression::Expression(v) => v.hash(state),
+            ValueExpression::Expression { text, expr } => {
+                text.hash(state);
+                expr.hash(state);
+            }
         }
     }
 }
@@ -43,7 +47,7 @@ impl std::hash::Hash for ValueExpression {
 /// Extract a `ValueExpression` from a parser `Expression` node.
 ///
 /// For simple literals (single token), returns a typed variant.
-/// For complex expressions, falls back to storing the raw source text.
+/// Anything else keeps its source text alongside the lowered [`Expr`] tree.
 pub fn extract_value_expression(expr: &Expression) -> ValueExpression {
     let syntax = expr.syntax();
     // Collect non-trivia tokens from the expression
@@ -95,8 +99,10 @@ pub fn extract_value_expression(expr: &Expression) -> ValueExpression {
             return ValueExpression::Null;
         }
     }
-    // Fallback: store the full expression text
-    ValueExpression::Expression(syntax.text().to_string().trim().to_string())
+    ValueExpression::Expression {
+        text: syntax.text().to_string().trim().to_string(),
+        expr: expr.tree(),
+    }
 }
 
 // ============================================================================
@@ -105,6 +111,7 @@ pub fn extract_value_expression(expr: &Expression) -> ValueExpression {
 
 /// Multiplicity bounds (lower, upper) where None means unbounded (*)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct Multiplicity {
     pub lower: Option<u64>,
     pub upper: Option<u64>,
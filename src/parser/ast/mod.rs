@@ -4,7 +4,7 @@
 //! Each struct wraps a SyntaxNode and provides methods to access children.
 
 use super::syntax_kind::SyntaxKind;
-use super::{SyntaxNode, SyntaxToken};
+use super::{SyntaxNode, SyntaxToken, TextRange, TextSize};
 // ============================================================================
 // Helper utilities for reducing code duplication
 // ============================================================================
@@ -24,6 +24,9 @@ fn is_name_token(kind: SyntaxKind) -> bool {
             | SyntaxKind::THIS_KW
             | SyntaxKind::MEMBER_KW
             | SyntaxKind::FRAME_KW // Used as name: `in frame : SpatialFrame`
+            | SyntaxKind::ENTRY_KW // Used as names in KerML: `step entry[1];`
+            | SyntaxKind::DO_KW
+            | SyntaxKind::EXIT_KW
     )
 }
 
@@ -361,6 +364,41 @@ fn split_at_keyword<T: AstNode>(node: &SyntaxNode, keyword: SyntaxKind) -> (Vec<
     (before, after)
 }
 
+/// Bounds of a MULTIPLICITY node as `(lower, upper)`, where None means `*`.
+///
+/// Only literal bounds are known statically: a bound given by an expression
+/// (`[1..n-1]`, `[size(x)]`) makes the whole multiplicity `None`. A single
+/// bound is both the lower and upper one (`[4]` -> `(Some(4), Some(4))`).
+fn multiplicity_bounds(node: &SyntaxNode) -> Option<(Option<u64>, Option<u64>)> {
+    let tokens: Vec<SyntaxToken> = node
+        .descendants_with_tokens()
+        .filter_map(|elem| elem.into_token())
+        .filter(|token| {
+            !token.kind().is_trivia()
+                && !matches!(
+                    token.kind(),
+                    SyntaxKind::L_BRACKET
+                        | SyntaxKind::R_BRACKET
+                        | SyntaxKind::ORDERED_KW
+                        | SyntaxKind::NONUNIQUE_KW
+                )
+        })
+        .collect();
+
+    let bound = |tokens: &[SyntaxToken]| match tokens {
+        [token] if token.kind() == SyntaxKind::INTEGER => token.text().parse().ok().map(Some),
+        [token] if token.kind() == SyntaxKind::STAR => Some(None),
+        _ => None,
+    };
+    match tokens.iter().position(|t| t.kind() == SyntaxKind::DOT_DOT) {
+        Some(dot_dot) => Some((bound(&tokens[..dot_dot])?, bound(&tokens[dot_dot + 1..])?)),
+        None => {
+            let bound = bound(&tokens)?;
+            Some((bound, bound))
+        }
+    }
+}
+
 /// Trait for AST nodes that wrap a SyntaxNode
 pub trait AstNode: Sized {
     fn can_cast(kind: SyntaxKind) -> bool;
@@ -483,6 +521,60 @@ fn clean_doc_comment(s: &str) -> String {
         .join("\n")
 }
 
+/// The range of `node` without its leading and trailing trivia.
+pub fn trimmed_range(node: &SyntaxNode) -> TextRange {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.kind().is_trivia());
+    let Some(first) = tokens.next() else {
+        return node.text_range();
+    };
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+    TextRange::new(first.text_range().start(), last.text_range().end())
+}
+
+/// The range of a member's declaration, with its visibility keyword.
+pub fn declaration_range(node: &SyntaxNode) -> TextRange {
+    let range = trimmed_range(node);
+    let visibility = node
+        .siblings_with_tokens(rowan::Direction::Prev)
+        .skip(1)
+        .find(|element| !element.kind().is_trivia())
+        .filter(|element| {
+            matches!(
+                element.kind(),
+                SyntaxKind::PUBLIC_KW | SyntaxKind::PRIVATE_KW | SyntaxKind::PROTECTED_KW
+            )
+        });
+    match visibility {
+        Some(visibility) => TextRange::new(visibility.text_range().start(), range.end()),
+        None => range,
+    }
+}
+
+/// The range deleting a member's declaration removes from `text`, the text
+/// of its file: its whole lines if nothing else is on them, else the
+/// declaration and the blanks after it.
+pub fn declaration_removal_range(node: &SyntaxNode, text: &str) -> TextRange {
+    let range = declaration_range(node);
+    let line_start = text[..usize::from(range.start())]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let starts_line = text[line_start..usize::from(range.start())]
+        .chars()
+        .all(char::is_whitespace);
+    let rest = &text[usize::from(range.end())..];
+    let trailing = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    let rest = &rest[trailing..];
+    if starts_line && (rest.is_empty() || rest.starts_with('\n')) {
+        let end = usize::from(range.end()) + trailing + usize::from(rest.starts_with('\n'));
+        TextRange::new(TextSize::from(line_start as u32), TextSize::from(end as u32))
+    } else {
+        TextRange::new(range.start(), range.end() + TextSize::from(trailing as u32))
+    }
+}
+
 /// Trait for AST tokens that wrap a SyntaxToken
 pub trait AstToken: Sized {
     fn can_cast(kind: SyntaxKind) -> bool;
@@ -524,12 +616,14 @@ macro_rules! ast_node {
 
 // Submodules — declared after macros so macro_rules! are in scope
 mod elements;
+mod expr_tree;
 mod expressions;
 mod namespace;
 mod relationships;
 
 // Re-export all public types so external code sees a flat namespace
 pub use self::elements::*;
+pub use self::expr_tree::*;
 pub use self::expressions::*;
 pub use self::namespace::*;
 pub use self::relationships::*;
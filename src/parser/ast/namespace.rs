@@ -164,6 +164,23 @@ impl AstNode for NamespaceMember {
     }
 }
 
+/// The innermost namespace member at `offset` in the tree under `root`: the
+/// member of the token starting there, or of the token ending there when the
+/// former is trivia.
+pub fn member_at(root: &SyntaxNode, offset: TextSize) -> Option<SyntaxNode> {
+    if offset > root.text_range().end() {
+        return None;
+    }
+    let tokens = root.token_at_offset(offset);
+    let token = match tokens.clone().right_biased() {
+        Some(token) if !token.kind().is_trivia() => token,
+        _ => tokens.left_biased()?,
+    };
+    token
+        .parent_ancestors()
+        .find(|node| NamespaceMember::can_cast(node.kind()))
+}
+
 // ============================================================================
 // Package
 // ============================================================================
@@ -6,7 +6,9 @@ use super::*;
 ast_node!(Typing, TYPING);
 
 impl Typing {
+    has_token_method!(is_conjugated, TILDE, "port fuelIn : ~FuelPort;");
     first_child_method!(target, QualifiedName);
+    children_method!(targets, QualifiedName);
 }
 
 ast_node!(Specialization, SPECIALIZATION);
@@ -24,6 +26,14 @@ impl Specialization {
         FROM_KW => FeatureChain,
         TO_KW => FeatureChain,
         CHAINS_KW => FeatureChain,
+        DISJOINT_KW => Other,
+        INTERSECTS_KW => Other,
+        DIFFERENCES_KW => Other,
+        UNIONS_KW => Other,
+        INVERSE_KW => Other,
+        FEATURING_KW => Other,
+        CROSSES_KW => Other,
+        FAT_ARROW => Other,
     ]);
 
     /// Check if this is a shorthand redefines (`:>>`) vs keyword (`redefines`)
@@ -46,6 +56,10 @@ pub enum SpecializationKind {
     /// Per SysML v2 Spec §7.3.4.5: indicates a feature chain relationship.
     /// e.g., `feature x ::> a.b` or `feature self subsets things chains things.that`
     FeatureChain,
+    /// Relationships that share the specialization syntax but don't specialize:
+    /// `unions`, `intersects`, `differences`, `disjoint from`, `inverse of`,
+    /// `featuring` and `crosses` (`=>`).
+    Other,
 }
 
 // ============================================================================
@@ -406,6 +420,16 @@ impl ConnectorEnd {
         self.0.children().find_map(QualifiedName::cast)
     }
 
+    /// Get the end's multiplicity bounds, e.g. `[1]` in `connect [1] a to b`.
+    /// See [`Usage::multiplicity`] for the representation.
+    pub fn multiplicity(&self) -> Option<(Option<u64>, Option<u64>)> {
+        let mult_node = self
+            .0
+            .children()
+            .find(|n| n.kind() == SyntaxKind::MULTIPLICITY)?;
+        multiplicity_bounds(&mult_node)
+    }
+
     /// Get the endpoint name (LHS of ::> if present).
     /// For patterns like `cause1 ::> a`, returns `cause1`.
     /// For simple patterns like `comp.lugNutPort`, returns None.
@@ -155,3 +155,166 @@ fn test_parenthesized_literal_is_not_null() {
     let expr = first_expression(&parsed.syntax());
     assert_ne!(extract_value_expression(&expr), ValueExpression::Null);
 }
+
+fn expression_tree(value: &str) -> Expr {
+    let parsed = parse_sysml(&format!("package P {{ attribute x = {value}; }}"));
+    assert!(parsed.ok(), "errors: {:?}", parsed.errors);
+    first_expression(&parsed.syntax()).tree()
+}
+
+fn name(name: &str) -> Box<Expr> {
+    Box::new(Expr::Name(name.to_string()))
+}
+
+fn int(v: i64) -> Box<Expr> {
+    Box::new(Expr::Literal(Literal::Integer(v)))
+}
+
+#[test]
+fn test_expression_tree_precedence() {
+    // 2 * wheelMass + 10 ** 2 ** 3  ==>  (2 * wheelMass) + (10 ** (2 ** 3))
+    assert_eq!(
+        expression_tree("2 * wheelMass + 10 ** 2 ** 3"),
+        Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: Box::new(Expr::Binary {
+                op: BinaryOp::Mul,
+                lhs: int(2),
+                rhs: name("wheelMass"),
+            }),
+            rhs: Box::new(Expr::Binary {
+                op: BinaryOp::Pow,
+                lhs: int(10),
+                rhs: Box::new(Expr::Binary {
+                    op: BinaryOp::Pow,
+                    lhs: int(2),
+                    rhs: int(3),
+                }),
+            }),
+        }
+    );
+
+    // not a istype T and b  ==>  ((not a) istype T) and b
+    assert_eq!(
+        expression_tree("not a istype T and b"),
+        Expr::Binary {
+            op: BinaryOp::And,
+            lhs: Box::new(Expr::Classification {
+                op: ClassificationOp::IsType,
+                operand: Some(Box::new(Expr::Unary {
+                    op: UnaryOp::Not,
+                    operand: name("a"),
+                })),
+                type_name: "T".to_string(),
+            }),
+            rhs: name("b"),
+        }
+    );
+}
+
+#[test]
+fn test_expression_tree_chains_and_invocations() {
+    assert_eq!(
+        expression_tree("ISQ::mass.num"),
+        Expr::Member {
+            target: name("ISQ::mass"),
+            feature: "num".to_string(),
+        }
+    );
+    assert_eq!(
+        expression_tree("f(1, limit = a)"),
+        Expr::Invocation {
+            target: name("f"),
+            args: vec![
+                Argument {
+                    name: None,
+                    value: *int(1),
+                },
+                Argument {
+                    name: Some("limit".to_string()),
+                    value: *name("a"),
+                },
+            ],
+        }
+    );
+    assert_eq!(
+        expression_tree("xs->select { in x; x > 1 }->size()"),
+        Expr::CollectionCall {
+            target: Box::new(Expr::CollectionCall {
+                target: name("xs"),
+                name: "select".to_string(),
+                args: vec![Argument {
+                    name: None,
+                    value: Expr::Body {
+                        params: vec!["x".to_string()],
+                        result: Box::new(Expr::Binary {
+                            op: BinaryOp::Gt,
+                            lhs: name("x"),
+                            rhs: int(1),
+                        }),
+                    },
+                }],
+            }),
+            name: "size".to_string(),
+            args: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_expression_tree_conditionals_and_sequences() {
+    assert_eq!(
+        expression_tree("if a then 1 else 2"),
+        Expr::Conditional {
+            condition: name("a"),
+            then_branch: int(1),
+            else_branch: Some(int(2)),
+        }
+    );
+    assert_eq!(
+        expression_tree("exists a, b : a == b"),
+        Expr::Exists {
+            names: vec!["a".to_string(), "b".to_string()],
+            body: Box::new(Expr::Binary {
+                op: BinaryOp::Eq,
+                lhs: name("a"),
+                rhs: name("b"),
+            }),
+        }
+    );
+    assert_eq!(
+        expression_tree("(1, 2)#(1) union ys"),
+        Expr::Binary {
+            op: BinaryOp::Union,
+            lhs: Box::new(Expr::Index {
+                target: Box::new(Expr::Sequence(vec![*int(1), *int(2)])),
+                indices: vec![*int(1)],
+            }),
+            rhs: name("ys"),
+        }
+    );
+    assert_eq!(
+        expression_tree("5 [kg]"),
+        Expr::Bracket {
+            target: int(5),
+            index: name("kg"),
+        }
+    );
+}
+
+#[test]
+fn test_value_expression_keeps_source_text() {
+    let parsed = parse_sysml("package P { attribute x = -(1 + 2); }");
+    let expr = first_expression(&parsed.syntax());
+    let ValueExpression::Expression { text, expr } = extract_value_expression(&expr) else {
+        panic!("expected a non-literal expression");
+    };
+    assert_eq!(text, "-(1 + 2)");
+    assert!(matches!(
+        expr,
+        Expr::Unary {
+            op: UnaryOp::Minus,
+            ..
+        }
+    ));
+}
@@ -569,6 +569,15 @@ fn looks_like_expression<P: KerMLParser>(p: &P) -> bool {
     }
 }
 
+/// Parse one member of a braced body: a result expression or a namespace element
+pub fn parse_body_element<P: KerMLParser>(p: &mut P) {
+    if looks_like_expression(p) {
+        kerml_expressions::parse_expression(p);
+    } else {
+        parse_namespace_element(p);
+    }
+}
+
 /// Try to recover from parsing failure in body
 fn recover_body_element<P: KerMLParser>(p: &mut P) {
     let expr_start = p.get_pos();
@@ -600,11 +609,7 @@ pub fn parse_body<P: KerMLParser>(p: &mut P) {
         while !p.at(SyntaxKind::ERROR) && !p.at(SyntaxKind::R_BRACE) {
             let start_pos = p.get_pos();
 
-            if looks_like_expression(p) {
-                kerml_expressions::parse_expression(p);
-            } else {
-                parse_namespace_element(p);
-            }
+            parse_body_element(p);
 
             p.skip_trivia();
 
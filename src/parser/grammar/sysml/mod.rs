@@ -56,7 +56,7 @@ pub use self::definitions::{
     parse_constraint_body, parse_dependency, parse_filter, parse_metadata_usage,
     parse_redefines_feature_member, parse_shorthand_feature_member, parse_variant_usage,
 };
-pub use self::entry::parse_sysml_file;
+pub use self::entry::{parse_package_body_element, parse_sysml_file};
 
 /// SysML definition keywords (used with 'def')
 pub const SYSML_DEFINITION_KEYWORDS: &[SyntaxKind] = &[
@@ -29,6 +29,8 @@
 //! 1. Find the smallest subtree containing the change
 //! 2. Reparse only that subtree
 //! 3. Reuse unchanged green nodes (they're immutable and cheap to share)
+//!
+//! See [`reparse_sysml`] and [`reparse_kerml`].
 
 #[allow(clippy::module_inception)]
 mod parser;
@@ -37,11 +39,13 @@ pub mod ast;
 pub mod grammar;
 pub mod keywords;
 mod lexer;
+mod reparsing;
 mod syntax_kind;
 
 pub use ast::*;
 pub use lexer::{Lexer, Token};
 pub use parser::{Parse, SyntaxError, kind_to_name, parse_kerml, parse_sysml};
+pub use reparsing::{reparse_kerml, reparse_sysml};
 pub use syntax_kind::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SysMLLanguage};
 
 /// Re-export rowan types for convenience
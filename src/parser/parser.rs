@@ -10,6 +10,7 @@ use super::grammar::sysml::SysMLParser;
 use super::lexer::{Lexer, Token};
 use super::syntax_kind::SyntaxKind;
 use rowan::{GreenNode, GreenNodeBuilder, TextRange, TextSize};
+use std::cell::Cell;
 
 /// Parse result containing the green tree and any errors
 #[derive(Debug, Clone)]
@@ -62,6 +63,49 @@ pub fn parse_kerml(input: &str) -> Parse {
     parser.finish()
 }
 
+/// Parse already-lexed tokens as a single member of a SysML package body.
+///
+/// Returns `None` if the member does not span all tokens.
+pub(super) fn parse_sysml_member(tokens: &[Token<'_>], input: &str) -> Option<Parse> {
+    parse_member(tokens, input, |p| {
+        super::grammar::sysml::parse_package_body_element(p)
+    })
+}
+
+/// Parse already-lexed tokens as a single KerML namespace member.
+///
+/// `in_body` selects the rules of a braced body over those of the file's top level.
+/// Returns `None` if the member does not span all tokens.
+pub(super) fn parse_kerml_member(
+    tokens: &[Token<'_>],
+    input: &str,
+    in_body: bool,
+) -> Option<Parse> {
+    parse_member(tokens, input, |p| {
+        if in_body {
+            super::grammar::kerml::parse_body_element(p)
+        } else {
+            super::grammar::kerml::parse_namespace_element(p)
+        }
+    })
+}
+
+/// Run `parse_element` under a SOURCE_FILE root so the builder always has a single root.
+fn parse_member<'a>(
+    tokens: &'a [Token<'a>],
+    input: &'a str,
+    parse_element: impl FnOnce(&mut Parser<'a>),
+) -> Option<Parse> {
+    let mut parser = Parser::new(tokens, input);
+    parser.start_node(SyntaxKind::SOURCE_FILE);
+    parse_element(&mut parser);
+    // In context the lookahead would have seen the tokens after the member,
+    // so a parse that looked past the end may differ from the full parse
+    let complete = parser.at_eof() && !parser.peeked_past_end.get();
+    parser.finish_node();
+    complete.then(|| parser.finish())
+}
+
 /// Convert a SyntaxKind to a human-readable name for error messages.
 ///
 /// **Deprecated**: Use `kind.display_name()` instead.
@@ -85,6 +129,8 @@ struct Parser<'a> {
     errors: Vec<SyntaxError>,
     source: &'a str,
     depth: usize,
+    /// Set when lookahead runs past the last token (see `parse_member`)
+    peeked_past_end: Cell<bool>,
 }
 
 impl<'a> Parser<'a> {
@@ -96,6 +142,7 @@ impl<'a> Parser<'a> {
             errors: Vec::new(),
             source,
             depth: 0,
+            peeked_past_end: Cell::new(false),
         }
     }
 
@@ -166,6 +213,7 @@ impl<'a> Parser<'a> {
             }
             idx += 1;
         }
+        self.peeked_past_end.set(true);
         SyntaxKind::ERROR
     }
 
@@ -0,0 +1,399 @@
+//! Incremental reparsing
+//!
+//! After an edit, only the innermost member containing it (a package,
+//! definition or usage in a package body, a plain definition body or at the top
+//! of the file) is relexed and reparsed, and its new green node is spliced into
+//! the old tree. Everything outside the member is shared with the old tree.
+//!
+//! Members of action, state, calc, constraint, case and metadata bodies are not
+//! reparsed on their own, since those bodies parse members with their own
+//! rules; an edit there reparses the enclosing member instead.
+//!
+//! The edit is rejected (and the caller falls back to a full parse) when it
+//! reaches a member's first or last character, or when the edited member no
+//! longer lexes as one balanced block, since either could change how the
+//! surrounding text is parsed.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+use super::lexer::{Lexer, Token};
+use super::parser::{Parse, SyntaxError, parse_kerml_member, parse_sysml_member};
+use super::syntax_kind::{SyntaxKind, SyntaxNode};
+
+/// Reparse a SysML tree after replacing `range` of its text with `replace_with`.
+///
+/// Returns `None` if the edit can't be confined to a single member; the new
+/// text then has to be parsed with [`parse_sysml`](super::parse_sysml).
+pub fn reparse_sysml(parse: &Parse, range: TextRange, replace_with: &str) -> Option<Parse> {
+    reparse(parse, range, replace_with, |tokens, text, _| {
+        parse_sysml_member(tokens, text)
+    })
+}
+
+/// Reparse a KerML tree after replacing `range` of its text with `replace_with`.
+///
+/// Returns `None` if the edit can't be confined to a single member; the new
+/// text then has to be parsed with [`parse_kerml`](super::parse_kerml).
+pub fn reparse_kerml(parse: &Parse, range: TextRange, replace_with: &str) -> Option<Parse> {
+    reparse(parse, range, replace_with, |tokens, text, container| {
+        parse_kerml_member(tokens, text, container == SyntaxKind::NAMESPACE_BODY)
+    })
+}
+
+fn reparse(
+    parse: &Parse,
+    range: TextRange,
+    replace_with: &str,
+    parse_member: impl Fn(&[Token<'_>], &str, SyntaxKind) -> Option<Parse>,
+) -> Option<Parse> {
+    let root = parse.syntax();
+    if range.end() > root.text_range().end() {
+        return None;
+    }
+
+    // Innermost candidate first; an outer member may still absorb an edit the
+    // inner one can't (e.g. one that deletes the inner member's closing brace)
+    let start = match root.covering_element(range) {
+        NodeOrToken::Node(node) => Some(node),
+        NodeOrToken::Token(token) => token.parent(),
+    };
+    start
+        .into_iter()
+        .flat_map(|node| node.ancestors())
+        .filter(is_reparse_target)
+        .find_map(|node| reparse_member(parse, &node, range, replace_with, &parse_member))
+}
+
+/// Whether `node` is a member whose rule doesn't depend on its container.
+fn is_reparse_target(node: &SyntaxNode) -> bool {
+    if !matches!(
+        node.kind(),
+        SyntaxKind::PACKAGE
+            | SyntaxKind::LIBRARY_PACKAGE
+            | SyntaxKind::DEFINITION
+            | SyntaxKind::USAGE
+    ) {
+        return false;
+    }
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        SyntaxKind::SOURCE_FILE => true,
+        SyntaxKind::NAMESPACE_BODY => parent.parent().is_some_and(|owner| match owner.kind() {
+            SyntaxKind::PACKAGE | SyntaxKind::LIBRARY_PACKAGE => true,
+            SyntaxKind::DEFINITION => has_plain_definition_body(&owner),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// Whether `owner` is a SysML definition whose body members are parsed like
+/// package members (i.e. not a state, case or metadata definition).
+///
+/// KerML definitions have no `def` keyword and are never matched.
+fn has_plain_definition_body(owner: &SyntaxNode) -> bool {
+    let mut is_def = false;
+    for token in owner
+        .children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+    {
+        match token.kind() {
+            SyntaxKind::STATE_KW
+            | SyntaxKind::ANALYSIS_KW
+            | SyntaxKind::VERIFICATION_KW
+            | SyntaxKind::USE_KW
+            | SyntaxKind::METADATA_KW => return false,
+            SyntaxKind::DEF_KW => is_def = true,
+            _ => {}
+        }
+    }
+    is_def
+}
+
+fn reparse_member(
+    parse: &Parse,
+    node: &SyntaxNode,
+    range: TextRange,
+    replace_with: &str,
+    parse_member: &impl Fn(&[Token<'_>], &str, SyntaxKind) -> Option<Parse>,
+) -> Option<Parse> {
+    let node_range = node.text_range();
+    // The first and last characters delimit the member; keep them untouched
+    if range.start() <= node_range.start() || range.end() >= node_range.end() {
+        return None;
+    }
+
+    let mut text = node.text().to_string();
+    let local = range.checked_sub(node_range.start())?;
+    text.replace_range(std::ops::Range::<usize>::from(local), replace_with);
+
+    let tokens: Vec<_> = Lexer::new(&text).collect();
+    if !is_balanced_block(&tokens) {
+        return None;
+    }
+
+    let container = node.parent()?.kind();
+    let fragment = parse_member(&tokens, &text, container)?;
+
+    // The member must come back as one node of the same kind
+    let fragment_root = fragment.syntax();
+    let mut children = fragment_root.children_with_tokens();
+    let member = match (children.next(), children.next()) {
+        (Some(NodeOrToken::Node(member)), None) if member.kind() == node.kind() => member,
+        _ => return None,
+    };
+
+    let errors = splice_errors(
+        &parse.errors,
+        node_range,
+        TextSize::of(text.as_str()),
+        fragment.errors,
+    )?;
+    Some(Parse {
+        green: node.replace_with(member.green().into_owned()),
+        errors,
+    })
+}
+
+/// Whether the tokens form one member whose braces close exactly at its end.
+///
+/// A member always ends with `;` or `}`. Anything else (an unterminated comment
+/// or string, a dangling brace) would let the edit change how the text after
+/// the member is lexed or parsed.
+fn is_balanced_block(tokens: &[Token<'_>]) -> bool {
+    let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+        return false;
+    };
+    if first.kind.is_trivia() || !matches!(last.kind, SyntaxKind::SEMICOLON | SyntaxKind::R_BRACE) {
+        return false;
+    }
+
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            SyntaxKind::ERROR => return false,
+            SyntaxKind::L_BRACE => depth += 1,
+            SyntaxKind::R_BRACE => {
+                depth = match depth.checked_sub(1) {
+                    Some(depth) => depth,
+                    None => return false,
+                };
+                // Only the last token may close the outermost brace
+                if depth == 0 && i + 1 != tokens.len() {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+/// Replace the errors inside `old_range` with `new_errors` (relative to the
+/// member start) and shift the errors after it.
+///
+/// Returns `None` if an existing error touches the member's boundaries, since
+/// it could belong to either the member or its neighbour.
+fn splice_errors(
+    errors: &[SyntaxError],
+    old_range: TextRange,
+    new_len: TextSize,
+    new_errors: Vec<SyntaxError>,
+) -> Option<Vec<SyntaxError>> {
+    let new_end = old_range.start() + new_len;
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+
+    for error in errors {
+        let error_range = error.range;
+        if error_range.end() < old_range.start() || error_range == TextRange::default() {
+            // Errors at the end of file are reported at offset 0
+            before.push(error.clone());
+        } else if error_range.start() > old_range.end() {
+            let shifted = error_range - old_range.end() + new_end;
+            after.push(SyntaxError::new(error.message.clone(), shifted));
+        } else if error_range.start() > old_range.start() && error_range.end() < old_range.end() {
+            // Replaced by the member's new errors
+        } else {
+            return None;
+        }
+    }
+
+    let mut spliced = before;
+    for error in new_errors {
+        // An end-of-file error would mean the member wasn't complete on its own
+        if error.range == TextRange::default() {
+            return None;
+        }
+        spliced.push(SyntaxError::new(
+            error.message,
+            error.range + old_range.start(),
+        ));
+    }
+    spliced.extend(after);
+    Some(spliced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_kerml, parse_sysml};
+
+    const SYSML: &str = "package Vehicles {
+    part def Engine {
+        attribute power : Real;
+    }
+    part def Wheel;
+    part vehicle : Vehicle {
+        part engine : Engine;
+    }
+}
+";
+
+    fn edit(text: &str, range: TextRange, replace_with: &str) -> String {
+        let mut edited = text.to_string();
+        edited.replace_range(std::ops::Range::<usize>::from(range), replace_with);
+        edited
+    }
+
+    fn range_of(text: &str, needle: &str) -> TextRange {
+        let start = text.find(needle).expect("needle not found");
+        TextRange::at(TextSize::new(start as u32), TextSize::of(needle))
+    }
+
+    /// Reparse incrementally and check the result matches a full parse.
+    fn check_sysml(text: &str, range: TextRange, replace_with: &str) -> bool {
+        let old = parse_sysml(text);
+        let expected = parse_sysml(&edit(text, range, replace_with));
+        match reparse_sysml(&old, range, replace_with) {
+            Some(reparsed) => {
+                assert_eq!(reparsed.syntax().to_string(), expected.syntax().to_string());
+                assert_eq!(reparsed.green, expected.green);
+                assert_eq!(reparsed.errors, expected.errors);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[test]
+    fn test_edit_inside_definition() {
+        assert!(check_sysml(SYSML, range_of(SYSML, "power"), "torque"));
+    }
+
+    #[test]
+    fn test_insert_member_into_definition() {
+        let at = range_of(SYSML, "power : Real;").end();
+        assert!(check_sysml(
+            SYSML,
+            TextRange::empty(at),
+            "\n        attribute mass : Real;"
+        ));
+    }
+
+    #[test]
+    fn test_reuses_unchanged_members() {
+        let old = parse_sysml(SYSML);
+        let reparsed = reparse_sysml(&old, range_of(SYSML, "power"), "torque").unwrap();
+
+        let vehicle = |parse: &Parse| {
+            parse
+                .syntax()
+                .descendants()
+                .filter(|node| node.kind() == SyntaxKind::USAGE)
+                .find(|node| node.text().to_string().starts_with("part vehicle"))
+                .unwrap()
+                .green()
+                .into_owned()
+        };
+        // Same allocation, not just an equal tree
+        assert!(std::ptr::eq(&*vehicle(&old), &*vehicle(&reparsed)));
+    }
+
+    #[test]
+    fn test_reparses_innermost_nested_member() {
+        let text = "package P {\n    part def Outer {\n        part def Inner {\n            attribute a : Real;\n        }\n        part def Sibling;\n    }\n}\n";
+        let range = range_of(text, "a : Real");
+        assert!(check_sysml(text, range, "b : Integer"));
+
+        let old = parse_sysml(text);
+        let reparsed = reparse_sysml(&old, range, "b : Integer").unwrap();
+        let definition = |parse: &Parse, name: &str| {
+            parse
+                .syntax()
+                .descendants()
+                .filter(|node| node.kind() == SyntaxKind::DEFINITION)
+                .find(|node| node.text().to_string().starts_with(name))
+                .unwrap()
+                .green()
+                .into_owned()
+        };
+        // Only `Inner` was rebuilt; its sibling inside `Outer` is shared
+        assert!(std::ptr::eq(
+            &*definition(&old, "part def Sibling"),
+            &*definition(&reparsed, "part def Sibling")
+        ));
+    }
+
+    #[test]
+    fn test_state_body_reparses_enclosing_member() {
+        let text = "package P {\n    state def S {\n        state idle;\n    }\n}\n";
+        assert!(check_sysml(text, range_of(text, "idle"), "running"));
+    }
+
+    #[test]
+    fn test_errors_are_replaced_and_shifted() {
+        let text = "package P {\n    part def A { attribute x Real; }\n    part def B { part b Real; }\n}\n";
+        let old = parse_sysml(text);
+        assert_eq!(old.errors.len(), 2);
+
+        // Fix the first error; the second one moves
+        assert!(check_sysml(text, range_of(text, "x Real"), "longer : Real"));
+        // Introduce an error
+        assert!(check_sysml(SYSML, range_of(SYSML, ": Real"), "Real"));
+    }
+
+    #[test]
+    fn test_structural_edits_fall_back() {
+        // Unbalanced brace inside a definition
+        assert!(!check_sysml(
+            "part def A { }",
+            TextRange::empty(TextSize::new(13)),
+            "{"
+        ));
+        // Unterminated comment swallows the rest of the file
+        assert!(!check_sysml(
+            "part def A { }\npart def B;",
+            TextRange::empty(TextSize::new(13)),
+            "/*"
+        ));
+        // Edit across two members
+        let range = TextRange::new(
+            range_of(SYSML, "Real").start(),
+            range_of(SYSML, "Wheel").end(),
+        );
+        let old = parse_sysml(SYSML);
+        let whole_package = reparse_sysml(&old, range, "Real; } part def Tyre");
+        assert!(whole_package.is_some());
+        assert!(check_sysml(SYSML, range, "Real; } part def Tyre"));
+        // Edit touching the first character of the only member
+        assert!(!check_sysml(
+            SYSML,
+            TextRange::empty(TextSize::new(0)),
+            "standard "
+        ));
+    }
+
+    #[test]
+    fn test_reparse_kerml() {
+        let text = "package P {\n    class A {\n        feature x : Real;\n    }\n    classifier B :> A;\n}\n";
+        let range = range_of(text, "x : Real");
+        let old = parse_kerml(text);
+        let reparsed = reparse_kerml(&old, range, "y : Integer").unwrap();
+        let expected = parse_kerml(&edit(text, range, "y : Integer"));
+        assert_eq!(reparsed.green, expected.green);
+        assert_eq!(reparsed.errors, expected.errors);
+    }
+}
@@ -12,13 +12,22 @@
 //! // First call parses and indexes stdlib (slow), subsequent calls clone (~100ms)
 //! let host = CachedStdLib::analysis_host();
 //! ```
+//!
+//! With the `cache` feature, the built index is also persisted in the on-disk
+//! [`IndexCache`](crate::project::IndexCache), so only the first process pays for the build.
 
 use crate::ide::AnalysisHost;
 use crate::project::file_loader;
+#[cfg(feature = "cache")]
+use crate::project::index_cache::{self, IndexCache};
 use rayon::prelude::*;
 use std::path::PathBuf;
 use std::sync::{Arc, LazyLock};
 
+/// Name of the stdlib entry in the on-disk index cache.
+#[cfg(feature = "cache")]
+const STDLIB_CACHE_ENTRY: &str = "stdlib";
+
 /// Pre-built AnalysisHost with stdlib fully indexed, wrapped in Arc for cheap cloning.
 struct CachedHost {
     host: Arc<AnalysisHost>,
@@ -27,16 +36,59 @@ struct CachedHost {
 impl CachedHost {
     /// Parse all stdlib files, build index, and cache the result.
     fn load(stdlib_path: &PathBuf) -> Self {
+        #[cfg(feature = "cache")]
+        return Self::load_with_cache(stdlib_path, stdlib_index_cache().as_ref());
+        #[cfg(not(feature = "cache"))]
+        {
+            let mut host = Self::parse(stdlib_path);
+            build_index(&mut host);
+            Self {
+                host: Arc::new(host),
+            }
+        }
+    }
+
+    /// Like [`load()`](Self::load), reusing and filling the on-disk `cache`.
+    #[cfg(feature = "cache")]
+    fn load_with_cache(stdlib_path: &PathBuf, cache: Option<&IndexCache>) -> Self {
+        let mut host = Self::parse(stdlib_path);
+
+        // Reuse an index built by an earlier process, if one matches these files
+        let cache = cache.filter(|_| host.file_count() > 0);
+        let restored = cache.is_some_and(|cache| {
+            cache
+                .load_into(STDLIB_CACHE_ENTRY, &mut host)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read stdlib index cache: {}", e);
+                    false
+                })
+        });
+
+        if !restored {
+            build_index(&mut host);
+            if let Some(cache) = cache {
+                if let Err(e) = cache.store(STDLIB_CACHE_ENTRY, &mut host) {
+                    tracing::warn!("Failed to write stdlib index cache: {}", e);
+                }
+            }
+        }
+
+        Self {
+            host: Arc::new(host),
+        }
+    }
+
+    /// Add every stdlib file to a new host, without building its index.
+    fn parse(stdlib_path: &PathBuf) -> AnalysisHost {
         let mut host = AnalysisHost::new();
 
         if !stdlib_path.exists() || !stdlib_path.is_dir() {
-            return Self {
-                host: Arc::new(host),
-            };
+            return host;
         }
 
-        // Collect all file paths
-        let file_paths = file_loader::collect_file_paths(stdlib_path).unwrap_or_default();
+        // Collect all file paths (sorted so FileIds are stable across runs)
+        let mut file_paths = file_loader::collect_file_paths(stdlib_path).unwrap_or_default();
+        file_paths.sort();
 
         // Parse files in parallel
         let files: Vec<_> = file_paths
@@ -49,18 +101,29 @@ impl CachedHost {
             .collect();
 
         // Add all files to host
-        for (path, file) in files {
-            host.set_file(path, file);
-        }
+        host.set_files(files);
+        host
+    }
+}
 
-        // Build the index once (this is the expensive part!)
-        host.mark_dirty();
-        let _ = host.analysis(); // Forces index rebuild
+/// Build the index once (this is the expensive part!)
+fn build_index(host: &mut AnalysisHost) {
+    host.mark_dirty();
+    let _ = host.analysis(); // Forces index rebuild
+}
 
-        Self {
-            host: Arc::new(host),
-        }
+/// The on-disk cache the stdlib index is kept in.
+///
+/// [`IndexCache::default_dir()`](crate::project::IndexCache::default_dir),
+/// except in this crate's own tests: unless `$SYSTER_CACHE_DIR` says otherwise,
+/// they use a directory under the system temp dir, so `cargo test` never
+/// writes to the user's cache.
+#[cfg(feature = "cache")]
+fn stdlib_index_cache() -> Option<IndexCache> {
+    if cfg!(test) && std::env::var_os(index_cache::CACHE_DIR_ENV).is_none() {
+        return Some(IndexCache::new(std::env::temp_dir().join("syster-tests")));
     }
+    IndexCache::default_dir().map(IndexCache::new)
 }
 
 /// Discover the stdlib path (same logic as StdLibLoader).
@@ -110,8 +173,9 @@ static CACHED_HOST: LazyLock<CachedHost> =
 /// ## Performance Notes
 ///
 /// - **Arc reference** (`analysis_host_arc()`): Instant (~1µs), for read-only access
-/// - **Full clone** (`analysis_host()`): Slow (~15-20s), for tests that modify state
-/// - **First access**: Builds cache (~15-20s), subsequent accesses reuse it
+/// - **Clone** (`analysis_host()`): Cheap, the index is shared until the clone is edited
+/// - **First access**: Builds cache (~15-20s), subsequent accesses reuse it;
+///   with the `cache` feature, a matching on-disk index skips the build
 ///
 /// ## Usage Recommendations
 ///
@@ -123,8 +187,8 @@ pub struct CachedStdLib;
 impl CachedStdLib {
     /// Get a clone of the cached AnalysisHost with stdlib fully indexed.
     ///
-    /// **Warning:** This is slow (~15-20s) because it deep-clones all symbols.
-    /// Use `analysis_host_arc()` for read-only access.
+    /// The symbol index is shared with the cache and only copied once the
+    /// clone applies its first change. Use `analysis_host_arc()` for read-only access.
     pub fn analysis_host() -> AnalysisHost {
         (*CACHED_HOST.host).clone()
     }
@@ -139,13 +203,10 @@ impl CachedStdLib {
 
     /// Load cached stdlib into an existing AnalysisHost.
     ///
-    /// Note: This is less efficient than `analysis_host()` because the index
-    /// will need to be rebuilt. Prefer `analysis_host()` for new hosts.
+    /// The parsed files are shared with the cache, but the index will need to
+    /// be rebuilt. Prefer `analysis_host()` for new hosts.
     pub fn load_into(host: &mut AnalysisHost) {
-        // Clone all files from cached host
-        for (path, file) in CACHED_HOST.host.files() {
-            host.set_file(path.clone(), file.clone());
-        }
+        host.copy_files_from(&CACHED_HOST.host);
         host.mark_dirty();
     }
 
@@ -212,4 +273,33 @@ mod tests {
         // Check the static count
         assert!(CachedStdLib::file_count() > 50, "Expected 50+ files");
     }
+
+    #[test]
+    #[cfg(feature = "cache")]
+    fn test_stdlib_index_is_stored_in_the_given_cache() {
+        let stdlib = tempfile::tempdir().unwrap();
+        std::fs::write(
+            stdlib.path().join("Base.sysml"),
+            "standard library package Base { part def Anything; }",
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let stdlib_path = stdlib.path().to_path_buf();
+        let built = CachedHost::load_with_cache(&stdlib_path, Some(&cache));
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        // A second load reads the entry back
+        let restored = CachedHost::load_with_cache(&stdlib_path, Some(&cache));
+        assert_eq!(restored.host.content_hash(), built.host.content_hash());
+        let mut host = (*restored.host).clone();
+        assert!(
+            host.analysis()
+                .symbol_index()
+                .lookup_qualified("Base::Anything")
+                .is_some()
+        );
+    }
 }
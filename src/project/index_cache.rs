@@ -0,0 +1,354 @@
+//! Persistent on-disk cache of built symbol indexes.
+//!
+//! Building the index for the standard library takes seconds: every file has to
+//! be extracted, visibility maps built and all type references resolved. The
+//! result only depends on the source files, so it can be written to disk once
+//! and restored by later processes (CLI runs, fresh editor sessions).
+//!
+//! Entries are keyed by [`AnalysisHost::content_hash()`] and validated on load:
+//! the header must match this build of the crate, and the stored file list must
+//! match the host's files exactly (path, FileId, text length and fingerprint).
+//! Anything else is treated as a cache miss.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use syster::project::IndexCache;
+//!
+//! let cache = IndexCache::new(IndexCache::default_dir().unwrap());
+//! if !cache.load_into("workspace", &mut host)? {
+//!     cache.store("workspace", &mut host)?;
+//! }
+//! ```
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ide::{AnalysisHost, IndexSnapshot};
+
+/// Identifies a file as a syster index cache entry.
+const MAGIC: [u8; 8] = *b"SYSTRIDX";
+
+/// Bump when the on-disk layout changes in a way the crate version doesn't capture.
+const FORMAT_VERSION: u32 = 1;
+
+/// Environment variable overriding the default cache directory.
+pub const CACHE_DIR_ENV: &str = "SYSTER_CACHE_DIR";
+
+/// Errors that can occur while reading or writing the index cache.
+#[derive(Debug, Error)]
+pub enum IndexCacheError {
+    /// IO error while accessing the cache directory.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The index could not be encoded.
+    #[error("Encoding error: {0}")]
+    Encode(#[from] bincode::Error),
+}
+
+/// Written ahead of the snapshot so stale entries are rejected before decoding it.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    magic: [u8; 8],
+    format_version: u32,
+    crate_version: String,
+    content_hash: u64,
+}
+
+impl Header {
+    fn new(content_hash: u64) -> Self {
+        Self {
+            magic: MAGIC,
+            format_version: FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            content_hash,
+        }
+    }
+}
+
+/// A directory of serialized symbol indexes.
+///
+/// Each entry has a name (e.g. `"stdlib"`) and holds the index for one set of
+/// sources. Storing a new entry under a name replaces the previous one.
+#[derive(Clone, Debug)]
+pub struct IndexCache {
+    dir: PathBuf,
+}
+
+impl IndexCache {
+    /// Create a cache rooted at `dir`. The directory is created on first store.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The per-user cache directory.
+    ///
+    /// Uses `$SYSTER_CACHE_DIR` if set, otherwise the platform cache directory
+    /// (`$XDG_CACHE_HOME`, `~/.cache` or `%LOCALAPPDATA%`) with a `syster` subdirectory.
+    pub fn default_dir() -> Option<PathBuf> {
+        let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+
+        if let Some(dir) = var(CACHE_DIR_ENV) {
+            return Some(PathBuf::from(dir));
+        }
+
+        let base = var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .or_else(|| var("LOCALAPPDATA").map(PathBuf::from))?;
+        Some(base.join("syster"))
+    }
+
+    /// The directory entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the entry `name` for sources with the given content hash.
+    pub fn entry_path(&self, name: &str, content_hash: u64) -> PathBuf {
+        self.dir.join(format!("{name}-{content_hash:016x}.idx"))
+    }
+
+    /// Restore the index for the host's current files from the entry `name`.
+    ///
+    /// Returns `Ok(true)` if the host adopted the cached index, `Ok(false)` on a
+    /// cache miss. Missing, outdated or corrupt entries are all misses.
+    pub fn load_into(&self, name: &str, host: &mut AnalysisHost) -> Result<bool, IndexCacheError> {
+        let content_hash = host.content_hash();
+        let path = self.entry_path(name, content_hash);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut reader = BufReader::new(file);
+        let header: Header = match bincode::deserialize_from(&mut reader) {
+            Ok(header) => header,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable index cache {}: {}", path.display(), e);
+                return Ok(false);
+            }
+        };
+        if header != Header::new(content_hash) {
+            tracing::debug!("Ignoring outdated index cache {}", path.display());
+            return Ok(false);
+        }
+
+        let snapshot: IndexSnapshot = match bincode::deserialize_from(&mut reader) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt index cache {}: {}", path.display(), e);
+                return Ok(false);
+            }
+        };
+
+        Ok(host.restore_index_snapshot(snapshot))
+    }
+
+    /// Write the host's index to the entry `name`, replacing older versions of it.
+    ///
+    /// The index is rebuilt first if it is out of date. Returns the entry path.
+    pub fn store(&self, name: &str, host: &mut AnalysisHost) -> Result<PathBuf, IndexCacheError> {
+        let snapshot = host.index_snapshot();
+        let content_hash = host.content_hash();
+        let path = self.entry_path(name, content_hash);
+        fs::create_dir_all(&self.dir)?;
+
+        // Write to a temporary file first so readers never see a partial entry
+        let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        let result = (|| {
+            let mut writer = BufWriter::new(File::create(&tmp_path)?);
+            bincode::serialize_into(&mut writer, &Header::new(content_hash))?;
+            bincode::serialize_into(&mut writer, &snapshot)?;
+            writer.flush()?;
+            fs::rename(&tmp_path, &path)?;
+            Ok::<_, IndexCacheError>(())
+        })();
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+
+        self.remove_stale_entries(name, &path);
+        Ok(path)
+    }
+
+    /// Remove every entry in the cache directory.
+    pub fn clear(&self) -> Result<(), IndexCacheError> {
+        match fs::read_dir(&self.dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|ext| ext == "idx") {
+                        fs::remove_file(path)?;
+                    }
+                }
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete older entries stored under `name`, keeping `current`.
+    fn remove_stale_entries(&self, name: &str, current: &Path) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let prefix = format!("{name}-");
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_entry = path.extension().is_some_and(|ext| ext == "idx")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.strip_prefix(&prefix))
+                    .is_some_and(|hash| {
+                        hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit())
+                    });
+            if is_entry && path != current {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VEHICLE: &str = "package Vehicle {\n    part def Engine;\n    part engine : Engine;\n}";
+
+    fn host_with(files: &[(&str, &str)]) -> AnalysisHost {
+        let mut host = AnalysisHost::new();
+        for (path, text) in files {
+            host.set_file_content(path, text);
+        }
+        host
+    }
+
+    fn resolved_engine_type(host: &mut AnalysisHost) -> Option<String> {
+        let analysis = host.analysis();
+        let engine = analysis
+            .symbol_index()
+            .lookup_qualified("Vehicle::engine")?;
+        let type_ref = engine.type_refs.first()?.as_refs()[0].clone();
+        type_ref.resolved_target.map(|target| target.to_string())
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let mut original = host_with(&[("vehicle.sysml", VEHICLE)]);
+        let path = cache.store("test", &mut original).unwrap();
+        assert!(path.exists());
+
+        let mut restored = host_with(&[("vehicle.sysml", VEHICLE)]);
+        assert!(cache.load_into("test", &mut restored).unwrap());
+        assert_eq!(
+            resolved_engine_type(&mut restored).as_deref(),
+            Some("Vehicle::Engine")
+        );
+        assert!(
+            restored
+                .analysis()
+                .symbol_index()
+                .visibility_for_scope("Vehicle")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_changed_content_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let mut original = host_with(&[("vehicle.sysml", VEHICLE)]);
+        cache.store("test", &mut original).unwrap();
+
+        let mut edited = host_with(&[("vehicle.sysml", "package Vehicle { part def Motor; }")]);
+        assert!(!cache.load_into("test", &mut edited).unwrap());
+        assert!(
+            edited
+                .analysis()
+                .symbol_index()
+                .lookup_qualified("Vehicle::Motor")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_different_file_ids_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+        let other = "package Other {}";
+
+        let mut original = host_with(&[("a.sysml", VEHICLE), ("b.sysml", other)]);
+        cache.store("test", &mut original).unwrap();
+
+        // Same sources, added in a different order
+        let mut reordered = host_with(&[("b.sysml", other), ("a.sysml", VEHICLE)]);
+        assert_eq!(reordered.content_hash(), original.content_hash());
+        assert!(!cache.load_into("test", &mut reordered).unwrap());
+    }
+
+    #[test]
+    fn test_content_hash_roundtrips_through_the_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let mut original = host_with(&[("vehicle.sysml", VEHICLE)]);
+        let path = cache.store("test", &mut original).unwrap();
+
+        // A host built separately from the same sources computes the stored key
+        let fresh = host_with(&[("vehicle.sysml", VEHICLE)]);
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        let header: Header = bincode::deserialize_from(&mut reader).unwrap();
+        assert_eq!(header, Header::new(fresh.content_hash()));
+        assert_eq!(path, cache.entry_path("test", fresh.content_hash()));
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let mut original = host_with(&[("vehicle.sysml", VEHICLE)]);
+        let path = cache.store("test", &mut original).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let mut restored = host_with(&[("vehicle.sysml", VEHICLE)]);
+        assert!(!cache.load_into("test", &mut restored).unwrap());
+        assert_eq!(
+            resolved_engine_type(&mut restored).as_deref(),
+            Some("Vehicle::Engine")
+        );
+    }
+
+    #[test]
+    fn test_store_replaces_previous_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IndexCache::new(dir.path());
+
+        let mut host = host_with(&[("vehicle.sysml", VEHICLE)]);
+        let first = cache.store("test", &mut host).unwrap();
+        host.set_file_content("vehicle.sysml", "package Vehicle {}");
+        let second = cache.store("test", &mut host).unwrap();
+
+        assert_ne!(first, second);
+        assert!(!first.exists());
+        assert!(second.exists());
+
+        cache.clear().unwrap();
+        assert!(!second.exists());
+    }
+}
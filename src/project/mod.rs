@@ -1,9 +1,13 @@
 pub mod cached_stdlib;
 pub mod file_loader;
+#[cfg(feature = "cache")]
+pub mod index_cache;
 pub mod stdlib_loader;
 pub mod workspace_loader;
 
 pub use cached_stdlib::CachedStdLib;
+#[cfg(feature = "cache")]
+pub use index_cache::{IndexCache, IndexCacheError};
 pub use stdlib_loader::StdLibLoader;
 pub use workspace_loader::WorkspaceLoader;
 
@@ -3,8 +3,11 @@
 //! This module provides a unified interface for working with parsed files
 //! from the rowan-based parser.
 
-use crate::base::LineIndex;
-use crate::parser::{AstNode, NamespaceMember, Parse, SourceFile, parse_kerml, parse_sysml};
+use crate::base::{LineCol, LineIndex, TextRange};
+use crate::parser::{
+    AstNode, NamespaceMember, Parse, SourceFile, SyntaxNode, member_at, parse_kerml, parse_sysml,
+    reparse_kerml, reparse_sysml,
+};
 
 /// A parsed syntax file that wraps a rowan Parse result.
 ///
@@ -19,18 +22,22 @@ pub struct SyntaxFile {
 }
 
 // Manual PartialEq implementation - two SyntaxFiles are equal if they have the same extension
-// and produce the same syntax tree (approximated by checking errors)
+// and produce the same syntax tree. Salsa relies on this to decide whether a re-parse can be
+// backdated, so comparing only the errors would hide edits from downstream queries.
 impl PartialEq for SyntaxFile {
     fn eq(&self, other: &Self) -> bool {
-        self.extension == other.extension && self.parse.errors == other.parse.errors
+        self.extension == other.extension
+            && self.parse.errors == other.parse.errors
+            && self.parse.green == other.parse.green
     }
 }
 
 impl Eq for SyntaxFile {}
 
 /// File extension type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum FileExtension {
+    #[default]
     SysML,
     KerML,
 }
@@ -61,6 +68,34 @@ impl SyntaxFile {
         }
     }
 
+    /// Apply a text edit, replacing `range` of the source with `replace_with`.
+    ///
+    /// Only the innermost member containing the edit is reparsed when
+    /// possible, and the rest of the tree is shared with `self`. Edits that
+    /// change the block structure around them fall back to parsing the whole
+    /// new text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or not on character boundaries.
+    pub fn reparse(&self, range: TextRange, replace_with: &str) -> Self {
+        let incremental = match self.extension {
+            FileExtension::SysML => reparse_sysml(&self.parse, range, replace_with),
+            FileExtension::KerML => reparse_kerml(&self.parse, range, replace_with),
+        };
+        match incremental {
+            Some(parse) => Self {
+                parse,
+                extension: self.extension,
+            },
+            None => {
+                let mut source = self.source_text();
+                source.replace_range(std::ops::Range::<usize>::from(range), replace_with);
+                Self::new(&source, self.extension)
+            }
+        }
+    }
+
     /// Get the underlying parse result
     pub fn parse(&self) -> &Parse {
         &self.parse
@@ -81,6 +116,11 @@ impl SyntaxFile {
         &self.parse.errors
     }
 
+    /// Get the language this file was parsed as
+    pub fn extension(&self) -> FileExtension {
+        self.extension
+    }
+
     /// Check if this is a SysML file
     pub fn is_sysml(&self) -> bool {
         self.extension == FileExtension::SysML
@@ -131,6 +171,13 @@ impl SyntaxFile {
     pub fn line_index(&self) -> LineIndex {
         LineIndex::new(&self.source_text())
     }
+
+    /// The innermost namespace member at a position, such as where a symbol
+    /// is declared, given the file's `line_index`
+    pub fn member_at(&self, line_index: &LineIndex, line: u32, col: u32) -> Option<SyntaxNode> {
+        let offset = line_index.offset(LineCol::new(line, col))?;
+        member_at(&self.parse.syntax(), offset)
+    }
 }
 
 // Legacy compatibility: provide SysML/KerML specific types as aliases
@@ -142,41 +142,8 @@ pub struct Multiplicity {
     pub upper: Option<u64>,
 }
 
-/// A value expression assigned to a feature (e.g., `= 42`, `= "hello"`, `= true`).
-#[derive(Debug, Clone, PartialEq)]
-pub enum ValueExpression {
-    /// Integer literal (e.g., `100`)
-    LiteralInteger(i64),
-    /// Real/decimal literal (e.g., `0.75`)
-    LiteralReal(f64),
-    /// String literal (e.g., `"temperature-01"`) — stored without quotes
-    LiteralString(String),
-    /// Boolean literal (`true` or `false`)
-    LiteralBoolean(bool),
-    /// Null literal
-    Null,
-    /// A non-literal expression, stored as raw source text
-    Expression(String),
-}
-
-// Manual Eq impl because f64 doesn't implement Eq (NaN != NaN).
-// We treat two LiteralReal values as equal when their bit patterns match.
-impl Eq for ValueExpression {}
-
-// Manual Hash impl consistent with the Eq impl above.
-impl std::hash::Hash for ValueExpression {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        std::mem::discriminant(self).hash(state);
-        match self {
-            ValueExpression::LiteralInteger(v) => v.hash(state),
-            ValueExpression::LiteralReal(v) => v.to_bits().hash(state),
-            ValueExpression::LiteralString(v) => v.hash(state),
-            ValueExpression::LiteralBoolean(v) => v.hash(state),
-            ValueExpression::Null => {}
-            ValueExpression::Expression(v) => v.hash(state),
-        }
-    }
-}
+// The value expression type is shared with the parser AST
+pub use crate::parser::ValueExpression;
 
 /// A normalized usage (SysML usage or KerML feature).
 #[derive(Debug, Clone)]
@@ -485,67 +452,6 @@ fn extract_expression_chains(
 }
 
 /// Helper to create a feature chain or simple target from a qualified name
-/// Extract a `ValueExpression` from a parser `Expression` node.
-///
-/// For simple literals (single token), returns a typed variant.
-/// For complex expressions, falls back to storing the raw source text.
-fn extract_value_expression(expr: &crate::parser::Expression) -> ValueExpression {
-    use crate::parser::SyntaxKind;
-
-    let syntax = expr.syntax();
-    // Collect non-trivia tokens from the expression
-    let mut tokens = syntax
-        .descendants_with_tokens()
-        .filter_map(|el| el.into_token())
-        .filter(|t| !t.kind().is_trivia());
-
-    if let Some(token) = tokens.next() {
-        let second = tokens.next();
-        // If there's only one non-trivia token, it's a simple literal
-        let is_single = second.is_none();
-        if is_single {
-            match token.kind() {
-                SyntaxKind::INTEGER => {
-                    if let Ok(v) = token.text().parse::<i64>() {
-                        return ValueExpression::LiteralInteger(v);
-                    }
-                }
-                SyntaxKind::DECIMAL => {
-                    if let Ok(v) = token.text().parse::<f64>() {
-                        return ValueExpression::LiteralReal(v);
-                    }
-                }
-                SyntaxKind::STRING => {
-                    let text = token.text();
-                    // Strip surrounding quotes
-                    let inner = if (text.starts_with('"') && text.ends_with('"'))
-                        || (text.starts_with('\'') && text.ends_with('\''))
-                    {
-                        &text[1..text.len() - 1]
-                    } else {
-                        text
-                    };
-                    return ValueExpression::LiteralString(inner.to_string());
-                }
-                SyntaxKind::TRUE_KW => return ValueExpression::LiteralBoolean(true),
-                SyntaxKind::FALSE_KW => return ValueExpression::LiteralBoolean(false),
-                SyntaxKind::NULL_KW => return ValueExpression::Null,
-                _ => {}
-            }
-        } else if token.kind() == SyntaxKind::L_PAREN
-            && second.as_ref().map(|t| t.kind()) == Some(SyntaxKind::R_PAREN)
-            && tokens.next().is_none()
-        {
-            // NullExpression per spec: 'null' | '(' ')' -- the empty-parens form
-            // is a second concrete syntax for the same null value, not a
-            // degenerate parenthesized/sequence expression.
-            return ValueExpression::Null;
-        }
-    }
-    // Fallback: store the full expression text
-    ValueExpression::Expression(syntax.text().to_string().trim().to_string())
-}
-
 fn make_chain_or_simple(target_str: &str, qn: &crate::parser::QualifiedName) -> RelTarget {
     if target_str.contains('.') {
         // Get segments with their ranges for proper hover resolution
@@ -832,7 +738,7 @@ impl NormalizedDefinition {
                     Some(SpecializationKind::References) => NormalizedRelKind::References,
                     Some(SpecializationKind::Conjugates) => NormalizedRelKind::Specializes,
                     Some(SpecializationKind::FeatureChain) => NormalizedRelKind::Specializes,
-                    None => NormalizedRelKind::Specializes, // Comma-continuation inherits Specializes
+                    Some(SpecializationKind::Other) | None => NormalizedRelKind::Specializes, // Comma-continuation inherits Specializes
                 };
                 let target_node = spec.target()?;
                 let target = target_node.to_string();
@@ -986,7 +892,8 @@ impl NormalizedUsage {
             .typing()
             .or_else(|| usage.perform_action_usage().and_then(|p| p.typing()));
         if let Some(typing) = typing {
-            if let Some(target) = typing.target() {
+            // `part p : A, B;` is typed by both
+            for target in typing.targets() {
                 relationships.push(NormalizedRelationship {
                     kind: NormalizedRelKind::TypedBy,
                     target: RelTarget::Simple(target.to_string()),
@@ -1028,7 +935,7 @@ impl NormalizedUsage {
                 Some(SpecializationKind::References) => NormalizedRelKind::References,
                 Some(SpecializationKind::Conjugates) => NormalizedRelKind::Specializes,
                 Some(SpecializationKind::FeatureChain) => NormalizedRelKind::FeatureChain,
-                None => NormalizedRelKind::Subsets, // Comma-continuation inherits Subsets for usages
+                Some(SpecializationKind::Other) | None => NormalizedRelKind::Subsets, // Comma-continuation inherits Subsets for usages
             };
             if let Some(target) = spec.target() {
                 let target_str = target.to_string();
@@ -1359,7 +1266,9 @@ impl NormalizedUsage {
                         is_nonunique: false,
                         is_portion: false,
                         direction: None,
-                        multiplicity: None,
+                        multiplicity: end
+                            .multiplicity()
+                            .map(|(l, u)| Multiplicity { lower: l, upper: u }),
                         value: None,
                     }));
                 } else if let Some(qn) = end.target() {
@@ -1743,7 +1652,7 @@ impl NormalizedUsage {
                 .map(|(l, u)| Multiplicity { lower: l, upper: u }),
             value: usage
                 .value_expression()
-                .map(|expr| extract_value_expression(&expr)),
+                .map(|expr| parser::extract_value_expression(&expr)),
         }
     }
 
@@ -144,31 +144,24 @@ pub fn parse_content(content: &str, path: &Path) -> Result<SyntaxFile, String> {
     Ok(SyntaxFile::new(content, extension))
 }
 
-/// Parses content and returns a ParseResult with detailed error information.
-/// This is the primary function for LSP usage - errors don't fail, they're captured.
-pub fn parse_with_result(content: &str, path: &Path) -> ParseResult<SyntaxFile> {
-    let ext = match get_extension(path) {
-        Ok(e) => e,
-        Err(e) => return ParseResult::with_errors(vec![e]),
-    };
-
-    let extension = match ext {
-        SYSML_EXT => FileExtension::SysML,
-        KERML_EXT => FileExtension::KerML,
-        _ => {
-            return ParseResult::with_errors(vec![ParseError::syntax_error(
-                "Unsupported file extension",
-                0,
-                0,
-            )]);
-        }
-    };
-
-    let syntax_file = SyntaxFile::new(content, extension);
+/// Determine the language of a file from its extension.
+pub fn file_extension(path: &Path) -> Result<FileExtension, ParseError> {
+    match get_extension(path)? {
+        SYSML_EXT => Ok(FileExtension::SysML),
+        KERML_EXT => Ok(FileExtension::KerML),
+        _ => Err(ParseError::syntax_error("Unsupported file extension", 0, 0)),
+    }
+}
 
-    // Convert rowan syntax errors to our ParseError type with line/column info
+/// Convert the rowan syntax errors of a parsed file to `ParseError`s with line/column info.
+///
+/// `content` must be the text the file was parsed from.
+pub fn syntax_errors(syntax_file: &SyntaxFile, content: &str) -> Vec<ParseError> {
+    if !syntax_file.has_errors() {
+        return Vec::new();
+    }
     let line_index = crate::base::LineIndex::new(content);
-    let errors: Vec<ParseError> = syntax_file
+    syntax_file
         .errors()
         .iter()
         .map(|e| {
@@ -176,7 +169,19 @@ pub fn parse_with_result(content: &str, path: &Path) -> ParseResult<SyntaxFile>
             let line_col = line_index.line_col(e.range.start());
             ParseError::syntax_error(&e.message, line_col.line as usize, line_col.col as usize)
         })
-        .collect();
+        .collect()
+}
+
+/// Parses content and returns a ParseResult with detailed error information.
+/// This is the primary function for LSP usage - errors don't fail, they're captured.
+pub fn parse_with_result(content: &str, path: &Path) -> ParseResult<SyntaxFile> {
+    let extension = match file_extension(path) {
+        Ok(e) => e,
+        Err(e) => return ParseResult::with_errors(vec![e]),
+    };
+
+    let syntax_file = SyntaxFile::new(content, extension);
+    let errors = syntax_errors(&syntax_file, content);
 
     if errors.is_empty() {
         ParseResult::ok(syntax_file)
@@ -91,7 +91,9 @@ package Test {
         );
 
         let ref_ = wheel_port1_ref.unwrap();
-        let hover = analysis.hover(file_id, ref_.start_line, ref_.start_col + 1);
+        let hover = analysis
+            .hover(file_id, ref_.start_line, ref_.start_col + 1)
+            .unwrap();
 
         assert!(
             hover.is_some(),
@@ -190,7 +192,7 @@ package Test {
         // We'll search for hover success in the expected range
         let mut found_outport_hover = false;
         for col in 30..50 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -309,7 +311,7 @@ package Test {
         // Test hover on 'sendCmd' - should resolve to Driver::sendCmd
         let mut found_sendcmd = false;
         for col in 35..55 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -365,7 +367,7 @@ package Test {
         // Test hover on 'started' - should resolve to Vehicle::started
         let mut found_started = false;
         for col in 14..30 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -386,7 +388,7 @@ package Test {
         // Test hover on 'acknowledged' - should resolve to Driver::acknowledged
         let mut found_ack = false;
         for col in 35..55 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -450,7 +452,7 @@ package Test {
         // Test hover on first 'subPort1' after compositeA
         let mut found_subport1 = false;
         for col in 30..45 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -528,7 +530,7 @@ package Test {
         for (name, expected_contains) in expected {
             let mut found = false;
             for col in 0..70 {
-                if let Some(hover) = analysis.hover(file_id, line, col) {
+                if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                     if let Some(qn) = &hover.qualified_name {
                         if qn.contains(expected_contains) {
                             found = true;
@@ -581,7 +583,7 @@ package Test {
         // Test hover on 'trigger' - the deepest member
         let mut found_trigger = false;
         for col in 50..70 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -647,7 +649,7 @@ package Test {
         // Test hover on 'dataValue' - should resolve via DataPort
         let mut found = false;
         for col in 30..50 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -708,7 +710,7 @@ package Test {
 
         let mut found_inner_value = false;
         for col in 40..60 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -784,7 +786,7 @@ package Test {
         // Test hover on 'wheelToRoadPort' (3rd level) - should resolve to Wheel::wheelToRoadPort
         let mut found_wheel_port = false;
         for col in 40..60 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -855,7 +857,7 @@ package Test {
         // Test hover on 'lugNutPort' after ::> - should resolve to Wheel::lugNutPort
         let mut found_lug_port = false;
         for col in 35..55 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -876,7 +878,7 @@ package Test {
         // Test hover on 'shankPort' after ::> - should resolve to LugNut::shankPort
         let mut found_shank_port = false;
         for col in 75..95 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -946,7 +948,7 @@ package Test {
         // Test hover on 'start' - should resolve to Starter::start
         let mut found_start = false;
         for col in 45..60 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -967,7 +969,7 @@ package Test {
         // Test hover on 'receive' - should resolve to Trigger::receive
         let mut found_receive = false;
         for col in 65..85 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -1034,7 +1036,7 @@ package Test {
         // Test hover on 'setSpeedReceived' (depth 3) - should resolve to SetSpeedPort::setSpeedReceived
         let mut found_set_speed = false;
         for col in 55..75 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -1097,7 +1099,7 @@ package Test {
         // Test hover on 'sensedSpeed' (depth 3) - should resolve to SpeedSensorPort::sensedSpeed
         let mut found_sensed_speed = false;
         for col in 50..75 {
-            if let Some(hover) = analysis.hover(file_id, line, col) {
+            if let Some(hover) = analysis.hover(file_id, line, col).unwrap() {
                 if hover
                     .qualified_name
                     .as_ref()
@@ -464,3 +464,205 @@ fn test_qualified_reference_no_false_positive() {
             .collect::<Vec<_>>()
     );
 }
+
+#[test]
+fn test_circular_specialization_detected() {
+    let source = r#"
+        package Test {
+            part def Vehicle :> Car;
+            part def Car :> Vehicle;
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let circular: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0007"))
+        .collect();
+
+    assert_eq!(
+        circular.len(),
+        2,
+        "Both participants should be reported. Got: {:?}",
+        errors
+            .iter()
+            .map(|d| d.message.as_ref())
+            .collect::<Vec<_>>()
+    );
+    assert!(circular.iter().all(|d| d.related.len() == 1));
+}
+
+#[test]
+fn test_attribute_typed_by_part_def_detected() {
+    let source = r#"
+        package Test {
+            part def Engine;
+            part car {
+                attribute x : Engine;
+            }
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let invalid: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0006"))
+        .collect();
+
+    assert_eq!(invalid.len(), 1);
+    assert!(
+        invalid[0].message.contains("expected attribute def")
+            && invalid[0].message.contains("found part def"),
+        "Should report expected and actual kinds. Got: {}",
+        invalid[0].message
+    );
+}
+
+#[test]
+fn test_redefinition_of_non_inherited_feature_detected() {
+    let source = r#"
+        package Test {
+            part def Other {
+                attribute speed;
+            }
+            part def Vehicle;
+            part def Car :> Vehicle {
+                attribute speed redefines Other::speed;
+            }
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let invalid: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0009"))
+        .collect();
+
+    assert_eq!(invalid.len(), 1);
+    assert!(
+        invalid[0].message.contains("not inherited by 'Car'"),
+        "Should name the redefining feature's owner. Got: {}",
+        invalid[0].message
+    );
+    assert_eq!(invalid[0].related.len(), 1);
+}
+
+#[test]
+fn test_inverted_multiplicity_detected() {
+    let source = r#"
+        package Test {
+            part def Wheel;
+            part car {
+                part wheels : Wheel[5..2];
+            }
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let invalid: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0015"))
+        .collect();
+
+    assert_eq!(invalid.len(), 1);
+    assert!(
+        invalid[0].message.contains("[5..2]") && invalid[0].message.contains("'wheels'"),
+        "Should name the feature and its bounds. Got: {}",
+        invalid[0].message
+    );
+}
+
+#[test]
+fn test_abstract_instantiation_is_warning() {
+    let source = r#"
+        package Test {
+            abstract part def Engine;
+            part def V8 :> Engine;
+            part car {
+                part engine : Engine;
+                part spare : V8;
+            }
+        }
+    "#;
+
+    let diagnostics = get_diagnostics_for_source(source);
+    let abstract_uses: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("W0005"))
+        .collect();
+
+    assert_eq!(abstract_uses.len(), 1);
+    assert_eq!(abstract_uses[0].severity, Severity::Warning);
+    assert!(
+        abstract_uses[0].message.contains("'engine'")
+            && abstract_uses[0].message.contains("'Engine'"),
+        "Should name the usage and its abstract type. Got: {}",
+        abstract_uses[0].message
+    );
+}
+
+#[test]
+fn test_unresolved_import_detected() {
+    let source = r#"
+        package Defs {
+            part def Engine;
+        }
+        package Model {
+            import Defs::Engin;
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let invalid: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0014"))
+        .collect();
+
+    assert_eq!(invalid.len(), 1);
+    assert!(
+        invalid[0].message.contains("'Defs::Engin'"),
+        "Should name the import target. Got: {}",
+        invalid[0].message
+    );
+    assert_eq!(invalid[0].start_line, 5);
+}
+
+#[test]
+fn test_unconjugated_ports_connected() {
+    let source = r#"
+        package Vehicle {
+            item def Fuel;
+            port def FuelPort {
+                out item fuel : Fuel;
+            }
+            part def Tank {
+                port fuelOut : FuelPort;
+            }
+            part def Engine {
+                port fuelIn : FuelPort;
+            }
+            part car {
+                part tank : Tank;
+                part engine : Engine;
+                connect tank.fuelOut to engine.fuelIn;
+            }
+        }
+    "#;
+
+    let errors = get_errors_for_source(source);
+    let incompatible: Vec<_> = errors
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("E0016"))
+        .collect();
+
+    assert_eq!(incompatible.len(), 1);
+    assert!(
+        incompatible[0]
+            .message
+            .contains("'tank.fuelOut' and 'engine.fuelIn' both send 'fuel'"),
+        "Should name both endpoints and the feature. Got: {}",
+        incompatible[0].message
+    );
+    assert_eq!(incompatible[0].start_line, 15);
+    assert_eq!(incompatible[0].related.len(), 1);
+}
@@ -47,10 +47,10 @@ package CauseEffect {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 14: `( cause1 ::> a, cause2 ::> b, effect1 ::> c );`
-    let hover = analysis.hover(file_id, 14, 14);
+    let hover = analysis.hover(file_id, 14, 14).unwrap();
     assert!(hover.is_some(), "Should hover on 'cause1' endpoint name");
 
-    let hover = analysis.hover(file_id, 14, 28);
+    let hover = analysis.hover(file_id, 14, 28).unwrap();
     assert!(hover.is_some(), "Should hover on 'cause2' endpoint name");
 }
 
@@ -82,7 +82,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 16: `interface producer_2.publicationPort to server_2.publicationPort;`
-    let hover = analysis.hover(file_id, 16, 48);
+    let hover = analysis.hover(file_id, 16, 48).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'server_2' in interface endpoint"
@@ -117,7 +117,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 16: `interface producer_2.publicationPort to server_2.publicationPort;`
-    let hover = analysis.hover(file_id, 16, 58);
+    let hover = analysis.hover(file_id, 16, 58).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'publicationPort' chain member"
@@ -39,7 +39,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `attribute totalMass: MassValue;`
-    let hover = analysis.hover(file_id, 5, 18);
+    let hover = analysis.hover(file_id, 5, 18).unwrap();
 
     assert!(
         hover.is_some(),
@@ -64,7 +64,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `attribute totalMass: MassValue;`
-    let hover = analysis.hover(file_id, 5, 30);
+    let hover = analysis.hover(file_id, 5, 30).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'MassValue' type in constraint def attribute"
@@ -88,7 +88,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `attribute val: MyValue;`
-    let hover = analysis.hover(file_id, 5, 23);
+    let hover = analysis.hover(file_id, 5, 23).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'MyValue' local type in constraint def"
@@ -113,7 +113,7 @@ package Test {
 
     // Line 5: `constraint { val <= max }`
     // Hover on 'val'
-    let hover = analysis.hover(file_id, 5, 21);
+    let hover = analysis.hover(file_id, 5, 21).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'val' in constraint expression"
@@ -137,7 +137,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 4: `attribute x;`
-    let hover = analysis.hover(file_id, 4, 22);
+    let hover = analysis.hover(file_id, 4, 22).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'x' in nested constraint def"
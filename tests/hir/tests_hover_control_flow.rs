@@ -41,11 +41,11 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 7: `if x == 1 then A1;`
-    let hover = analysis.hover(file_id, 7, 23);
+    let hover = analysis.hover(file_id, 7, 23).unwrap();
     assert!(hover.is_some(), "Should hover on 'A1' in if-then");
 
     // Line 8: `if x > 1 then A2;`
-    let hover = analysis.hover(file_id, 8, 22);
+    let hover = analysis.hover(file_id, 8, 22).unwrap();
     assert!(hover.is_some(), "Should hover on 'A2' in if-then");
 }
 
@@ -71,7 +71,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 7: `for n : Integer in (1, 2, 3) {`
-    let hover = analysis.hover(file_id, 7, 12);
+    let hover = analysis.hover(file_id, 7, 12).unwrap();
     assert!(hover.is_some(), "Should hover on 'n' loop variable");
 }
 
@@ -95,13 +95,13 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 8: `assign i := i * n;`
-    let hover = analysis.hover(file_id, 8, 15);
+    let hover = analysis.hover(file_id, 8, 15).unwrap();
     assert!(hover.is_some(), "Should hover on 'i' in assign target");
 
-    let hover = analysis.hover(file_id, 8, 20);
+    let hover = analysis.hover(file_id, 8, 20).unwrap();
     assert!(hover.is_some(), "Should hover on 'i' in assign expression");
 
-    let hover = analysis.hover(file_id, 8, 24);
+    let hover = analysis.hover(file_id, 8, 24).unwrap();
     assert!(hover.is_some(), "Should hover on 'n' in assign expression");
 }
 
@@ -123,10 +123,10 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 6: `first step1 then step2;`
-    let hover = analysis.hover(file_id, 6, 14);
+    let hover = analysis.hover(file_id, 6, 14).unwrap();
     assert!(hover.is_some(), "Should hover on 'step1' in succession");
 
-    let hover = analysis.hover(file_id, 6, 25);
+    let hover = analysis.hover(file_id, 6, 25).unwrap();
     assert!(hover.is_some(), "Should hover on 'step2' in succession");
 }
 
@@ -148,9 +148,9 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 6: `transition idle then active;`
-    let hover = analysis.hover(file_id, 6, 19);
+    let hover = analysis.hover(file_id, 6, 19).unwrap();
     assert!(hover.is_some(), "Should hover on 'idle' in transition");
 
-    let hover = analysis.hover(file_id, 6, 29);
+    let hover = analysis.hover(file_id, 6, 29).unwrap();
     assert!(hover.is_some(), "Should hover on 'active' in transition");
 }
@@ -38,7 +38,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `part e[n];`
-    let hover = analysis.hover(file_id, 5, 15);
+    let hover = analysis.hover(file_id, 5, 15).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'n' in multiplicity bounds"
@@ -62,7 +62,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `part f[n..*];`
-    let hover = analysis.hover(file_id, 5, 15);
+    let hover = analysis.hover(file_id, 5, 15).unwrap();
     assert!(hover.is_some(), "Should hover on 'n' in lower bound");
 }
 
@@ -83,7 +83,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `part g[1..n];`
-    let hover = analysis.hover(file_id, 5, 18);
+    let hover = analysis.hover(file_id, 5, 18).unwrap();
     assert!(hover.is_some(), "Should hover on 'n' in upper bound");
 }
 
@@ -104,7 +104,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `attribute x : ScalarValues::Integer[i];`
-    let hover = analysis.hover(file_id, 5, 44);
+    let hover = analysis.hover(file_id, 5, 44).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'i' in attribute multiplicity"
@@ -135,10 +135,10 @@ package Test {
     //          01234567890123456789012345678901234567890123456789012345678901234567890123
     //          0         1         2         3         4         5         6         7
     // engine4cyl in tuple: cols 50-59, engine6cyl in tuple: cols 62-71
-    let hover = analysis.hover(file_id, 9, 50);
+    let hover = analysis.hover(file_id, 9, 50).unwrap();
     assert!(hover.is_some(), "Should hover on 'engine4cyl' in tuple");
 
-    let hover = analysis.hover(file_id, 9, 62);
+    let hover = analysis.hover(file_id, 9, 62).unwrap();
     assert!(hover.is_some(), "Should hover on 'engine6cyl' in tuple");
 }
 
@@ -161,12 +161,12 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 7: `return selectedEngine :> engine;`
-    let hover = analysis.hover(file_id, 7, 15);
+    let hover = analysis.hover(file_id, 7, 15).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'selectedEngine' in return"
     );
 
-    let hover = analysis.hover(file_id, 7, 33);
+    let hover = analysis.hover(file_id, 7, 33).unwrap();
     assert!(hover.is_some(), "Should hover on 'engine' ref in return");
 }
@@ -42,11 +42,11 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 5: `#service port def ServiceDiscovery`
-    let hover = analysis.hover(file_id, 5, 5);
+    let hover = analysis.hover(file_id, 5, 5).unwrap();
     assert!(hover.is_some(), "Should hover on 'service' metadata prefix");
 
     // Line 8: `#clouddd part def ArrowheadCore`
-    let hover = analysis.hover(file_id, 8, 5);
+    let hover = analysis.hover(file_id, 8, 5).unwrap();
     assert!(hover.is_some(), "Should hover on 'clouddd' metadata prefix");
 }
 
@@ -65,7 +65,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 3: `ref :>> annotatedElement : SysML::Usage;`
-    let hover = analysis.hover(file_id, 3, 17);
+    let hover = analysis.hover(file_id, 3, 17).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'annotatedElement' redefines ref"
@@ -92,7 +92,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 8: `ref classificationLevel : ClassificationLevel;`
-    let hover = analysis.hover(file_id, 8, 12);
+    let hover = analysis.hover(file_id, 8, 12).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'classificationLevel' ref declaration"
@@ -112,7 +112,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Line 2: `metadata def MyMeta;`
-    let hover = analysis.hover(file_id, 2, 18);
+    let hover = analysis.hover(file_id, 2, 18).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'MyMeta' metadata def name"
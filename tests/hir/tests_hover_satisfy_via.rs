@@ -81,7 +81,9 @@ package Test {
             .find(|r| r.target.as_ref() == "vehicle_b" && r.start_line == line as u32);
 
         if let Some(ref_) = vehicle_b_ref {
-            let hover = analysis.hover(file_id, ref_.start_line, ref_.start_col + 1);
+            let hover = analysis
+                .hover(file_id, ref_.start_line, ref_.start_col + 1)
+                .unwrap();
             assert!(
                 hover.is_some(),
                 "hover on 'vehicle_b' by-target should resolve"
@@ -151,7 +153,9 @@ package Test {
             .find(|r| r.target.as_ref() == "ignitionPort");
 
         if let Some(ref_) = via_ref {
-            let hover = analysis.hover(file_id, ref_.start_line, ref_.start_col + 1);
+            let hover = analysis
+                .hover(file_id, ref_.start_line, ref_.start_col + 1)
+                .unwrap();
             assert!(hover.is_some(), "hover on via target should resolve");
             let qn = hover.unwrap().qualified_name;
             assert!(
@@ -218,7 +222,9 @@ package Test {
             .find(|r| r.target.as_ref() == "ignitionCmdPort");
 
         if let Some(ref_) = via_ref {
-            let hover = analysis.hover(file_id, ref_.start_line, ref_.start_col + 1);
+            let hover = analysis
+                .hover(file_id, ref_.start_line, ref_.start_col + 1)
+                .unwrap();
             assert!(
                 hover.is_some(),
                 "hover on transition via target should resolve"
@@ -57,14 +57,14 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Hover on `ignitionCmd` in the if condition (line 17, around col 23)
-    let hover = analysis.hover(file_id, 17, 23);
+    let hover = analysis.hover(file_id, 17, 23).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'ignitionCmd' in if condition"
     );
 
     // Hover on `ignitionOnOff` in the if condition (line 17, around col 35)
-    let hover = analysis.hover(file_id, 17, 40);
+    let hover = analysis.hover(file_id, 17, 40).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'ignitionOnOff' in if condition"
@@ -101,7 +101,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Hover on `controller` in the send statement (line 13, col 40)
-    let hover = analysis.hover(file_id, 13, 40);
+    let hover = analysis.hover(file_id, 13, 40).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'controller' in send target"
@@ -149,7 +149,7 @@ package Test {
     // temp (cols 45-49) should now work after parser fix
 
     // Hover on `temp` in the when condition (col 46)
-    let hover = analysis.hover(file_id, 20, 46);
+    let hover = analysis.hover(file_id, 20, 46).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'temp' in accept when condition"
@@ -197,18 +197,18 @@ package Test {
 
     // Line 21: `connect speedSensor.speedSensorPort to vehicleSoftware.controller.sensorPort;`
     // Hover on `speedSensor` (first segment, cols 16-27)
-    let hover = analysis.hover(file_id, 21, 18);
+    let hover = analysis.hover(file_id, 21, 18).unwrap();
     assert!(hover.is_some(), "Should hover on 'speedSensor' in connect");
 
     // Hover on `speedSensorPort` (second segment, cols 28-42)
-    let hover = analysis.hover(file_id, 21, 30);
+    let hover = analysis.hover(file_id, 21, 30).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'speedSensorPort' in connect"
     );
 
     // Hover on `vehicleSoftware` (target first segment)
-    let hover = analysis.hover(file_id, 21, 50);
+    let hover = analysis.hover(file_id, 21, 50).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'vehicleSoftware' in connect"
@@ -251,7 +251,7 @@ package Test {
 
     // Line 21: `bind axle.wheel1.wheelToRoadPort = roadPort.wheelPort1;`
     // Hover on `wheelPort1` at end of path (cols 52-62)
-    let hover = analysis.hover(file_id, 21, 53);
+    let hover = analysis.hover(file_id, 21, 53).unwrap();
     assert!(hover.is_some(), "Should hover on 'wheelPort1' in bind");
 }
 
@@ -296,7 +296,7 @@ package Test {
 
     // Line 25: `from sensor.sensorPort.sensedSpeedSent`
     // Hover on `sensedSpeedSent` (cols 35-50)
-    let hover = analysis.hover(file_id, 25, 40);
+    let hover = analysis.hover(file_id, 25, 40).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'sensedSpeedSent' in message from"
@@ -333,7 +333,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Hover on `cylinders` in subset clause (line 11, around col 35)
-    let hover = analysis.hover(file_id, 11, 35);
+    let hover = analysis.hover(file_id, 11, 35).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'cylinders' in subset target"
@@ -378,7 +378,7 @@ package Test {
         .map(|(i, _)| i as u32)
         .expect("subset line not found");
 
-    let hover = analysis.hover(file_id, subset_line, 55);
+    let hover = analysis.hover(file_id, subset_line, 55).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'getInVehicle_a' in subset"
@@ -412,7 +412,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Hover on `off` in first clause (line 8, around col 18)
-    let hover = analysis.hover(file_id, 8, 18);
+    let hover = analysis.hover(file_id, 8, 18).unwrap();
     assert!(hover.is_some(), "Should hover on 'off' in first clause");
 }
 
@@ -451,7 +451,7 @@ package Test {
     let file_id = analysis.get_file_id("test.sysml").unwrap();
 
     // Hover on `on` in then clause (line 8, col 17-18)
-    let hover = analysis.hover(file_id, 8, 17);
+    let hover = analysis.hover(file_id, 8, 17).unwrap();
     assert!(hover.is_some(), "Should hover on 'on' in then clause");
     let qn = hover.unwrap().qualified_name;
     assert_eq!(qn.as_deref(), Some("Test::VehicleStates::on"));
@@ -487,7 +487,7 @@ package Test {
 
     // Line 11: `then step2 after trigger1;`
     // Hover on `trigger1` in after clause (col ~27)
-    let hover = analysis.hover(file_id, 11, 27);
+    let hover = analysis.hover(file_id, 11, 27).unwrap();
     assert!(hover.is_some(), "Should hover on 'trigger1' reference");
 }
 
@@ -516,7 +516,7 @@ package Test {
 
     // Line 8: `then step2 after step1.done;`
     // Hover on `done` (col ~33)
-    let hover = analysis.hover(file_id, 8, 33);
+    let hover = analysis.hover(file_id, 8, 33).unwrap();
     assert!(hover.is_some(), "Should hover on 'done' reference");
 }
 
@@ -555,7 +555,7 @@ package Test {
 
     // Line 16: "        attribute totalMass : MassValue = engine.mass + body.mass;"
     // Hover on `engine` in calculation (col 42)
-    let hover = analysis.hover(file_id, 16, 42);
+    let hover = analysis.hover(file_id, 16, 42).unwrap();
     assert!(hover.is_some(), "Should hover on 'engine' in calculation");
 }
 
@@ -583,18 +583,18 @@ package Test {
     //                              ^22    ^32        ^44
 
     // Hover on `status` (col 22)
-    let hover = analysis.hover(file_id, 7, 22);
+    let hover = analysis.hover(file_id, 7, 22).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'status' in constraint expression"
     );
 
     // Hover on `StatusKind` (col 32)
-    let hover = analysis.hover(file_id, 7, 32);
+    let hover = analysis.hover(file_id, 7, 32).unwrap();
     assert!(hover.is_some(), "Should hover on 'StatusKind' enum type");
 
     // Hover on `closed` (col 44)
-    let hover = analysis.hover(file_id, 7, 44);
+    let hover = analysis.hover(file_id, 7, 44).unwrap();
     assert!(hover.is_some(), "Should hover on 'closed' enum member");
 }
 
@@ -634,7 +634,7 @@ package Test {
 
     // Line 16: "        message msg from controller.speedPort.speedReceived to ..."
     // Hover on `speedReceived` (col 50)
-    let hover = analysis.hover(file_id, 16, 50);
+    let hover = analysis.hover(file_id, 16, 50).unwrap();
     assert!(
         hover.is_some(),
         "Should hover on 'speedReceived' nested event"
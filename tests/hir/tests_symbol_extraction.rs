@@ -624,8 +624,6 @@ fn test_satisfy_and_verify_reference_forms_extract_requirement_usage_and_relatio
 
 #[test]
 fn test_metadata_def_extraction() {
-    // Metadata definitions currently fall through to SymbolKind::Other
-    // (no dedicated MetadataDef variant yet)
     let source = r#"
         package MetaPkg {
             metadata def Safety;
@@ -636,8 +634,7 @@ fn test_metadata_def_extraction() {
 
     assert_symbol_exists(analysis.symbol_index(), "MetaPkg::Safety");
     let sym = get_symbol(analysis.symbol_index(), "MetaPkg::Safety");
-    // Currently maps to Other, could add MetadataDef variant in the future
-    assert_symbol_kind(sym, SymbolKind::Other);
+    assert_symbol_kind(sym, SymbolKind::MetadataDefinition);
 }
 
 // =============================================================================
@@ -11,11 +11,19 @@
 //! - Folding ranges
 //! - Inlay hints
 //! - Document links
+//! - Code actions
+//! - Rename
+//! - Signature help
+//! - Type hierarchy
 
+pub mod tests_code_actions;
 pub mod tests_completion;
 pub mod tests_folding;
 pub mod tests_goto;
 pub mod tests_hover;
 pub mod tests_references;
+pub mod tests_rename;
 pub mod tests_semantic_tokens;
+pub mod tests_signature_help;
 pub mod tests_symbols;
+pub mod tests_type_hierarchy;
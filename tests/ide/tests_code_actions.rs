@@ -0,0 +1,284 @@
+//! Code action tests for the IDE layer.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::helpers::hir_helpers::*;
+use syster::base::apply_edits;
+use syster::ide::CodeActionKind;
+use syster::ide::{Analysis, Cancelled, CodeAction, CodeActionData, refactorings};
+use syster::syntax::SyntaxFile;
+use tokio_util::sync::CancellationToken;
+
+/// The quick fixes among the code actions at `range`.
+fn quick_fixes(
+    analysis: &Analysis,
+    file_id: syster::base::FileId,
+    range: (u32, u32, u32, u32),
+) -> Vec<CodeAction> {
+    let actions = analysis.code_actions(file_id, range).unwrap();
+    actions
+        .into_iter()
+        .filter(|a| a.kind == CodeActionKind::QuickFix)
+        .collect()
+}
+
+// =============================================================================
+// QUICK FIXES
+// =============================================================================
+
+#[test]
+fn test_quick_fix_for_misspelled_type() {
+    let source =
+        "package Pkg {\n    part def Engine;\n    part car {\n        part e : Engin;\n    }\n}";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    let actions = quick_fixes(&analysis, file_id, (3, 0, 3, 30));
+    assert_eq!(actions.len(), 1, "{actions:?}");
+    let action = &actions[0];
+    assert_eq!(action.kind, CodeActionKind::QuickFix);
+    assert_eq!(&*action.title, "did you mean `Engine`?");
+    assert!(action.is_preferred);
+    assert_eq!(action.edits.len(), 1);
+    assert_eq!(&*action.edits[0].new_text, "Engine");
+    assert_eq!(
+        (action.edits[0].start_line, action.edits[0].start_col),
+        (3, 17)
+    );
+
+    // Plain diagnostics don't search for similar names
+    let diagnostics = analysis.diagnostics(file_id).unwrap();
+    assert!(
+        diagnostics.iter().all(|d| d.fixes.is_empty()),
+        "{diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_quick_fix_imports_across_files() {
+    let host_files = [
+        (
+            "vehicle.sysml",
+            "package Vehicle {\n    part def Car {\n        part wheels : Wheel;\n    }\n}",
+        ),
+        ("chassis.sysml", "package Chassis { part def Wheel; }"),
+    ];
+    let mut host = analysis_from_sources(&host_files);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("vehicle.sysml").unwrap();
+
+    let actions = quick_fixes(&analysis, file_id, (2, 15, 2, 15));
+    let titles: Vec<_> = actions.iter().map(|a| &*a.title).collect();
+    assert_eq!(titles, ["add `import Chassis::*;`"]);
+    assert_eq!(&*actions[0].edits[0].new_text, "import Chassis::*;\n    ");
+}
+
+#[test]
+fn test_no_code_actions_outside_diagnostics() {
+    let source = "package Pkg {\n    part def Engine;\n    part def Engine;\n}";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    assert!(quick_fixes(&analysis, file_id, (0, 0, 0, 5)).is_empty());
+    let actions = quick_fixes(&analysis, file_id, (2, 0, 2, 20));
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        &*actions[0].title,
+        "remove duplicate definition of 'Engine'"
+    );
+}
+
+// =============================================================================
+// REFACTORINGS
+// =============================================================================
+
+/// Apply the edits of the code action titled `title` at `range` to `source`,
+/// checking the result parses.
+fn refactor(source: &str, range: (u32, u32, u32, u32), title: &str) -> String {
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+    let actions = analysis.code_actions(file_id, range).unwrap();
+    let action = actions
+        .into_iter()
+        .find(|a| &*a.title == title)
+        .unwrap_or_else(|| panic!("no `{title}`"));
+    let action = analysis.resolve_code_action(action).unwrap().unwrap();
+    let text = apply_edits(source, &action.edits);
+    let errors = SyntaxFile::sysml(&text).errors().len();
+    assert_eq!(errors, 0, "{text}");
+    text
+}
+
+fn titles(source: &str, range: (u32, u32, u32, u32)) -> Vec<String> {
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+    let actions = analysis.code_actions(file_id, range).unwrap();
+    actions.iter().map(|a| a.title.to_string()).collect()
+}
+
+#[test]
+fn test_extract_definition() {
+    let source = "package P {\n    part def Vehicle;\n    part car : Vehicle {\n        part engine;\n    }\n}";
+
+    assert_eq!(
+        refactor(source, (2, 10, 2, 10), "extract definition `Car`"),
+        "package P {\n    part def Vehicle;\n    part def Car :> Vehicle {\n        part engine;\n    }\n\n    part car : Car;\n}"
+    );
+}
+
+#[test]
+fn test_inline_definition() {
+    let source = "package P {\n    part def Base;\n    part def Engine :> Base {\n        attribute power;\n    }\n    part car {\n        part engine : Engine;\n    }\n}";
+
+    assert_eq!(
+        refactor(source, (6, 14, 6, 14), "inline definition `Engine`"),
+        "package P {\n    part def Base;\n    part car {\n        part engine : Base {\n            attribute power;\n        }\n    }\n}"
+    );
+
+    // Not offered when the definition has other users
+    let source = "package P {\n    part def Engine;\n    part a : Engine;\n    part b : Engine;\n}";
+    assert!(!titles(source, (2, 10, 2, 10)).contains(&"inline definition `Engine`".to_string()));
+}
+
+#[test]
+fn test_convert_usage_to_definition() {
+    let source = "package P {\n    part def Vehicle;\n    part car : Vehicle {\n        part engine;\n    }\n}";
+
+    assert_eq!(
+        refactor(
+            source,
+            (2, 10, 2, 10),
+            "convert `car` to a definition specializing `Vehicle`"
+        ),
+        "package P {\n    part def Vehicle;\n    part def car :> Vehicle {\n        part engine;\n    }\n}"
+    );
+}
+
+#[test]
+fn test_name_anonymous_usage() {
+    let source = "package P {\n    part def Wheel;\n    part wheel;\n    part : Wheel;\n}";
+
+    assert_eq!(
+        refactor(source, (3, 6, 3, 6), "name usage `wheel2`"),
+        "package P {\n    part def Wheel;\n    part wheel;\n    part wheel2 : Wheel;\n}"
+    );
+}
+
+#[test]
+fn test_move_definition_rewrites_references() {
+    let source = "package A {\n    part def Engine;\n    part e : Engine;\n}\npackage B {\n    part def Car;\n}\npackage C {\n    part x : A::Engine;\n}";
+
+    assert_eq!(
+        refactor(source, (1, 14, 1, 14), "move `Engine` to `B`"),
+        "package A {\n    part e : B::Engine;\n}\npackage B {\n    part def Car;\n    part def Engine;\n}\npackage C {\n    part x : B::Engine;\n}"
+    );
+}
+
+#[test]
+fn test_move_definition_to_another_file() {
+    let files = [
+        (
+            "a.sysml",
+            "package A {\n    part def Engine;\n    part e : Engine;\n}",
+        ),
+        ("b.sysml", "package B {\n    part def Car;\n}"),
+        ("c.sysml", "package C {\n    part x : A::Engine;\n}"),
+        ("lib.sysml", "library package Lib {\n    part def Wheel;\n}"),
+    ];
+    let mut host = analysis_from_sources(&files);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("a.sysml").unwrap();
+    // Only the packages of the file are offered, without edits until resolved
+    let actions = analysis.code_actions(file_id, (1, 14, 1, 14)).unwrap();
+    let moves: Vec<_> = actions
+        .iter()
+        .filter(|a| a.kind == CodeActionKind::RefactorMove)
+        .collect();
+    assert!(moves.is_empty(), "{moves:?}");
+
+    let move_to = |package: &str| {
+        let action = CodeAction {
+            title: "move".into(),
+            kind: CodeActionKind::RefactorMove,
+            edits: Vec::new(),
+            diagnostic: None,
+            is_preferred: false,
+            data: Some(CodeActionData::MoveDefinition {
+                file: file_id,
+                line: 1,
+                col: 13,
+                package: package.into(),
+            }),
+        };
+        analysis.resolve_code_action(action).unwrap()
+    };
+    // Library packages can't be targeted
+    assert!(move_to("Lib").is_none());
+
+    let action = move_to("B").unwrap();
+    let edited: Vec<_> = files
+        .iter()
+        .map(|(path, source)| {
+            let file = analysis.get_file_id(path).unwrap();
+            let edits: Vec<_> = action
+                .edits
+                .iter()
+                .filter(|edit| edit.file == file)
+                .cloned()
+                .collect();
+            apply_edits(source, &edits)
+        })
+        .collect();
+    assert_eq!(
+        edited,
+        [
+            "package A {\n    part e : B::Engine;\n}",
+            "package B {\n    part def Car;\n    part def Engine;\n}",
+            "package C {\n    part x : B::Engine;\n}",
+            "library package Lib {\n    part def Wheel;\n}",
+        ]
+    );
+}
+
+#[test]
+fn test_wrap_in_package() {
+    let source =
+        "package P {\n    part def Engine;\n    part def Wheel;\n    part car : Engine;\n}";
+
+    assert_eq!(
+        refactor(source, (1, 4, 2, 19), "wrap in package"),
+        "package P {\n    package NewPackage {\n        part def Engine;\n        part def Wheel;\n    }\n    public import NewPackage::*;\n    part car : Engine;\n}"
+    );
+}
+
+#[test]
+fn test_refactorings_give_up_when_cancelled() {
+    let source = "package P {\n    part def Engine;\n    part a : Engine;\n}";
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+    let syntax_files = HashMap::from([(file_id, Arc::new(SyntaxFile::sysml(source)))]);
+    let cancel = CancellationToken::new();
+    let range = (2, 10, 2, 10);
+
+    let actions = refactorings(
+        analysis.symbol_index(),
+        &syntax_files,
+        file_id,
+        range,
+        &cancel,
+    );
+    assert!(!actions.unwrap().is_empty());
+
+    cancel.cancel();
+    let actions = refactorings(
+        analysis.symbol_index(),
+        &syntax_files,
+        file_id,
+        range,
+        &cancel,
+    );
+    assert!(matches!(actions, Err(Cancelled)));
+}
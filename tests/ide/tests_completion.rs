@@ -5,7 +5,9 @@
 
 use crate::helpers::hir_helpers::*;
 use syster::ide::AnalysisHost;
-use syster::ide::completions;
+use syster::ide::CompletionKind;
+use syster::ide::{completions, completions_cancellable};
+use tokio_util::sync::CancellationToken;
 
 // =============================================================================
 // COMPLETION - INCOMPLETE SYNTAX (REAL TYPING SCENARIOS)
@@ -166,6 +168,42 @@ fn test_completion_from_imported_scope() {
     );
 }
 
+#[test]
+fn test_completion_skips_members_of_packages_not_imported() {
+    let source = r#"
+        package Secrets {
+            private part def Hidden;
+            part def Shared;
+        }
+        package Consumer {
+            part h : H;
+        }
+    "#;
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    let items = completions(analysis.symbol_index(), file_id, 6, 21, None);
+    let labels: Vec<_> = items.iter().map(|i| i.label.as_ref()).collect();
+    assert!(
+        !labels.contains(&"Hidden") && !labels.contains(&"Shared"),
+        "Members of a package that isn't imported should not be suggested. Got: {:?}",
+        labels
+    );
+    assert!(labels.contains(&"Secrets"), "Got: {:?}", labels);
+
+    let items = completions_cancellable(
+        analysis.symbol_index(),
+        file_id,
+        6,
+        21,
+        Some(':'),
+        &CancellationToken::new(),
+    )
+    .unwrap();
+    assert!(!items.iter().any(|i| i.label.as_ref() == "Hidden"));
+}
+
 #[test]
 fn test_completion_has_correct_kind() {
     let source = r#"
@@ -220,3 +258,143 @@ fn test_completion_at_file_end() {
     // Should not crash
     let _ = items;
 }
+
+// =============================================================================
+// COMPLETION - SCOPES AND VISIBILITY
+// =============================================================================
+
+fn labels_at(source: &str, line: u32, col: u32) -> Vec<String> {
+    let mut host = AnalysisHost::new();
+    let _errors = host.set_file_content("test.sysml", source);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("test.sysml").unwrap();
+    let items = analysis.completions(file_id, line, col, None).unwrap();
+    items.iter().map(|i| i.label.to_string()).collect()
+}
+
+#[test]
+fn test_completion_respects_imports() {
+    let source = r#"
+        package Lib {
+            part def Engine;
+        }
+        package Other {
+            part def Wheel;
+        }
+        package App {
+            private import Lib::*;
+            part e : 
+        }
+    "#;
+
+    let labels = labels_at(source, 9, 21);
+    assert!(labels.contains(&"Engine".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"Wheel".to_string()), "{labels:?}");
+}
+
+#[test]
+fn test_completion_member_access_hides_private_members() {
+    let source = r#"
+        package Lib {
+            part def Engine;
+            private part def Secret;
+        }
+        package App {
+            part e : Lib::
+        }
+    "#;
+
+    let labels = labels_at(source, 6, 26);
+    assert_eq!(labels, ["Engine"]);
+}
+
+#[test]
+fn test_completion_redefines_inherited_features() {
+    let source = r#"
+        package P {
+            part def Base {
+                attribute id;
+            }
+            part def Vehicle :> Base {
+                attribute mass;
+            }
+            part def Car :> Vehicle {
+                attribute :>> 
+            }
+        }
+    "#;
+
+    let labels = labels_at(source, 9, 30);
+    assert!(labels.contains(&"mass".to_string()), "{labels:?}");
+    assert!(labels.contains(&"id".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"Vehicle".to_string()), "{labels:?}");
+}
+
+#[test]
+fn test_completion_feature_chain() {
+    let source = r#"
+        package P {
+            part def Engine {
+                attribute power;
+            }
+            part def Car {
+                part engine : Engine;
+            }
+            part car : Car;
+            attribute p = car.engine.
+        }
+    "#;
+
+    let labels = labels_at(source, 9, 37);
+    assert_eq!(labels, ["power"]);
+}
+
+#[test]
+fn test_completion_keywords_depend_on_position() {
+    let source = "package P {\n    part def Car {\n        \n    }\n    part def Wheel \n}";
+
+    // In a definition body: directions, but no packages
+    let labels = labels_at(source, 2, 8);
+    assert!(labels.contains(&"part def".to_string()), "{labels:?}");
+    assert!(labels.contains(&"in".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"package".to_string()), "{labels:?}");
+
+    // After a definition's name
+    let labels = labels_at(source, 4, 19);
+    assert!(labels.contains(&"specializes".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"part def".to_string()), "{labels:?}");
+}
+
+#[test]
+fn test_completion_snippets() {
+    let source = "package P {\n    \n}";
+
+    let mut host = AnalysisHost::new();
+    let _errors = host.set_file_content("test.sysml", source);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("test.sysml").unwrap();
+    let items = analysis.completions(file_id, 1, 4, None).unwrap();
+    let part_def = items.iter().find(|i| &*i.label == "part def").unwrap();
+    assert_eq!(part_def.kind, CompletionKind::Snippet);
+    assert_eq!(
+        part_def.insert_text.as_deref(),
+        Some("part def ${1:Name} {\n\t$0\n}")
+    );
+}
+
+#[test]
+fn test_completion_auto_imports() {
+    let source = "package Lib {\n    part def Engine;\n}\npackage App {\n    part e : Eng\n}";
+
+    let mut host = AnalysisHost::new();
+    let _errors = host.set_file_content("test.sysml", source);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("test.sysml").unwrap();
+    let items = analysis.completions(file_id, 4, 16, None).unwrap();
+    let engine = items.iter().find(|i| &*i.label == "Engine").unwrap();
+    assert_eq!(engine.detail.as_deref(), Some("import Lib::Engine"));
+    assert_eq!(engine.additional_edits.len(), 1);
+    let edit = &engine.additional_edits[0];
+    assert_eq!((edit.start_line, edit.start_col), (4, 4));
+    assert_eq!(&*edit.new_text, "import Lib::Engine;\n    ");
+}
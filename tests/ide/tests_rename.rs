@@ -0,0 +1,199 @@
+//! Rename tests for the IDE layer.
+
+use crate::helpers::hir_helpers::*;
+use syster::base::{FileId, LineCol, LineIndex, WorkspaceEdit};
+use syster::ide::RenameError;
+
+/// Apply the edits of `file` to its `source`.
+fn apply(source: &str, edit: &WorkspaceEdit, file: FileId) -> String {
+    let line_index = LineIndex::new(source);
+    let mut text = source.to_string();
+    for e in edit.edits(file).iter().rev() {
+        let offset = |line, col| usize::from(line_index.offset(LineCol { line, col }).unwrap());
+        text.replace_range(
+            offset(e.start_line, e.start_col)..offset(e.end_line, e.end_col),
+            &e.new_text,
+        );
+    }
+    text
+}
+
+// =============================================================================
+// RENAME - EDITS
+// =============================================================================
+
+#[test]
+fn test_rename_definition_updates_references() {
+    let source = "package Lib {\n    part def Engine;\n}\npackage Vehicles {\n    import Lib::Engine;\n    alias E for Lib::Engine;\n    part def Car :> Lib::Engine;\n    part e : Engine;\n}";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    let edit = analysis.rename(file_id, 1, 15, "Motor").unwrap().unwrap();
+    assert_eq!(
+        apply(source, &edit, file_id),
+        "package Lib {\n    part def Motor;\n}\npackage Vehicles {\n    import Lib::Motor;\n    alias E for Lib::Motor;\n    part def Car :> Lib::Motor;\n    part e : Motor;\n}"
+    );
+}
+
+#[test]
+fn test_rename_from_reference_across_files() {
+    let files = [
+        ("lib.sysml", "package Lib { part def Engine; }"),
+        (
+            "car.sysml",
+            "package Car {\n    private import Lib::*;\n    part e : Engine;\n}",
+        ),
+    ];
+    let mut host = analysis_from_sources(&files);
+    let analysis = host.analysis();
+    let lib = analysis.get_file_id("lib.sysml").unwrap();
+    let car = analysis.get_file_id("car.sysml").unwrap();
+
+    // Rename the package from the import path
+    let edit = analysis.rename(car, 1, 21, "Parts").unwrap().unwrap();
+    assert_eq!(
+        apply(files[0].1, &edit, lib),
+        "package Parts { part def Engine; }"
+    );
+    assert_eq!(
+        apply(files[1].1, &edit, car),
+        "package Car {\n    private import Parts::*;\n    part e : Engine;\n}"
+    );
+}
+
+#[test]
+fn test_rename_short_name_and_quoted_names() {
+    let source = "package Units {\n    attribute def <m> metre;\n    part def 'Fuel Tank';\n    attribute length : m;\n    part tank : 'Fuel Tank';\n}";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    let edit = analysis.rename(file_id, 1, 19, "mtr").unwrap().unwrap();
+    assert_eq!(
+        apply(source, &edit, file_id),
+        source.replace("<m>", "<mtr>").replace(": m;", ": mtr;")
+    );
+
+    let edit = analysis.rename(file_id, 4, 19, "Tank").unwrap().unwrap();
+    assert_eq!(
+        apply(source, &edit, file_id),
+        source.replace("'Fuel Tank'", "Tank")
+    );
+}
+
+// =============================================================================
+// RENAME - VALIDATION
+// =============================================================================
+
+#[test]
+fn test_prepare_rename() {
+    let source = "package P {\n    part def 'Fuel Tank';\n    part tank : 'Fuel Tank';\n}";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    let target = analysis.prepare_rename(file_id, 2, 20).unwrap().unwrap();
+    assert_eq!(&*target.placeholder, "'Fuel Tank'");
+    assert_eq!(&*target.qualified_name, "P::Fuel Tank");
+    assert_eq!((target.start_line, target.start_col), (2, 16));
+    assert_eq!((target.end_line, target.end_col), (2, 27));
+
+    assert_eq!(
+        analysis.prepare_rename(file_id, 1, 6).unwrap(),
+        Err(RenameError::NoSymbol)
+    );
+}
+
+#[test]
+fn test_rename_rejects_invalid_names() {
+    let source = "package P { part def Engine; }";
+
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    for new_name in ["part", "Lib::Engine", "two words", ""] {
+        assert_eq!(
+            analysis.rename(file_id, 0, 22, new_name).unwrap(),
+            Err(RenameError::InvalidName(new_name.into()))
+        );
+    }
+    assert!(
+        analysis
+            .rename(file_id, 0, 22, "'two words'")
+            .unwrap()
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_rename_rejects_library_symbols() {
+    let files = [
+        (
+            "units.sysml",
+            "standard library package Units { attribute def Mass; }",
+        ),
+        (
+            "car.sysml",
+            "package Car {\n    private import Units::*;\n    attribute m : Mass;\n}",
+        ),
+    ];
+    let mut host = analysis_from_sources(&files);
+    let analysis = host.analysis();
+    let units = analysis.get_file_id("units.sysml").unwrap();
+    let car = analysis.get_file_id("car.sysml").unwrap();
+
+    // From a reference in user code, and from the declaration itself
+    for (file, line, col) in [(car, 2, 18), (car, 1, 22), (units, 0, 49)] {
+        assert!(
+            matches!(
+                analysis.rename(file, line, col, "Weight").unwrap(),
+                Err(RenameError::Library(_))
+            ),
+            "{line}:{col}"
+        );
+        assert!(matches!(
+            analysis.prepare_rename(file, line, col).unwrap(),
+            Err(RenameError::Library(_))
+        ));
+    }
+    // User symbols referencing the library can still be renamed
+    assert!(analysis.rename(car, 2, 14, "mass").unwrap().is_ok());
+}
+
+#[test]
+fn test_rename_conflicts() {
+    // Collides with a sibling
+    let source = "package P {\n    part def Engine;\n    part def Motor;\n}";
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let err = host.analysis().rename(file_id, 1, 15, "Motor").unwrap();
+    assert!(
+        matches!(err, Err(RenameError::Conflict { line: 2, .. })),
+        "{err:?}"
+    );
+
+    // A reference would find a closer symbol with the new name
+    let source = "package P {\n    part def Engine;\n    package Q {\n        part def Motor;\n        part e : Engine;\n    }\n}";
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let err = host.analysis().rename(file_id, 1, 15, "Motor").unwrap();
+    assert!(
+        matches!(
+            err,
+            Err(RenameError::Conflict {
+                line: 4,
+                col: 17,
+                ..
+            })
+        ),
+        "{err:?}"
+    );
+
+    // The renamed symbol would capture a reference to an imported one
+    let source = "package P {\n    private import R::*;\n    package Q {\n        part def Engine;\n        part m : Motor;\n    }\n}\npackage R { part def Motor; }";
+    let (mut host, file_id) = analysis_from_sysml(source);
+    let err = host.analysis().rename(file_id, 3, 19, "Motor").unwrap();
+    assert!(
+        matches!(err, Err(RenameError::Conflict { line: 4, .. })),
+        "{err:?}"
+    );
+}
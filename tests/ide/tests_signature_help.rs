@@ -0,0 +1,119 @@
+//! Signature help tests for the IDE layer.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use syster::ide::{AnalysisHost, Cancelled, SignatureHelp, signature_help};
+use syster::syntax::SyntaxFile;
+use tokio_util::sync::CancellationToken;
+
+/// Signature help at a position of a file that may not parse.
+fn signature_at(source: &str, line: u32, col: u32) -> Option<SignatureHelp> {
+    let mut host = AnalysisHost::new();
+    let _errors = host.set_file_content("test.sysml", source);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("test.sysml").unwrap();
+    analysis.signature_help(file_id, line, col).unwrap()
+}
+
+const CALCS: &str = "package P {\n    calc def Compute {\n        in a : Real;\n        in b : Real[0..1];\n        out c[*];\n        return r : Real;\n    }\n";
+
+// =============================================================================
+// SIGNATURE HELP - PARAMETERS
+// =============================================================================
+
+#[test]
+fn test_signature_help_lists_parameters() {
+    let source = format!("{CALCS}    attribute x = Compute(\n}}");
+
+    let help = signature_at(&source, 7, 26).unwrap();
+    assert_eq!(
+        &*help.label,
+        "Compute(in a : Real, in b : Real[0..1], out c[*]) : Real"
+    );
+    assert_eq!(&*help.qualified_name, "P::Compute");
+    let labels: Vec<_> = help.parameters.iter().map(|p| &*p.label).collect();
+    assert_eq!(labels, ["in a : Real", "in b : Real[0..1]", "out c[*]"]);
+    assert_eq!(help.parameters[1].type_name.as_deref(), Some("Real"));
+    assert_eq!(help.active_parameter, Some(0));
+}
+
+#[test]
+fn test_signature_help_inherited_parameters() {
+    let source = format!("{CALCS}    calc def Twice :> Compute;\n    attribute x = Twice(1, \n}}");
+
+    let help = signature_at(&source, 8, 27).unwrap();
+    assert_eq!(help.parameters.len(), 3);
+    assert_eq!(help.active_parameter, Some(1));
+}
+
+// =============================================================================
+// SIGNATURE HELP - ACTIVE ARGUMENT
+// =============================================================================
+
+#[test]
+fn test_signature_help_active_argument() {
+    let source = format!("{CALCS}    attribute x = Compute(f(1, 2), [1, 2], \n}}");
+
+    // Nested calls and sequences don't count as arguments
+    let help = signature_at(&source, 7, 43).unwrap();
+    assert_eq!(
+        help.active_parameter, None,
+        "out parameters take no argument"
+    );
+
+    let help = signature_at(&source, 7, 35).unwrap();
+    assert_eq!(help.active_parameter, Some(1));
+
+    // Inside the nested call, which doesn't resolve
+    assert_eq!(signature_at(&source, 7, 30), None);
+}
+
+#[test]
+fn test_signature_help_named_arguments() {
+    let source = "package P {\n    action def Move {\n        in speed : Real;\n        in target;\n    }\n    part p;\n    perform Move(target = p\n}";
+
+    let help = signature_at(source, 6, 26).unwrap();
+    assert_eq!(&*help.label, "Move(in speed : Real, in target)");
+    assert_eq!(help.active_parameter, Some(1));
+}
+
+#[test]
+fn test_signature_help_outside_invocation() {
+    let source = format!("{CALCS}    attribute x = Compute(1);\n}}");
+
+    assert_eq!(signature_at(&source, 7, 29), None);
+    assert_eq!(signature_at(&source, 1, 10), None);
+}
+
+#[test]
+fn test_signature_help_gives_up_when_cancelled() {
+    let source = format!("{CALCS}    calc def Twice :> Compute;\n    attribute x = Twice(1, \n}}");
+    let mut host = AnalysisHost::new();
+    let _errors = host.set_file_content("test.sysml", &source);
+    let analysis = host.analysis();
+    let file_id = analysis.get_file_id("test.sysml").unwrap();
+    let syntax_files = HashMap::from([(file_id, Arc::new(SyntaxFile::sysml(&source)))]);
+    let cancel = CancellationToken::new();
+
+    let help = signature_help(
+        analysis.symbol_index(),
+        &syntax_files,
+        file_id,
+        8,
+        27,
+        &cancel,
+    );
+    assert!(help.unwrap().is_some());
+
+    cancel.cancel();
+    let help = signature_help(
+        analysis.symbol_index(),
+        &syntax_files,
+        file_id,
+        8,
+        27,
+        &cancel,
+    );
+    assert!(matches!(help, Err(Cancelled)));
+}
@@ -0,0 +1,167 @@
+//! Type hierarchy tests for the IDE layer.
+
+use crate::helpers::hir_helpers::*;
+use syster::hir::RelationshipKind;
+use syster::ide::{
+    Analysis, Cancelled, TypeHierarchyItem, type_hierarchy_subtypes, type_hierarchy_supertypes,
+};
+use tokio_util::sync::CancellationToken;
+
+fn names(items: &[TypeHierarchyItem]) -> Vec<(&str, RelationshipKind)> {
+    items
+        .iter()
+        .map(|item| (&*item.qualified_name, item.relationship))
+        .collect()
+}
+
+fn supertypes(analysis: &Analysis, path: &str, line: u32, col: u32) -> Vec<TypeHierarchyItem> {
+    let file_id = analysis.get_file_id(path).unwrap();
+    analysis
+        .type_hierarchy_supertypes(file_id, line, col)
+        .unwrap()
+}
+
+fn subtypes(analysis: &Analysis, path: &str, line: u32, col: u32) -> Vec<TypeHierarchyItem> {
+    let file_id = analysis.get_file_id(path).unwrap();
+    analysis
+        .type_hierarchy_subtypes(file_id, line, col)
+        .unwrap()
+}
+
+const PARTS: (&str, &str) = ("parts.sysml", "package Parts {\n    part def Part;\n}");
+
+const VEHICLES: &str = "package V {\n    part def Vehicle;\n    part def Car :> Vehicle;\n    part def Truck :> Vehicle;\n    part fleet : Vehicle;\n}";
+
+// =============================================================================
+// SUPERTYPES
+// =============================================================================
+
+#[test]
+fn test_supertypes_of_definition_and_usage() {
+    let mut host = analysis_from_sources(&[("v.sysml", VEHICLES)]);
+    let analysis = host.analysis();
+
+    // On the declaration of `Car`
+    assert_eq!(
+        names(&supertypes(&analysis, "v.sysml", 2, 14)),
+        [("V::Vehicle", RelationshipKind::Specializes)]
+    );
+    // On the usage `fleet`
+    let items = supertypes(&analysis, "v.sysml", 4, 10);
+    assert_eq!(names(&items), [("V::Vehicle", RelationshipKind::TypedBy)]);
+    assert_eq!((items[0].start_line, items[0].start_col), (1, 13));
+}
+
+#[test]
+fn test_supertypes_include_library_roots() {
+    let mut host = analysis_from_sources(&[PARTS, ("v.sysml", VEHICLES)]);
+    let analysis = host.analysis();
+
+    // On the reference to `Vehicle` in `Car :> Vehicle`
+    assert_eq!(
+        names(&supertypes(&analysis, "v.sysml", 2, 22)),
+        [("Parts::Part", RelationshipKind::Specializes)]
+    );
+    assert!(supertypes(&analysis, "parts.sysml", 1, 14).is_empty());
+}
+
+// =============================================================================
+// SUBTYPES
+// =============================================================================
+
+#[test]
+fn test_subtypes_across_files() {
+    let mut host = analysis_from_sources(&[
+        ("v.sysml", VEHICLES),
+        (
+            "sports.sysml",
+            "package S {\n    private import V::*;\n    part def SportsCar :> Car;\n    part racer : Vehicle;\n}",
+        ),
+    ]);
+    let analysis = host.analysis();
+
+    assert_eq!(
+        names(&subtypes(&analysis, "v.sysml", 1, 14)),
+        [
+            ("V::Car", RelationshipKind::Specializes),
+            ("V::Truck", RelationshipKind::Specializes),
+            ("V::fleet", RelationshipKind::TypedBy),
+            ("S::racer", RelationshipKind::TypedBy),
+        ]
+    );
+    // From the reference to `Car` in another file
+    assert_eq!(
+        names(&subtypes(&analysis, "sports.sysml", 2, 27)),
+        [("S::SportsCar", RelationshipKind::Specializes)]
+    );
+}
+
+#[test]
+fn test_subtypes_of_library_root() {
+    let mut host = analysis_from_sources(&[PARTS, ("v.sysml", VEHICLES)]);
+    let analysis = host.analysis();
+
+    // Only definitions without an explicit supertype specialize `Part` directly
+    assert_eq!(
+        names(&subtypes(&analysis, "parts.sysml", 1, 14)),
+        [("V::Vehicle", RelationshipKind::Specializes)]
+    );
+}
+
+#[test]
+fn test_subtypes_through_subsetting_and_redefinition() {
+    let source = "package P {\n    part def Wheel;\n    part def Car {\n        part wheels : Wheel[4];\n    }\n    part def SportsCar :> Car {\n        part sportWheels :>> wheels;\n        part frontWheels subsets wheels;\n    }\n}";
+    let (mut host, _) = analysis_from_sysml(source);
+    let analysis = host.analysis();
+
+    assert_eq!(
+        names(&subtypes(&analysis, "test.sysml", 3, 15)),
+        [
+            ("P::SportsCar::sportWheels", RelationshipKind::Redefines),
+            ("P::SportsCar::frontWheels", RelationshipKind::Subsets),
+        ]
+    );
+}
+
+#[test]
+fn test_subtypes_follow_edits() {
+    let mut host = analysis_from_sources(&[
+        ("v.sysml", VEHICLES),
+        (
+            "sports.sysml",
+            "package S {\n    private import V::*;\n    part def SportsCar :> Car;\n}",
+        ),
+    ]);
+    let analysis = host.analysis();
+    assert_eq!(subtypes(&analysis, "v.sysml", 2, 14).len(), 1);
+
+    let errors = host.set_file_content("sports.sysml", "package S {\n    part def SportsCar;\n}");
+    assert!(errors.is_empty());
+    let analysis = host.analysis();
+    assert!(subtypes(&analysis, "v.sysml", 2, 14).is_empty());
+}
+
+#[test]
+fn test_type_hierarchy_gives_up_when_cancelled() {
+    let mut host = analysis_from_sources(&[("v.sysml", VEHICLES)]);
+    let analysis = host.analysis();
+    let index = analysis.symbol_index();
+    let file_id = analysis.get_file_id("v.sysml").unwrap();
+    let cancel = CancellationToken::new();
+    assert_eq!(
+        type_hierarchy_subtypes(index, file_id, 1, 14, &cancel)
+            .unwrap()
+            .len(),
+        3
+    );
+
+    cancel.cancel();
+    assert!(matches!(
+        type_hierarchy_subtypes(index, file_id, 1, 14, &cancel),
+        Err(Cancelled)
+    ));
+    assert!(matches!(
+        type_hierarchy_supertypes(index, file_id, 2, 14, &cancel),
+        Err(Cancelled)
+    ));
+}
@@ -40,7 +40,9 @@ fn test_line_970_shankPort_first_occurrence() {
     // Find first 'shankPort' (the one before ::>)
     let col = line_content.find("shankPort").expect("shankPort not found");
 
-    let hover = analysis.hover(file_id, line_idx as u32, col as u32);
+    let hover = analysis
+        .hover(file_id, line_idx as u32, col as u32)
+        .unwrap();
 
     assert!(
         hover.is_some(),
@@ -67,7 +69,9 @@ fn test_line_970_shankPort_second_occurrence() {
         .map(|p| p + first_pos + 1);
     let col = second_pos.expect("second shankPort not found");
 
-    let hover = analysis.hover(file_id, line_idx as u32, col as u32);
+    let hover = analysis
+        .hover(file_id, line_idx as u32, col as u32)
+        .unwrap();
 
     assert!(
         hover.is_some(),
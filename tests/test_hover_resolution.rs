@@ -14,7 +14,10 @@ fn create_analysis(source: &str) -> AnalysisHost {
 fn hover_at(host: &mut AnalysisHost, line: u32, col: u32) -> Option<String> {
     let analysis = host.analysis();
     let file_id = analysis.get_file_id("test.sysml").expect("file not found");
-    analysis.hover(file_id, line, col).map(|h| h.contents)
+    analysis
+        .hover(file_id, line, col)
+        .unwrap()
+        .map(|h| h.contents)
 }
 
 // =============================================================================
@@ -92,7 +92,7 @@ fn run_hover_triage() {
     // Test hover on each identifier
     for (line, col, text) in &idents {
         total_refs += 1;
-        let hover = analysis.hover(file_id, *line, *col);
+        let hover = analysis.hover(file_id, *line, *col).unwrap();
 
         if hover.is_some() {
             hover_success += 1;
@@ -71,7 +71,7 @@ fn test_line_781_message_chain_member_turnVehicleOn() {
     let col = find_col(line_content, "turnVehicleOn", 1);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
@@ -94,7 +94,7 @@ fn test_line_781_message_chain_member_trigger1() {
     let col = find_col(line_content, "trigger1", 1);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
@@ -118,7 +118,7 @@ fn test_line_817_deep_chain_sensedSpeedSent() {
     let col = find_col(line_content, "sensedSpeedSent", 1);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
@@ -148,7 +148,7 @@ fn test_line_840_event_short_chain() {
     let col = find_col(line_content, "setSpeedReceived", 2);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
@@ -174,7 +174,7 @@ fn test_line_968_connect_endpoint_name() {
     let col = find_col(line_content, "lugNutCompositePort", 1);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
@@ -203,7 +203,7 @@ fn test_line_893_enum_member_access() {
     let col = find_col(line_content, "closed", 1);
 
     let analysis = host.analysis();
-    let hover = analysis.hover(file_id, line, col);
+    let hover = analysis.hover(file_id, line, col).unwrap();
 
     assert!(
         hover.is_some(),
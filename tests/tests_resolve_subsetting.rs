@@ -57,6 +57,7 @@ fn make_symbol(name: &str, qualified: &str, kind: SymbolKind, supertypes: Vec<&s
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
@@ -105,6 +106,7 @@ fn make_symbol_with_type_refs(
         is_ordered: false,
         is_nonunique: false,
         is_portion: false,
+        is_conjugated: false,
         direction: None,
         multiplicity: None,
         value: None,
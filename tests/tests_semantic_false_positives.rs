@@ -72,7 +72,7 @@ fn load_directory_into_index(dir: &Path) -> (SymbolIndex, Vec<(FileId, PathBuf)>
         let content = std::fs::read_to_string(path).unwrap_or_default();
         let syntax = parse_content(&content, path).unwrap();
         let symbols = extract_symbols_unified(file_id, &syntax);
-        index.add_file(file_id, symbols);
+        index.add_file_with_kind(file_id, symbols, syntax.extension());
         file_info.push((file_id, path.clone()));
     }
 
@@ -206,7 +206,7 @@ fn test_examples_zero_semantic_errors() {
         let content = std::fs::read_to_string(path).unwrap_or_default();
         let syntax = parse_content(&content, path).unwrap();
         let symbols = extract_symbols_unified(file_id, &syntax);
-        index.add_file(file_id, symbols);
+        index.add_file_with_kind(file_id, symbols, syntax.extension());
 
         // Only track example files for error reporting
         if i >= examples_start_idx {
@@ -291,7 +291,7 @@ fn test_all_zero_semantic_errors() {
         let content = std::fs::read_to_string(path).unwrap_or_default();
         let syntax = parse_content(&content, path).unwrap();
         let symbols = extract_symbols_unified(file_id, &syntax);
-        index.add_file(file_id, symbols);
+        index.add_file_with_kind(file_id, symbols, syntax.extension());
         file_info.push((file_id, path.clone()));
     }
 
@@ -346,3 +346,92 @@ fn test_all_zero_semantic_errors() {
         }
     );
 }
+
+/// Error checks that depend on resolved type references, which the loaders
+/// above only run for the stdlib: specialization kinds (E0006), redefinition
+/// and subsetting (E0009, E0010), multiplicity (E0015) and connection
+/// endpoints (E0016).
+const RESOLUTION_BASED_CODES: &[&str] = &["E0006", "E0009", "E0010", "E0015", "E0016"];
+
+// Errors the examples genuinely contain, as `path:line` of the diagnostic.
+//
+// - AnalysisIndividualExample: `individual action :>> fuelConsumption :
+//   FuelEconomyAnalysis_1` redefines a `FuelConsumption` action with an
+//   unrelated analysis definition (`FuelConsumption_1` is declared just above).
+const EXAMPLE_MODEL_ERRORS: &[&str] =
+    &["tests/sysml-examples/Individuals Examples/AnalysisIndividualExample.sysml:86"];
+
+// Errors caused by known resolver limitations rather than by the examples.
+//
+// - VariabilityTest: inherited members are propagated from a supertype looked
+//   up as a global qualified name first, so `variant part x : Q` inherits from
+//   FeaturePathTest's top-level package `Q` instead of `VariabilityTest::Q`,
+//   and `attribute b : B` resolves to that package's `part def B`.
+const KNOWN_RESOLUTION_FALSE_POSITIVES: &[&str] =
+    &["tests/sysml-examples/Simple Tests/VariabilityTest.sysml:10"];
+
+/// Test that resolution-based checks report no errors on the stdlib and
+/// examples once every type reference has been resolved.
+#[test]
+fn test_examples_no_resolution_based_errors() {
+    let stdlib_dir = get_stdlib_dir();
+    let examples_dir = get_examples_dir();
+
+    if !stdlib_dir.exists() || !examples_dir.exists() {
+        eprintln!("⏭️  Skipping: sysml.library or sysml-examples not found");
+        return;
+    }
+
+    let mut all_files = Vec::new();
+    collect_model_files(&stdlib_dir, &mut all_files);
+    collect_model_files(&examples_dir, &mut all_files);
+    all_files.sort();
+
+    let mut index = SymbolIndex::new();
+    let mut file_info = Vec::new();
+
+    for (i, path) in all_files.iter().enumerate() {
+        let file_id = FileId::new(i as u32);
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        let syntax = parse_content(&content, path).unwrap();
+        let symbols = extract_symbols_unified(file_id, &syntax);
+        index.add_file_with_kind(file_id, symbols, syntax.extension());
+        file_info.push((file_id, path.clone()));
+    }
+
+    index.ensure_visibility_maps();
+    index.resolve_all_type_refs();
+
+    let base_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut errors = Vec::new();
+    for (file_id, path) in &file_info {
+        for diag in check_file(&index, *file_id) {
+            let resolution_based = diag
+                .code
+                .as_deref()
+                .is_some_and(|code| RESOLUTION_BASED_CODES.contains(&code));
+            if diag.severity != Severity::Error || !resolution_based {
+                continue;
+            }
+            let relative = path.strip_prefix(&base_dir).unwrap_or(path).display();
+            let location = format!("{}:{}", relative, diag.start_line + 1);
+            if !EXAMPLE_MODEL_ERRORS.contains(&location.as_str())
+                && !KNOWN_RESOLUTION_FALSE_POSITIVES.contains(&location.as_str())
+            {
+                errors.push(format!(
+                    "{}:{}: {}",
+                    location,
+                    diag.start_col + 1,
+                    diag.message
+                ));
+            }
+        }
+    }
+
+    assert!(
+        errors.is_empty(),
+        "Expected no resolution-based errors, but found {}:\n{}",
+        errors.len(),
+        errors.join("\n")
+    );
+}
@@ -691,6 +691,7 @@ fn test_short_name_visibility_via_import() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -729,6 +730,7 @@ fn test_short_name_visibility_via_import() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -774,6 +776,7 @@ fn test_short_name_visibility_via_import() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -812,6 +815,7 @@ fn test_short_name_visibility_via_import() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -933,6 +937,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -972,6 +977,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -1011,6 +1017,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -1050,6 +1057,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -1089,6 +1097,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -1128,6 +1137,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,
@@ -1167,6 +1177,7 @@ fn test_usage_inherits_type_members() {
                 is_ordered: false,
                 is_nonunique: false,
                 is_portion: false,
+                is_conjugated: false,
                 direction: None,
                 multiplicity: None,
                 value: None,